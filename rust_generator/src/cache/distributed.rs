@@ -419,9 +419,9 @@ mod tests {
         assert!(cache.set("test", &CachedQR {
             svg: "test".to_string(),
             metadata: crate::cache::redis::QRMetadata {
-                version: 1,
-                modules: 21,
-                error_correction: "M".to_string(),
+                version: Some(1),
+                modules: Some(21),
+                error_correction: Some("M".to_string()),
                 processing_time_ms: 10,
             },
             generated_at: 0,
@@ -18,11 +18,12 @@ pub struct CachedQR {
     pub generated_at: i64,
 }
 
+// None cuando quien guarda la entrada no conoce el dato
 #[derive(Serialize, Deserialize, Clone)]
 pub struct QRMetadata {
-    pub version: u8,
-    pub modules: usize,
-    pub error_correction: String,
+    pub version: Option<u8>,
+    pub modules: Option<usize>,
+    pub error_correction: Option<String>,
     pub processing_time_ms: u64,
 }
 
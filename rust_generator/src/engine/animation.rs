@@ -0,0 +1,510 @@
+// engine/animation.rs - Animación de códigos QR (GIF y SVG animado con SMIL)
+// Los efectos se superponen al SVG estático sobre la geometría medida por el
+// lector; cada fotograma se renderiza y decodifica antes de entregar la animación
+
+use std::fmt::Write;
+
+use image::RgbaImage;
+use rayon::prelude::*;
+
+use crate::standards::decoder::QrDecoder;
+
+use super::error::{QrError, QrResult};
+use super::raster::{self, RasterImage};
+use super::types::{AnimationEffect, AnimationOptions};
+
+/// Duración por defecto de un ciclo
+pub const DEFAULT_DURATION_MS: u32 = 2000;
+
+/// Fotogramas por ciclo por defecto
+pub const DEFAULT_FRAME_COUNT: u32 = 12;
+
+/// Límite de fotogramas por ciclo
+const MAX_FRAME_COUNT: u32 = 60;
+
+/// Ancho al que se verifican los fotogramas del SVG animado
+const SVG_VERIFY_WIDTH: u32 = 512;
+
+/// Opacidad máxima del velo sobre los módulos aún no revelados
+const REVEAL_VEIL_OPACITY: f32 = 0.5;
+
+/// Opacidad máxima del brillo del pulso sobre los ojos
+const EYE_PULSE_OPACITY: f32 = 0.45;
+
+/// Opacidad máxima de la franja de brillo
+const GLOW_SWEEP_OPACITY: f32 = 0.6;
+
+/// Veces que se reduce a la mitad la intensidad antes de desistir
+const MAX_INTENSITY_REDUCTIONS: u32 = 3;
+
+/// Velocidad de cuantización NeuQuant del GIF (1 = mejor, 30 = más rápida)
+const GIF_QUANTIZATION_SPEED: i32 = 10;
+
+/// Resumen de la verificación de fotogramas
+#[derive(Debug, Clone, Copy)]
+pub struct AnimationReport {
+    /// Fotogramas renderizados y decodificados correctamente
+    pub frames_verified: u32,
+    /// Intensidad aplicada tras las reducciones necesarias para que todos se lean
+    pub intensity: f32,
+}
+
+/// Posición del símbolo en unidades de usuario del SVG
+#[derive(Debug, Clone, Copy)]
+struct SymbolGeometry {
+    x: f32,
+    y: f32,
+    side: f32,
+    module: f32,
+    /// Centros de los ojos: superior izquierdo, superior derecho, inferior izquierdo
+    eyes: [(f32, f32); 3],
+}
+
+impl SymbolGeometry {
+    fn center(&self) -> (f32, f32) {
+        (self.x + self.side / 2.0, self.y + self.side / 2.0)
+    }
+}
+
+/// Momento de la animación: fotograma estático en una fase (0.0 - 1.0) o animación SMIL
+#[derive(Debug, Clone, Copy)]
+enum Timing {
+    Frame(f32),
+    Smil { duration_s: f32 },
+}
+
+/// Generador de animaciones
+pub struct QrAnimator {
+    decoder: QrDecoder,
+}
+
+impl QrAnimator {
+    pub fn new() -> Self {
+        Self {
+            decoder: QrDecoder::new(),
+        }
+    }
+
+    /// Genera un SVG animado con SMIL tras verificar que todos sus fotogramas se leen
+    pub fn animate_svg(
+        &self,
+        svg: &str,
+        expected: &str,
+        options: &AnimationOptions,
+    ) -> QrResult<(String, AnimationReport)> {
+        let (geometry, _, report) = self.verified_frames(svg, expected, options, SVG_VERIFY_WIDTH)?;
+        let timing = Timing::Smil {
+            duration_s: options.duration_ms.unwrap_or(DEFAULT_DURATION_MS) as f32 / 1000.0,
+        };
+        Ok((compose(svg, &geometry, &effects(options), report.intensity, timing), report))
+    }
+
+    /// Genera un GIF animado del ancho indicado; sin efectos produce un único fotograma
+    pub fn render_gif(
+        &self,
+        svg: &str,
+        expected: &str,
+        options: &AnimationOptions,
+        width: u32,
+    ) -> QrResult<(Vec<u8>, AnimationReport)> {
+        let (_, frames, report) = self.verified_frames(svg, expected, options, width)?;
+        let delay_cs = (options.duration_ms.unwrap_or(DEFAULT_DURATION_MS) / frames.len() as u32 / 10)
+            .clamp(2, u16::MAX as u32) as u16;
+        let bytes = encode_gif(&frames, delay_cs, options.loop_count.unwrap_or(0))?;
+        Ok((bytes, report))
+    }
+
+    /// Renderiza y decodifica los fotogramas de un ciclo; si alguno no se lee,
+    /// reduce la intensidad a la mitad y lo vuelve a intentar
+    fn verified_frames(
+        &self,
+        svg: &str,
+        expected: &str,
+        options: &AnimationOptions,
+        width: u32,
+    ) -> QrResult<(SymbolGeometry, Vec<RasterImage>, AnimationReport)> {
+        let geometry = self.locate_symbol(svg, expected, width)?;
+        let effects = effects(options);
+        let frame_count = if effects.is_empty() {
+            1
+        } else {
+            options.frame_count.unwrap_or(DEFAULT_FRAME_COUNT).clamp(2, MAX_FRAME_COUNT)
+        };
+        let mut intensity = options.intensity.unwrap_or(1.0).clamp(0.0, 1.0);
+
+        for _ in 0..=MAX_INTENSITY_REDUCTIONS {
+            let frames = (0..frame_count)
+                .into_par_iter()
+                .map(|index| {
+                    let phase = index as f32 / frame_count as f32;
+                    let frame = raster::render_svg(
+                        &compose(svg, &geometry, &effects, intensity, Timing::Frame(phase)),
+                        width,
+                    )?;
+                    let readable = self.frame_decodes(&frame, expected);
+                    Ok((frame, readable))
+                })
+                .collect::<QrResult<Vec<_>>>()?;
+
+            match frames.iter().position(|(_, readable)| !readable) {
+                None => {
+                    let report = AnimationReport { frames_verified: frame_count, intensity };
+                    return Ok((geometry, frames.into_iter().map(|(frame, _)| frame).collect(), report));
+                }
+                Some(index) => {
+                    tracing::debug!(
+                        "Animation frame {} unreadable at intensity {:.3}, reducing", index, intensity
+                    );
+                    intensity /= 2.0;
+                }
+            }
+        }
+
+        Err(QrError::ValidationError(
+            "La animación produce fotogramas ilegibles incluso con intensidad reducida".to_string(),
+        ))
+    }
+
+    /// Localiza el símbolo decodificando el diseño estático y lo pasa a unidades del SVG
+    fn locate_symbol(&self, svg: &str, expected: &str, width: u32) -> QrResult<SymbolGeometry> {
+        let base = raster::render_svg(svg, width)?;
+        let image = RgbaImage::from_raw(base.width, base.height, base.pixels)
+            .ok_or_else(|| QrError::RenderError("Rasterización SVG inválida".to_string()))?;
+        let decoded = self.decoder.decode_rgba(&image)?;
+        if !self.decoder.verify_content(&decoded, expected) {
+            return Err(QrError::DecodingError(
+                "El diseño estático no contiene los datos solicitados".to_string(),
+            ));
+        }
+
+        let (centers, module_px) = decoded
+            .metadata
+            .finder_centers_px
+            .zip(decoded.metadata.module_size_px)
+            .ok_or_else(|| QrError::DecodingError(
+                "No se pudo medir la posición del símbolo para animarlo".to_string(),
+            ))?;
+
+        let view_box = raster::parse_svg(svg)?.view_box().rect;
+        let scale = view_box.width() / base.width as f32;
+        let to_user = |(x, y): (f32, f32)| (view_box.x() + x * scale, view_box.y() + y * scale);
+
+        let module = module_px * scale;
+        let eyes = centers.map(to_user);
+        let (top_left, top_right) = (eyes[0], eyes[1]);
+        Ok(SymbolGeometry {
+            x: top_left.0 - 3.5 * module,
+            y: top_left.1 - 3.5 * module,
+            side: top_right.0 - top_left.0 + 7.0 * module,
+            module,
+            eyes,
+        })
+    }
+
+    fn frame_decodes(&self, frame: &RasterImage, expected: &str) -> bool {
+        RgbaImage::from_raw(frame.width, frame.height, frame.pixels.clone())
+            .and_then(|image| self.decoder.decode_rgba(&image).ok())
+            .is_some_and(|decoded| self.decoder.verify_content(&decoded, expected))
+    }
+}
+
+impl Default for QrAnimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Efectos solicitados sin duplicados, en el orden original
+fn effects(options: &AnimationOptions) -> Vec<AnimationEffect> {
+    let mut effects = Vec::new();
+    for effect in &options.effects {
+        if !effects.contains(effect) {
+            effects.push(*effect);
+        }
+    }
+    effects
+}
+
+/// Aplica los efectos al SVG estático: rota los gradientes y superpone el resto
+fn compose(
+    svg: &str,
+    geometry: &SymbolGeometry,
+    effects: &[AnimationEffect],
+    intensity: f32,
+    timing: Timing,
+) -> String {
+    let mut result = if effects.contains(&AnimationEffect::GradientRotation) {
+        rotate_gradients(svg, geometry.center(), timing)
+    } else {
+        svg.to_string()
+    };
+
+    let overlay = overlay(geometry, effects, intensity, timing);
+    if let Some(end) = result.rfind("</svg>") {
+        result.insert_str(end, &overlay);
+    }
+    result
+}
+
+/// Rota todos los gradientes del diseño alrededor de su centro
+fn rotate_gradients(svg: &str, center: (f32, f32), timing: Timing) -> String {
+    let mut out = String::with_capacity(svg.len());
+    let mut rest = svg;
+
+    while let Some(start) = [rest.find("<linearGradient"), rest.find("<radialGradient")]
+        .into_iter()
+        .flatten()
+        .min()
+    {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        let tag = &rest[start..end];
+        let self_closing = tag.ends_with('/');
+
+        // Gradientes en espacio de usuario giran sobre el símbolo; el resto sobre su caja
+        let (cx, cy) = if tag.contains(r#"gradientUnits="userSpaceOnUse""#) {
+            center
+        } else {
+            (0.5, 0.5)
+        };
+
+        match timing {
+            Timing::Frame(phase) => {
+                let rotation = format!("rotate({:.2} {:.3} {:.3})", phase * 360.0, cx, cy);
+                out.push_str(&with_gradient_transform(tag, &rotation));
+                out.push('>');
+            }
+            Timing::Smil { duration_s } => {
+                out.push_str(tag);
+                out.push('>');
+                if !self_closing {
+                    let _ = write!(
+                        out,
+                        r#"<animateTransform attributeName="gradientTransform" type="rotate" from="0 {cx:.3} {cy:.3}" to="360 {cx:.3} {cy:.3}" dur="{duration_s:.3}s" repeatCount="indefinite" additive="sum"/>"#
+                    );
+                }
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Añade una rotación a la transformación del gradiente (después de la existente,
+/// igual que `additive="sum"` en SMIL)
+fn with_gradient_transform(tag: &str, rotation: &str) -> String {
+    const ATTRIBUTE: &str = "gradientTransform=\"";
+
+    if let Some(value_start) = tag.find(ATTRIBUTE).map(|i| i + ATTRIBUTE.len()) {
+        if let Some(value_end) = tag[value_start..].find('"').map(|i| value_start + i) {
+            return format!("{} {}{}", &tag[..value_end], rotation, &tag[value_end..]);
+        }
+    }
+
+    match tag.strip_suffix('/') {
+        Some(open) => format!(r#"{} gradientTransform="{}"/"#, open.trim_end(), rotation),
+        None => format!(r#"{} gradientTransform="{}""#, tag, rotation),
+    }
+}
+
+/// Capa superpuesta con el pulso de ojos, la revelación de módulos y la franja de brillo
+fn overlay(geometry: &SymbolGeometry, effects: &[AnimationEffect], intensity: f32, timing: Timing) -> String {
+    let mut svg = String::from(r#"<g class="qr-animation">"#);
+    let g = geometry;
+
+    for effect in effects {
+        match effect {
+            AnimationEffect::GradientRotation => {}
+            AnimationEffect::EyePulse => {
+                let max = EYE_PULSE_OPACITY * intensity;
+                let half = 3.5 * g.module;
+                for (cx, cy) in g.eyes {
+                    let _ = write!(
+                        svg,
+                        r##"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#FFFFFF""##,
+                        cx - half, cy - half, 2.0 * half, 2.0 * half
+                    );
+                    match timing {
+                        Timing::Frame(phase) => {
+                            // Onda triangular: 0 → máximo → 0, como la interpolación lineal de SMIL
+                            let level = 1.0 - (2.0 * phase - 1.0).abs();
+                            let _ = write!(svg, r#" opacity="{:.3}"/>"#, max * level);
+                        }
+                        Timing::Smil { duration_s } => {
+                            let _ = write!(
+                                svg,
+                                r#" opacity="0"><animate attributeName="opacity" values="0;{max:.3};0" dur="{duration_s:.3}s" repeatCount="indefinite"/></rect>"#
+                            );
+                        }
+                    }
+                }
+            }
+            AnimationEffect::ModuleReveal => {
+                let opacity = REVEAL_VEIL_OPACITY * intensity;
+                let _ = write!(
+                    svg,
+                    r##"<rect y="{:.2}" height="{:.2}" fill="#FFFFFF" opacity="{:.3}""##,
+                    g.y, g.side, opacity
+                );
+                match timing {
+                    Timing::Frame(phase) => {
+                        let _ = write!(
+                            svg,
+                            r#" x="{:.2}" width="{:.2}"/>"#,
+                            g.x + phase * g.side, (1.0 - phase) * g.side
+                        );
+                    }
+                    Timing::Smil { duration_s } => {
+                        let (start, end, side) = (g.x, g.x + g.side, g.side);
+                        let _ = write!(
+                            svg,
+                            r#" x="{start:.2}" width="{side:.2}"><animate attributeName="x" from="{start:.2}" to="{end:.2}" dur="{duration_s:.3}s" repeatCount="indefinite"/><animate attributeName="width" from="{side:.2}" to="0" dur="{duration_s:.3}s" repeatCount="indefinite"/></rect>"#
+                        );
+                    }
+                }
+            }
+            AnimationEffect::GlowSweep => {
+                let opacity = GLOW_SWEEP_OPACITY * intensity;
+                let band = g.side * 0.3;
+                let (cx, cy) = g.center();
+                // Recorrido amplio para que la franja girada entre y salga por completo
+                let (start, end) = (g.x - g.side * 0.5 - band, g.x + g.side * 1.5);
+                let _ = write!(
+                    svg,
+                    r##"<defs><linearGradient id="qr_anim_glow"><stop offset="0" stop-color="#FFFFFF" stop-opacity="0"/><stop offset="0.5" stop-color="#FFFFFF" stop-opacity="1"/><stop offset="1" stop-color="#FFFFFF" stop-opacity="0"/></linearGradient></defs><rect y="{:.2}" width="{:.2}" height="{:.2}" fill="url(#qr_anim_glow)" opacity="{:.3}" transform="rotate(30 {:.2} {:.2})""##,
+                    g.y - g.side * 0.5, band, g.side * 2.0, opacity, cx, cy
+                );
+                match timing {
+                    Timing::Frame(phase) => {
+                        let _ = write!(svg, r#" x="{:.2}"/>"#, start + phase * (end - start));
+                    }
+                    Timing::Smil { duration_s } => {
+                        let _ = write!(
+                            svg,
+                            r#" x="{start:.2}"><animate attributeName="x" from="{start:.2}" to="{end:.2}" dur="{duration_s:.3}s" repeatCount="indefinite"/></rect>"#
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    svg.push_str("</g>");
+    svg
+}
+
+/// Codifica los fotogramas como GIF (compuestos sobre blanco; GIF no tiene alfa parcial)
+fn encode_gif(frames: &[RasterImage], delay_cs: u16, loop_count: u16) -> QrResult<Vec<u8>> {
+    let gif_error = |e: gif::EncodingError| QrError::RenderError(format!("Error codificando GIF: {}", e));
+
+    let first = frames
+        .first()
+        .ok_or_else(|| QrError::RenderError("La animación no tiene fotogramas".to_string()))?;
+    let (width, height) = match (u16::try_from(first.width), u16::try_from(first.height)) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => {
+            return Err(QrError::RenderError(format!(
+                "GIF admite como máximo {} px por lado", u16::MAX
+            )))
+        }
+    };
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, width, height, &[]).map_err(gif_error)?;
+        if frames.len() > 1 {
+            let repeat = match loop_count {
+                0 => gif::Repeat::Infinite,
+                count => gif::Repeat::Finite(count),
+            };
+            encoder.set_repeat(repeat).map_err(gif_error)?;
+        }
+
+        for frame in frames {
+            let mut rgba: Vec<u8> = raster::flatten_on_white(&frame.pixels)
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect();
+            let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, GIF_QUANTIZATION_SPEED);
+            gif_frame.delay = delay_cs;
+            encoder.write_frame(&gif_frame).map_err(gif_error)?;
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::QrGenerator;
+
+    fn options(effects: Vec<AnimationEffect>) -> AnimationOptions {
+        AnimationOptions {
+            effects,
+            duration_ms: Some(1200),
+            frame_count: Some(6),
+            intensity: None,
+            loop_count: None,
+        }
+    }
+
+    #[test]
+    fn test_animated_svg_uses_smil() {
+        let svg = QrGenerator::new().generate_basic("ANIMATED-SVG", 300).unwrap().to_svg();
+        let animator = QrAnimator::new();
+
+        let (animated, report) = animator
+            .animate_svg(&svg, "ANIMATED-SVG", &options(vec![
+                AnimationEffect::EyePulse,
+                AnimationEffect::ModuleReveal,
+                AnimationEffect::GlowSweep,
+            ]))
+            .unwrap();
+
+        assert_eq!(report.frames_verified, 6);
+        assert_eq!(animated.matches("<animate ").count(), 3 + 2 + 1);
+        assert!(animated.contains(r#"dur="1.200s""#));
+        assert!(animated.ends_with("</g></svg>"));
+    }
+
+    #[test]
+    fn test_gradient_rotation_keeps_existing_transform() {
+        let svg = r#"<svg><defs><linearGradient id="a" gradientUnits="userSpaceOnUse"><stop/></linearGradient><radialGradient id="b" gradientTransform="scale(1, 0.5)"/></defs></svg>"#;
+
+        let frame = rotate_gradients(svg, (50.0, 60.0), Timing::Frame(0.25));
+        assert!(frame.contains(r#"gradientUnits="userSpaceOnUse" gradientTransform="rotate(90.00 50.000 60.000)">"#));
+        assert!(frame.contains(r#"gradientTransform="scale(1, 0.5) rotate(90.00 0.500 0.500)"/>"#));
+
+        let smil = rotate_gradients(svg, (50.0, 60.0), Timing::Smil { duration_s: 2.0 });
+        assert_eq!(smil.matches("<animateTransform").count(), 1);
+    }
+
+    #[test]
+    fn test_gif_frames_decode() {
+        let svg = QrGenerator::new().generate_basic("ANIMATED-GIF", 300).unwrap().to_svg();
+        let animator = QrAnimator::new();
+
+        let (gif_bytes, report) = animator
+            .render_gif(&svg, "ANIMATED-GIF", &options(vec![AnimationEffect::GlowSweep]), 300)
+            .unwrap();
+        assert_eq!(report.frames_verified, 6);
+        assert!(gif_bytes.starts_with(b"GIF89a"));
+
+        // Los fotogramas cuantizados del archivo final también se leen
+        let decoder = QrDecoder::new();
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut reader = options.read_info(gif_bytes.as_slice()).unwrap();
+        let mut frames = 0;
+        while let Some(frame) = reader.read_next_frame().unwrap() {
+            let image = RgbaImage::from_raw(300, 300, frame.buffer.to_vec()).unwrap();
+            assert_eq!(decoder.decode_rgba(&image).unwrap().raw_data, "ANIMATED-GIF");
+            frames += 1;
+        }
+        assert_eq!(frames, 6);
+    }
+}
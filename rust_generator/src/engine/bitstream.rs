@@ -0,0 +1,128 @@
+// engine/bitstream.rs - Flujo de bits de datos QR con cabeceras de modo propias
+// qrcodegen no expone los modos Structured Append ni FNC1: los codewords de datos
+// se construyen aquí y se entregan a `QrCode::encode_codewords`
+
+use qrcodegen::{QrCodeEcc, QrSegment, QrSegmentMode, Version};
+use rxing::qrcode::decoder::{ErrorCorrectionLevel as RxingEcl, Version as RxingVersion};
+
+/// Indicador de modo Structured Append
+pub const MODE_STRUCTURED_APPEND: u32 = 0b0011;
+
+/// Indicador de modo FNC1 en primera posición (datos GS1)
+pub const MODE_FNC1_FIRST: u32 = 0b0101;
+
+/// Menor versión del rango en la que caben la cabecera y los segmentos
+pub fn smallest_version(
+    header_bits: usize,
+    segments: &[QrSegment],
+    ecl: QrCodeEcc,
+    min_version: u8,
+    max_version: u8,
+) -> Option<u8> {
+    let min_version = min_version.max(Version::MIN.value());
+    let max_version = max_version.min(Version::MAX.value());
+    (min_version..=max_version).find(|&version| fits(header_bits, segments, version, ecl))
+}
+
+/// Nivel de corrección más alto con el que los datos siguen cabiendo en la versión
+pub fn boosted_ecl(header_bits: usize, segments: &[QrSegment], version: u8, ecl: QrCodeEcc) -> QrCodeEcc {
+    [QrCodeEcc::Medium, QrCodeEcc::Quartile, QrCodeEcc::High]
+        .into_iter()
+        .filter(|&candidate| candidate > ecl)
+        .take_while(|&candidate| fits(header_bits, segments, version, candidate))
+        .last()
+        .unwrap_or(ecl)
+}
+
+fn fits(header_bits: usize, segments: &[QrSegment], version: u8, ecl: QrCodeEcc) -> bool {
+    segment_bits(segments, version).is_some_and(|bits| header_bits + bits <= data_capacity(version, ecl) * 8)
+}
+
+/// Bits que ocupan los segmentos en una versión (None si un contador desborda)
+pub fn segment_bits(segments: &[QrSegment], version: u8) -> Option<usize> {
+    segments.iter().try_fold(0usize, |total, segment| {
+        let count_bits = char_count_bits(segment.mode(), version);
+        if count_bits < usize::BITS as usize && segment.num_chars() >= 1 << count_bits {
+            return None;
+        }
+        Some(total + 4 + count_bits + segment.data().len())
+    })
+}
+
+/// Codewords de datos de una versión y nivel
+pub fn data_capacity(version: u8, ecl: QrCodeEcc) -> usize {
+    RxingVersion::getVersionForNumber(version as u32)
+        .map(|v| (v.getTotalCodewords() - v.getECBlocksForLevel(rxing_ecl(ecl)).getTotalECCodewords()) as usize)
+        .unwrap_or(0)
+}
+
+/// Nivel de corrección equivalente en rxing, que aporta las tablas de bloques
+pub(crate) fn rxing_ecl(ecl: QrCodeEcc) -> RxingEcl {
+    match ecl {
+        QrCodeEcc::Low => RxingEcl::L,
+        QrCodeEcc::Medium => RxingEcl::M,
+        QrCodeEcc::Quartile => RxingEcl::Q,
+        QrCodeEcc::High => RxingEcl::H,
+    }
+}
+
+fn mode_bits(mode: QrSegmentMode) -> u32 {
+    match mode {
+        QrSegmentMode::Numeric => 0b0001,
+        QrSegmentMode::Alphanumeric => 0b0010,
+        QrSegmentMode::Byte => 0b0100,
+        QrSegmentMode::Kanji => 0b1000,
+        QrSegmentMode::Eci => 0b0111,
+    }
+}
+
+fn char_count_bits(mode: QrSegmentMode, version: u8) -> usize {
+    let range = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    match mode {
+        QrSegmentMode::Numeric => [10, 12, 14][range],
+        QrSegmentMode::Alphanumeric => [9, 11, 13][range],
+        QrSegmentMode::Byte => [8, 16, 16][range],
+        QrSegmentMode::Kanji => [8, 10, 12][range],
+        QrSegmentMode::Eci => 0,
+    }
+}
+
+/// Construye los codewords de datos: cabecera (pares valor/bits), segmentos,
+/// terminador y relleno hasta la capacidad de la versión
+pub fn data_codewords(header: &[(u32, usize)], segments: &[QrSegment], version: u8, ecl: QrCodeEcc) -> Vec<u8> {
+    let mut bits: Vec<bool> = Vec::new();
+    let append = |bits: &mut Vec<bool>, value: u32, len: usize| {
+        bits.extend((0..len).rev().map(|i| (value >> i) & 1 != 0));
+    };
+
+    for &(value, len) in header {
+        append(&mut bits, value, len);
+    }
+    for segment in segments {
+        append(&mut bits, mode_bits(segment.mode()), 4);
+        append(&mut bits, segment.num_chars() as u32, char_count_bits(segment.mode(), version));
+        bits.extend_from_slice(segment.data());
+    }
+
+    let capacity_bits = data_capacity(version, ecl) * 8;
+    let terminator = (capacity_bits - bits.len()).min(4);
+    append(&mut bits, 0, terminator);
+    let padding = (8 - bits.len() % 8) % 8;
+    append(&mut bits, 0, padding);
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() * 8 >= capacity_bits {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
@@ -0,0 +1,229 @@
+// engine/codeword_map.rs - Mapa módulo → codeword → bloque Reed-Solomon de un símbolo QR
+// Reproduce la colocación en zigzag de ISO/IEC 18004 (saltando los módulos de función)
+// y el entrelazado de bloques, para contar el daño de cada bloque frente a su capacidad
+
+use qrcodegen::QrCodeEcc;
+use rxing::qrcode::decoder::Version as RxingVersion;
+
+use super::bitstream::rxing_ecl;
+
+/// Bloque Reed-Solomon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RsBlock {
+    pub data_codewords: usize,
+    pub ec_codewords: usize,
+}
+
+/// Codeword del flujo entrelazado y los módulos que lo forman
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodewordPlacement {
+    /// Bloque Reed-Solomon al que pertenece
+    pub block: usize,
+    /// Posición dentro del bloque (primero los de datos, después los de corrección)
+    pub index: usize,
+    /// Módulos (x, y) del bit más significativo al menos significativo
+    pub modules: Vec<(usize, usize)>,
+}
+
+/// Mapa de codewords de una versión y nivel de corrección
+#[derive(Debug, Clone)]
+pub struct CodewordMap {
+    size: usize,
+    /// Codeword de cada módulo (None en módulos de función y bits de resto)
+    module_codewords: Vec<Option<usize>>,
+    /// Codewords en orden de colocación
+    pub codewords: Vec<CodewordPlacement>,
+    pub blocks: Vec<RsBlock>,
+    /// Codewords de corrección reservados contra decodificaciones erróneas (versiones 1-3)
+    pub misdecode_protection: usize,
+}
+
+impl CodewordMap {
+    pub fn new(version: u8, ecl: QrCodeEcc) -> Option<Self> {
+        let rxing_version = RxingVersion::getVersionForNumber(version as u32).ok()?;
+        let function_pattern = rxing_version.buildFunctionPattern().ok()?;
+        let ec_blocks = rxing_version.getECBlocksForLevel(rxing_ecl(ecl));
+        let ec_per_block = ec_blocks.getECCodewordsPerBlock() as usize;
+
+        let blocks: Vec<RsBlock> = ec_blocks
+            .getECBlocks()
+            .iter()
+            .flat_map(|group| {
+                std::iter::repeat_n(
+                    RsBlock {
+                        data_codewords: group.getDataCodewords() as usize,
+                        ec_codewords: ec_per_block,
+                    },
+                    group.getCount() as usize,
+                )
+            })
+            .collect();
+
+        // Entrelazado: el i-ésimo codeword de datos de cada bloque (los bloques cortos
+        // se agotan antes) y después el i-ésimo de corrección
+        let max_data = blocks.iter().map(|b| b.data_codewords).max().unwrap_or(0);
+        let mut order = Vec::new();
+        for index in 0..max_data {
+            for (block, rs_block) in blocks.iter().enumerate() {
+                if index < rs_block.data_codewords {
+                    order.push((block, index));
+                }
+            }
+        }
+        for index in 0..ec_per_block {
+            for (block, rs_block) in blocks.iter().enumerate() {
+                order.push((block, rs_block.data_codewords + index));
+            }
+        }
+
+        // Zigzag en columnas de dos módulos desde la esquina inferior derecha,
+        // saltando la columna del patrón de temporización
+        let size = rxing_version.getDimensionForVersion() as usize;
+        let total_bits = order.len() * 8;
+        let mut module_codewords = vec![None; size * size];
+        let mut codewords: Vec<CodewordPlacement> = order
+            .iter()
+            .map(|&(block, index)| CodewordPlacement { block, index, modules: Vec::with_capacity(8) })
+            .collect();
+        let mut bit = 0;
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                for offset in 0..2 {
+                    let x = (right - offset) as usize;
+                    let y = if upward { size - 1 - vertical } else { vertical };
+                    if function_pattern.get(x as u32, y as u32) || bit >= total_bits {
+                        continue;
+                    }
+                    module_codewords[y * size + x] = Some(bit / 8);
+                    codewords[bit / 8].modules.push((x, y));
+                    bit += 1;
+                }
+            }
+            right -= 2;
+        }
+
+        Some(Self {
+            size,
+            module_codewords,
+            codewords,
+            blocks,
+            misdecode_protection: misdecode_protection(version, ecl),
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Codeword que contiene el módulo (x, y)
+    pub fn codeword_at(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.size || y >= self.size {
+            return None;
+        }
+        self.module_codewords[y * self.size + x]
+    }
+
+    /// Codewords erróneos que un bloque puede corregir
+    pub fn correction_capacity(&self, block: usize) -> usize {
+        self.blocks
+            .get(block)
+            .map(|b| b.ec_codewords.saturating_sub(self.misdecode_protection) / 2)
+            .unwrap_or(0)
+    }
+
+    /// Codewords distintos afectados en cada bloque por un conjunto de módulos
+    pub fn block_damage(&self, modules: impl IntoIterator<Item = (usize, usize)>) -> Vec<usize> {
+        let mut affected = vec![false; self.codewords.len()];
+        for (x, y) in modules {
+            if let Some(codeword) = self.codeword_at(x, y) {
+                affected[codeword] = true;
+            }
+        }
+        let mut damage = vec![0; self.blocks.len()];
+        for (codeword, _) in affected.iter().enumerate().filter(|(_, &hit)| hit) {
+            damage[self.codewords[codeword].block] += 1;
+        }
+        damage
+    }
+
+    /// Corrección de errores no utilizada (ISO/IEC 15415): 1 - 2·errores / (corrección - protección),
+    /// el peor valor entre bloques
+    pub fn unused_error_correction(&self, errors_per_block: &[usize]) -> f32 {
+        self.blocks
+            .iter()
+            .zip(errors_per_block)
+            .map(|(block, &errors)| {
+                let available = block.ec_codewords.saturating_sub(self.misdecode_protection).max(1) as f32;
+                (1.0 - 2.0 * errors as f32 / available).max(0.0)
+            })
+            .fold(1.0, f32::min)
+    }
+}
+
+/// Codewords de protección contra decodificación errónea (ISO/IEC 18004, tabla 9)
+fn misdecode_protection(version: u8, ecl: QrCodeEcc) -> usize {
+    match (version, ecl) {
+        (1, QrCodeEcc::Low) => 3,
+        (1, QrCodeEcc::Medium) | (2, QrCodeEcc::Low) => 2,
+        (1, _) | (3, QrCodeEcc::Low) => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qrcodegen::{QrCode as QrCodeGen, QrSegment, Version};
+
+    #[test]
+    fn test_block_structure() {
+        let map = CodewordMap::new(1, QrCodeEcc::Medium).unwrap();
+        assert_eq!(map.size(), 21);
+        assert_eq!(map.codewords.len(), 26);
+        assert_eq!(map.blocks, vec![RsBlock { data_codewords: 16, ec_codewords: 10 }]);
+        assert_eq!(map.correction_capacity(0), 4);
+
+        // 5-Q: dos bloques de 15 y dos de 16 codewords de datos, 18 de corrección
+        let map = CodewordMap::new(5, QrCodeEcc::Quartile).unwrap();
+        let data: Vec<usize> = map.blocks.iter().map(|b| b.data_codewords).collect();
+        assert_eq!(data, vec![15, 15, 16, 16]);
+        assert_eq!(map.codewords.len(), 134);
+        assert!(map.codewords.iter().all(|c| c.modules.len() == 8));
+        // El último codeword de datos solo existe en los bloques largos
+        assert_eq!((map.codewords[60].block, map.codewords[60].index), (2, 15));
+        assert_eq!((map.codewords[62].block, map.codewords[62].index), (0, 15));
+    }
+
+    #[test]
+    fn test_placement_matches_encoder() {
+        // Un símbolo sin máscara efectiva no existe: se comparan los bits del codeword
+        // colocado con los del símbolo deshaciendo la máscara 0 ((x + y) % 2 == 0)
+        let segments = QrSegment::make_segments("CODEWORD MAP 0123456789");
+        let qr = QrCodeGen::encode_segments_advanced(
+            &segments, QrCodeEcc::Low, Version::new(2), Version::new(2), Some(qrcodegen::Mask::new(0)), false,
+        )
+        .unwrap();
+        let map = CodewordMap::new(2, QrCodeEcc::Low).unwrap();
+
+        // Primer codeword: indicador de modo alfanumérico (0010) y los 4 bits altos del contador
+        let first: Vec<bool> = map.codewords[0]
+            .modules
+            .iter()
+            .map(|&(x, y)| qr.get_module(x as i32, y as i32) ^ ((x + y) % 2 == 0))
+            .collect();
+        assert_eq!(first[..4], [false, false, true, false]);
+
+        // 7 bits de resto en la versión 2
+        let mapped = (0..25).flat_map(|y| (0..25).map(move |x| (x, y)))
+            .filter(|&(x, y)| map.codeword_at(x, y).is_some())
+            .count();
+        assert_eq!(mapped, map.codewords.len() * 8);
+        assert_eq!(map.block_damage([(24, 24), (23, 24), (24, 23)]), vec![1]);
+        assert_eq!(map.unused_error_correction(&[1]), 1.0 - 2.0 / 8.0);
+    }
+}
@@ -112,6 +112,83 @@ fn is_finder_pattern_position(version: u8, row: u8, col: u8) -> bool {
     false
 }
 
+/// Dimensiones (ancho, alto) de las versiones rMQR R7x43 … R17x139
+/// Fuente: ISO/IEC 23941, tabla 1 (versión = índice + 1)
+pub const RMQR_SIZES: [(u8, u8); 32] = [
+    (43, 7), (59, 7), (77, 7), (99, 7), (139, 7),
+    (43, 9), (59, 9), (77, 9), (99, 9), (139, 9),
+    (27, 11), (43, 11), (59, 11), (77, 11), (99, 11), (139, 11),
+    (27, 13), (43, 13), (59, 13), (77, 13), (99, 13), (139, 13),
+    (43, 15), (59, 15), (77, 15), (99, 15), (139, 15),
+    (43, 17), (59, 17), (77, 17), (99, 17), (139, 17),
+];
+
+/// Columnas de los patrones de alineación rMQR según el ancho del símbolo
+/// Cada columna lleva un patrón arriba, otro abajo y un patrón de temporización vertical entre ambos
+pub fn get_rmqr_alignment_columns(width: u8) -> &'static [u8] {
+    match width {
+        43 => &[21],
+        59 => &[19, 39],
+        77 => &[25, 51],
+        99 => &[23, 49, 75],
+        139 => &[27, 55, 83, 111],
+        _ => &[],
+    }
+}
+
+/// Dimensiones de una versión rMQR (1-32)
+pub fn get_rmqr_size(version: u8) -> Option<(u8, u8)> {
+    RMQR_SIZES.get((version as usize).checked_sub(1)?).copied()
+}
+
+/// Dimensiones Data Matrix ECC 200 (filas, columnas, filas y columnas de cada región de datos)
+/// Fuente: ISO/IEC 16022, tabla 7 (versiones 1-30) e ISO/IEC 21471, tabla 7 (DMRE, 31-48);
+/// la versión es el índice + 1, con la misma numeración que el lector de rxing
+pub const DATA_MATRIX_SIZES: [(u8, u8, u8, u8); 48] = [
+    (10, 10, 8, 8), (12, 12, 10, 10), (14, 14, 12, 12), (16, 16, 14, 14), (18, 18, 16, 16),
+    (20, 20, 18, 18), (22, 22, 20, 20), (24, 24, 22, 22), (26, 26, 24, 24), (32, 32, 14, 14),
+    (36, 36, 16, 16), (40, 40, 18, 18), (44, 44, 20, 20), (48, 48, 22, 22), (52, 52, 24, 24),
+    (64, 64, 14, 14), (72, 72, 16, 16), (80, 80, 18, 18), (88, 88, 20, 20), (96, 96, 22, 22),
+    (104, 104, 24, 24), (120, 120, 18, 18), (132, 132, 20, 20), (144, 144, 22, 22),
+    (8, 18, 6, 16), (8, 32, 6, 14), (12, 26, 10, 24), (12, 36, 10, 16), (16, 36, 14, 16),
+    (16, 48, 14, 22),
+    (8, 48, 6, 22), (8, 64, 6, 14), (8, 80, 6, 18), (8, 96, 6, 22), (8, 120, 6, 18),
+    (8, 144, 6, 22), (12, 64, 10, 14), (12, 88, 10, 20), (16, 64, 14, 14), (20, 36, 18, 16),
+    (20, 44, 18, 20), (20, 64, 18, 14), (22, 48, 20, 22), (24, 48, 22, 22), (24, 64, 22, 14),
+    (26, 40, 24, 18), (26, 48, 24, 22), (26, 64, 24, 14),
+];
+
+/// Dimensiones de una versión Data Matrix (1-48)
+pub fn get_datamatrix_size(version: u8) -> Option<(u8, u8, u8, u8)> {
+    DATA_MATRIX_SIZES.get((version as usize).checked_sub(1)?).copied()
+}
+
+/// Versión Data Matrix con las dimensiones dadas (ancho, alto en módulos)
+pub fn get_datamatrix_version(width: usize, height: usize) -> Option<u8> {
+    DATA_MATRIX_SIZES
+        .iter()
+        .position(|&(rows, cols, _, _)| rows as usize == height && cols as usize == width)
+        .map(|index| index as u8 + 1)
+}
+
+/// Lado en módulos de un símbolo Aztec de `layers` capas (ISO/IEC 24778, tabla 1); el
+/// completo incluye las líneas de la rejilla de referencia
+pub fn get_aztec_size(layers: u8, compact: bool) -> usize {
+    let layers = layers as usize;
+    if compact {
+        11 + 4 * layers
+    } else {
+        let base = 14 + 4 * layers;
+        base + 1 + 2 * ((base / 2 - 1) / 15)
+    }
+}
+
+/// Capas de un símbolo Aztec compacto (1-4) o completo (1-32) de `size` módulos de lado
+pub fn get_aztec_layers(size: usize, compact: bool) -> Option<u8> {
+    let max_layers = if compact { 4 } else { 32 };
+    (1..=max_layers).find(|&layers| get_aztec_size(layers, compact) == size)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,4 +233,13 @@ mod tests {
         assert!(get_alignment_pattern_positions(0).is_empty());
         assert!(get_alignment_pattern_positions(41).is_empty());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_rmqr_alignment_columns() {
+        assert_eq!(get_rmqr_size(1), Some((43, 7)));
+        assert_eq!(get_rmqr_size(32), Some((139, 17)));
+        assert_eq!(get_rmqr_size(33), None);
+        assert!(get_rmqr_alignment_columns(27).is_empty());
+        assert_eq!(get_rmqr_alignment_columns(139), &[27, 55, 83, 111]);
+    }
+}
@@ -2,6 +2,9 @@
 
 use super::types::*;
 use super::error::{QrResult, QrError};
+use super::svg_logo::{VectorLogo, DEFAULT_SVG_LOGO_RASTER_PX};
+use super::geometry::{LogoExclusionZone, alpha_channel, calculate_logo_position};
+use super::zones::{UntouchableZone, ZoneType};
 use crate::shapes::{EyeShapeRenderer, PatternRenderer, FrameRenderer};
 use crate::processing::{ColorProcessor, GradientProcessor, EffectProcessor};
 use crate::processing::effects::{EffectConfig, ShadowConfig, GlowConfig, BlurConfig, NoiseConfig, VintageConfig};
@@ -37,6 +40,7 @@ impl QrCustomizer {
         customization: &QrCustomization,
     ) -> QrResult<QrCode> {
         // TODO: Implementar en Fase 2
+        Self::validate_symbology_rules(&qr, customization)?;
         
         // Por ahora solo guardamos la personalización
         qr.customization = Some(customization.clone());
@@ -45,33 +49,82 @@ impl QrCustomizer {
         Ok(qr)
     }
     
+    /// Reglas de personalización por simbología. Micro QR y rMQR no tienen corrección
+    /// de errores suficiente para ocultar módulos bajo un logo; Data Matrix y PDF417 lo
+    /// admiten si no tapa sus zonas fijas, y Aztec nunca (su centro es el patrón de
+    /// localización). Los ojos se estilizan solo sobre el patrón de búsqueda
+    /// superior izquierdo (ver `QrCode::to_svg_with_options`).
+    fn validate_symbology_rules(qr: &QrCode, customization: &QrCustomization) -> QrResult<()> {
+        if qr.symbology == QrSymbology::Qr {
+            return Ok(());
+        }
+        
+        let untouchable_zones = qr.untouchable_zones();
+        for logo in customization.logos() {
+            match qr.symbology {
+                // El centro de Aztec es el patrón de localización
+                QrSymbology::Aztec => {
+                    return Err(QrError::LogoError(
+                        "Aztec no admite logo: el centro del símbolo es su patrón de localización".to_string(),
+                    ))
+                }
+                // Data Matrix y PDF417 admiten logo mientras no tape patrones fijos
+                QrSymbology::DataMatrix | QrSymbology::Pdf417 => {
+                    let (_, area) = Self::logo_area(qr.width(), qr.height(), logo, &untouchable_zones)?;
+                    let overlaps = untouchable_zones.iter().any(|zone| {
+                        zone.intersects_rect(area.x as u16, area.y as u16, area.width as u16, area.height as u16)
+                    });
+                    if overlaps {
+                        return Err(QrError::LogoError(format!(
+                            "El logo taparía los patrones fijos de {:?}; reduce su tamaño",
+                            qr.symbology
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(QrError::LogoError(format!(
+                        "{:?} no admite logo: su corrección de errores no cubre la oclusión",
+                        qr.symbology
+                    )))
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
     /// Aplica personalizaciones avanzadas
     pub fn apply_advanced_customization(
         &mut self,
         mut qr: QrCode,
         customization: &QrCustomization,
         assets: AdvancedAssets,
-    ) -> QrResult<(QrCode, Option<LogoIntegrationResult>, Option<FrameInfo>, Option<EffectsInfo>)> {
-        // Aplicar logo si existe
-        let logo_result = if let Some(logo_opts) = &customization.logo {
+    ) -> QrResult<AdvancedCustomization> {
+        Self::validate_symbology_rules(&qr, customization)?;
+        
+        // Aplicar logo y overlays; cada área colocada pasa a ser un obstáculo para los siguientes
+        let mut obstacles = qr.untouchable_zones();
+        let mut prepared = assets.logos.into_iter();
+        let mut logo_results = Vec::new();
+        for logo_opts in customization.logos() {
             let result = self.integrate_logo(
                 &mut qr.matrix,
                 logo_opts,
-                assets.logo,
+                prepared.next(),
+                &obstacles,
             )?;
-            
-            // Si el logo requiere alta corrección de errores, validar
-            if result.requires_high_ecc {
-                tracing::warn!(
-                    "Logo ocupa {:.1}% del QR. Se recomienda usar corrección de errores alta.",
-                    result.capacity_loss_percentage
-                );
-            }
-            
-            Some(result)
-        } else {
-            None
-        };
+            obstacles.push(result.logo_area.as_zone());
+            logo_results.push(result);
+        }
+        
+        // Si la oclusión combinada requiere alta corrección de errores, avisar
+        let capacity_loss: f32 = logo_results.iter().map(|r| r.capacity_loss_percentage).sum();
+        if capacity_loss > 15.0 {
+            tracing::warn!(
+                "Los logos ocupan {:.1}% del QR. Se recomienda usar corrección de errores alta.",
+                capacity_loss
+            );
+        }
         
         // Preparar información del marco si existe
         let frame_info = if let Some(frame_opts) = &customization.frame {
@@ -106,7 +159,7 @@ impl QrCustomizer {
         qr.customization = Some(customization.clone());
         
         tracing::debug!("Advanced customization applied with logo, frame and effects");
-        Ok((qr, logo_result, frame_info, effects_info))
+        Ok((qr, logo_results, frame_info, effects_info))
     }
     
     /// Pre-computa datos de gradiente
@@ -120,23 +173,35 @@ impl QrCustomizer {
         })
     }
 
-    /// Integra un logo en el centro del QR
+    /// Integra un logo en su posición (el centro si no se indica otra). Las posiciones
+    /// explícitas y las anclas no pueden tapar `untouchable_zones`.
     pub fn integrate_logo(
         &self,
         qr_matrix: &mut Vec<Vec<bool>>,
         logo_options: &LogoOptions,
-        logo_image: Option<DynamicImage>,
+        prepared: Option<PreparedLogo>,
+        untouchable_zones: &[UntouchableZone],
     ) -> QrResult<LogoIntegrationResult> {
-        let matrix_size = qr_matrix.len();
-        if matrix_size == 0 {
+        let matrix_height = qr_matrix.len();
+        let matrix_width = qr_matrix.first().map_or(0, Vec::len);
+        if matrix_height == 0 {
             return Err(QrError::InternalError("QR matrix vacía".to_string()));
         }
 
         // Si no hay imagen pre-cargada, intentar decodificar desde base64
+        let (logo_image, mut logo_vector) = match prepared {
+            Some(prepared) => (Some(prepared.image), prepared.vector),
+            None => (None, None),
+        };
         let logo = match logo_image {
             Some(img) => img,
             None => {
-                if logo_options.data.starts_with("data:image") {
+                if VectorLogo::is_svg_data_url(&logo_options.data) {
+                    let vector = VectorLogo::from_data_url(&logo_options.data)?;
+                    let raster = vector.rasterize(DEFAULT_SVG_LOGO_RASTER_PX)?;
+                    logo_vector.get_or_insert(vector);
+                    raster
+                } else if logo_options.data.starts_with("data:image") {
                     // Extraer base64 de data URL
                     let base64_start = logo_options.data.find(',').unwrap_or(0) + 1;
                     let base64_data = &logo_options.data[base64_start..];
@@ -152,6 +217,65 @@ impl QrCustomizer {
             }
         };
 
+        let (logo_size, logo_area) = Self::logo_area(matrix_width, matrix_height, logo_options, untouchable_zones)?;
+
+        // Silueta del logo dilatada por el padding, la misma que estima el optimizador de ECL
+        let logo_zone = LogoExclusionZone::for_area(
+            logo_area.x,
+            logo_area.y,
+            logo_area.width,
+            logo_options.padding,
+            Some(&alpha_channel(&logo)),
+        );
+
+        // Redimensionar logo conservando la proporción, como se ajusta la máscara
+        let resized_logo = logo.resize(logo_size, logo_size, imageops::FilterType::Lanczos3);
+
+        // Limpiar solo los módulos que tapa el logo
+        let cleared_modules = self.clear_logo_area(qr_matrix, &logo_area, &logo_zone)?;
+
+        // Calcular pérdida de capacidad
+        let total_modules = matrix_width * matrix_height;
+        let capacity_loss = cleared_modules as f32 / total_modules as f32 * 100.0;
+
+        Ok(LogoIntegrationResult {
+            logo_image: resized_logo,
+            logo_vector,
+            logo_shape: logo_options.shape,
+            logo_area,
+            logo_padding: logo_options.padding,
+            logo_zone,
+            capacity_loss_percentage: capacity_loss,
+            requires_high_ecc: capacity_loss > 15.0,
+        })
+    }
+
+    /// Áreas que ocuparán los logos, en orden de colocación: como al integrarlos,
+    /// cada área es un obstáculo para las siguientes
+    pub fn plan_logo_areas(
+        matrix_width: usize,
+        matrix_height: usize,
+        logos: &[LogoOptions],
+        untouchable_zones: &[UntouchableZone],
+    ) -> QrResult<Vec<LogoArea>> {
+        let mut obstacles = untouchable_zones.to_vec();
+        let mut areas = Vec::with_capacity(logos.len());
+        for logo_options in logos {
+            let (_, area) = Self::logo_area(matrix_width, matrix_height, logo_options, &obstacles)?;
+            obstacles.push(area.as_zone());
+            areas.push(area);
+        }
+        Ok(areas)
+    }
+
+    /// Tamaño del logo en módulos y área que ocupa con su padding según su colocación
+    /// (centrada por defecto). En símbolos rectangulares el porcentaje se aplica al lado menor.
+    fn logo_area(
+        matrix_width: usize,
+        matrix_height: usize,
+        logo_options: &LogoOptions,
+        untouchable_zones: &[UntouchableZone],
+    ) -> QrResult<(u32, LogoArea)> {
         // Validar tamaño del logo
         let max_logo_percentage = 30.0;
         if logo_options.size_percentage > max_logo_percentage {
@@ -159,6 +283,7 @@ impl QrCustomizer {
         }
 
         // Calcular dimensiones del logo
+        let matrix_size = matrix_width.min(matrix_height);
         let logo_size = (matrix_size as f32 * logo_options.size_percentage / 100.0) as u32;
         let logo_with_padding = logo_size + 2 * logo_options.padding;
         
@@ -170,91 +295,66 @@ impl QrCustomizer {
             ));
         }
 
-        // Redimensionar logo
-        let resized_logo = imageops::resize(
-            &logo,
-            logo_size,
-            logo_size,
-            imageops::FilterType::Lanczos3
-        );
-
-        // Calcular posición central
-        let center_x = (matrix_size / 2) as i32;
-        let center_y = (matrix_size / 2) as i32;
-        let half_size = (logo_with_padding / 2) as i32;
+        // Calcular posición (centrada o según `placement`)
+        let side = logo_with_padding as usize;
+        let (x, y) = calculate_logo_position(
+            matrix_width,
+            matrix_height,
+            side,
+            side,
+            logo_options.placement,
+            untouchable_zones,
+        )
+        .ok_or_else(|| QrError::LogoError(format!(
+            "No hay posición para el logo ({:?}) sin tapar patrones de función u otros logos",
+            logo_options.placement
+        )))?;
 
         // Área que ocupará el logo (con padding)
         let logo_area = LogoArea {
-            x: (center_x - half_size) as usize,
-            y: (center_y - half_size) as usize,
-            width: logo_with_padding as usize,
-            height: logo_with_padding as usize,
+            x,
+            y,
+            width: side,
+            height: side,
         };
-
-        // Limpiar área del logo en la matriz
-        self.clear_logo_area(qr_matrix, &logo_area)?;
-
-        // Calcular pérdida de capacidad
-        let total_modules = matrix_size * matrix_size;
-        let cleared_modules = logo_area.width * logo_area.height;
-        let capacity_loss = cleared_modules as f32 / total_modules as f32 * 100.0;
-
-        Ok(LogoIntegrationResult {
-            logo_image: DynamicImage::ImageRgba8(resized_logo),
-            logo_area,
-            capacity_loss_percentage: capacity_loss,
-            requires_high_ecc: capacity_loss > 15.0,
-        })
+        Ok((logo_size, logo_area))
     }
 
-    /// Limpia el área donde se colocará el logo
+    /// Limpia los módulos del área que cubre la silueta del logo; devuelve cuántos
     fn clear_logo_area(
         &self,
-        matrix: &mut Vec<Vec<bool>>,
+        matrix: &mut [Vec<bool>],
         area: &LogoArea,
-    ) -> QrResult<()> {
-        let matrix_size = matrix.len();
+        zone: &LogoExclusionZone,
+    ) -> QrResult<usize> {
+        let matrix_height = matrix.len();
+        let matrix_width = matrix.first().map_or(0, Vec::len);
         
         // Validar límites
-        if area.x + area.width > matrix_size || area.y + area.height > matrix_size {
+        if area.x + area.width > matrix_width || area.y + area.height > matrix_height {
             return Err(QrError::LogoError("Área del logo excede límites de la matriz".to_string()));
         }
 
-        // Limpiar con patrón de borde suave
+        // Las zonas transparentes del logo conservan sus módulos
+        let mut cleared = 0;
         for y in area.y..area.y + area.height {
             for x in area.x..area.x + area.width {
-                // Calcular distancia al borde del área
-                let dist_to_edge = self.distance_to_edge(x, y, area);
-                
-                // Crear borde suave (fade)
-                if dist_to_edge < 2 {
-                    // Mantener algunos módulos en el borde para suavizar
-                    if (x + y) % 2 == 0 {
-                        matrix[y][x] = false;
-                    }
-                } else {
-                    // Limpiar completamente el centro
+                if zone.contains_module(x as u16, y as u16) {
                     matrix[y][x] = false;
+                    cleared += 1;
                 }
             }
         }
 
-        Ok(())
-    }
-
-    /// Calcula la distancia de un punto al borde del área
-    fn distance_to_edge(&self, x: usize, y: usize, area: &LogoArea) -> usize {
-        let dist_left = x - area.x;
-        let dist_right = area.x + area.width - x - 1;
-        let dist_top = y - area.y;
-        let dist_bottom = area.y + area.height - y - 1;
-        
-        dist_left.min(dist_right).min(dist_top).min(dist_bottom)
+        Ok(cleared)
     }
 
     /// Procesa un logo desde base64 o URL
     pub fn process_logo_data(&self, logo_data: &str) -> QrResult<DynamicImage> {
-        if logo_data.starts_with("data:image") {
+        if VectorLogo::is_svg_data_url(logo_data) {
+            // SVG saneado y rasterizado con resvg
+            VectorLogo::from_data_url(logo_data)?.rasterize(DEFAULT_SVG_LOGO_RASTER_PX)
+        } else if logo_data.starts_with("data:image") {
             // Procesar data URL
             let base64_start = logo_data.find(',').unwrap_or(0) + 1;
             let base64_data = &logo_data[base64_start..];
@@ -470,10 +570,20 @@ impl QrCustomizer {
     }
 }
 
+/// Código personalizado con los logos, el marco y los efectos aplicados
+pub type AdvancedCustomization = (QrCode, Vec<LogoIntegrationResult>, Option<FrameInfo>, Option<EffectsInfo>);
+
 /// Resultado de la integración del logo
 pub struct LogoIntegrationResult {
     pub logo_image: DynamicImage,
+    /// Versión vectorial del logo; si existe, el SVG la usa en lugar del PNG
+    pub logo_vector: Option<VectorLogo>,
+    pub logo_shape: LogoShape,
     pub logo_area: LogoArea,
+    /// Módulos de separación entre el logo y el borde de `logo_area`
+    pub logo_padding: u32,
+    /// Módulos limpiados bajo el logo (silueta más padding)
+    pub logo_zone: LogoExclusionZone,
     pub capacity_loss_percentage: f32,
     pub requires_high_ecc: bool,
 }
@@ -487,6 +597,19 @@ pub struct LogoArea {
     pub height: usize,
 }
 
+impl LogoArea {
+    /// Zona que el área reserva frente a otros logos
+    pub fn as_zone(&self) -> UntouchableZone {
+        UntouchableZone::new(
+            ZoneType::LogoArea,
+            self.x as u16,
+            self.y as u16,
+            self.width as u16,
+            self.height as u16,
+        )
+    }
+}
+
 /// Información del marco
 #[derive(Debug, Clone)]
 pub struct FrameInfo {
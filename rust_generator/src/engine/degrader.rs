@@ -0,0 +1,195 @@
+// engine/degrader.rs - Degradación de efectos según presupuesto de tiempo
+// Estima el coste de renderizado de los efectos y elimina los más costosos
+// (o los que perjudican la lectura) cuando la pipeline ultra lo requiere
+
+use super::types::{Effect, EffectOptions, QrCustomization};
+
+/// Coste estimado de renderizar un efecto, en ms por megapíxel de salida
+fn effect_cost_per_megapixel(effect: Effect) -> f64 {
+    match effect {
+        Effect::Outline => 15.0,
+        Effect::Shadow => 30.0,
+        Effect::DropShadow | Effect::InnerShadow => 35.0,
+        Effect::Blur => 40.0,
+        Effect::Glow => 45.0,
+        Effect::Vintage => 50.0,
+        Effect::Emboss => 55.0,
+        Effect::Noise => 60.0,
+        Effect::Distort => 80.0,
+    }
+}
+
+/// Efectos que alteran la geometría o el contraste de los módulos de datos
+fn harms_scanability(effect: Effect) -> bool {
+    matches!(effect, Effect::Distort | Effect::Noise | Effect::Blur)
+}
+
+/// Degradador de efectos
+pub struct EffectDegrader;
+
+impl EffectDegrader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Estima el coste total de los efectos para un lado de salida en píxeles
+    pub fn estimate_cost_ms(&self, customization: &QrCustomization, size: u32) -> f64 {
+        let megapixels = (size as f64 * size as f64) / 1_000_000.0;
+        Self::effect_lists(customization)
+            .iter()
+            .flat_map(|list| list.iter())
+            .map(|e| effect_cost_per_megapixel(e.effect_type) * megapixels)
+            .sum()
+    }
+
+    /// Elimina el efecto más costoso. Retorna el efecto eliminado, o `None` si no quedan.
+    pub fn degrade_most_expensive(&self, customization: &mut QrCustomization) -> Option<Effect> {
+        let mut lists = Self::effect_lists_mut(customization);
+
+        let (list_index, effect_index) = lists
+            .iter()
+            .enumerate()
+            .flat_map(|(li, list)| list.iter().enumerate().map(move |(ei, e)| (li, ei, e.effect_type)))
+            .max_by(|a, b| effect_cost_per_megapixel(a.2).total_cmp(&effect_cost_per_megapixel(b.2)))
+            .map(|(li, ei, _)| (li, ei))?;
+
+        let removed = lists[list_index].remove(effect_index);
+        Self::prune_empty(customization);
+        Some(removed.effect_type)
+    }
+
+    /// Elimina los efectos que perjudican la lectura; retorna los eliminados
+    pub fn strip_scan_hazards(&self, customization: &mut QrCustomization) -> Vec<Effect> {
+        let mut removed = Vec::new();
+        for list in Self::effect_lists_mut(customization) {
+            list.retain(|e| {
+                let hazard = harms_scanability(e.effect_type);
+                if hazard {
+                    removed.push(e.effect_type);
+                }
+                !hazard
+            });
+        }
+        Self::prune_empty(customization);
+        removed
+    }
+
+    /// Listas de efectos: legacy y selectivos por componente
+    fn effect_lists(customization: &QrCustomization) -> Vec<&Vec<EffectOptions>> {
+        let mut lists = Vec::new();
+        if let Some(effects) = &customization.effects {
+            lists.push(effects);
+        }
+        if let Some(selective) = &customization.selective_effects {
+            for component in [&selective.eyes, &selective.data, &selective.frame, &selective.global]
+                .into_iter()
+                .flatten()
+            {
+                lists.push(&component.effects);
+            }
+        }
+        lists
+    }
+
+    fn effect_lists_mut(customization: &mut QrCustomization) -> Vec<&mut Vec<EffectOptions>> {
+        let mut lists = Vec::new();
+        if let Some(effects) = customization.effects.as_mut() {
+            lists.push(effects);
+        }
+        if let Some(selective) = customization.selective_effects.as_mut() {
+            for component in [&mut selective.eyes, &mut selective.data, &mut selective.frame, &mut selective.global]
+                .into_iter()
+                .flatten()
+            {
+                lists.push(&mut component.effects);
+            }
+        }
+        lists
+    }
+
+    /// Quita listas vacías para que el renderizado no genere filtros sin efectos
+    fn prune_empty(customization: &mut QrCustomization) {
+        if customization.effects.as_ref().is_some_and(|e| e.is_empty()) {
+            customization.effects = None;
+        }
+        if let Some(selective) = customization.selective_effects.as_mut() {
+            for component in [&mut selective.eyes, &mut selective.data, &mut selective.frame, &mut selective.global] {
+                if component.as_ref().is_some_and(|c| c.effects.is_empty()) {
+                    *component = None;
+                }
+            }
+            if selective.eyes.is_none() && selective.data.is_none() && selective.frame.is_none() && selective.global.is_none() {
+                customization.selective_effects = None;
+            }
+        }
+    }
+}
+
+impl Default for EffectDegrader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::{ComponentEffects, EffectConfiguration, SelectiveEffects};
+
+    fn effect(effect_type: Effect) -> EffectOptions {
+        EffectOptions {
+            effect_type,
+            config: EffectConfiguration::Noise { intensity: None },
+        }
+    }
+
+    fn customization() -> QrCustomization {
+        QrCustomization {
+            effects: Some(vec![effect(Effect::Shadow), effect(Effect::Distort)]),
+            selective_effects: Some(SelectiveEffects {
+                eyes: None,
+                data: Some(ComponentEffects {
+                    effects: vec![effect(Effect::Glow)],
+                    blend_mode: None,
+                    render_priority: None,
+                    apply_to_fill: None,
+                    apply_to_stroke: None,
+                }),
+                frame: None,
+                global: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cost_scales_with_area() {
+        let degrader = EffectDegrader::new();
+        let custom = customization();
+        let small = degrader.estimate_cost_ms(&custom, 1000);
+        assert_eq!(small, 30.0 + 80.0 + 45.0);
+        assert_eq!(degrader.estimate_cost_ms(&custom, 2000), small * 4.0);
+    }
+
+    #[test]
+    fn test_degrades_most_expensive_first() {
+        let degrader = EffectDegrader::new();
+        let mut custom = customization();
+
+        assert_eq!(degrader.degrade_most_expensive(&mut custom), Some(Effect::Distort));
+        assert_eq!(degrader.degrade_most_expensive(&mut custom), Some(Effect::Glow));
+        assert!(custom.selective_effects.is_none());
+        assert_eq!(degrader.degrade_most_expensive(&mut custom), Some(Effect::Shadow));
+        assert!(custom.effects.is_none());
+        assert_eq!(degrader.degrade_most_expensive(&mut custom), None);
+    }
+
+    #[test]
+    fn test_strip_scan_hazards() {
+        let degrader = EffectDegrader::new();
+        let mut custom = customization();
+
+        assert_eq!(degrader.strip_scan_hazards(&mut custom), vec![Effect::Distort]);
+        assert_eq!(custom.effects.as_ref().unwrap().len(), 1);
+    }
+}
@@ -1,25 +1,14 @@
 // engine/ecl_optimizer.rs - Optimizador dinámico de nivel de corrección de errores
 
-use qrcodegen::{QrCode as QrCodeGen, QrCodeEcc};
+use image::{DynamicImage, GrayImage};
+use qrcodegen::{QrCodeEcc, QrSegment};
+use super::bitstream;
+use super::codeword_map::CodewordMap;
 use super::error::{QrError, QrResult};
-use super::geometry::{LogoExclusionZone, count_excludable_modules};
-use super::zones::{calculate_untouchable_zones, is_module_untouchable};
-use super::types::ErrorCorrectionLevel;
-
-/// Información sobre la capacidad de corrección de errores
-#[derive(Debug, Clone)]
-pub struct EclCapacity {
-    /// Nivel de corrección
-    pub level: ErrorCorrectionLevel,
-    /// Porcentaje de codewords que puede recuperar
-    pub recovery_percentage: f32,
-    /// Número total de codewords de datos
-    pub data_codewords: usize,
-    /// Número total de codewords de corrección
-    pub ecc_codewords: usize,
-    /// Número total de codewords
-    pub total_codewords: usize,
-}
+use super::geometry::{LogoExclusionZone, alpha_channel, calculate_logo_position, count_excludable_modules};
+use super::zones::{calculate_untouchable_zones, UntouchableZone, ZoneType};
+use super::segmenter::ContentSegmenter;
+use super::types::{BlockDamage, ErrorCorrectionLevel, LogoPlacement, PreparedLogo, QrCustomization, QrSize};
 
 /// Resultado del análisis de oclusión
 #[derive(Debug, Clone)]
@@ -28,198 +17,366 @@ pub struct OcclusionAnalysis {
     pub occluded_modules: usize,
     /// Número de codewords afectados
     pub affected_codewords: usize,
-    /// Porcentaje de oclusión
+    /// Porcentaje de oclusión (codewords afectados sobre el total del símbolo)
     pub occlusion_percentage: f32,
     /// ECL mínimo recomendado
     pub recommended_ecl: ErrorCorrectionLevel,
     /// Versión del QR analizado
     pub qr_version: u8,
+    /// Codewords dañados en cada bloque Reed-Solomon frente a su capacidad
+    pub block_damage: Vec<BlockDamage>,
+    /// Si todos los bloques se recuperan dejando el margen de seguridad
+    pub recoverable: bool,
+}
+
+/// Niveles de corrección de menor a mayor redundancia
+const ECL_LEVELS: [ErrorCorrectionLevel; 4] = [
+    ErrorCorrectionLevel::Low,
+    ErrorCorrectionLevel::Medium,
+    ErrorCorrectionLevel::Quartile,
+    ErrorCorrectionLevel::High,
+];
+
+/// Logo adicional (sello, insignia) cuya oclusión se suma a la del logo principal
+#[derive(Debug, Clone)]
+pub struct OverlayFootprint {
+    /// Lado del logo respecto al lado del símbolo (0.0 - 1.0)
+    pub size_ratio: f32,
+    /// Padding en módulos alrededor del logo
+    pub padding: u32,
+    /// Colocación; sin ella se centra
+    pub placement: Option<LogoPlacement>,
+    /// Canal alfa; sin él se ocluye el área completa
+    pub alpha: Option<GrayImage>,
+}
+
+impl OverlayFootprint {
+    /// Overlay ocluyendo su área cuadrada completa
+    pub fn new(size_ratio: f32, padding: u32, placement: Option<LogoPlacement>) -> Self {
+        Self {
+            size_ratio,
+            padding,
+            placement,
+            alpha: None,
+        }
+    }
+    
+    /// Usa la silueta del logo en lugar de su área
+    pub fn with_alpha(mut self, logo: &DynamicImage) -> Self {
+        self.alpha = Some(alpha_channel(logo));
+        self
+    }
 }
 
 /// Optimizador de ECL basado en zona de exclusión
+#[derive(Debug, Clone)]
 pub struct EclOptimizer {
-    /// Margen de seguridad (porcentaje adicional)
+    /// Margen de seguridad: fracción de la capacidad de corrección de cada bloque
+    /// que el logo no puede consumir
     safety_margin: f32,
+    /// Canal alfa del logo; sin él la oclusión se estima con un cuadrado
+    logo_alpha: Option<GrayImage>,
+    /// Padding del logo en módulos (dilatación de la silueta)
+    logo_padding: u32,
+    /// Lado del logo principal como fracción del símbolo; 0 sin logo principal
+    logo_size_ratio: f32,
+    /// Logos adicionales colocados junto al principal
+    overlays: Vec<OverlayFootprint>,
+    /// Versiones admitidas al codificar de prueba, como en la generación real
+    min_version: u8,
+    max_version: u8,
+    /// Si la codificación sube el ECL cuando cabe en la misma versión
+    boost_ecl: bool,
 }
 
 impl EclOptimizer {
     /// Crea un nuevo optimizador con margen de seguridad por defecto (5%)
     pub fn new() -> Self {
-        Self {
-            safety_margin: 0.05,
-        }
+        Self::with_safety_margin(0.05)
     }
     
     /// Crea un optimizador con margen de seguridad personalizado
     pub fn with_safety_margin(safety_margin: f32) -> Self {
-        Self { safety_margin }
+        Self {
+            safety_margin,
+            logo_alpha: None,
+            logo_padding: 0,
+            logo_size_ratio: 0.0,
+            overlays: Vec::new(),
+            min_version: 1,
+            max_version: 40,
+            boost_ecl: true,
+        }
+    }
+    
+    /// Optimizador para los logos de una personalización (principal y overlays), con
+    /// sus imágenes ya cargadas en el orden de `QrCustomization::logos` y el rango de
+    /// versiones que imponen `version` o `fixed_size`
+    pub fn for_customization(customization: &QrCustomization, logos: &[PreparedLogo]) -> Self {
+        let mut optimizer = Self::new();
+        let mut images = logos.iter().map(|logo| &logo.image);
+        if let Some(logo) = &customization.logo {
+            if let Some(image) = images.next() {
+                optimizer = optimizer.with_logo_alpha(image, logo.padding);
+                optimizer.logo_size_ratio = logo.size_percentage / 100.0;
+            }
+        }
+        for (overlay, image) in customization.overlays.iter().flatten().zip(images) {
+            optimizer = optimizer.with_overlay(
+                OverlayFootprint::new(overlay.size_percentage / 100.0, overlay.padding, overlay.placement)
+                    .with_alpha(image),
+            );
+        }
+        
+        let (min_version, max_version) = match customization.version {
+            Some(version) => (version, version),
+            None => {
+                let (min, max) = customization.fixed_size.unwrap_or(QrSize::Auto).version_range();
+                (min as u8, max as u8)
+            }
+        };
+        optimizer.with_version_range(min_version, max_version)
+    }
+    
+    /// Analiza la oclusión con la silueta real del logo en lugar de su caja
+    pub fn with_logo_alpha(mut self, logo: &DynamicImage, padding: u32) -> Self {
+        self.logo_alpha = Some(alpha_channel(logo));
+        self.logo_padding = padding;
+        self
+    }
+    
+    /// Suma la oclusión de un logo adicional
+    pub fn with_overlay(mut self, overlay: OverlayFootprint) -> Self {
+        self.overlays.push(overlay);
+        self
+    }
+    
+    /// Limita las versiones de la codificación de prueba
+    pub fn with_version_range(mut self, min_version: u8, max_version: u8) -> Self {
+        self.min_version = min_version;
+        self.max_version = max_version;
+        self
+    }
+    
+    /// Indica si la codificación aplica el boost de ECL (por defecto sí)
+    pub fn with_boost(mut self, boost_ecl: bool) -> Self {
+        self.boost_ecl = boost_ecl;
+        self
+    }
+    
+    /// Tamaño del logo principal con el que se construyó el optimizador: 0 cuando
+    /// solo hay overlays, que ya cuentan por su cuenta en `logo_zone`
+    pub fn logo_size_ratio(&self) -> f32 {
+        self.logo_size_ratio
+    }
+    
+    /// Versiones admitidas al codificar
+    pub fn version_range(&self) -> (u8, u8) {
+        (self.min_version, self.max_version)
+    }
+    
+    /// Zona de exclusión para un símbolo de `qr_size` módulos: el logo principal
+    /// centrado más los overlays en sus posiciones. Falla si un overlay no cabe sin
+    /// tapar patrones de función u otro logo.
+    pub fn logo_zone(&self, qr_size: u16, logo_size_ratio: f32) -> QrResult<LogoExclusionZone> {
+        let center = qr_size as f64 / 2.0;
+        let logo_size = (qr_size as f64 * logo_size_ratio as f64) / 2.0;
+        if logo_size <= 0.0 && !self.overlays.is_empty() {
+            // Solo overlays: sin logo principal no hay zona central
+            return self.overlay_zones(qr_size, Vec::new(), calculate_untouchable_zones(Self::version_of(qr_size)));
+        }
+        let primary = match &self.logo_alpha {
+            Some(alpha) => LogoExclusionZone::from_alpha(alpha, center, center, logo_size, self.logo_padding),
+            None => LogoExclusionZone::new(
+                super::geometry::LogoShape::Square,
+                center,
+                center,
+                logo_size,
+            ),
+        };
+        if self.overlays.is_empty() {
+            return Ok(primary);
+        }
+        
+        // Los overlays no pueden pisar patrones de función ni la caja del logo principal
+        let mut obstacles = calculate_untouchable_zones(Self::version_of(qr_size));
+        if logo_size > 0.0 {
+            let start = (center - logo_size).floor().max(0.0) as u16;
+            let side = ((center + logo_size).ceil() as u16).min(qr_size) - start;
+            obstacles.push(UntouchableZone::new(ZoneType::LogoArea, start, start, side, side));
+        }
+        self.overlay_zones(qr_size, vec![primary], obstacles)
+    }
+    
+    fn version_of(qr_size: u16) -> u8 {
+        (qr_size.saturating_sub(17) / 4) as u8
     }
     
-    /// Determina el ECL óptimo para un logo de tamaño dado
+    /// Coloca los overlays evitando `obstacles` y une sus zonas a `zones`
+    fn overlay_zones(
+        &self,
+        qr_size: u16,
+        mut zones: Vec<LogoExclusionZone>,
+        mut obstacles: Vec<UntouchableZone>,
+    ) -> QrResult<LogoExclusionZone> {
+        let version = Self::version_of(qr_size);
+        for overlay in &self.overlays {
+            let side = (qr_size as f32 * overlay.size_ratio).round() as usize + 2 * overlay.padding as usize;
+            let (x, y) = calculate_logo_position(
+                qr_size as usize,
+                qr_size as usize,
+                side,
+                side,
+                overlay.placement,
+                &obstacles,
+            )
+            .ok_or_else(|| QrError::LogoError(format!(
+                "El overlay ({:?}) no cabe en la versión {} sin tapar patrones de función",
+                overlay.placement, version
+            )))?;
+            obstacles.push(UntouchableZone::new(ZoneType::LogoArea, x as u16, y as u16, side as u16, side as u16));
+            zones.push(LogoExclusionZone::for_area(x, y, side, overlay.padding, overlay.alpha.as_ref()));
+        }
+        
+        Ok(LogoExclusionZone::combined(&zones, qr_size as usize, qr_size as usize))
+    }
+    
+    /// Determina el ECL óptimo para un logo de tamaño dado: el nivel más bajo cuyo
+    /// símbolo recupera todos los bloques Reed-Solomon que tapa el logo. Los niveles se
+    /// prueban sobre el símbolo que se codificaría (segmentación y rango de versiones)
+    /// sin boost, para que un dato corto no oculte la protección que necesita el logo;
+    /// el análisis devuelto es el del símbolo final, con boost si está activo. Si ningún
+    /// nivel lo consigue se devuelve el más alto en el que caben los datos con
+    /// `recoverable == false`.
     pub fn determine_optimal_ecl(
         &self,
         data: &str,
         logo_size_ratio: f32,
         ecl_override: Option<ErrorCorrectionLevel>,
     ) -> QrResult<(ErrorCorrectionLevel, OcclusionAnalysis)> {
-        // Si hay override, usarlo directamente
-        if let Some(ecl) = ecl_override {
-            let analysis = self.analyze_with_ecl(data, logo_size_ratio, ecl)?;
-            return Ok((ecl, analysis));
+        let segments = ContentSegmenter::new()
+            .analyze_and_segment(data)
+            .map_err(QrError::EncodingError)?;
+        
+        let mut minimal = None;
+        for ecl in ECL_LEVELS {
+            // Un nivel en el que ya no caben los datos cierra la búsqueda
+            let Some(analysis) = self.analyze_encoding(&segments, logo_size_ratio, ecl, false)? else {
+                break;
+            };
+            let recoverable = analysis.recoverable;
+            minimal = Some((ecl, analysis));
+            if recoverable {
+                break;
+            }
         }
+        let (recommended_ecl, minimal) = minimal.ok_or_else(|| self.does_not_fit())?;
         
-        // Empezar con ECL Medium como punto de partida
-        let mut current_ecl = ErrorCorrectionLevel::Medium;
-        let mut iterations = 0;
-        const MAX_ITERATIONS: u8 = 3;
+        // Con override se analiza el nivel pedido, pero se informa del mínimo recuperable
+        let ecl = ecl_override.unwrap_or(recommended_ecl);
+        let mut analysis = if ecl == recommended_ecl && !self.boost_ecl {
+            minimal
+        } else {
+            self.analyze_encoding(&segments, logo_size_ratio, ecl, self.boost_ecl)?
+                .ok_or_else(|| self.does_not_fit())?
+        };
+        analysis.recommended_ecl = recommended_ecl;
         
-        loop {
-            iterations += 1;
-            if iterations > MAX_ITERATIONS {
-                return Err(QrError::EncodingError(
-                    "No se pudo estabilizar el ECL después de 3 iteraciones".into()
-                ));
-            }
-            
-            // Analizar con el ECL actual
-            let analysis = self.analyze_with_ecl(data, logo_size_ratio, current_ecl)?;
-            
-            // Si el ECL recomendado es el mismo que el actual, hemos convergido
-            if analysis.recommended_ecl == current_ecl {
-                return Ok((current_ecl, analysis));
-            }
-            
-            // Actualizar ECL y continuar
-            current_ecl = analysis.recommended_ecl;
+        if !analysis.recoverable {
+            tracing::warn!(
+                "El logo deja bloques sin recuperar con ECL {:?} (versión {}): {:?}",
+                ecl, analysis.qr_version, analysis.block_damage
+            );
         }
+        Ok((ecl, analysis))
     }
     
-    /// Analiza la oclusión con un ECL específico
-    fn analyze_with_ecl(
+    /// Analiza la oclusión sobre el símbolo que se codificaría con los segmentos y el
+    /// ECL dados; `None` si los datos no caben en el rango de versiones
+    fn analyze_encoding(
         &self,
-        data: &str,
+        segments: &[QrSegment],
         logo_size_ratio: f32,
         ecl: ErrorCorrectionLevel,
-    ) -> QrResult<OcclusionAnalysis> {
-        // Generar QR con el ECL dado
+        boost_ecl: bool,
+    ) -> QrResult<Option<OcclusionAnalysis>> {
         let qr_ecl = map_error_correction(ecl);
-        let qr = QrCodeGen::encode_text(data, qr_ecl)
-            .map_err(|e| QrError::EncodingError(format!("{:?}", e)))?;
+        let Some(version) = bitstream::smallest_version(0, segments, qr_ecl, self.min_version, self.max_version) else {
+            return Ok(None);
+        };
+        let symbol_ecl = if boost_ecl {
+            map_from_qrcodegen(bitstream::boosted_ecl(0, segments, version, qr_ecl))
+        } else {
+            ecl
+        };
         
-        let version = qr.version().value();
-        let size = qr.size() as u16;
+        self.analyze_symbol(version, symbol_ecl, logo_size_ratio).map(Some)
+    }
+    
+    fn does_not_fit(&self) -> QrError {
+        QrError::ValidationError(format!(
+            "Los datos no caben en las versiones {}-{}",
+            self.min_version, self.max_version
+        ))
+    }
+    
+    /// Daño del logo en cada bloque Reed-Solomon de un símbolo de la versión y ECL dados.
+    /// La máscara no mueve los codewords, solo cambia su valor; como bajo el logo un
+    /// módulo puede leerse de cualquier color, todo codeword con algún módulo tapado
+    /// cuenta como erróneo.
+    pub fn analyze_symbol(
+        &self,
+        version: u8,
+        ecl: ErrorCorrectionLevel,
+        logo_size_ratio: f32,
+    ) -> QrResult<OcclusionAnalysis> {
+        let map = CodewordMap::new(version, map_error_correction(ecl))
+            .ok_or_else(|| QrError::EncodingError(format!("Versión QR inválida: {}", version)))?;
+        let size = map.size();
         
-        // Calcular zonas intocables
+        // Zona de exclusión del logo y módulos que tapa fuera de los patrones de función
         let untouchable_zones = calculate_untouchable_zones(version);
+        let logo_zone = self.logo_zone(size as u16, logo_size_ratio)?;
+        let occluded_modules = count_excludable_modules(version, &logo_zone, &untouchable_zones);
         
-        // Crear zona de exclusión del logo
-        let center = size as f64 / 2.0;
-        let logo_size = (size as f64 * logo_size_ratio as f64) / 2.0;
-        let logo_zone = LogoExclusionZone::new(
-            super::geometry::LogoShape::Square,
-            center,
-            center,
-            logo_size,
+        let damage = map.block_damage(
+            (0..size)
+                .flat_map(|y| (0..size).map(move |x| (x, y)))
+                .filter(|&(x, y)| logo_zone.contains_module(x as u16, y as u16)),
         );
+        let block_damage: Vec<BlockDamage> = damage
+            .iter()
+            .enumerate()
+            .map(|(block, &damaged_codewords)| BlockDamage {
+                block,
+                damaged_codewords,
+                correction_capacity: map.correction_capacity(block),
+            })
+            .collect();
         
-        // Contar módulos excluibles
-        let excludable_modules = count_excludable_modules(version, &logo_zone, &untouchable_zones);
-        
-        // Calcular codewords afectados
-        let modules_per_codeword = 8; // Cada codeword son 8 bits/módulos
-        let affected_codewords = (excludable_modules + modules_per_codeword - 1) / modules_per_codeword;
+        // Margen de seguridad: fracción de la capacidad de cada bloque que no se gasta,
+        // redondeada al codeword más cercano (con `ceil` un bloque de capacidad 4 reservaría
+        // el 25% en lugar del 5%)
+        let recoverable = block_damage.iter().all(|block| {
+            let reserved = (block.correction_capacity as f32 * self.safety_margin).round() as usize;
+            block.damaged_codewords + reserved <= block.correction_capacity
+        });
         
-        // Obtener capacidad del ECL
-        let capacity = get_ecl_capacity(version, ecl)?;
-        
-        // Calcular porcentaje de oclusión
-        let occlusion_percentage = (affected_codewords as f32 / capacity.total_codewords as f32) * 100.0;
-        
-        // Determinar ECL recomendado con margen de seguridad
-        let required_percentage = occlusion_percentage + (self.safety_margin * 100.0);
-        let recommended_ecl = select_ecl_for_percentage(required_percentage);
+        let affected_codewords: usize = damage.iter().sum();
+        let occlusion_percentage = affected_codewords as f32 / map.codewords.len() as f32 * 100.0;
         
         Ok(OcclusionAnalysis {
-            occluded_modules: excludable_modules,
+            occluded_modules,
             affected_codewords,
             occlusion_percentage,
-            recommended_ecl,
+            recommended_ecl: ecl,
             qr_version: version,
+            block_damage,
+            recoverable,
         })
     }
 }
 
-/// Obtiene la capacidad de corrección para una versión y ECL específicos
-fn get_ecl_capacity(version: u8, ecl: ErrorCorrectionLevel) -> QrResult<EclCapacity> {
-    // Tabla simplificada de capacidades
-    // En producción, esto debería venir de la especificación completa ISO/IEC 18004
-    let (data_codewords, total_codewords) = match (version, ecl) {
-        // Version 1
-        (1, ErrorCorrectionLevel::Low) => (19, 26),
-        (1, ErrorCorrectionLevel::Medium) => (16, 26),
-        (1, ErrorCorrectionLevel::Quartile) => (13, 26),
-        (1, ErrorCorrectionLevel::High) => (9, 26),
-        
-        // Version 2
-        (2, ErrorCorrectionLevel::Low) => (34, 44),
-        (2, ErrorCorrectionLevel::Medium) => (28, 44),
-        (2, ErrorCorrectionLevel::Quartile) => (22, 44),
-        (2, ErrorCorrectionLevel::High) => (16, 44),
-        
-        // Version 5
-        (5, ErrorCorrectionLevel::Low) => (106, 134),
-        (5, ErrorCorrectionLevel::Medium) => (84, 134),
-        (5, ErrorCorrectionLevel::Quartile) => (60, 134),
-        (5, ErrorCorrectionLevel::High) => (46, 134),
-        
-        // Version 10
-        (10, ErrorCorrectionLevel::Low) => (293, 346),
-        (10, ErrorCorrectionLevel::Medium) => (231, 346),
-        (10, ErrorCorrectionLevel::Quartile) => (163, 346),
-        (10, ErrorCorrectionLevel::High) => (125, 346),
-        
-        // Para otras versiones, usar aproximación
-        _ => {
-            let base_capacity = 4 + version as usize * 16;
-            match ecl {
-                ErrorCorrectionLevel::Low => ((base_capacity as f32 * 0.85) as usize, base_capacity),
-                ErrorCorrectionLevel::Medium => ((base_capacity as f32 * 0.70) as usize, base_capacity),
-                ErrorCorrectionLevel::Quartile => ((base_capacity as f32 * 0.50) as usize, base_capacity),
-                ErrorCorrectionLevel::High => ((base_capacity as f32 * 0.35) as usize, base_capacity),
-            }
-        }
-    };
-    
-    let ecc_codewords = total_codewords - data_codewords;
-    let recovery_percentage = match ecl {
-        ErrorCorrectionLevel::Low => 7.0,
-        ErrorCorrectionLevel::Medium => 15.0,
-        ErrorCorrectionLevel::Quartile => 25.0,
-        ErrorCorrectionLevel::High => 30.0,
-    };
-    
-    Ok(EclCapacity {
-        level: ecl,
-        recovery_percentage,
-        data_codewords,
-        ecc_codewords,
-        total_codewords,
-    })
-}
-
-/// Selecciona el ECL mínimo para un porcentaje de oclusión dado
-fn select_ecl_for_percentage(percentage: f32) -> ErrorCorrectionLevel {
-    if percentage <= 7.0 {
-        ErrorCorrectionLevel::Low
-    } else if percentage <= 15.0 {
-        ErrorCorrectionLevel::Medium
-    } else if percentage <= 25.0 {
-        ErrorCorrectionLevel::Quartile
-    } else {
-        ErrorCorrectionLevel::High
-    }
-}
-
 /// Mapea nuestro enum a qrcodegen
 fn map_error_correction(ecl: ErrorCorrectionLevel) -> QrCodeEcc {
     match ecl {
@@ -230,25 +387,96 @@ fn map_error_correction(ecl: ErrorCorrectionLevel) -> QrCodeEcc {
     }
 }
 
+fn map_from_qrcodegen(ecl: QrCodeEcc) -> ErrorCorrectionLevel {
+    match ecl {
+        QrCodeEcc::Low => ErrorCorrectionLevel::Low,
+        QrCodeEcc::Medium => ErrorCorrectionLevel::Medium,
+        QrCodeEcc::Quartile => ErrorCorrectionLevel::Quartile,
+        QrCodeEcc::High => ErrorCorrectionLevel::High,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use qrcodegen::QrCode as QrCodeGen;
+    
+    /// Símbolo con los módulos de `inverted` invertidos, escalado para el lector
+    fn symbol_image(qr: &QrCodeGen, inverted: impl Fn(usize, usize) -> bool) -> DynamicImage {
+        let (scale, quiet) = (8, 4);
+        let side = (qr.size() as u32 + 2 * quiet) * scale;
+        DynamicImage::ImageLuma8(GrayImage::from_fn(side, side, |px, py| {
+            let (x, y) = ((px / scale) as i32 - quiet as i32, (py / scale) as i32 - quiet as i32);
+            let dark = qr.get_module(x, y)
+                ^ (x >= 0 && y >= 0 && x < qr.size() && y < qr.size() && inverted(x as usize, y as usize));
+            image::Luma([if dark { 0 } else { 255 }])
+        }))
+    }
     
     #[test]
-    fn test_ecl_selection() {
-        assert_eq!(select_ecl_for_percentage(5.0), ErrorCorrectionLevel::Low);
-        assert_eq!(select_ecl_for_percentage(10.0), ErrorCorrectionLevel::Medium);
-        assert_eq!(select_ecl_for_percentage(20.0), ErrorCorrectionLevel::Quartile);
-        assert_eq!(select_ecl_for_percentage(28.0), ErrorCorrectionLevel::High);
+    fn test_block_damage_structure() {
+        // 5-Q: cuatro bloques con 18 codewords de corrección, 9 corregibles cada uno
+        let analysis = EclOptimizer::new().analyze_symbol(5, ErrorCorrectionLevel::Quartile, 0.3).unwrap();
+        assert_eq!(analysis.block_damage.len(), 4);
+        assert!(analysis.block_damage.iter().all(|block| block.correction_capacity == 9));
+        assert_eq!(
+            analysis.block_damage.iter().map(|block| block.damaged_codewords).sum::<usize>(),
+            analysis.affected_codewords
+        );
+        // El entrelazado reparte el daño: ningún bloque se lleva todo
+        assert!(analysis.block_damage.iter().all(|block| block.damaged_codewords < analysis.affected_codewords));
+        assert!((analysis.occlusion_percentage - analysis.affected_codewords as f32 / 134.0 * 100.0).abs() < 1e-4);
     }
     
     #[test]
-    fn test_ecl_capacity() {
-        let capacity = get_ecl_capacity(1, ErrorCorrectionLevel::Medium).unwrap();
-        assert_eq!(capacity.data_codewords, 16);
-        assert_eq!(capacity.total_codewords, 26);
-        assert_eq!(capacity.ecc_codewords, 10);
-        assert_eq!(capacity.recovery_percentage, 15.0);
+    fn test_block_damage_predicts_decoding() {
+        use crate::engine::codeword_map::CodewordMap;
+        use crate::standards::decoder::QrDecoder;
+        
+        // 47 bytes: versión 5-Q
+        let data = "https://qreable.com/analisis/bloques/0123456789";
+        let qr = QrCodeGen::encode_text(data, QrCodeEcc::Quartile).unwrap();
+        let version = qr.version().value();
+        let map = CodewordMap::new(version, QrCodeEcc::Quartile).unwrap();
+        let optimizer = EclOptimizer::with_safety_margin(0.0);
+        let decoder = QrDecoder::new();
+        
+        let mut outcomes = Vec::new();
+        for ratio in [0.15, 0.3, 0.45, 0.6] {
+            let analysis = optimizer.analyze_symbol(version, ErrorCorrectionLevel::Quartile, ratio).unwrap();
+            let zone = optimizer.logo_zone(qr.size() as u16, ratio).unwrap();
+            
+            // Peor caso: el logo invierte todos los módulos de datos que tapa
+            let image = symbol_image(&qr, |x, y| {
+                zone.contains_module(x as u16, y as u16) && map.codeword_at(x, y).is_some()
+            });
+            let decoded = decoder.decode_image(&image).map(|d| d.raw_data == data).unwrap_or(false);
+            assert_eq!(analysis.recoverable, decoded, "ratio {}: {:?}", ratio, analysis.block_damage);
+            outcomes.push(decoded);
+        }
+        assert!(outcomes.contains(&true) && outcomes.contains(&false));
+    }
+    
+    #[test]
+    fn test_ecl_selection_by_blocks() {
+        let data = "https://qreable.com/analisis/bloques/0123456789";
+        // Sin boost cada análisis es el del nivel pedido, no el del símbolo reforzado
+        let optimizer = EclOptimizer::new().with_boost(false);
+        let (ecl, analysis) = optimizer.determine_optimal_ecl(data, 0.3, None).unwrap();
+        
+        // El nivel elegido recupera todos los bloques y el inmediatamente inferior no
+        assert!(analysis.recoverable);
+        assert_eq!(analysis.recommended_ecl, ecl);
+        if let Some(&below) = ECL_LEVELS.iter().rev().find(|&&level| level < ecl) {
+            let (_, lower) = optimizer.determine_optimal_ecl(data, 0.3, Some(below)).unwrap();
+            assert!(!lower.recoverable);
+            assert_eq!(lower.recommended_ecl, ecl);
+        }
+        
+        // Un logo que tapa más de lo que High puede corregir se informa como irrecuperable
+        let (ecl, analysis) = optimizer.determine_optimal_ecl(data, 0.8, None).unwrap();
+        assert_eq!(ecl, ErrorCorrectionLevel::High);
+        assert!(!analysis.recoverable);
     }
     
     #[test]
@@ -261,6 +489,84 @@ mod tests {
         
         // Con un logo tan pequeño, debería recomendar ECL Low
         assert_eq!(ecl, ErrorCorrectionLevel::Low);
-        assert!(analysis.occlusion_percentage < 7.0);
+        // 19 bytes: versión 2 (44 codewords). El logo tapa 9 módulos que caen en 4
+        // codewords distintos, un 9% de los codewords aunque sea el 1,4% de los módulos
+        assert!(analysis.occlusion_percentage < 10.0);
+    }
+    
+    #[test]
+    fn test_trial_encoding_matches_generation() {
+        use crate::engine::QrGenerator;
+        
+        // Contenido mixto: la segmentación cambia la versión frente a un único segmento
+        let data = "PEDIDO 0123456789012345678901234567890123456789 https://qreable.com/p";
+        let generator = QrGenerator::new();
+        for boost in [true, false] {
+            let optimizer = EclOptimizer::new().with_boost(boost);
+            let (ecl, analysis) = optimizer.determine_optimal_ecl(data, 0.2, None).unwrap();
+            let (qr, generated) = generator
+                .generate_with_dynamic_ecl_and_boost(data, 300, 0.2, None, boost)
+                .unwrap();
+            assert_eq!(analysis.qr_version, generated.qr_version);
+            assert_eq!(qr.size, 17 + 4 * analysis.qr_version as usize);
+            assert_eq!(analysis.block_damage.len(), generated.block_damage.len());
+            assert!(generated.recommended_ecl >= ecl);
+        }
+        
+        // El rango de versiones limita la codificación de prueba
+        let optimizer = EclOptimizer::new().with_version_range(10, 10);
+        let (_, analysis) = optimizer.determine_optimal_ecl(data, 0.2, None).unwrap();
+        assert_eq!(analysis.qr_version, 10);
+        let optimizer = EclOptimizer::new().with_version_range(1, 1);
+        assert!(matches!(
+            optimizer.determine_optimal_ecl(data, 0.2, None),
+            Err(QrError::ValidationError(_))
+        ));
+    }
+    
+    #[test]
+    fn test_optimizer_with_logo_alpha() {
+        let data = "https://example.com/marca/wordmark";
+        // Wordmark 5:1: la caja cuadrada tapa cinco veces más módulos que la silueta
+        let wordmark = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(500, 100, |_, _| {
+            image::Rgba([20, 20, 20, 255])
+        }));
+        
+        let (_, square) = EclOptimizer::new().determine_optimal_ecl(data, 0.3, None).unwrap();
+        let optimizer = EclOptimizer::new().with_logo_alpha(&wordmark, 0);
+        let (ecl, masked) = optimizer.determine_optimal_ecl(data, 0.3, None).unwrap();
+        
+        assert!(masked.occluded_modules * 2 < square.occluded_modules);
+        assert!(ecl < square.recommended_ecl);
+        
+        let zone = optimizer.logo_zone(29, 0.3).unwrap();
+        assert!(zone.mask.is_some());
+        assert_eq!(zone.area() as usize, zone.mask.as_ref().unwrap().count());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_optimizer_with_overlays() {
+        use crate::engine::types::LogoAnchor;
+        
+        let data = "https://example.com/marca/sello";
+        let badge = |anchor| OverlayFootprint::new(0.15, 0, Some(LogoPlacement::Anchor(anchor)));
+        
+        let (_, single) = EclOptimizer::new().determine_optimal_ecl(data, 0.2, Some(ErrorCorrectionLevel::High)).unwrap();
+        let optimizer = EclOptimizer::new()
+            .with_overlay(badge(LogoAnchor::BottomRight))
+            .with_overlay(badge(LogoAnchor::TopRight));
+        let (_, combined) = optimizer.determine_optimal_ecl(data, 0.2, Some(ErrorCorrectionLevel::High)).unwrap();
+        
+        // Cada insignia suma su área a la oclusión del logo principal
+        let version = combined.qr_version;
+        let size = 17 + 4 * version as u16;
+        let badge_side = (size as f32 * 0.15).round() as usize;
+        assert_eq!(combined.occluded_modules, single.occluded_modules + 2 * badge_side * badge_side);
+        assert!(combined.occlusion_percentage > single.occlusion_percentage);
+        
+        // Coordenadas explícitas sobre un finder pattern: no hay zona válida
+        let misplaced = EclOptimizer::new()
+            .with_overlay(OverlayFootprint::new(0.15, 0, Some(LogoPlacement::Modules { x: 0, y: 0 })));
+        assert!(matches!(misplaced.logo_zone(size, 0.2), Err(QrError::LogoError(_))));
+    }
+}
@@ -65,6 +65,9 @@ pub enum QrError {
     
     #[error("Error de cache: {0}")]
     CacheError(String),
+    
+    #[error("Contenido estructurado inválido: {0}")]
+    PayloadError(String),
 }
 
 impl QrError {
@@ -78,6 +81,7 @@ impl QrError {
             QrError::InsufficientContrast(_, _) => 400,
             QrError::LowQualityScore(_, _) => 400,
             QrError::UnsupportedFormat(_) => 400,
+            QrError::PayloadError(_) => 400,
             QrError::MaliciousUrl(_) => 403,
             QrError::GenerationTimeout(_) => 408,
             QrError::MemoryExceeded(_, _) => 413,
@@ -1,9 +1,12 @@
 // engine/generator.rs - Generador base de códigos QR
 
-use qrcodegen::{QrCode as QrCodeGen, QrCodeEcc, QrSegment, Version};
+use qrcodegen::{Mask, QrCode as QrCodeGen, QrCodeEcc, Version};
 use super::types::*;
 use super::error::{QrError, QrResult};
 use super::segmenter::ContentSegmenter;
+use super::bitstream::{self, MODE_FNC1_FIRST};
+use super::mask_selector::MaskChoice;
+use crate::standards::gs1::{Gs1Encoder, Gs1Parser};
 use crate::shapes::eyes::{EyeShapeRenderer, EyePosition, EyeComponent};
 
 /// Constantes de configuración
@@ -11,6 +14,7 @@ const MIN_SIZE: u32 = 100;
 const MAX_SIZE: u32 = 4000;
 const MAX_DATA_LENGTH: usize = 2953; // Límite QR v40
 const DEFAULT_QUIET_ZONE: usize = 4;
+const MAX_QUIET_ZONE: usize = 40;
 
 /// Generador principal de códigos QR
 pub struct QrGenerator {
@@ -55,6 +59,8 @@ impl QrGenerator {
             quiet_zone: self.quiet_zone,
             customization: None,
             logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
         })
     }
     
@@ -94,6 +100,8 @@ impl QrGenerator {
             quiet_zone: self.quiet_zone,
             customization: None,
             logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
         })
     }
     
@@ -116,12 +124,28 @@ impl QrGenerator {
         logo_size_ratio: f32,
         ecl_override: Option<ErrorCorrectionLevel>,
         enable_boost: bool,
+    ) -> QrResult<(QrCode, super::ecl_optimizer::OcclusionAnalysis)> {
+        let optimizer = super::ecl_optimizer::EclOptimizer::new().with_boost(enable_boost);
+        self.generate_with_ecl_optimizer(&optimizer, data, size, logo_size_ratio, ecl_override, enable_boost)
+    }
+    
+    /// Genera con el ECL que elige `optimizer` (silueta del logo, overlays...), en su
+    /// rango de versiones
+    pub fn generate_with_ecl_optimizer(
+        &self,
+        optimizer: &super::ecl_optimizer::EclOptimizer,
+        data: &str,
+        size: u32,
+        logo_size_ratio: f32,
+        ecl_override: Option<ErrorCorrectionLevel>,
+        enable_boost: bool,
     ) -> QrResult<(QrCode, super::ecl_optimizer::OcclusionAnalysis)> {
         self.validate_input(data, size)?;
         
-        // Usar el optimizador para determinar el ECL óptimo
-        let optimizer = super::ecl_optimizer::EclOptimizer::new();
-        let (optimal_ecl, mut analysis) = optimizer.determine_optimal_ecl(
+        // Usar el optimizador para determinar el ECL óptimo; su codificación de prueba
+        // aplica el mismo boost que la generación
+        let optimizer = &optimizer.clone().with_boost(enable_boost);
+        let (optimal_ecl, analysis) = optimizer.determine_optimal_ecl(
             data,
             logo_size_ratio,
             ecl_override,
@@ -129,49 +153,192 @@ impl QrGenerator {
         
         // Generar el QR con el ECL óptimo y boost si está habilitado
         let qr_ecl = self.map_error_correction(optimal_ecl);
-        let qr = if enable_boost {
-            self.generate_with_segmentation_and_boost(data, qr_ecl, true)?
-        } else {
-            self.generate_with_segmentation_and_boost(data, qr_ecl, false)?
-        };
-        
+        let (min_version, max_version) = optimizer.version_range();
+        let qr = self.generate_with_segmentation_boost_and_version(
+            data,
+            qr_ecl,
+            Version::new(min_version),
+            Version::new(max_version),
+            enable_boost,
+        )?;
         let matrix = self.qr_to_matrix(&qr);
         
-        // Si se aplicó boost, actualizar el análisis
-        if enable_boost {
-            // Comprobar si se aplicó boost comparando tamaños
-            let qr_no_boost = self.generate_with_segmentation_and_boost(data, qr_ecl, false)?;
-            if qr.size() == qr_no_boost.size() && qr.version() == qr_no_boost.version() {
-                // Se aplicó boost, actualizar el ECL recomendado
-                analysis.recommended_ecl = match optimal_ecl {
-                    ErrorCorrectionLevel::Low => ErrorCorrectionLevel::Medium,
-                    ErrorCorrectionLevel::Medium => ErrorCorrectionLevel::Quartile,
-                    ErrorCorrectionLevel::Quartile => ErrorCorrectionLevel::High,
-                    ErrorCorrectionLevel::High => ErrorCorrectionLevel::High,
-                };
-            }
-        }
+        // La segmentación y el boost pueden cambiar versión y ECL: el análisis final
+        // se hace sobre los bloques del símbolo que realmente se genera
+        let symbol_ecl = match qr.error_correction_level() {
+            QrCodeEcc::Low => ErrorCorrectionLevel::Low,
+            QrCodeEcc::Medium => ErrorCorrectionLevel::Medium,
+            QrCodeEcc::Quartile => ErrorCorrectionLevel::Quartile,
+            QrCodeEcc::High => ErrorCorrectionLevel::High,
+        };
+        let mut symbol_analysis = optimizer.analyze_symbol(qr.version().value(), symbol_ecl, logo_size_ratio)?;
+        symbol_analysis.recommended_ecl = symbol_ecl.max(analysis.recommended_ecl);
+        let analysis = symbol_analysis;
         
-        let mut qr_code = QrCode {
+        let qr_code = QrCode {
             matrix,
             size: qr.size() as usize,
             quiet_zone: self.quiet_zone,
             customization: None,
+            // Zona del logo: la misma que usó el análisis de oclusión
+            logo_zone: Some(optimizer.logo_zone(qr.size() as u16, logo_size_ratio)?),
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
+        };
+        
+        Ok((qr_code, analysis))
+    }
+    
+    /// Genera un conjunto Structured Append para mensajes que no caben en un símbolo
+    pub fn generate_structured_append(
+        &self,
+        data: &str,
+        size: u32,
+        ecl: ErrorCorrectionLevel,
+        options: &StructuredAppendOptions,
+    ) -> QrResult<StructuredAppendSet> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+            return Err(QrError::InvalidSize(size, MIN_SIZE, MAX_SIZE));
+        }
+
+        let encoded = super::structured_append::encode(
+            data,
+            self.map_error_correction(ecl),
+            options.max_symbols.map(usize::from).unwrap_or(super::structured_append::MAX_SYMBOLS),
+            options.max_version.unwrap_or(Version::MAX.value()),
+        )?;
+
+        let total = encoded.symbols.len();
+        let symbols = encoded.symbols
+            .into_iter()
+            .enumerate()
+            .map(|(index, symbol)| StructuredAppendSymbol {
+                index: index as u8,
+                total: total as u8,
+                qr: QrCode {
+                    matrix: self.qr_to_matrix(&symbol.qr),
+                    size: symbol.qr.size() as usize,
+                    quiet_zone: self.quiet_zone,
+                    customization: None,
+                    logo_zone: None,
+                    symbology: QrSymbology::Qr,
+                    gs1_mode: false,
+                },
+                data: symbol.data,
+            })
+            .collect();
+
+        Ok(StructuredAppendSet {
+            parity: encoded.parity,
+            symbols,
+        })
+    }
+    
+    /// Genera un símbolo Micro QR o rMQR en la versión más pequeña que admite los datos
+    pub fn generate_micro(
+        &self,
+        data: &str,
+        size: u32,
+        symbology: QrSymbology,
+        ecl: ErrorCorrectionLevel,
+    ) -> QrResult<QrCode> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+            return Err(QrError::InvalidSize(size, MIN_SIZE, MAX_SIZE));
+        }
+        
+        let symbol = super::micro_qr::encode(data, symbology, ecl)?;
+        
+        Ok(QrCode {
+            size: symbol.width(),
+            matrix: symbol.modules,
+            quiet_zone: symbology.min_quiet_zone(),
+            customization: None,
             logo_zone: None,
+            symbology,
+            gs1_mode: false,
+        })
+    }
+    
+    /// Genera un símbolo Data Matrix, Aztec o PDF417 con la zona silenciosa mínima de su norma
+    pub fn generate_matrix_symbol(
+        &self,
+        data: &str,
+        size: u32,
+        symbology: QrSymbology,
+        ecl: Option<ErrorCorrectionLevel>,
+        options: &MatrixCodeOptions,
+    ) -> QrResult<QrCode> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+            return Err(QrError::InvalidSize(size, MIN_SIZE, MAX_SIZE));
+        }
+        
+        let symbol = super::matrix_codes::encode(data, symbology, ecl, options)?;
+        
+        Ok(QrCode {
+            size: symbol.width(),
+            matrix: symbol.modules,
+            quiet_zone: symbology.min_quiet_zone(),
+            customization: None,
+            logo_zone: None,
+            symbology,
+            gs1_mode: false,
+        })
+    }
+    
+    /// Genera un símbolo GS1: indicador de modo FNC1 en primera posición y los
+    /// separadores entre elementos como GS (0x1D) en modo byte
+    pub fn generate_gs1(
+        &self,
+        data: &str,
+        size: u32,
+        ecl: Option<ErrorCorrectionLevel>,
+        qr_size: Option<QrSize>,
+    ) -> QrResult<QrCode> {
+        let elements = Gs1Parser::new().parse(data)?;
+        let element_string = Gs1Encoder::new().encode_element_string(&elements)?;
+        self.validate_input(&element_string, size)?;
+        
+        let qr_ecl = match ecl {
+            Some(ecl) => self.map_error_correction(ecl),
+            None => self.determine_error_correction(&element_string),
         };
+        let (min_ver, max_ver) = qr_size.unwrap_or(QrSize::Auto).version_range();
+        
+        let segments = ContentSegmenter::for_gs1()
+            .analyze_and_segment(&element_string)
+            .map_err(QrError::EncodingError)?;
+        let header = [(MODE_FNC1_FIRST, 4)];
+        let version = bitstream::smallest_version(4, &segments, qr_ecl, min_ver as u8, max_ver as u8)
+            .ok_or_else(|| QrError::ValidationError(
+                "Los datos GS1 no caben en el rango de versiones solicitado".to_string(),
+            ))?;
+        let qr_ecl = bitstream::boosted_ecl(4, &segments, version, qr_ecl);
+        let codewords = bitstream::data_codewords(&header, &segments, version, qr_ecl);
+        let qr = QrCodeGen::encode_codewords(Version::new(version), qr_ecl, &codewords, None);
         
-        // Crear y almacenar la zona del logo
-        let qr_size = qr_code.size as f64;
-        let center = qr_size / 2.0;
-        let logo_size = (qr_size * logo_size_ratio as f64) / 2.0;
-        qr_code.logo_zone = Some(super::geometry::LogoExclusionZone::new(
-            super::geometry::LogoShape::Square,
-            center,
-            center,
-            logo_size,
-        ));
+        Ok(QrCode {
+            matrix: self.qr_to_matrix(&qr),
+            size: qr.size() as usize,
+            quiet_zone: self.quiet_zone,
+            customization: None,
+            logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: true,
+        })
+    }
+    
+    /// Aplica una zona de silencio por solicitud, en módulos. Se admite 0 para
+    /// incrustar el código en un diseño que ya aporta el margen claro.
+    pub fn apply_quiet_zone(&self, qr: &mut QrCode, quiet_zone: usize) -> QrResult<()> {
+        if quiet_zone > MAX_QUIET_ZONE {
+            return Err(QrError::ValidationError(format!(
+                "Zona de silencio de {} módulos excede el máximo de {}",
+                quiet_zone, MAX_QUIET_ZONE
+            )));
+        }
         
-        Ok((qr_code, analysis))
+        qr.quiet_zone = quiet_zone;
+        Ok(())
     }
     
     /// Valida los datos de entrada
@@ -186,7 +353,7 @@ impl QrGenerator {
         }
         
         // Validar tamaño
-        if size < MIN_SIZE || size > MAX_SIZE {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
             return Err(QrError::InvalidSize(size, MIN_SIZE, MAX_SIZE));
         }
         
@@ -289,6 +456,8 @@ impl QrGenerator {
             quiet_zone: self.quiet_zone,
             customization: None,
             logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
         }, boost_info))
     }
     
@@ -390,6 +559,89 @@ impl QrGenerator {
             quiet_zone: self.quiet_zone,
             customization: None,
             logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
+        })
+    }
+    
+    /// Genera con versión explícita (dentro del rango de `qr_size`, si se indica) y con
+    /// la máscara que indique `mask`. Un `ecl` explícito se respeta; sin él se elige
+    /// por contenido y se refuerza hasta donde quepa en la versión.
+    pub fn generate_with_version_and_mask(
+        &self,
+        data: &str,
+        size: u32,
+        ecl: Option<ErrorCorrectionLevel>,
+        qr_size: Option<QrSize>,
+        version: Option<u8>,
+        mask: MaskChoice,
+    ) -> QrResult<QrCode> {
+        self.validate_input(data, size)?;
+        if let MaskChoice::Fixed(mask) = mask {
+            if mask > 7 {
+                return Err(QrError::ValidationError(format!("Máscara {} fuera del rango 0-7", mask)));
+            }
+        }
+        
+        let (min_ver, max_ver) = qr_size.unwrap_or(QrSize::Auto).version_range();
+        let (min_ver, max_ver) = match version {
+            Some(version) if !(1..=40).contains(&version) => {
+                return Err(QrError::ValidationError(format!("Versión QR {} fuera del rango 1-40", version)));
+            }
+            Some(version) if !(min_ver..=max_ver).contains(&(version as i16)) => {
+                return Err(QrError::ValidationError(format!(
+                    "La versión {} no corresponde al tamaño {:?}",
+                    version,
+                    qr_size.unwrap_or(QrSize::Auto)
+                )));
+            }
+            Some(version) => (version, version),
+            None => (min_ver as u8, max_ver as u8),
+        };
+        
+        let qr_ecl = match ecl {
+            Some(ecl) => self.map_error_correction(ecl),
+            None => self.determine_error_correction(data),
+        };
+        let segments = ContentSegmenter::new()
+            .analyze_and_segment(data)
+            .map_err(QrError::EncodingError)?;
+        let version = bitstream::smallest_version(0, &segments, qr_ecl, min_ver, max_ver)
+            .ok_or_else(|| QrError::ValidationError(if min_ver == max_ver {
+                format!("Los datos no caben en la versión {} con ECL {:?}", min_ver, qr_ecl)
+            } else {
+                format!("Los datos no caben en las versiones {}-{} con ECL {:?}", min_ver, max_ver, qr_ecl)
+            }))?;
+        let qr_ecl = match ecl {
+            Some(_) => qr_ecl,
+            None => bitstream::boosted_ecl(0, &segments, version, qr_ecl),
+        };
+        let codewords = bitstream::data_codewords(&[], &segments, version, qr_ecl);
+        let encode = |mask: Option<u8>| {
+            QrCodeGen::encode_codewords(Version::new(version), qr_ecl, &codewords, mask.map(Mask::new))
+        };
+        
+        let qr = match mask {
+            MaskChoice::Standard => encode(None),
+            MaskChoice::Fixed(mask) => encode(Some(mask)),
+            MaskChoice::Design(selector) => {
+                // Las 8 máscaras comparten versión y codewords: solo cambia el enmascarado
+                let mut candidates: Vec<QrCodeGen> = (0..8).map(|mask| encode(Some(mask))).collect();
+                let matrices: Vec<Vec<Vec<bool>>> = candidates.iter().map(|qr| self.qr_to_matrix(qr)).collect();
+                let chosen = selector.select(&matrices)?;
+                tracing::debug!("Máscara de diseño: {:?}", chosen);
+                candidates.swap_remove(chosen.mask as usize)
+            }
+        };
+        
+        Ok(QrCode {
+            matrix: self.qr_to_matrix(&qr),
+            size: qr.size() as usize,
+            quiet_zone: self.quiet_zone,
+            customization: None,
+            logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
         })
     }
     
@@ -438,15 +690,14 @@ impl QrCode {
         
         // Obtener zonas intocables si hay logo
         let untouchable_zones = if logo_zone.is_some() {
-            let version = self.get_version();
-            super::zones::calculate_untouchable_zones(version)
+            self.untouchable_zones()
         } else {
             vec![]
         };
         
         // Generar path data excluyendo módulos en zona de logo
-        for y in 0..self.size {
-            for x in 0..self.size {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
                 if self.matrix[y][x] {
                     // Verificar si el módulo debe ser excluido
                     let should_exclude = if let Some(zone) = logo_zone {
@@ -510,40 +761,103 @@ impl QrCode {
         output
     }
     
-    /// Obtiene la versión del código QR basado en su tamaño
-    fn get_version(&self) -> u8 {
-        match self.size {
-            21 => 1,
-            25 => 2,
-            29 => 3,
-            33 => 4,
-            37 => 5,
-            41 => 6,
-            45 => 7,
-            49 => 8,
-            53 => 9,
-            57 => 10,
-            _ => ((self.size - 21) / 4 + 1) as u8,
+    /// Obtiene la versión del símbolo a partir de sus dimensiones
+    pub(crate) fn get_version(&self) -> u8 {
+        match self.symbology {
+            QrSymbology::MicroQr => (self.size.saturating_sub(9) / 2) as u8,
+            QrSymbology::Rmqr => super::constants::RMQR_SIZES
+                .iter()
+                .position(|&(w, h)| w as usize == self.width() && h as usize == self.height())
+                .map_or(0, |index| index as u8 + 1),
+            QrSymbology::Qr => match self.size {
+                21 => 1,
+                25 => 2,
+                29 => 3,
+                33 => 4,
+                37 => 5,
+                41 => 6,
+                45 => 7,
+                49 => 8,
+                53 => 9,
+                57 => 10,
+                _ => ((self.size - 21) / 4 + 1) as u8,
+            },
+            QrSymbology::DataMatrix => {
+                super::constants::get_datamatrix_version(self.width(), self.height()).unwrap_or(0)
+            }
+            QrSymbology::Aztec => super::constants::get_aztec_layers(
+                self.size,
+                super::matrix_codes::is_compact_aztec(&self.matrix),
+            )
+            .unwrap_or(0),
+            QrSymbology::Pdf417 => {
+                let overhead = if super::matrix_codes::is_compact_pdf417(&self.matrix) { 35 } else { 69 };
+                (self.width().saturating_sub(overhead) / 17) as u8
+            }
+        }
+    }
+    
+    /// Ancho en módulos (en rMQR difiere del alto)
+    pub fn width(&self) -> usize {
+        self.matrix.first().map_or(self.size, Vec::len)
+    }
+    
+    /// Alto en módulos
+    pub fn height(&self) -> usize {
+        self.matrix.len()
+    }
+    
+    /// Zonas intocables según la simbología y la versión del símbolo
+    pub fn untouchable_zones(&self) -> Vec<super::zones::UntouchableZone> {
+        use super::zones;
+        match self.symbology {
+            QrSymbology::DataMatrix => zones::calculate_datamatrix_untouchable_zones(self.width(), self.height()),
+            QrSymbology::Aztec => zones::calculate_aztec_untouchable_zones(
+                self.size,
+                super::matrix_codes::is_compact_aztec(&self.matrix),
+            ),
+            QrSymbology::Pdf417 => zones::calculate_pdf417_untouchable_zones(
+                self.width(),
+                self.height(),
+                super::matrix_codes::is_compact_pdf417(&self.matrix),
+            ),
+            _ => zones::calculate_symbol_untouchable_zones(self.symbology, self.get_version()),
+        }
+    }
+    
+    /// Patrones de búsqueda que se estilizan como ojos: tres en QR, solo el superior
+    /// izquierdo en Micro QR y rMQR (el subpatrón de rMQR se dibuja como módulos).
+    /// Data Matrix, Aztec y PDF417 no tienen ojos: sus patrones se dibujan como módulos
+    fn finder_eyes(&self) -> Vec<(EyePosition, usize, usize)> {
+        match self.symbology {
+            QrSymbology::Qr => vec![
+                (EyePosition::TopLeft, 0, 0),
+                (EyePosition::TopRight, self.size - 7, 0),
+                (EyePosition::BottomLeft, 0, self.size - 7),
+            ],
+            QrSymbology::MicroQr | QrSymbology::Rmqr => vec![(EyePosition::TopLeft, 0, 0)],
+            QrSymbology::DataMatrix | QrSymbology::Aztec | QrSymbology::Pdf417 => Vec::new(),
         }
     }
     
     /// Convierte el QR a SVG básico
     pub fn to_svg(&self) -> String {
         // Usar la customización almacenada en el QrCode
-        self.to_svg_with_options(10, None, None, None, self.customization.as_ref())
+        self.to_svg_with_options(10, &[], None, None, self.customization.as_ref())
     }
     
     /// Convierte el QR a SVG con opciones avanzadas
     pub fn to_svg_with_options(
         &self, 
         module_size: usize,
-        logo_result: Option<&crate::engine::customizer::LogoIntegrationResult>,
+        logo_results: &[crate::engine::customizer::LogoIntegrationResult],
         frame_info: Option<&crate::engine::customizer::FrameInfo>,
         effects_info: Option<&crate::engine::customizer::EffectsInfo>,
         customization: Option<&QrCustomization>,
     ) -> String {
         let quiet_zone_size = self.quiet_zone * module_size;
         let image_size = (self.size * module_size) + (2 * quiet_zone_size);
+        let image_height = (self.height() * module_size) + (2 * quiet_zone_size);
         
         // Para tamaños grandes, usar renderizado optimizado
         // PERO: Si hay stroke habilitado, usar renderizado estándar para que cada módulo tenga bordes visibles
@@ -563,7 +877,7 @@ impl QrCode {
         
         let mut svg = format!(
             r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}">"#,
-            image_size, image_size, image_size, image_size
+            image_size, image_height, image_size, image_height
         );
         
         // Inicializar sección de definiciones
@@ -619,9 +933,23 @@ impl QrCode {
             
         svg.push_str(&format!(
             r#"<rect width="{}" height="{}" fill="{}"/>"#,
-            image_size, image_size, bg_color
+            image_size, image_height, bg_color
         ));
         
+        // Marco detrás de los módulos: su relleno no debe tapar el código
+        if let Some(frame) = frame_info {
+            let frame_renderer = crate::shapes::FrameRenderer::new(module_size as u32);
+            let frame_svg = frame_renderer.render_frame_rect(
+                image_size,
+                image_height,
+                frame.frame_type,
+                frame.text.as_deref(),
+                frame.text_position,
+                &frame.color,
+            );
+            svg.push_str(&frame_svg);
+        }
+        
         // Color o gradiente para módulos
         let fill_color = if let Some(ref grad_fill) = gradient_fill {
             grad_fill.as_str()
@@ -755,24 +1083,11 @@ impl QrCode {
             svg.push_str("</g>");
         }
         
-        // Renderizar logo si existe
-        if let Some(logo_info) = logo_result {
+        // Renderizar logos en orden (el principal primero, luego los overlays)
+        for logo_info in logo_results {
             svg.push_str(&self.render_logo_svg(logo_info, module_size, quiet_zone_size));
         }
         
-        // Renderizar marco si existe
-        if let Some(frame) = frame_info {
-            let frame_renderer = crate::shapes::FrameRenderer::new(module_size as u32);
-            let frame_svg = frame_renderer.render_frame(
-                image_size,
-                frame.frame_type,
-                frame.text.as_deref(),
-                frame.text_position,
-                &frame.color,
-            );
-            svg.push_str(&frame_svg);
-        }
-        
         svg.push_str("</svg>");
         svg
     }
@@ -786,6 +1101,20 @@ impl QrCode {
     ) -> String {
         use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
         
+        // Caja del logo dentro de su área: el padding queda como margen sin módulos.
+        // No se pinta un fondo opaco: los módulos bajo las zonas transparentes del
+        // logo se conservan (ver `QrCustomizer::integrate_logo`).
+        let inset = logo_info.logo_padding as usize * module_size;
+        let x = logo_info.logo_area.x * module_size + quiet_zone_size + inset;
+        let y = logo_info.logo_area.y * module_size + quiet_zone_size + inset;
+        let width = (logo_info.logo_area.width * module_size).saturating_sub(2 * inset);
+        let height = (logo_info.logo_area.height * module_size).saturating_sub(2 * inset);
+        
+        // Logo vectorial: se incrusta tal cual, recortado a su forma
+        if let Some(vector) = &logo_info.logo_vector {
+            return vector.to_svg_element(x as f32, y as f32, width as f32, height as f32, logo_info.logo_shape);
+        }
+        
         // Convertir la imagen del logo a base64
         let mut png_bytes = Vec::new();
         if let Ok(_) = logo_info.logo_image.write_to(
@@ -794,28 +1123,11 @@ impl QrCode {
         ) {
             let base64_logo = BASE64.encode(&png_bytes);
             
-            // Calcular posición y tamaño en píxeles SVG
-            let x = (logo_info.logo_area.x * module_size) + quiet_zone_size;
-            let y = (logo_info.logo_area.y * module_size) + quiet_zone_size;
-            let width = logo_info.logo_area.width * module_size;
-            let height = logo_info.logo_area.height * module_size;
-            
-            // Agregar fondo blanco opcional para el logo
-            let mut logo_svg = String::new();
-            
-            // Fondo blanco con borde redondeado
-            logo_svg.push_str(&format!(
-                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white" rx="4" ry="4" opacity="0.9"/>"#,
-                x - 4, y - 4, width + 8, height + 8
-            ));
-            
             // Imagen del logo
-            logo_svg.push_str(&format!(
+            format!(
                 r#"<image x="{}" y="{}" width="{}" height="{}" href="data:image/png;base64,{}" preserveAspectRatio="xMidYMid meet"/>"#,
                 x, y, width, height, base64_logo
-            ));
-            
-            logo_svg
+            )
         } else {
             String::new()
         }
@@ -978,12 +1290,44 @@ impl QrCode {
         if let Some(data_pattern) = pattern {
             use crate::shapes::PatternRenderer;
             
+            // Los ojos se retiran aquí: su número depende de la simbología
+            let eye_regions = self.identify_eye_regions();
+            // Data Matrix, Aztec y PDF417 no tienen ojos: sus patrones de localización y
+            // de filas se dibujan como cuadrados y el patrón solo afecta a los datos
+            let fixed_zones = if self.symbology.is_qr_family() {
+                Vec::new()
+            } else {
+                self.untouchable_zones()
+            };
+            let is_fixed = |x: usize, y: usize| {
+                super::zones::is_in_untouchable_zone(x as u16, y as u16, &fixed_zones)
+            };
+            let data_matrix: Vec<Vec<bool>> = self.matrix.iter().enumerate()
+                .map(|(y, row)| row.iter().enumerate()
+                    .map(|(x, &module)| module && !self.is_in_eye_region(x, y, &eye_regions) && !is_fixed(x, y))
+                    .collect())
+                .collect();
+            
+            for (y, row) in self.matrix.iter().enumerate() {
+                for (x, &module) in row.iter().enumerate() {
+                    if module && is_fixed(x, y) {
+                        svg.push_str(&format!(
+                            r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                            (x * module_size) + quiet_zone_size,
+                            (y * module_size) + quiet_zone_size,
+                            module_size,
+                            module_size
+                        ));
+                    }
+                }
+            }
+            
             let pattern_renderer = PatternRenderer::new(module_size as u32);
             let pattern_svg = pattern_renderer.render_matrix_with_pattern(
-                &self.matrix,
+                &data_matrix,
                 data_pattern,
                 "inherit", // Heredar color del grupo padre
-                true // Excluir ojos
+                false
             );
             
             // Aplicar transformación para el quiet zone
@@ -993,18 +1337,13 @@ impl QrCode {
             ));
         } else {
             // Renderizado estándar con cuadrados
-            let eye_size = 7;
-            let qr_size = self.size;
+            let eye_regions = self.identify_eye_regions();
             
             for (y, row) in self.matrix.iter().enumerate() {
                 for (x, &module) in row.iter().enumerate() {
                     if module {
                         // Verificar si está en el área de un ojo
-                        let in_top_left = x < eye_size && y < eye_size;
-                        let in_top_right = x >= qr_size - eye_size && y < eye_size;
-                        let in_bottom_left = x < eye_size && y >= qr_size - eye_size;
-                        
-                        if !in_top_left && !in_top_right && !in_bottom_left {
+                        if !self.is_in_eye_region(x, y, &eye_regions) {
                             let x_pos = (x * module_size) + quiet_zone_size;
                             let y_pos = (y * module_size) + quiet_zone_size;
                             
@@ -1032,14 +1371,9 @@ impl QrCode {
     ) -> String {
         let mut svg = String::new();
         let eye_renderer = EyeShapeRenderer::new(module_size as u32);
-        let qr_size = self.size;
         
         // Calcular posiciones reales de los ojos
-        let eye_positions = [
-            (EyePosition::TopLeft, 0, 0),
-            (EyePosition::TopRight, qr_size - 7, 0),
-            (EyePosition::BottomLeft, 0, qr_size - 7),
-        ];
+        let eye_positions = self.finder_eyes();
         
         svg.push_str(&format!(r#"<g transform="translate({}, {})">"#, quiet_zone_size, quiet_zone_size));
         
@@ -1183,8 +1517,8 @@ impl QrCode {
         let mut path_data = String::new();
         
         // Generar path data sin quiet zone
-        for y in 0..self.size {
-            for x in 0..self.size {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
                 if self.matrix[y][x] {
                     // Offset por quiet zone en coordenadas
                     let x_pos = x + self.quiet_zone;
@@ -1200,19 +1534,7 @@ impl QrCode {
         let content_hash = format!("{:x}", hasher.finalize());
         
         // Determinar versión QR basada en tamaño
-        let version = match self.size {
-            21 => 1,
-            25 => 2,
-            29 => 3,
-            33 => 4,
-            37 => 5,
-            41 => 6,
-            45 => 7,
-            49 => 8,
-            53 => 9,
-            57 => 10,
-            _ => ((self.size - 21) / 4 + 1) as u8, // Fórmula general
-        };
+        let version = self.get_version();
         
         // Determinar nivel de corrección basado en customization
         let error_correction = self.customization
@@ -1241,19 +1563,7 @@ impl QrCode {
                 content_hash,
                 total_modules: (self.size + 2 * self.quiet_zone) as u32,
                 data_modules: self.size as u32,
-                version: match self.size {
-                    21 => 1,
-                    25 => 2,
-                    29 => 3,
-                    33 => 4,
-                    37 => 5,
-                    41 => 6,
-                    45 => 7,
-                    49 => 8,
-                    53 => 9,
-                    57 => 10,
-                    _ => ((self.size - 21) / 4 + 1) as u32,
-                },
+                version: self.get_version() as u32,
                 error_correction: self.customization
                     .as_ref()
                     .and_then(|c| c.error_correction)
@@ -1316,19 +1626,7 @@ impl QrCode {
                 content_hash,
                 total_modules: (self.size + 2 * self.quiet_zone) as u32,
                 data_modules: self.size as u32,
-                version: match self.size {
-                    21 => 1,
-                    25 => 2,
-                    29 => 3,
-                    33 => 4,
-                    37 => 5,
-                    41 => 6,
-                    45 => 7,
-                    49 => 8,
-                    53 => 9,
-                    57 => 10,
-                    _ => ((self.size - 21) / 4 + 1) as u32,
-                },
+                version: self.get_version() as u32,
                 error_correction: self.customization
                     .as_ref()
                     .and_then(|c| c.error_correction)
@@ -1361,8 +1659,7 @@ impl QrCode {
         
         // Obtener zonas intocables si hay logo
         let untouchable_zones = if logo_zone.is_some() {
-            let version = self.get_version();
-            super::zones::calculate_untouchable_zones(version)
+            self.untouchable_zones()
         } else {
             vec![]
         };
@@ -1381,8 +1678,8 @@ impl QrCode {
         eprintln!("[DEBUG] per_module_gradient = {}", per_module_gradient);
         
         // Generar path optimizado para datos (excluyendo ojos y zona de logo si aplica)
-        for y in 0..self.size {
-            for x in 0..self.size {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
                 if self.matrix[y][x] && !self.is_in_eye_region(x, y, &eye_regions) {
                     // Verificar si el módulo debe ser excluido por el logo
                     let should_exclude = if let Some(zone) = logo_zone {
@@ -1667,7 +1964,7 @@ impl QrCode {
                         DataPattern::Square => {
                             // Patrón cuadrado estándar (optimizado para renderizado rápido)
                             let mut width = 1;
-                            while x + width < self.size && 
+                            while x + width < self.width() && 
                                   self.matrix[y][x + width] && 
                                   !self.is_in_eye_region(x + width, y, &eye_regions) {
                                 width += 1;
@@ -1677,7 +1974,7 @@ impl QrCode {
                                 data_path.push_str(&format!("M{} {}h{}v1H{}z", x_pos, y_pos, width, x_pos));
                                 // Saltar los módulos ya procesados
                                 for _ in 1..width {
-                                    if x + 1 < self.size {
+                                    if x + 1 < self.width() {
                                         // Skip processed modules
                                     }
                                 }
@@ -2107,18 +2404,17 @@ impl QrCode {
     
     /// Identifica las regiones de los ojos
     fn identify_eye_regions(&self) -> Vec<(String, EyeRegion)> {
-        let mut regions = Vec::new();
-        
-        // Top-left eye
-        regions.push(("top_left".to_string(), EyeRegion { x: 0, y: 0, size: 7 }));
-        
-        // Top-right eye
-        regions.push(("top_right".to_string(), EyeRegion { x: self.size - 7, y: 0, size: 7 }));
-        
-        // Bottom-left eye
-        regions.push(("bottom_left".to_string(), EyeRegion { x: 0, y: self.size - 7, size: 7 }));
-        
-        regions
+        self.finder_eyes()
+            .into_iter()
+            .map(|(position, x, y)| {
+                let name = match position {
+                    EyePosition::TopLeft => "top_left",
+                    EyePosition::TopRight => "top_right",
+                    EyePosition::BottomLeft => "bottom_left",
+                };
+                (name.to_string(), EyeRegion { x, y, size: 7 })
+            })
+            .collect()
     }
     
     /// Verifica si una coordenada está en una región de ojo
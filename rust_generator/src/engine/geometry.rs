@@ -1,6 +1,8 @@
 // engine/geometry.rs - Funciones de geometría para detección de colisiones
 
 use geo_types::{Coord, Point, Rect};
+use image::{DynamicImage, GrayImage};
+use super::types::{LogoAnchor, LogoPlacement};
 use super::zones::UntouchableZone;
 
 /// Representa la zona de exclusión del logo
@@ -14,6 +16,116 @@ pub struct LogoExclusionZone {
     pub center_y: f64,
     /// Tamaño (radio para círculo, lado/2 para cuadrado)
     pub size: f64,
+    /// Silueta real del logo; si existe, sustituye a `shape`
+    pub mask: Option<LogoMask>,
+}
+
+/// Módulos cubiertos por el canal alfa del logo, ya dilatados por el padding
+#[derive(Debug, Clone)]
+pub struct LogoMask {
+    /// Módulo de la esquina superior izquierda de la máscara
+    pub origin_x: i32,
+    pub origin_y: i32,
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<bool>,
+}
+
+impl LogoMask {
+    /// Alfa mínimo para que un píxel del logo tape el módulo bajo él
+    pub const ALPHA_THRESHOLD: u8 = 32;
+
+    /// Rasteriza la silueta del logo a la rejilla de módulos. El logo se ajusta
+    /// (conservando proporción) al cuadrado de lado `2 * size` centrado en el
+    /// centro dado, y cada módulo tocado por un píxel opaco queda cubierto.
+    pub fn from_alpha(alpha: &GrayImage, center_x: f64, center_y: f64, size: f64, padding: u32) -> Self {
+        let padding = padding as i32;
+        let left = (center_x - size).floor() as i32;
+        let top = (center_y - size).floor() as i32;
+        let width = ((center_x + size).ceil() as i32 - left + 2 * padding).max(0) as usize;
+        let height = ((center_y + size).ceil() as i32 - top + 2 * padding).max(0) as usize;
+        let mut mask = Self {
+            origin_x: left - padding,
+            origin_y: top - padding,
+            width,
+            height,
+            cells: vec![false; width * height],
+        };
+
+        let (image_width, image_height) = alpha.dimensions();
+        if image_width == 0 || image_height == 0 || size <= 0.0 {
+            return mask;
+        }
+
+        // Módulos por píxel del logo y esquina del logo ajustado dentro de la caja
+        let scale = 2.0 * size / image_width.max(image_height) as f64;
+        let logo_left = center_x - image_width as f64 * scale / 2.0;
+        let logo_top = center_y - image_height as f64 * scale / 2.0;
+
+        let mut covered = vec![false; width * height];
+        for (x, y, pixel) in alpha.enumerate_pixels() {
+            if pixel.0[0] < Self::ALPHA_THRESHOLD {
+                continue;
+            }
+            // Módulos que toca el píxel (puede ser más de uno si el logo es pequeño)
+            let x0 = (logo_left + x as f64 * scale).floor() as i32 - mask.origin_x;
+            let x1 = (logo_left + (x + 1) as f64 * scale).ceil() as i32 - mask.origin_x;
+            let y0 = (logo_top + y as f64 * scale).floor() as i32 - mask.origin_y;
+            let y1 = (logo_top + (y + 1) as f64 * scale).ceil() as i32 - mask.origin_y;
+            for my in y0.max(0)..y1.min(height as i32) {
+                for mx in x0.max(0)..x1.min(width as i32) {
+                    covered[my as usize * width + mx as usize] = true;
+                }
+            }
+        }
+
+        // Dilatación cuadrada: el padding separa el logo de los módulos en todas direcciones
+        for (index, _) in covered.iter().enumerate().filter(|(_, c)| **c) {
+            let (cx, cy) = ((index % width) as i32, (index / width) as i32);
+            for my in (cy - padding).max(0)..(cy + padding + 1).min(height as i32) {
+                for mx in (cx - padding).max(0)..(cx + padding + 1).min(width as i32) {
+                    mask.cells[my as usize * width + mx as usize] = true;
+                }
+            }
+        }
+
+        mask
+    }
+
+    /// Une varias zonas en una máscara del tamaño del símbolo
+    pub fn from_zones(zones: &[LogoExclusionZone], width: usize, height: usize) -> Self {
+        let cells = (0..width * height)
+            .map(|i| zones.iter().any(|zone| zone.contains_module((i % width) as u16, (i / width) as u16)))
+            .collect();
+        Self {
+            origin_x: 0,
+            origin_y: 0,
+            width,
+            height,
+            cells,
+        }
+    }
+    
+    /// Indica si el módulo está cubierto
+    pub fn contains(&self, module_x: i32, module_y: i32) -> bool {
+        let (x, y) = (module_x - self.origin_x, module_y - self.origin_y);
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.cells[y as usize * self.width + x as usize]
+    }
+
+    /// Número de módulos cubiertos
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|c| **c).count()
+    }
+}
+
+/// Canal alfa de un logo como imagen en escala de grises
+pub fn alpha_channel(logo: &DynamicImage) -> GrayImage {
+    let rgba = logo.to_rgba8();
+    GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| image::Luma([rgba.get_pixel(x, y).0[3]]))
 }
 
 /// Forma del área de exclusión del logo
@@ -35,11 +147,51 @@ impl LogoExclusionZone {
             center_x,
             center_y,
             size,
+            mask: None,
+        }
+    }
+    
+    /// Crea la zona a partir del canal alfa del logo, dilatado por `padding` módulos.
+    /// `size` es el semilado de la caja en la que se ajusta el logo.
+    pub fn from_alpha(alpha: &GrayImage, center_x: f64, center_y: f64, size: f64, padding: u32) -> Self {
+        Self {
+            mask: Some(LogoMask::from_alpha(alpha, center_x, center_y, size, padding)),
+            ..Self::new(LogoShape::Square, center_x, center_y, size)
+        }
+    }
+    
+    /// Zona de un logo colocado en el área `(x, y, side, side)` (padding incluido).
+    /// Con canal alfa se usa la silueta; sin él, el área completa.
+    pub fn for_area(x: usize, y: usize, side: usize, padding: u32, alpha: Option<&GrayImage>) -> Self {
+        let center_x = x as f64 + side as f64 / 2.0;
+        let center_y = y as f64 + side as f64 / 2.0;
+        match alpha {
+            Some(alpha) => {
+                let logo_half = (side as f64 / 2.0 - padding as f64).max(0.0);
+                Self::from_alpha(alpha, center_x, center_y, logo_half, padding)
+            }
+            None => Self::new(LogoShape::Square, center_x, center_y, side as f64 / 2.0),
+        }
+    }
+    
+    /// Zona única con la unión de varias (logo principal más overlays)
+    pub fn combined(zones: &[LogoExclusionZone], width: usize, height: usize) -> Self {
+        Self {
+            mask: Some(LogoMask::from_zones(zones, width, height)),
+            ..Self::new(
+                LogoShape::Square,
+                width as f64 / 2.0,
+                height as f64 / 2.0,
+                width.max(height) as f64 / 2.0,
+            )
         }
     }
     
     /// Verifica si un punto está contenido en la zona del logo
     pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        if let Some(mask) = &self.mask {
+            return mask.contains(x.floor() as i32, y.floor() as i32);
+        }
         match self.shape {
             LogoShape::Square => {
                 let half_size = self.size;
@@ -95,6 +247,10 @@ impl LogoExclusionZone {
     
     /// Verifica si un módulo está dentro de la zona de exclusión
     pub fn contains_module(&self, module_x: u16, module_y: u16) -> bool {
+        if let Some(mask) = &self.mask {
+            return mask.contains(module_x as i32, module_y as i32);
+        }
+        
         // Centro del módulo (agregamos 0.5 para obtener el centro)
         let mx = module_x as f64 + 0.5;
         let my = module_y as f64 + 0.5;
@@ -155,6 +311,9 @@ impl LogoExclusionZone {
     
     /// Calcula el área en módulos²
     pub fn area(&self) -> f64 {
+        if let Some(mask) = &self.mask {
+            return mask.count() as f64;
+        }
         match self.shape {
             LogoShape::Square => (2.0 * self.size) * (2.0 * self.size),
             LogoShape::Circle => std::f64::consts::PI * self.size * self.size,
@@ -205,6 +364,64 @@ pub fn calculate_logo_center(version: u8) -> (f64, f64) {
     (center, center)
 }
 
+/// Esquina superior izquierda (en módulos) de un área de logo de `width`×`height`.
+/// Sin colocación se centra, como `calculate_logo_center`. Las coordenadas explícitas
+/// se validan tal cual y las anclas toman la posición libre más cercana; en ambos casos
+/// el área no puede tapar ninguna zona intocable. `None` si no hay posición válida.
+pub fn calculate_logo_position(
+    matrix_width: usize,
+    matrix_height: usize,
+    width: usize,
+    height: usize,
+    placement: Option<LogoPlacement>,
+    untouchable_zones: &[UntouchableZone],
+) -> Option<(usize, usize)> {
+    if width == 0 || height == 0 || width > matrix_width || height > matrix_height {
+        return None;
+    }
+    let (max_x, max_y) = (matrix_width - width, matrix_height - height);
+    let fits = |x: usize, y: usize| {
+        !untouchable_zones.iter().any(|zone| {
+            zone.intersects_rect(x as u16, y as u16, width as u16, height as u16)
+        })
+    };
+    
+    match placement {
+        None => Some((matrix_width / 2 - width / 2, matrix_height / 2 - height / 2))
+            .filter(|&(x, y)| x <= max_x && y <= max_y),
+        Some(LogoPlacement::Modules { x, y }) => {
+            (x <= max_x && y <= max_y && fits(x, y)).then_some((x, y))
+        }
+        Some(LogoPlacement::Anchor(anchor)) => {
+            let along = |side: i8, max: usize| match side {
+                -1 => 0,
+                0 => max / 2,
+                _ => max,
+            };
+            let (side_x, side_y) = match anchor {
+                LogoAnchor::Center => (0, 0),
+                LogoAnchor::Top => (0, -1),
+                LogoAnchor::Bottom => (0, 1),
+                LogoAnchor::Left => (-1, 0),
+                LogoAnchor::Right => (1, 0),
+                LogoAnchor::TopLeft => (-1, -1),
+                LogoAnchor::TopRight => (1, -1),
+                LogoAnchor::BottomLeft => (-1, 1),
+                LogoAnchor::BottomRight => (1, 1),
+            };
+            let (ideal_x, ideal_y) = (along(side_x, max_x), along(side_y, max_y));
+            
+            (0..=max_y)
+                .flat_map(|y| (0..=max_x).map(move |x| (x, y)))
+                .filter(|&(x, y)| fits(x, y))
+                .min_by_key(|&(x, y)| {
+                    let (dx, dy) = (x.abs_diff(ideal_x), y.abs_diff(ideal_y));
+                    dx * dx + dy * dy
+                })
+        }
+    }
+}
+
 /// Calcula el tamaño máximo seguro del logo para un ratio dado
 pub fn calculate_logo_size(version: u8, size_ratio: f64) -> f64 {
     let qr_size = 17 + 4 * version as u16;
@@ -295,6 +512,68 @@ mod tests {
         assert!(!is_module_excludable(20, 20, &logo, &untouchable));
     }
     
+    #[test]
+    fn test_alpha_mask_follows_logo() {
+        // Wordmark 4:1 opaco con una "o" transparente en el centro
+        let alpha = GrayImage::from_fn(400, 100, |x, y| {
+            let (dx, dy) = (x as i32 - 200, y as i32 - 50);
+            image::Luma([if dx * dx + dy * dy < 30 * 30 { 0 } else { 255 }])
+        });
+        let square = LogoExclusionZone::new(LogoShape::Square, 20.5, 20.5, 6.0);
+        let zone = LogoExclusionZone::from_alpha(&alpha, 20.5, 20.5, 6.0, 0);
+
+        // Solo la franja central de 12x3 módulos, menos el hueco
+        assert!(zone.contains_module(15, 20) && zone.contains_module(25, 19));
+        assert!(!zone.contains_module(20, 16) && !zone.contains_module(20, 24));
+        assert!(!zone.contains_module(20, 20));
+        assert!(zone.area() < square.area() / 3.0);
+
+        let untouchable = crate::engine::zones::calculate_untouchable_zones(4);
+        let square_count = count_excludable_modules(4, &square, &untouchable);
+        let mask_count = count_excludable_modules(4, &zone, &untouchable);
+        assert_eq!(mask_count, zone.area() as usize);
+        assert!(mask_count * 3 < square_count);
+
+        // El padding dilata la silueta un módulo en cada dirección
+        let padded = LogoExclusionZone::from_alpha(&alpha, 20.5, 20.5, 6.0, 1);
+        assert!(padded.contains_module(20, 18) && padded.contains_module(13, 20));
+        assert!(!padded.contains_module(20, 16));
+
+        // Un logo totalmente transparente no excluye nada
+        let empty = LogoExclusionZone::from_alpha(&GrayImage::new(10, 10), 20.5, 20.5, 6.0, 2);
+        assert_eq!(count_excludable_modules(4, &empty, &untouchable), 0);
+    }
+    
+    #[test]
+    fn test_calculate_logo_position() {
+        let untouchable = crate::engine::zones::calculate_untouchable_zones(4);
+        let overlaps = |(x, y): (usize, usize), side: usize| {
+            untouchable.iter().any(|zone| zone.intersects_rect(x as u16, y as u16, side as u16, side as u16))
+        };
+
+        // Sin colocación: centrado, como hasta ahora
+        assert_eq!(calculate_logo_position(33, 33, 9, 9, None, &untouchable), Some((12, 12)));
+
+        // Coordenadas explícitas: se validan contra los patrones de función
+        let explicit = |x, y| Some(LogoPlacement::Modules { x, y });
+        assert_eq!(calculate_logo_position(33, 33, 5, 5, explicit(12, 12), &untouchable), Some((12, 12)));
+        assert_eq!(calculate_logo_position(33, 33, 5, 5, explicit(2, 2), &untouchable), None);
+        assert_eq!(calculate_logo_position(33, 33, 5, 5, explicit(30, 12), &untouchable), None);
+
+        // Anclas: la posición libre más cercana a la esquina pedida
+        for anchor in [LogoAnchor::TopLeft, LogoAnchor::BottomRight, LogoAnchor::Right, LogoAnchor::Bottom] {
+            let position = calculate_logo_position(33, 33, 5, 5, Some(LogoPlacement::Anchor(anchor)), &untouchable)
+                .unwrap_or_else(|| panic!("{:?} sin posición", anchor));
+            assert!(!overlaps(position, 5), "{:?} tapa un patrón en {:?}", anchor, position);
+        }
+        let bottom_right = calculate_logo_position(33, 33, 5, 5, Some(LogoPlacement::Anchor(LogoAnchor::BottomRight)), &untouchable).unwrap();
+        // La esquina (28, 28) la ocupa el patrón de alineación (24..=28): sube por encima
+        assert_eq!(bottom_right, (28, 19));
+
+        // Un logo que no cabe en ningún hueco libre
+        assert_eq!(calculate_logo_position(33, 33, 30, 30, Some(LogoPlacement::Anchor(LogoAnchor::Center)), &untouchable), None);
+    }
+    
     #[test]
     fn test_logo_area_calculations() {
         let square = LogoExclusionZone::new(LogoShape::Square, 10.0, 10.0, 5.0);
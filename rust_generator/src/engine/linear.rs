@@ -0,0 +1,524 @@
+// engine/linear.rs - Códigos de barras lineales (1D)
+// Codifica con los escritores de rxing y renderiza barras agrupadas con su alto real,
+// interpretación legible, add-ons EAN/UPC de 2 o 5 dígitos y barras portadoras ITF-14
+
+use super::error::{QrError, QrResult};
+use super::types::{ColorOptions, LinearOptions, LinearSymbology};
+use crate::standards::gs1::{check_digit, is_valid_check_digit};
+use rxing::oned::{
+    CodaBarWriter, Code128Writer, Code39Writer, Code93Writer, EAN13Writer, EAN8Writer, ITFWriter,
+    OneDimensionalCodeWriter, UPCEWriter,
+};
+use rxing::{BarcodeFormat, DecodeHints, RXingResultMetadataType, RXingResultMetadataValue};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Unidades SVG por módulo en la salida del motor
+pub const MODULE_UNITS: usize = 10;
+
+/// Alto del texto legible (módulos; 2.75 mm con X = 0.33 mm)
+const TEXT_SIZE: f32 = 8.0;
+
+/// Prolongación de las guardas EAN/UPC bajo las barras (módulos)
+const GUARD_EXTENSION: f32 = 5.0;
+
+/// Separación entre el símbolo principal y el add-on (módulos, 7-12 según GS1)
+const ADD_ON_GAP: usize = 9;
+
+/// Grosor de las barras portadoras (módulos; 4.8 mm con X = 1.016 mm)
+const BEARER_WIDTH: f32 = 5.0;
+
+/// Margen vertical sobre y bajo el símbolo (módulos)
+const VERTICAL_MARGIN: f32 = 2.0;
+
+/// Ancho de rasterización para la verificación de lectura
+const DECODE_WIDTH: u32 = 1200;
+
+const FONT_FAMILY: &str = "OCR-B, 'DejaVu Sans Mono', monospace";
+
+/// Patrones L (paridad impar) de los dígitos EAN/UPC
+const L_PATTERNS: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011",
+    "0110001", "0101111", "0111011", "0110111", "0001011",
+];
+
+/// Patrones G (paridad par) de los dígitos EAN/UPC
+const G_PATTERNS: [&str; 10] = [
+    "0100111", "0110011", "0011011", "0100001", "0011101",
+    "0111001", "0000101", "0010001", "0001001", "0010111",
+];
+
+/// Paridades del add-on de 5 dígitos según su dígito de control
+const EAN5_PARITIES: [&str; 10] = [
+    "GGLLL", "GLGLL", "GLLGL", "GLLLG", "LGGLL", "LLGGL", "LLLGG", "LGLGL", "LGLLG", "LLGLG",
+];
+
+/// Código de barras lineal codificado
+#[derive(Debug, Clone)]
+pub struct LinearBarcode {
+    pub symbology: LinearSymbology,
+    /// Módulos del símbolo principal (true = barra)
+    pub modules: Vec<bool>,
+    /// Contenido que devuelve un lector, con los dígitos de control calculados
+    pub content: String,
+    /// Módulos del add-on EAN/UPC
+    pub add_on: Option<Vec<bool>>,
+    /// Dígitos del add-on
+    pub add_on_text: Option<String>,
+}
+
+/// Codifica los datos en la simbología de las opciones
+pub fn encode(data: &str, options: &LinearOptions) -> QrResult<LinearBarcode> {
+    let symbology = options.symbology;
+    let data = data.trim();
+    let invalid = |e: &dyn std::fmt::Display| {
+        QrError::ValidationError(format!("Datos inválidos para {}: {}", symbology.name(), e))
+    };
+
+    let (content, modules) = match symbology {
+        LinearSymbology::Ean13 => {
+            let content = with_check_digit(data, 12, symbology)?;
+            let modules = EAN13Writer.encode_oned(&content);
+            (content, modules)
+        }
+        LinearSymbology::UpcA => {
+            // UPC-A es un EAN-13 con el primer dígito a cero
+            let content = with_check_digit(data, 11, symbology)?;
+            let modules = EAN13Writer.encode_oned(&format!("0{}", content));
+            (content, modules)
+        }
+        LinearSymbology::Ean8 => {
+            let content = with_check_digit(data, 7, symbology)?;
+            let modules = EAN8Writer.encode_oned(&content);
+            (content, modules)
+        }
+        LinearSymbology::UpcE => {
+            let content = upc_e_content(data)?;
+            let modules = UPCEWriter.encode_oned(&content);
+            (content, modules)
+        }
+        LinearSymbology::Itf14 => {
+            let content = with_check_digit(data, 13, symbology)?;
+            let modules = ITFWriter.encode_oned(&content);
+            (content, modules)
+        }
+        LinearSymbology::Itf => (data.to_string(), ITFWriter.encode_oned(data)),
+        LinearSymbology::Code128 => (data.to_string(), Code128Writer.encode_oned(data)),
+        LinearSymbology::Code39 => (data.to_string(), Code39Writer.encode_oned(data)),
+        LinearSymbology::Code93 => (data.to_string(), Code93Writer.encode_oned(data)),
+        LinearSymbology::Codabar => {
+            // Los caracteres de arranque y parada no forman parte del contenido leído
+            let content = data.trim_matches(|c: char| "ABCDTNE*abcdtne".contains(c)).to_string();
+            (content, CodaBarWriter.encode_oned(data))
+        }
+    };
+    let mut modules = modules.map_err(|e| invalid(&e))?;
+
+    // Los escritores de rxing incluyen el margen: se recorta para aplicar el propio
+    let first = modules.iter().position(|&bar| bar).ok_or_else(|| invalid(&"símbolo vacío"))?;
+    let last = modules.iter().rposition(|&bar| bar).unwrap_or(first);
+    modules = modules[first..=last].to_vec();
+
+    let add_on_text = options.add_on.as_deref().map(str::trim).filter(|digits| !digits.is_empty());
+    let add_on = match add_on_text {
+        None => None,
+        Some(_) if !matches!(symbology, LinearSymbology::Ean13 | LinearSymbology::UpcA | LinearSymbology::UpcE) => {
+            return Err(QrError::ValidationError(
+                "Los add-ons solo están definidos para EAN-13, UPC-A y UPC-E".to_string(),
+            ));
+        }
+        Some(digits) => Some(encode_add_on(digits)?),
+    };
+
+    Ok(LinearBarcode {
+        symbology,
+        modules,
+        content,
+        add_on,
+        add_on_text: add_on_text.map(str::to_string),
+    })
+}
+
+/// Completa o verifica el dígito de control GS1 de una clave numérica
+fn with_check_digit(data: &str, body_len: usize, symbology: LinearSymbology) -> QrResult<String> {
+    if !data.chars().all(|c| c.is_ascii_digit()) {
+        return Err(QrError::ValidationError(format!("{} solo admite dígitos", symbology.name())));
+    }
+    match data.len() {
+        len if len == body_len => Ok(format!("{}{}", data, check_digit(data).unwrap_or(0))),
+        len if len == body_len + 1 && is_valid_check_digit(data) => Ok(data.to_string()),
+        len if len == body_len + 1 => Err(QrError::ValidationError(format!(
+            "Dígito de control incorrecto en {}: se esperaba {}",
+            data,
+            check_digit(&data[..body_len]).unwrap_or(0)
+        ))),
+        len => Err(QrError::ValidationError(format!(
+            "{} requiere {} o {} dígitos, se recibieron {}",
+            symbology.name(), body_len, body_len + 1, len
+        ))),
+    }
+}
+
+/// UPC-E: sistema numérico (0 o 1), 6 dígitos y control del UPC-A equivalente
+fn upc_e_content(data: &str) -> QrResult<String> {
+    if !data.chars().all(|c| c.is_ascii_digit()) || !matches!(data.len(), 7 | 8) {
+        return Err(QrError::ValidationError("UPC-E requiere 7 u 8 dígitos".to_string()));
+    }
+    if !data.starts_with(['0', '1']) {
+        return Err(QrError::ValidationError("UPC-E solo admite los sistemas numéricos 0 y 1".to_string()));
+    }
+    let expanded = rxing::oned::convertUPCEtoUPCA(&format!("{}0", &data[..7]))
+        .ok_or_else(|| QrError::ValidationError(format!("UPC-E inválido: {}", data)))?;
+    let check = check_digit(&expanded[..11]).unwrap_or(0);
+    let content = format!("{}{}", &data[..7], check);
+    if data.len() == 8 && data != content {
+        return Err(QrError::ValidationError(format!(
+            "Dígito de control incorrecto en {}: se esperaba {}", data, check
+        )));
+    }
+    Ok(content)
+}
+
+/// Add-on EAN-2 (paridad por el valor módulo 4) o EAN-5 (paridad por su dígito de control)
+fn encode_add_on(digits: &str) -> QrResult<Vec<bool>> {
+    let values: Vec<usize> = digits
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as usize))
+        .collect::<Option<_>>()
+        .ok_or_else(|| QrError::ValidationError("El add-on solo admite dígitos".to_string()))?;
+    let parities: Vec<bool> = match values.len() {
+        2 => {
+            let parity = (values[0] * 10 + values[1]) % 4;
+            vec![parity & 2 != 0, parity & 1 != 0]
+        }
+        5 => {
+            let check = (3 * (values[0] + values[2] + values[4]) + 9 * (values[1] + values[3])) % 10;
+            EAN5_PARITIES[check].chars().map(|c| c == 'G').collect()
+        }
+        _ => {
+            return Err(QrError::ValidationError("El add-on debe tener 2 o 5 dígitos".to_string()));
+        }
+    };
+
+    let mut pattern = String::from("1011");
+    for (i, (&value, &even)) in values.iter().zip(&parities).enumerate() {
+        if i > 0 {
+            pattern.push_str("01");
+        }
+        pattern.push_str(if even { G_PATTERNS[value] } else { L_PATTERNS[value] });
+    }
+    Ok(pattern.chars().map(|c| c == '1').collect())
+}
+
+impl LinearBarcode {
+    /// Barras que se prolongan bajo las barras normales: guardas EAN/UPC y,
+    /// en UPC-A, los dígitos de sistema y de control
+    fn extended_ranges(&self) -> Vec<Range<usize>> {
+        match self.symbology {
+            LinearSymbology::Ean13 => vec![0..3, 45..50, 92..95],
+            LinearSymbology::UpcA => vec![0..10, 45..50, 85..95],
+            LinearSymbology::Ean8 => vec![0..3, 31..36, 64..67],
+            LinearSymbology::UpcE => vec![0..3, 45..51],
+            _ => vec![],
+        }
+    }
+
+    /// Posición (en módulos desde la primera barra) del centro de cada carácter legible;
+    /// None si el texto va centrado bajo el símbolo
+    fn text_positions(&self) -> Option<Vec<f32>> {
+        let digit = |start: usize, index: usize| (start + 7 * index) as f32 + 3.5;
+        let positions: Vec<f32> = match self.symbology {
+            LinearSymbology::Ean13 => std::iter::once(-4.0)
+                .chain((0..6).map(|i| digit(3, i)))
+                .chain((0..6).map(|i| digit(50, i)))
+                .collect(),
+            LinearSymbology::UpcA => std::iter::once(-4.0)
+                .chain((0..5).map(|i| digit(10, i)))
+                .chain((0..5).map(|i| digit(50, i)))
+                .chain([99.0])
+                .collect(),
+            LinearSymbology::Ean8 => (0..4).map(|i| digit(3, i)).chain((0..4).map(|i| digit(36, i))).collect(),
+            LinearSymbology::UpcE => std::iter::once(-4.0)
+                .chain((0..6).map(|i| digit(3, i)))
+                .chain([55.0])
+                .collect(),
+            _ => return None,
+        };
+        Some(positions)
+    }
+
+    /// Renderiza el código como SVG.
+    /// `module_size` en unidades SVG; la zona silenciosa (módulos) nunca baja de la que
+    /// necesita el texto exterior de EAN/UPC.
+    pub fn to_svg(
+        &self,
+        module_size: usize,
+        options: &LinearOptions,
+        colors: Option<&ColorOptions>,
+        quiet_zone: Option<usize>,
+    ) -> String {
+        let unit = module_size.max(1) as f32;
+        let include_text = options.include_text.unwrap_or(true);
+        let bearer = if options.bearer_bars.unwrap_or(self.symbology == LinearSymbology::Itf14) {
+            BEARER_WIDTH
+        } else {
+            0.0
+        };
+        let bar_height = options
+            .bar_height
+            .filter(|h| *h > 0.0)
+            .unwrap_or_else(|| self.symbology.default_bar_height());
+        let mut quiet_zone = quiet_zone.unwrap_or_else(|| self.symbology.min_quiet_zone());
+        if include_text && self.symbology.is_ean_upc() {
+            quiet_zone = quiet_zone.max(7);
+        }
+        let foreground = colors.map(|c| c.foreground.as_str()).unwrap_or("#000000");
+        let background = colors.map(|c| c.background.as_str()).unwrap_or("#FFFFFF");
+
+        let add_on_width = self.add_on.as_ref().map_or(0, |a| ADD_ON_GAP + a.len());
+        let symbol_x = bearer + quiet_zone as f32;
+        let width = 2.0 * symbol_x + (self.modules.len() + add_on_width) as f32;
+        let bars_top = VERTICAL_MARGIN + bearer;
+        let bars_bottom = bars_top + bar_height;
+        let frame_bottom = bars_bottom + bearer;
+        let guard_bottom = if include_text { bars_bottom + GUARD_EXTENSION } else { bars_bottom };
+        let baseline = frame_bottom + TEXT_SIZE;
+        let height = if include_text { baseline + VERTICAL_MARGIN } else { frame_bottom + VERTICAL_MARGIN };
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+            w = width * unit,
+            h = height * unit
+        );
+        let _ = write!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, width * unit, height * unit, background);
+        let _ = write!(svg, r#"<g fill="{}" shape-rendering="crispEdges">"#, foreground);
+
+        // Barras: una rect por barra (racha de módulos), no por módulo
+        let extended = self.extended_ranges();
+        for (start, len) in bar_runs(&self.modules, &extended) {
+            let bottom = if extended.iter().any(|r| r.contains(&start)) { guard_bottom } else { bars_bottom };
+            push_rect(&mut svg, unit, symbol_x + start as f32, bars_top, len as f32, bottom - bars_top);
+        }
+
+        // Add-on: barras más bajas con sus dígitos encima
+        let add_on_x = symbol_x + (self.modules.len() + ADD_ON_GAP) as f32;
+        if let Some(add_on) = &self.add_on {
+            let top = if include_text { bars_top + TEXT_SIZE + 1.0 } else { bars_top };
+            for (start, len) in bar_runs(add_on, &[]) {
+                push_rect(&mut svg, unit, add_on_x + start as f32, top, len as f32, guard_bottom - top);
+            }
+        }
+
+        // Barras portadoras: marco completo alrededor de las barras y la zona silenciosa
+        if bearer > 0.0 {
+            let inner_height = frame_bottom - VERTICAL_MARGIN - 2.0 * bearer;
+            push_rect(&mut svg, unit, 0.0, VERTICAL_MARGIN, width, bearer);
+            push_rect(&mut svg, unit, 0.0, frame_bottom - bearer, width, bearer);
+            push_rect(&mut svg, unit, 0.0, bars_top, bearer, inner_height);
+            push_rect(&mut svg, unit, width - bearer, bars_top, bearer, inner_height);
+        }
+        svg.push_str("</g>");
+
+        if include_text {
+            let text = escape_text(&self.content);
+            match self.text_positions() {
+                Some(positions) => {
+                    // Un carácter por posición: los grupos quedan bajo sus mitades del símbolo
+                    let xs: Vec<String> = positions
+                        .iter()
+                        .take(self.content.chars().count())
+                        .map(|x| ((symbol_x + x) * unit).to_string())
+                        .collect();
+                    push_text(&mut svg, unit, foreground, &xs.join(" "), baseline, &text);
+                }
+                None => {
+                    let center = ((symbol_x + self.modules.len() as f32 / 2.0) * unit).to_string();
+                    push_text(&mut svg, unit, foreground, &center, baseline, &text);
+                }
+            }
+            if let (Some(add_on), Some(digits)) = (&self.add_on, &self.add_on_text) {
+                let center = ((add_on_x + add_on.len() as f32 / 2.0) * unit).to_string();
+                push_text(&mut svg, unit, foreground, &center, bars_top + TEXT_SIZE, &escape_text(digits));
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Rachas de barras (inicio, ancho); se cortan en los bordes de las zonas prolongadas
+fn bar_runs(modules: &[bool], extended: &[Range<usize>]) -> Vec<(usize, usize)> {
+    let in_extended = |i: usize| extended.iter().any(|r| r.contains(&i));
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < modules.len() {
+        if !modules[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < modules.len() && modules[i] && in_extended(i) == in_extended(start) {
+            i += 1;
+        }
+        runs.push((start, i - start));
+    }
+    runs
+}
+
+fn push_rect(svg: &mut String, unit: f32, x: f32, y: f32, width: f32, height: f32) {
+    let _ = write!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+        x * unit, y * unit, width * unit, height * unit
+    );
+}
+
+fn push_text(svg: &mut String, unit: f32, fill: &str, x: &str, baseline: f32, text: &str) {
+    let _ = write!(
+        svg,
+        r#"<text x="{}" y="{}" font-family="{}" font-size="{}" text-anchor="middle" fill="{}">{}</text>"#,
+        x, baseline * unit, FONT_FAMILY, TEXT_SIZE * unit, fill, text
+    );
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Formato de rxing equivalente (UPC-A se lee como tal aunque se codifique como EAN-13)
+fn barcode_format(symbology: LinearSymbology) -> BarcodeFormat {
+    match symbology {
+        LinearSymbology::Code128 => BarcodeFormat::CODE_128,
+        LinearSymbology::Ean8 => BarcodeFormat::EAN_8,
+        LinearSymbology::Ean13 => BarcodeFormat::EAN_13,
+        LinearSymbology::UpcA => BarcodeFormat::UPC_A,
+        LinearSymbology::UpcE => BarcodeFormat::UPC_E,
+        LinearSymbology::Itf | LinearSymbology::Itf14 => BarcodeFormat::ITF,
+        LinearSymbology::Code39 => BarcodeFormat::CODE_39,
+        LinearSymbology::Code93 => BarcodeFormat::CODE_93,
+        LinearSymbology::Codabar => BarcodeFormat::CODABAR,
+    }
+}
+
+/// Lee un código de barras lineal de un SVG renderizado: contenido y add-on, si lo hay.
+/// rxing 0.7 no localiza los add-ons de 2 dígitos: solo devuelve los de 5.
+pub fn decode_svg(svg: &str, symbology: LinearSymbology) -> QrResult<(String, Option<String>)> {
+    let raster = super::raster::render_svg(svg, DECODE_WIDTH)?;
+    let rgb = super::raster::flatten_on_white(&raster.pixels);
+    let image = image::RgbImage::from_raw(raster.width, raster.height, rgb)
+        .ok_or_else(|| QrError::DecodingError("Rasterización SVG inválida".to_string()))?;
+    let luma = image::DynamicImage::ImageRgb8(image).to_luma8();
+
+    let format = barcode_format(symbology);
+    let mut hints = DecodeHints {
+        TryHarder: Some(true),
+        PossibleFormats: Some(HashSet::from([format])),
+        ..Default::default()
+    };
+    let result = rxing::helpers::detect_in_luma_with_hints(
+        luma.as_raw().clone(), luma.width(), luma.height(), Some(format), &mut hints,
+    )
+    .map_err(|e| QrError::DecodingError(format!("No se pudo leer el código de barras: {}", e)))?;
+
+    let add_on = match result.getRXingResultMetadata().get(&RXingResultMetadataType::UPC_EAN_EXTENSION) {
+        Some(RXingResultMetadataValue::UpcEanExtension(extension)) => Some(extension.clone()),
+        _ => None,
+    };
+    Ok((result.getText().to_string(), add_on))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(symbology: LinearSymbology) -> LinearOptions {
+        LinearOptions { symbology, ..Default::default() }
+    }
+
+    #[test]
+    fn test_check_digits_and_lengths() {
+        let ean = encode("590123412345", &options(LinearSymbology::Ean13)).unwrap();
+        assert_eq!(ean.content, "5901234123457");
+        assert_eq!(ean.modules.len(), 95);
+        assert!(encode("5901234123450", &options(LinearSymbology::Ean13)).is_err());
+
+        let upc = encode("03600029145", &options(LinearSymbology::UpcA)).unwrap();
+        assert_eq!(upc.content, "036000291452");
+        assert_eq!(upc.modules.len(), 95);
+
+        let upce = encode("0123456", &options(LinearSymbology::UpcE)).unwrap();
+        assert_eq!(upce.content.len(), 8);
+        assert_eq!(upce.modules.len(), 51);
+        assert!(encode("2123456", &options(LinearSymbology::UpcE)).is_err());
+
+        let itf14 = encode("1540014128876", &options(LinearSymbology::Itf14)).unwrap();
+        assert_eq!(itf14.content, "15400141288763");
+        assert!(encode("12345", &options(LinearSymbology::Itf)).is_err());
+    }
+
+    #[test]
+    fn test_add_on_patterns() {
+        // EAN-2 "12": 12 % 4 = 0 → LL; EAN-5 "52495": control 1 → GLGLL
+        let two = encode_add_on("12").unwrap();
+        assert_eq!(two.len(), 4 + 7 + 2 + 7);
+        let five = encode_add_on("52495").unwrap();
+        assert_eq!(five.len(), 4 + 5 * 7 + 4 * 2);
+        let as_string: String = five.iter().map(|&b| if b { '1' } else { '0' }).collect();
+        assert!(as_string.starts_with(&format!("1011{}01{}", G_PATTERNS[5], L_PATTERNS[2])));
+
+        assert!(encode_add_on("123").is_err());
+        let code128 = LinearOptions { add_on: Some("12".to_string()), ..options(LinearSymbology::Code128) };
+        assert!(encode("ABC", &code128).is_err());
+    }
+
+    #[test]
+    fn test_svg_layout() {
+        let options = LinearOptions { bar_height: Some(40.0), ..options(LinearSymbology::Ean13) };
+        let barcode = encode("5901234123457", &options).unwrap();
+        let svg = barcode.to_svg(1, &options, None, None);
+
+        // 30 barras (3 + 3 de guardas laterales, 2 centrales y 2 por dígito)
+        assert_eq!(svg.matches("<rect").count(), 1 + 30);
+        assert!(svg.contains(r#"height="45""#), "las guardas se prolongan bajo el texto");
+        assert!(svg.contains(r#"height="40""#));
+        assert!(svg.contains(">5901234123457</text>"));
+
+        let plain = LinearOptions { include_text: Some(false), ..options.clone() };
+        assert!(!barcode.to_svg(1, &plain, None, None).contains("<text"));
+
+        let itf = LinearOptions { symbology: LinearSymbology::Itf14, ..Default::default() };
+        let svg = encode("15400141288763", &itf).unwrap().to_svg(1, &itf, None, None);
+        assert!(svg.contains(&format!(r#"height="{}""#, BEARER_WIDTH)), "ITF-14 lleva barras portadoras");
+    }
+
+    #[test]
+    fn test_rendered_barcodes_decode() {
+        let cases = [
+            (LinearSymbology::Ean13, "5901234123457", "5901234123457"),
+            (LinearSymbology::UpcA, "03600029145", "036000291452"),
+            (LinearSymbology::Ean8, "9638507", "96385074"),
+            (LinearSymbology::Itf14, "1540014128876", "15400141288763"),
+            (LinearSymbology::Code128, "QREABLE-128", "QREABLE-128"),
+            (LinearSymbology::Code39, "LOTE 42", "LOTE 42"),
+        ];
+        for (symbology, data, expected) in cases {
+            let options = options(symbology);
+            let svg = encode(data, &options).unwrap().to_svg(MODULE_UNITS, &options, None, None);
+            let (content, _) = decode_svg(&svg, symbology).unwrap();
+            assert_eq!(content, expected, "{:?}", symbology);
+        }
+    }
+
+    #[test]
+    fn test_add_on_decodes() {
+        let options = LinearOptions { add_on: Some("52495".to_string()), ..options(LinearSymbology::Ean13) };
+        let barcode = encode("9781234567897", &options).unwrap();
+        let svg = barcode.to_svg(MODULE_UNITS, &options, None, None);
+        let (content, add_on) = decode_svg(&svg, LinearSymbology::Ean13).unwrap();
+        assert_eq!(content, "9781234567897");
+        assert_eq!(add_on.as_deref(), Some("52495"));
+        assert!(svg.contains(">52495</text>"));
+    }
+}
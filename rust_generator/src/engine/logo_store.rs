@@ -0,0 +1,540 @@
+// engine/logo_store.rs - Biblioteca persistente de logos
+// Los logos se suben una vez y se referencian por `logo_id` (SHA-256 del archivo original).
+// Al subirlos se pre-calculan variantes redimensionadas en RGBA crudo; de los SVG se
+// conserva además el original para servirlo como vector. Cada subida
+// adquiere una referencia propia (`reference_id`) que solo su titular puede liberar;
+// al liberar la última el logo se borra del disco.
+
+use chrono::{DateTime, Utc};
+use image::{DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::error::{QrError, QrResult};
+use super::svg_logo::{VectorLogo, DEFAULT_SVG_LOGO_RASTER_PX};
+
+/// Lados (px) de las variantes pre-calculadas; nunca se amplía el original
+pub const LOGO_VARIANT_SIZES: [u32; 4] = [64, 128, 256, 512];
+
+/// Tamaño máximo del archivo subido
+pub const MAX_LOGO_BYTES: usize = 10 * 1024 * 1024;
+
+/// Lado máximo del logo original (px)
+pub const MAX_LOGO_DIMENSION: u32 = 4096;
+
+/// Directorio por defecto si no se define `LOGO_STORE_DIR`
+pub const DEFAULT_LOGO_STORE_DIR: &str = "data/logos";
+
+const ORIGINAL_FILE: &str = "original";
+const META_FILE: &str = "meta.json";
+const META_TMP_FILE: &str = "meta.json.tmp";
+
+/// Marca los directorios creados por el almacén: la limpieza no toca nada sin ella
+const MARKER_FILE: &str = ".qreable-logo";
+
+/// Sufijo de los directorios de una subida en curso (`<logo_id>.<uuid>.partial`)
+const STAGING_SUFFIX: &str = ".partial";
+
+/// Logo almacenado
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogoRecord {
+    /// SHA-256 en hexadecimal del archivo original
+    pub logo_id: String,
+    pub width: u32,
+    pub height: u32,
+    /// Tamaño del archivo original
+    pub byte_size: usize,
+    /// El original es un SVG: `load_vector` lo devuelve saneado para incrustarlo como vector
+    #[serde(default)]
+    pub vector: bool,
+    /// Subidas que aún no se han liberado
+    pub refs: u32,
+    /// Variantes pre-calculadas, de menor a mayor
+    pub variants: Vec<LogoVariant>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Variante redimensionada: cabe en `size`×`size` conservando la proporción
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogoVariant {
+    pub size: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl LogoVariant {
+    fn file_name(&self) -> String {
+        format!("{}.rgba", self.size)
+    }
+}
+
+/// Referencia adquirida al subir un logo; `reference_id` es necesario para liberarla
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogoReference {
+    pub reference_id: String,
+    pub record: LogoRecord,
+}
+
+/// Metadatos en disco: el registro público y las referencias vivas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredLogo {
+    #[serde(flatten)]
+    record: LogoRecord,
+    references: Vec<String>,
+}
+
+impl StoredLogo {
+    /// Adquiere una referencia nueva
+    fn acquire(&mut self) -> LogoReference {
+        let reference_id = uuid::Uuid::new_v4().simple().to_string();
+        self.references.push(reference_id.clone());
+        self.record.refs = self.references.len() as u32;
+        LogoReference { reference_id, record: self.record.clone() }
+    }
+}
+
+/// Almacén de logos direccionado por contenido en disco local
+pub struct LogoStore {
+    root: PathBuf,
+    entries: Mutex<HashMap<String, StoredLogo>>,
+}
+
+impl LogoStore {
+    /// Abre (o crea) el almacén y descarta lo que quedó a medias o sin referencias
+    pub fn open(root: impl AsRef<Path>) -> QrResult<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+
+        let mut entries = HashMap::new();
+        for entry in fs::read_dir(&root)? {
+            let path = entry?.path();
+            let Some(stored) = fs::read(path.join(META_FILE))
+                .ok()
+                .and_then(|meta| serde_json::from_slice::<StoredLogo>(&meta).ok())
+            else {
+                continue;
+            };
+            let complete = !stored.references.is_empty()
+                && path.file_name().and_then(|n| n.to_str()) == Some(stored.record.logo_id.as_str())
+                && stored.record.variants.iter().all(|v| path.join(v.file_name()).is_file());
+            if complete {
+                entries.insert(stored.record.logo_id.clone(), stored);
+            }
+        }
+
+        let store = Self { root, entries: Mutex::new(entries) };
+        // Al abrir no hay subidas en curso: también se descartan las interrumpidas
+        let removed = store.remove_orphans(true)?;
+        if removed > 0 {
+            tracing::info!("Logo store cleanup removed {} orphaned entries", removed);
+        }
+        Ok(store)
+    }
+
+    /// Guarda un logo (PNG, JPEG, WebP... o SVG) y adquiere una referencia. Si el contenido
+    /// ya estaba almacenado solo se suma la referencia.
+    pub fn put(&self, bytes: &[u8]) -> QrResult<LogoReference> {
+        if bytes.is_empty() || bytes.len() > MAX_LOGO_BYTES {
+            return Err(QrError::LogoError(format!(
+                "El logo debe ocupar entre 1 byte y {} MB", MAX_LOGO_BYTES / (1024 * 1024)
+            )));
+        }
+        let logo_id: String = Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect();
+
+        if let Some(reference) = self.acquire_existing(&mut *self.lock()?, &logo_id)? {
+            return Ok(reference);
+        }
+
+        // Decodificación, variantes y escritura sin bloquear el índice: en un directorio
+        // propio de esta subida que se publica con un rename
+        let source = LogoSource::parse(bytes)?;
+
+        let staging = self.root.join(format!("{}.{}{}", logo_id, uuid::Uuid::new_v4().simple(), STAGING_SUFFIX));
+        fs::create_dir_all(&staging)?;
+        fs::write(staging.join(MARKER_FILE), b"")?;
+        fs::write(staging.join(ORIGINAL_FILE), bytes)?;
+
+        // Variantes menores que el original y el original mismo como la mayor
+        let largest = source.largest_side();
+        let mut variants = Vec::new();
+        for size in LOGO_VARIANT_SIZES.iter().copied().filter(|&s| s < largest).chain([largest]) {
+            let resized = source.render(size)?;
+            let variant = LogoVariant { size, width: resized.width(), height: resized.height() };
+            fs::write(staging.join(variant.file_name()), resized.as_raw())?;
+            variants.push(variant);
+        }
+        // La mayor variante tiene las dimensiones del original (o de su rasterizado)
+        let (width, height) = variants.last().map_or((0, 0), |v| (v.width, v.height));
+
+        let mut entries = self.lock()?;
+        // Otra subida del mismo contenido pudo publicarse mientras tanto
+        if let Some(reference) = self.acquire_existing(&mut entries, &logo_id)? {
+            Self::remove_entry(&staging)?;
+            return Ok(reference);
+        }
+
+        let mut stored = StoredLogo {
+            record: LogoRecord {
+                logo_id: logo_id.clone(),
+                width,
+                height,
+                byte_size: bytes.len(),
+                vector: matches!(source, LogoSource::Vector(_)),
+                refs: 0,
+                variants,
+                created_at: Utc::now(),
+            },
+            references: Vec::new(),
+        };
+        let reference = stored.acquire();
+        // Los metadatos se escriben al final: sin ellos la entrada se considera incompleta
+        Self::write_meta(&staging, &stored)?;
+        fs::rename(&staging, self.root.join(&logo_id))?;
+        entries.insert(logo_id, stored);
+        Ok(reference)
+    }
+
+    /// Registro de un logo almacenado
+    pub fn get(&self, logo_id: &str) -> QrResult<Option<LogoRecord>> {
+        Ok(self.lock()?.get(logo_id).map(|stored| stored.record.clone()))
+    }
+
+    /// Carga la menor variante de al menos `target_px` de lado (o la mayor disponible)
+    pub fn load(&self, logo_id: &str, target_px: u32) -> QrResult<DynamicImage> {
+        let variant = {
+            let entries = self.lock()?;
+            let record = &entries.get(logo_id).ok_or_else(|| {
+                QrError::LogoError(format!("Logo no encontrado: {}", logo_id))
+            })?.record;
+            record.variants.iter()
+                .find(|v| v.size >= target_px)
+                .or(record.variants.last())
+                .copied()
+                .ok_or_else(|| QrError::LogoError(format!("Logo sin variantes: {}", logo_id)))?
+        };
+
+        let raw = fs::read(self.root.join(logo_id).join(variant.file_name()))?;
+        let pixels = RgbaImage::from_raw(variant.width, variant.height, raw).ok_or_else(|| {
+            QrError::LogoError(format!("Variante {} del logo {} corrupta", variant.size, logo_id))
+        })?;
+        Ok(DynamicImage::ImageRgba8(pixels))
+    }
+
+    /// Versión vectorial saneada de un logo subido como SVG (None si el original es raster)
+    pub fn load_vector(&self, logo_id: &str) -> QrResult<Option<VectorLogo>> {
+        let vector = self.lock()?.get(logo_id).ok_or_else(|| {
+            QrError::LogoError(format!("Logo no encontrado: {}", logo_id))
+        })?.record.vector;
+        if !vector {
+            return Ok(None);
+        }
+        let original = fs::read(self.root.join(logo_id).join(ORIGINAL_FILE))?;
+        VectorLogo::sanitize(&original).map(Some)
+    }
+
+    /// Libera la referencia `reference_id` y devuelve las restantes; con la última se
+    /// borra el logo. Solo se aceptan referencias adquiridas sobre ese logo.
+    pub fn release(&self, logo_id: &str, reference_id: &str) -> QrResult<u32> {
+        let mut entries = self.lock()?;
+        let stored = entries.get_mut(logo_id).ok_or_else(|| {
+            QrError::LogoError(format!("Logo no encontrado: {}", logo_id))
+        })?;
+        let position = stored.references.iter().position(|r| r == reference_id).ok_or_else(|| {
+            QrError::LogoError(format!("La referencia no pertenece al logo {}", logo_id))
+        })?;
+        stored.references.swap_remove(position);
+        stored.record.refs = stored.references.len() as u32;
+
+        let refs = stored.record.refs;
+        let dir = self.root.join(logo_id);
+        if refs == 0 {
+            entries.remove(logo_id);
+            Self::remove_entry(&dir)?;
+        } else {
+            Self::write_meta(&dir, stored)?;
+        }
+        Ok(refs)
+    }
+
+    /// Borra los directorios del almacén que no corresponden a un logo registrado
+    /// (liberaciones a medias); devuelve cuántos se eliminaron. Las subidas en curso
+    /// solo se descartan al abrir el almacén.
+    pub fn cleanup(&self) -> QrResult<usize> {
+        self.remove_orphans(false)
+    }
+
+    /// Solo considera directorios con la marca del almacén y nombre de logo (o de subida
+    /// en curso si `include_staging`); lo demás en la raíz no se toca
+    fn remove_orphans(&self, include_staging: bool) -> QrResult<usize> {
+        let entries = self.lock()?;
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let orphan = if is_logo_id(&name) {
+                !entries.contains_key(&name)
+            } else {
+                include_staging && is_staging_name(&name)
+            };
+            let path = entry.path();
+            if orphan && entry.file_type()?.is_dir() && path.join(MARKER_FILE).is_file() && Self::remove_entry(&path)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Borra un directorio del almacén sin recursión: solo los archivos que escribe el
+    /// propio almacén. Si queda algo ajeno el directorio se conserva y se devuelve false.
+    fn remove_entry(dir: &Path) -> QrResult<bool> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let own = entry.file_name().to_str().is_some_and(is_store_file);
+            if own && !entry.file_type()?.is_dir() {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        match fs::remove_dir(dir) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                tracing::warn!("Logo store entry {} kept: {}", dir.display(), e);
+                Ok(false)
+            }
+        }
+    }
+
+    fn acquire_existing(
+        &self,
+        entries: &mut HashMap<String, StoredLogo>,
+        logo_id: &str,
+    ) -> QrResult<Option<LogoReference>> {
+        let Some(stored) = entries.get_mut(logo_id) else {
+            return Ok(None);
+        };
+        let reference = stored.acquire();
+        Self::write_meta(&self.root.join(logo_id), stored)?;
+        Ok(Some(reference))
+    }
+
+    fn write_meta(dir: &Path, stored: &StoredLogo) -> QrResult<()> {
+        let json = serde_json::to_vec(stored)
+            .map_err(|e| QrError::InternalError(format!("Error serializando logo: {}", e)))?;
+        // Escritura atómica: un corte deja los metadatos anteriores o los nuevos
+        let tmp = dir.join(META_TMP_FILE);
+        fs::write(&tmp, json)?;
+        fs::rename(tmp, dir.join(META_FILE))?;
+        Ok(())
+    }
+
+    fn lock(&self) -> QrResult<std::sync::MutexGuard<'_, HashMap<String, StoredLogo>>> {
+        self.entries.lock().map_err(|_| QrError::InternalError("Índice de logos bloqueado".to_string()))
+    }
+}
+
+/// Original de una subida, ya comprobado
+enum LogoSource {
+    Raster(DynamicImage),
+    Vector(VectorLogo),
+}
+
+impl LogoSource {
+    /// Reconoce el SVG por su raíz. De un raster se leen primero las dimensiones de la
+    /// cabecera: lo que exceda `MAX_LOGO_DIMENSION` se rechaza antes de decodificarlo
+    fn parse(bytes: &[u8]) -> QrResult<Self> {
+        let is_svg = std::str::from_utf8(bytes)
+            .is_ok_and(|text| text.trim_start().starts_with('<') && text.contains("<svg"));
+        if is_svg {
+            return VectorLogo::sanitize(bytes).map(Self::Vector);
+        }
+
+        let invalid = |e: image::ImageError| QrError::LogoError(format!("Error cargando imagen: {}", e));
+        let (width, height) = image::io::Reader::new(Cursor::new(bytes))
+            .with_guessed_format()?
+            .into_dimensions()
+            .map_err(invalid)?;
+        if width.max(height) > MAX_LOGO_DIMENSION {
+            return Err(QrError::LogoError(format!(
+                "El logo mide {}x{} px (máximo: {} px por lado)", width, height, MAX_LOGO_DIMENSION
+            )));
+        }
+        image::load_from_memory(bytes).map(Self::Raster).map_err(invalid)
+    }
+
+    /// Lado de la mayor variante: el original o, para un SVG, su rasterizado por defecto
+    fn largest_side(&self) -> u32 {
+        match self {
+            Self::Raster(logo) => logo.width().max(logo.height()),
+            Self::Vector(_) => DEFAULT_SVG_LOGO_RASTER_PX,
+        }
+    }
+
+    /// Variante que cabe en `size`×`size`; el SVG se rasteriza a cada tamaño
+    fn render(&self, size: u32) -> QrResult<RgbaImage> {
+        Ok(match self {
+            Self::Raster(logo) if size >= self.largest_side() => logo.to_rgba8(),
+            Self::Raster(logo) => logo.resize(size, size, image::imageops::FilterType::Lanczos3).to_rgba8(),
+            Self::Vector(vector) => vector.rasterize(size)?.to_rgba8(),
+        })
+    }
+}
+
+/// SHA-256 en hexadecimal (minúsculas)
+fn is_logo_id(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// `<logo_id>.<uuid>.partial`
+fn is_staging_name(name: &str) -> bool {
+    name.strip_suffix(STAGING_SUFFIX)
+        .and_then(|rest| rest.split_once('.'))
+        .is_some_and(|(logo_id, nonce)| is_logo_id(logo_id) && !nonce.is_empty() && nonce.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Archivos que escribe el almacén dentro del directorio de un logo
+fn is_store_file(name: &str) -> bool {
+    matches!(name, ORIGINAL_FILE | META_FILE | META_TMP_FILE | MARKER_FILE)
+        || name.strip_suffix(".rgba").is_some_and(|size| !size.is_empty() && size.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::GenericImageView;
+
+    fn temp_store() -> (LogoStore, PathBuf) {
+        let root = std::env::temp_dir().join(format!("qreable_logos_{}", uuid::Uuid::new_v4()));
+        (LogoStore::open(&root).unwrap(), root)
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_fn(width, height, |x, y| image::Rgba([(x % 256) as u8, (y % 256) as u8, 90, 255]));
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_put_precomputes_variants() {
+        let (store, root) = temp_store();
+        let record = store.put(&png(300, 150)).unwrap().record;
+
+        assert_eq!(record.logo_id.len(), 64);
+        let sizes: Vec<u32> = record.variants.iter().map(|v| v.size).collect();
+        assert_eq!(sizes, vec![64, 128, 256, 300]);
+        // La proporción se conserva
+        assert_eq!((record.variants[0].width, record.variants[0].height), (64, 32));
+
+        let logo = store.load(&record.logo_id, 100).unwrap();
+        assert_eq!(logo.dimensions(), (128, 64));
+        assert_eq!(store.load(&record.logo_id, 1000).unwrap().dimensions(), (300, 150));
+        assert!(matches!(store.load("desconocido", 64), Err(QrError::LogoError(_))));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_ref_counting_and_persistence() {
+        let (store, root) = temp_store();
+        let bytes = png(80, 80);
+        let first = store.put(&bytes).unwrap();
+        let second = store.put(&bytes).unwrap();
+        let logo_id = first.record.logo_id.clone();
+        assert_eq!(logo_id, second.record.logo_id);
+        assert_eq!(second.record.refs, 2);
+        assert_ne!(first.reference_id, second.reference_id);
+
+        let reopened = LogoStore::open(&root).unwrap();
+        assert_eq!(reopened.get(&logo_id).unwrap().unwrap().refs, 2);
+
+        // Cada referencia solo la libera quien la adquirió, y una sola vez
+        assert!(matches!(reopened.release(&logo_id, "ajena"), Err(QrError::LogoError(_))));
+        assert_eq!(reopened.release(&logo_id, &first.reference_id).unwrap(), 1);
+        assert!(matches!(reopened.release(&logo_id, &first.reference_id), Err(QrError::LogoError(_))));
+        assert_eq!(reopened.release(&logo_id, &second.reference_id).unwrap(), 0);
+        assert!(reopened.get(&logo_id).unwrap().is_none());
+        assert!(!root.join(&logo_id).exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_only_removes_own_entries() {
+        let (store, root) = temp_store();
+        let logo_id = store.put(&png(80, 80)).unwrap().record.logo_id;
+
+        // Subida interrumpida: marcada y con nombre de subida en curso
+        let staging = root.join(format!("{}.0123abcd{}", logo_id, STAGING_SUFFIX));
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join(MARKER_FILE), b"").unwrap();
+        fs::write(staging.join(ORIGINAL_FILE), b"...").unwrap();
+        // Directorio ajeno en la raíz y otro con nombre de logo pero sin marca
+        fs::create_dir_all(root.join("ajeno")).unwrap();
+        fs::write(root.join("ajeno").join("datos.txt"), b"no tocar").unwrap();
+        let unmarked = root.join("f".repeat(64));
+        fs::create_dir_all(&unmarked).unwrap();
+        // Entrada propia sin registrar que contiene un archivo ajeno: no se recorre
+        let foreign = root.join("e".repeat(64));
+        fs::create_dir_all(foreign.join("sub")).unwrap();
+        fs::write(foreign.join(MARKER_FILE), b"").unwrap();
+        fs::write(foreign.join(META_FILE), b"{}").unwrap();
+        fs::write(foreign.join("sub").join("datos.txt"), b"no tocar").unwrap();
+
+        let reopened = LogoStore::open(&root).unwrap();
+        assert!(!staging.exists());
+        assert!(root.join("ajeno").join("datos.txt").is_file());
+        assert!(unmarked.is_dir());
+        assert!(foreign.join("sub").join("datos.txt").is_file());
+        assert!(!foreign.join(META_FILE).exists());
+        assert_eq!(reopened.get(&logo_id).unwrap().unwrap().refs, 1);
+        assert!(root.join(&logo_id).join(ORIGINAL_FILE).is_file());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_invalid_uploads() {
+        let (store, root) = temp_store();
+        assert!(matches!(store.put(b""), Err(QrError::LogoError(_))));
+        assert!(matches!(store.put(b"no es una imagen"), Err(QrError::LogoError(_))));
+
+        // PNG de 1x1 px que declara 60000x60000: se rechaza por sus dimensiones
+        let mut oversized = png(1, 1);
+        oversized[16..20].copy_from_slice(&60000u32.to_be_bytes());
+        oversized[20..24].copy_from_slice(&60000u32.to_be_bytes());
+        let mut crc = flate2::Crc::new();
+        crc.update(&oversized[12..29]);
+        oversized[29..33].copy_from_slice(&crc.sum().to_be_bytes());
+        match store.put(&oversized) {
+            Err(QrError::LogoError(message)) => assert!(message.contains("60000x60000"), "{}", message),
+            other => panic!("{:?}", other),
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_svg_logo_keeps_vector() {
+        let (store, root) = temp_store();
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20" fill="#0A0"/></svg>"##;
+        let record = store.put(svg).unwrap().record;
+
+        assert!(record.vector);
+        assert_eq!((record.width, record.height), (DEFAULT_SVG_LOGO_RASTER_PX, DEFAULT_SVG_LOGO_RASTER_PX / 2));
+        assert_eq!(store.load(&record.logo_id, 100).unwrap().dimensions(), (128, 64));
+        let vector = store.load_vector(&record.logo_id).unwrap().unwrap();
+        assert!(vector.markup.contains("<path"));
+
+        let raster = store.put(&png(80, 80)).unwrap().record;
+        assert!(!raster.vector && store.load_vector(&raster.logo_id).unwrap().is_none());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
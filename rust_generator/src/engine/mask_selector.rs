@@ -0,0 +1,303 @@
+// engine/mask_selector.rs - Selección de máscara QR según el diseño
+// ISO/IEC 18004 elige la máscara con menor penalización (bloques, rachas, patrones
+// parecidos a los de búsqueda y balance). Aquí se puntúan además las 8 máscaras
+// frente al patrón de datos y los logos, y se elige la mejor entre las que no
+// superan el techo de penalización.
+
+use super::customizer::{LogoArea, QrCustomizer};
+use super::error::{QrError, QrResult};
+use super::types::{DataPattern, LogoOptions, QrCustomization};
+use super::zones::calculate_untouchable_zones;
+
+/// Penalización máxima admitida respecto a la mejor máscara (0.15 = 15% más)
+pub const DEFAULT_PENALTY_TOLERANCE: f32 = 0.15;
+
+/// Peso de cada módulo oscuro que el logo borra: es un error de lectura seguro
+const LOGO_MODULE_WEIGHT: f32 = 4.0;
+
+const PENALTY_N1: u32 = 3;
+const PENALTY_N2: u32 = 3;
+const PENALTY_N3: u32 = 40;
+const PENALTY_N4: u32 = 10;
+
+/// Cómo se elige la máscara de un símbolo QR
+#[derive(Debug, Clone, Copy)]
+pub enum MaskChoice<'a> {
+    /// Menor penalización de ISO/IEC 18004
+    Standard,
+    /// Máscara fija (0-7)
+    Fixed(u8),
+    /// Mejor puntuación de diseño bajo el techo de penalización
+    Design(&'a DesignMaskSelector),
+}
+
+/// Puntuación de una máscara
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaskScore {
+    pub mask: u8,
+    /// Penalización de ISO/IEC 18004
+    pub penalty: u32,
+    /// Módulos oscuros bajo los logos
+    pub logo_dark_modules: usize,
+    /// Módulos oscuros sin vecinos oscuros (fuera de los logos)
+    pub isolated_modules: usize,
+    /// Puntuación de diseño: menor es mejor
+    pub design_score: f32,
+}
+
+/// Elige la máscara que mejor encaja con el patrón de datos y los logos
+#[derive(Debug, Clone)]
+pub struct DesignMaskSelector {
+    pattern: DataPattern,
+    logos: Vec<LogoOptions>,
+    penalty_tolerance: f32,
+}
+
+impl DesignMaskSelector {
+    pub fn new(pattern: Option<DataPattern>) -> Self {
+        Self {
+            pattern: pattern.unwrap_or(DataPattern::Square),
+            logos: Vec::new(),
+            penalty_tolerance: DEFAULT_PENALTY_TOLERANCE,
+        }
+    }
+
+    /// Selector con el patrón de datos, el logo y los overlays de la personalización
+    pub fn for_customization(customization: &QrCustomization) -> Self {
+        Self::new(customization.data_pattern).with_logos(customization.logos().cloned().collect())
+    }
+
+    /// Logos en orden de colocación; su área se calcula para cada tamaño de símbolo
+    pub fn with_logos(mut self, logos: Vec<LogoOptions>) -> Self {
+        self.logos = logos;
+        self
+    }
+
+    pub fn with_penalty_tolerance(mut self, penalty_tolerance: f32) -> Self {
+        self.penalty_tolerance = penalty_tolerance.max(0.0);
+        self
+    }
+
+    /// Áreas que ocuparán los logos en un símbolo de `size` módulos
+    pub fn logo_areas(&self, size: usize) -> QrResult<Vec<LogoArea>> {
+        let version = (size.saturating_sub(17) / 4) as u8;
+        QrCustomizer::plan_logo_areas(size, size, &self.logos, &calculate_untouchable_zones(version))
+    }
+
+    /// Puntúa la matriz (ya enmascarada) de una máscara
+    pub fn score(&self, mask: u8, matrix: &[Vec<bool>], logo_areas: &[LogoArea]) -> MaskScore {
+        let under_logo = |x: usize, y: usize| {
+            logo_areas.iter().any(|area| {
+                x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+            })
+        };
+        let dark = |x: usize, y: usize| matrix.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false);
+
+        let mut logo_dark_modules = 0;
+        let mut isolated_modules = 0;
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, &module)| module) {
+                if under_logo(x, y) {
+                    logo_dark_modules += 1;
+                } else if !(dark(x + 1, y) || dark(x, y + 1)
+                    || (x > 0 && dark(x - 1, y)) || (y > 0 && dark(x, y - 1)))
+                {
+                    isolated_modules += 1;
+                }
+            }
+        }
+
+        MaskScore {
+            mask,
+            penalty: penalty_score(matrix),
+            logo_dark_modules,
+            isolated_modules,
+            design_score: LOGO_MODULE_WEIGHT * logo_dark_modules as f32
+                + isolated_weight(self.pattern) * isolated_modules as f32,
+        }
+    }
+
+    /// Elige entre las matrices de las máscaras 0-7 (en orden)
+    pub fn select(&self, candidates: &[Vec<Vec<bool>>]) -> QrResult<MaskScore> {
+        let size = candidates.first().map_or(0, Vec::len);
+        let logo_areas = self.logo_areas(size)?;
+        let scores: Vec<MaskScore> = candidates
+            .iter()
+            .enumerate()
+            .map(|(mask, matrix)| self.score(mask as u8, matrix, &logo_areas))
+            .collect();
+
+        // Techo: la penalización de la mejor máscara estándar más la tolerancia
+        let best_penalty = scores.iter().map(|score| score.penalty).min().unwrap_or(0);
+        let ceiling = best_penalty as f32 * (1.0 + self.penalty_tolerance);
+        scores
+            .into_iter()
+            .filter(|score| score.penalty as f32 <= ceiling)
+            .min_by(|a, b| {
+                a.design_score
+                    .total_cmp(&b.design_score)
+                    .then(a.penalty.cmp(&b.penalty))
+                    .then(a.mask.cmp(&b.mask))
+            })
+            .ok_or_else(|| QrError::InternalError("Sin máscaras candidatas".to_string()))
+    }
+}
+
+/// Importancia de los módulos aislados según cómo dibuja el patrón cada módulo:
+/// en los patrones de figuras sueltas un módulo aislado se ve como una mota
+fn isolated_weight(pattern: DataPattern) -> f32 {
+    match pattern {
+        DataPattern::Dots | DataPattern::Star => 1.0,
+        DataPattern::Circular | DataPattern::Diamond | DataPattern::Cross | DataPattern::SquareSmall => 0.5,
+        _ => 0.0,
+    }
+}
+
+/// Penalización de ISO/IEC 18004 (8.8.2) de una matriz enmascarada; la misma que
+/// usa qrcodegen para elegir la máscara automática
+pub fn penalty_score(matrix: &[Vec<bool>]) -> u32 {
+    let size = matrix.len();
+    let mut result = 0;
+
+    // N1 y N3 por filas y por columnas
+    for (index, row) in matrix.iter().enumerate() {
+        result += line_penalty(row.iter().copied(), size);
+        result += line_penalty(matrix.iter().map(|column| column[index]), size);
+    }
+
+    // N2: bloques 2x2 del mismo color
+    for y in 0..size.saturating_sub(1) {
+        for x in 0..size - 1 {
+            let color = matrix[y][x];
+            if color == matrix[y][x + 1] && color == matrix[y + 1][x] && color == matrix[y + 1][x + 1] {
+                result += PENALTY_N2;
+            }
+        }
+    }
+
+    // N4: desviación de la proporción de oscuros respecto al 50%, en pasos del 5%
+    let dark = matrix.iter().flatten().filter(|&&module| module).count() as i64;
+    let total = (size * size).max(1) as i64;
+    let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+    result + k.max(0) as u32 * PENALTY_N4
+}
+
+/// Rachas de 5 o más (N1) y patrones 1:1:3:1:1 con 4 claros a un lado (N3) en una línea;
+/// la zona de silencio cuenta como claro
+fn line_penalty(line: impl Iterator<Item = bool>, size: usize) -> u32 {
+    let size = size as u32;
+    let mut result = 0;
+    let mut run_color = false;
+    let mut run_length = 0;
+    let mut history = [0u32; 7];
+
+    let push = |history: &mut [u32; 7], mut length: u32| {
+        if history[0] == 0 {
+            length += size;
+        }
+        history.copy_within(0..6, 1);
+        history[0] = length;
+    };
+    let patterns = |history: &[u32; 7]| {
+        let n = history[1];
+        let core = n > 0 && history[2] == n && history[3] == n * 3 && history[4] == n && history[5] == n;
+        u32::from(core && history[0] >= n * 4 && history[6] >= n)
+            + u32::from(core && history[6] >= n * 4 && history[0] >= n)
+    };
+
+    for color in line {
+        if color == run_color {
+            run_length += 1;
+            if run_length == 5 {
+                result += PENALTY_N1;
+            } else if run_length > 5 {
+                result += 1;
+            }
+        } else {
+            push(&mut history, run_length);
+            if !run_color {
+                result += patterns(&history) * PENALTY_N3;
+            }
+            run_color = color;
+            run_length = 1;
+        }
+    }
+
+    // Cierre con la zona de silencio
+    if run_color {
+        push(&mut history, run_length);
+        run_length = 0;
+    }
+    push(&mut history, run_length + size);
+    result + patterns(&history) * PENALTY_N3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qrcodegen::{Mask, QrCode as QrCodeGen, QrCodeEcc, QrSegment, Version};
+
+    fn matrix(qr: &QrCodeGen) -> Vec<Vec<bool>> {
+        (0..qr.size()).map(|y| (0..qr.size()).map(|x| qr.get_module(x, y)).collect()).collect()
+    }
+
+    fn all_masks(data: &str, ecl: QrCodeEcc) -> Vec<QrCodeGen> {
+        let segments = QrSegment::make_segments(data);
+        (0..8)
+            .map(|mask| {
+                QrCodeGen::encode_segments_advanced(&segments, ecl, Version::MIN, Version::MAX, Some(Mask::new(mask)), true)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_penalty_matches_standard_selection() {
+        for data in ["https://qreable.com", "HELLO WORLD 0123456789", "Lorem ipsum dolor sit amet, consectetur adipiscing elit"] {
+            let automatic = QrCodeGen::encode_text(data, QrCodeEcc::Medium).unwrap();
+            let penalties: Vec<u32> = all_masks(data, QrCodeEcc::Medium).iter().map(|qr| penalty_score(&matrix(qr))).collect();
+            let best = penalties.iter().enumerate().min_by_key(|(_, &penalty)| penalty).unwrap().0;
+            assert_eq!(best as u8, automatic.mask().value(), "{}: {:?}", data, penalties);
+        }
+    }
+
+    #[test]
+    fn test_design_selection_respects_ceiling() {
+        let data = "https://qreable.com/mascara/diseno";
+        let candidates: Vec<Vec<Vec<bool>>> = all_masks(data, QrCodeEcc::High).iter().map(matrix).collect();
+        let logo = LogoOptions {
+            data: String::new(),
+            logo_id: None,
+            size_percentage: 25.0,
+            padding: 1,
+            background: None,
+            shape: crate::engine::types::LogoShape::Square,
+            placement: None,
+        };
+        let selector = DesignMaskSelector::new(Some(DataPattern::Dots)).with_logos(vec![logo]);
+        let areas = selector.logo_areas(candidates[0].len()).unwrap();
+        assert_eq!(areas.len(), 1);
+
+        let chosen = selector.select(&candidates).unwrap();
+        let scores: Vec<MaskScore> = candidates
+            .iter()
+            .enumerate()
+            .map(|(mask, matrix)| selector.score(mask as u8, matrix, &areas))
+            .collect();
+        let best_penalty = scores.iter().map(|score| score.penalty).min().unwrap();
+
+        // Dentro del techo, ninguna máscara admitida tiene mejor puntuación de diseño
+        assert!(chosen.penalty as f32 <= best_penalty as f32 * (1.0 + DEFAULT_PENALTY_TOLERANCE));
+        for score in scores.iter().filter(|score| score.penalty as f32 <= best_penalty as f32 * (1.0 + DEFAULT_PENALTY_TOLERANCE)) {
+            assert!(chosen.design_score <= score.design_score);
+        }
+
+        // Sin tolerancia solo entra la(s) de menor penalización
+        let strict = selector.clone().with_penalty_tolerance(0.0).select(&candidates).unwrap();
+        assert_eq!(strict.penalty, best_penalty);
+
+        // Sin logos ni patrón de motas no hay criterio de diseño: gana la penalización
+        let plain = DesignMaskSelector::new(None).select(&candidates).unwrap();
+        assert_eq!(plain.penalty, best_penalty);
+    }
+}
@@ -0,0 +1,556 @@
+// engine/matrix_codes.rs - Data Matrix (ISO/IEC 16022 y DMRE), Aztec (ISO/IEC 24778) y PDF417 (ISO/IEC 15438)
+// La codificación de alto nivel, la corrección de errores y la colocación son las de rxing;
+// aquí se eligen las dimensiones (incluidos los tamaños DMRE que su writer no ofrece) y se
+// construye la matriz de módulos que el generador estiliza como la de un QR
+
+use rxing::aztec::encoder::aztec_encoder;
+use rxing::common::CharacterSet;
+use rxing::datamatrix::encoder::{
+    error_correction, high_level_encoder, DefaultPlacement, SymbolInfo, SymbolInfoLookup, SymbolShapeHint,
+};
+use rxing::pdf417::encoder::PDF417;
+
+use super::constants::{get_aztec_layers, get_datamatrix_size, get_datamatrix_version};
+use super::error::{QrError, QrResult};
+use super::types::{DataMatrixShape, ErrorCorrectionLevel, MatrixCodeOptions, QrSymbology};
+
+/// Alto de cada fila PDF417 en módulos (mínimo de ISO/IEC 15438: 3X)
+pub const PDF417_ROW_HEIGHT: usize = 3;
+
+/// Patrón de parada PDF417 (18 módulos)
+const PDF417_STOP_PATTERN: [bool; 18] = [
+    true, true, true, true, true, true, true, false, true,
+    false, false, false, true, false, true, false, false, true,
+];
+
+/// Tamaños rectangulares de ISO/IEC 16022 y DMRE ordenados por capacidad:
+/// (versión, palabras de datos, palabras de corrección), todos con un único bloque
+/// Reed-Solomon como los lee rxing. Con la misma capacidad se prefiere el de menos módulos.
+const DMRE_CANDIDATES: [(u8, u32, u32); 24] = [
+    (25, 5, 7), (26, 10, 11), (27, 16, 14), (31, 18, 15), (28, 22, 18), (32, 24, 18),
+    (29, 32, 24), (33, 32, 22), (34, 38, 28), (37, 43, 27), (40, 44, 28), (30, 49, 28),
+    (35, 49, 32), (41, 56, 34), (39, 62, 36), (36, 63, 36), (38, 64, 36), (46, 70, 38),
+    (43, 72, 38), (44, 80, 41), (42, 84, 42), (47, 90, 42), (45, 108, 46), (48, 118, 50),
+];
+
+/// Símbolo Data Matrix, Aztec o PDF417 codificado
+#[derive(Debug, Clone)]
+pub struct MatrixSymbol {
+    pub symbology: QrSymbology,
+    /// Versión Data Matrix (1-48, ver `DATA_MATRIX_SIZES`), capas Aztec o columnas PDF417
+    pub version: u8,
+    /// Aztec compacto o PDF417 compacto (truncado)
+    pub compact: bool,
+    /// Módulos por filas (`modules[y][x]`); cada fila PDF417 ocupa `PDF417_ROW_HEIGHT` filas
+    pub modules: Vec<Vec<bool>>,
+}
+
+impl MatrixSymbol {
+    pub fn width(&self) -> usize {
+        self.modules.first().map_or(0, Vec::len)
+    }
+
+    pub fn height(&self) -> usize {
+        self.modules.len()
+    }
+}
+
+/// Codifica `data` en el símbolo más pequeño que cumple las opciones. Sin nivel explícito,
+/// Aztec usa el 33% de corrección y PDF417 el nivel 2 (los valores por defecto de rxing).
+pub fn encode(
+    data: &str,
+    symbology: QrSymbology,
+    ecl: Option<ErrorCorrectionLevel>,
+    options: &MatrixCodeOptions,
+) -> QrResult<MatrixSymbol> {
+    if data.is_empty() {
+        return Err(QrError::InvalidCharacters);
+    }
+    match symbology {
+        QrSymbology::DataMatrix => encode_datamatrix(data, options.datamatrix_shape.unwrap_or_default()),
+        QrSymbology::Aztec => encode_aztec(data, ecl, options),
+        QrSymbology::Pdf417 => encode_pdf417(data, ecl, options),
+        _ => Err(QrError::UnsupportedFormat(format!(
+            "{:?} no es una simbología Data Matrix, Aztec o PDF417",
+            symbology
+        ))),
+    }
+}
+
+fn encode_datamatrix(data: &str, shape: DataMatrixShape) -> QrResult<MatrixSymbol> {
+    let dmre_symbols: Vec<SymbolInfo> = DMRE_CANDIDATES
+        .iter()
+        .map(|&(version, data_codewords, ec_codewords)| {
+            let (_, _, region_rows, region_cols) = get_datamatrix_size(version).expect("versión DMRE de la tabla");
+            SymbolInfo::new(true, data_codewords, ec_codewords, region_cols as u32, region_rows as u32, 1)
+        })
+        .collect();
+    let mut lookup = SymbolInfoLookup::new();
+    let hint = match shape {
+        DataMatrixShape::Square => SymbolShapeHint::FORCE_SQUARE,
+        DataMatrixShape::Rectangle => SymbolShapeHint::FORCE_RECTANGLE,
+        DataMatrixShape::Dmre => {
+            lookup.overrideSymbolSet(&dmre_symbols);
+            SymbolShapeHint::FORCE_RECTANGLE
+        }
+    };
+
+    // El codificador rellena hasta la capacidad del símbolo elegido, así que volver a
+    // buscar con esa longitud devuelve el mismo símbolo
+    let encoded = high_level_encoder::encodeHighLevelWithDimensionForceC40WithSymbolInfoLookup(
+        data, hint, None, None, false, Some(lookup),
+    )
+    .map_err(|e| QrError::EncodingError(format!("Data Matrix: {}", e)))?;
+    let info = lookup
+        .lookup_with_shape(encoded.chars().count() as u32, hint)
+        .ok()
+        .flatten()
+        .ok_or_else(|| QrError::ValidationError("Los datos no caben en un símbolo Data Matrix".to_string()))?;
+    let version = match shape {
+        DataMatrixShape::Dmre => DMRE_CANDIDATES
+            .iter()
+            .find(|candidate| candidate.1 == info.getDataCapacity())
+            .map(|candidate| candidate.0),
+        _ => get_datamatrix_version(
+            info.getSymbolWidth().unwrap_or(0) as usize,
+            info.getSymbolHeight().unwrap_or(0) as usize,
+        ),
+    }
+    .ok_or_else(|| QrError::InternalError("Tamaño Data Matrix fuera de la tabla".to_string()))?;
+
+    let codewords = match shape {
+        // rxing solo tiene los polinomios generadores de ISO/IEC 16022
+        DataMatrixShape::Dmre => {
+            let data_codewords: Vec<u8> = encoded.chars().map(|c| c as u8).collect();
+            let ec_codewords = reed_solomon_ecc200(&data_codewords, info.getErrorCodewords() as usize);
+            data_codewords.into_iter().chain(ec_codewords).map(char::from).collect()
+        }
+        _ => error_correction::encodeECC200(&encoded, info)
+            .map_err(|e| QrError::EncodingError(format!("Data Matrix: {}", e)))?,
+    };
+    let (rows, cols, region_rows, region_cols) = get_datamatrix_size(version).expect("versión de la tabla");
+    let (rows, cols) = (rows as usize, cols as usize);
+    let (region_rows, region_cols) = (region_rows as usize, region_cols as usize);
+    let data_rows = rows - 2 * (rows / (region_rows + 2));
+    let data_cols = cols - 2 * (cols / (region_cols + 2));
+    let mut placement = DefaultPlacement::new(codewords, data_cols, data_rows);
+    placement
+        .place()
+        .map_err(|e| QrError::EncodingError(format!("Data Matrix: {}", e)))?;
+
+    // Cada región: temporización arriba y a la derecha, borde sólido a la izquierda y abajo
+    let modules = (0..rows)
+        .map(|y| {
+            (0..cols)
+                .map(|x| {
+                    let (region_y, region_x) = (y % (region_rows + 2), x % (region_cols + 2));
+                    if region_y == 0 {
+                        x % 2 == 0
+                    } else if region_y == region_rows + 1 || region_x == 0 {
+                        true
+                    } else if region_x == region_cols + 1 {
+                        y % 2 == 1
+                    } else {
+                        placement.getBit(
+                            x - 1 - 2 * (x / (region_cols + 2)),
+                            y - 1 - 2 * (y / (region_rows + 2)),
+                        )
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(MatrixSymbol {
+        symbology: QrSymbology::DataMatrix,
+        version,
+        compact: false,
+        modules,
+    })
+}
+
+/// Corrección de errores ECC 200 de un único bloque: GF(256) con polinomio 0x12D y
+/// generador de raíces α^1..α^n
+fn reed_solomon_ecc200(data: &[u8], ec_len: usize) -> Vec<u8> {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut value: u16 = 1;
+    for (power, slot) in exp.iter_mut().enumerate() {
+        *slot = value as u8;
+        log[value as usize] = power as u8;
+        value <<= 1;
+        if value & 0x100 != 0 {
+            value ^= 0x12D;
+        }
+    }
+    let multiply = |a: u8, b: u8| {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            exp[(log[a as usize] as usize + log[b as usize] as usize) % 255]
+        }
+    };
+
+    // Coeficientes del generador de mayor a menor grado
+    let mut generator = vec![1u8];
+    for root in 1..=ec_len {
+        let mut next = vec![0u8; generator.len() + 1];
+        for (i, &coefficient) in generator.iter().enumerate() {
+            next[i] ^= coefficient;
+            next[i + 1] ^= multiply(coefficient, exp[root % 255]);
+        }
+        generator = next;
+    }
+
+    let mut remainder = vec![0u8; ec_len];
+    for &codeword in data {
+        let factor = codeword ^ remainder[0];
+        remainder.remove(0);
+        remainder.push(0);
+        for (slot, &coefficient) in remainder.iter_mut().zip(&generator[1..]) {
+            *slot ^= multiply(coefficient, factor);
+        }
+    }
+    remainder
+}
+
+fn encode_aztec(data: &str, ecl: Option<ErrorCorrectionLevel>, options: &MatrixCodeOptions) -> QrResult<MatrixSymbol> {
+    let ec_percent = match (options.aztec_ec_percent, ecl) {
+        (Some(percent), _) if !(5..=95).contains(&percent) => {
+            return Err(QrError::ValidationError(format!(
+                "Porcentaje de corrección Aztec fuera de rango (5-95): {}",
+                percent
+            )))
+        }
+        (Some(percent), _) => percent as u32,
+        (None, Some(ErrorCorrectionLevel::Low)) => 23,
+        (None, Some(ErrorCorrectionLevel::Medium)) | (None, None) => aztec_encoder::DEFAULT_EC_PERCENT,
+        (None, Some(ErrorCorrectionLevel::Quartile)) => 50,
+        (None, Some(ErrorCorrectionLevel::High)) => 66,
+    };
+
+    // Capas en la convención de rxing: negativas para compacto y 0 para elegir automáticamente.
+    // Con capas explícitas y sin indicar la forma se entiende un símbolo completo.
+    let candidates: Vec<i32> = match (options.aztec_compact, options.aztec_layers) {
+        (compact, Some(layers)) => {
+            let compact = compact.unwrap_or(false);
+            let max_layers = if compact { 4 } else { 32 };
+            if !(1..=max_layers).contains(&layers) {
+                return Err(QrError::ValidationError(format!(
+                    "Aztec {} admite de 1 a {} capas: {}",
+                    if compact { "compacto" } else { "completo" },
+                    max_layers,
+                    layers
+                )));
+            }
+            vec![if compact { -(layers as i32) } else { layers as i32 }]
+        }
+        (Some(true), None) => (1..=4).map(|layers| -layers).collect(),
+        (Some(false), None) => (1..=32).collect(),
+        (None, None) => vec![aztec_encoder::DEFAULT_AZTEC_LAYERS],
+    };
+
+    let latin1 = data.chars().all(|c| (c as u32) <= 0xFF);
+    let mut last_error = None;
+    for layers in candidates {
+        let encoded = if latin1 {
+            aztec_encoder::encode(data, ec_percent, layers)
+        } else {
+            aztec_encoder::encode_with_charset(data, ec_percent, layers, CharacterSet::UTF8)
+        };
+        match encoded {
+            Ok(code) => {
+                let matrix = code.getMatrix();
+                let size = matrix.getWidth() as usize;
+                let modules = (0..size)
+                    .map(|y| (0..size).map(|x| matrix.get(x as u32, y as u32)).collect())
+                    .collect();
+                return Ok(MatrixSymbol {
+                    symbology: QrSymbology::Aztec,
+                    version: code.getLayers() as u8,
+                    compact: code.isCompact(),
+                    modules,
+                });
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(QrError::ValidationError(format!(
+        "Los datos no caben en el símbolo Aztec solicitado: {}",
+        last_error.map(|e| e.to_string()).unwrap_or_default()
+    )))
+}
+
+fn encode_pdf417(data: &str, ecl: Option<ErrorCorrectionLevel>, options: &MatrixCodeOptions) -> QrResult<MatrixSymbol> {
+    let level = match (options.pdf417_ecc_level, ecl) {
+        (Some(level), _) if level > 8 => {
+            return Err(QrError::ValidationError(format!(
+                "Nivel de corrección PDF417 fuera de rango (0-8): {}",
+                level
+            )))
+        }
+        (Some(level), _) => level as u32,
+        (None, Some(ErrorCorrectionLevel::Low)) | (None, None) => 2,
+        (None, Some(ErrorCorrectionLevel::Medium)) => 3,
+        (None, Some(ErrorCorrectionLevel::Quartile)) => 4,
+        (None, Some(ErrorCorrectionLevel::High)) => 5,
+    };
+    let (min_columns, max_columns) = match options.pdf417_columns {
+        Some(columns) => (columns, columns),
+        None => (options.pdf417_min_columns.unwrap_or(1), options.pdf417_max_columns.unwrap_or(30)),
+    };
+    if let Some(columns) = [min_columns, max_columns].into_iter().find(|c| !(1..=30).contains(c)) {
+        return Err(QrError::ValidationError(format!("PDF417 admite de 1 a 30 columnas: {}", columns)));
+    }
+    if min_columns > max_columns {
+        return Err(QrError::ValidationError(format!(
+            "El máximo de columnas PDF417 ({}) es menor que el mínimo ({})",
+            max_columns, min_columns
+        )));
+    }
+    if let Some(rows) = options.pdf417_rows.filter(|r| !(3..=90).contains(r)) {
+        return Err(QrError::ValidationError(format!("PDF417 admite de 3 a 90 filas: {}", rows)));
+    }
+
+    let compact = options.pdf417_compact.unwrap_or(false);
+    let (min_rows, max_rows) = options.pdf417_rows.map_or((3, 90), |r| (r as u32, r as u32));
+    let mut pdf417 = PDF417::with_compact_hint(compact);
+    pdf417.setDimensions(max_columns as u32, min_columns as u32, max_rows, min_rows);
+    let latin1 = data.chars().all(|c| (c as u32) <= 0xFF);
+    pdf417
+        .generateBarcodeLogicWithAutoECI(data, level, !latin1)
+        .map_err(|e| QrError::ValidationError(format!("Los datos no caben en el símbolo PDF417 solicitado: {}", e)))?;
+    let matrix = pdf417
+        .getBarcodeMatrix()
+        .as_ref()
+        .ok_or_else(|| QrError::InternalError("PDF417 sin matriz".to_string()))?
+        .getMatrix();
+
+    // rxing guarda las filas de abajo arriba
+    let modules: Vec<Vec<bool>> = matrix
+        .iter()
+        .rev()
+        .flat_map(|row| {
+            let row: Vec<bool> = row.iter().map(|&module| module == 1).collect();
+            std::iter::repeat_n(row, PDF417_ROW_HEIGHT)
+        })
+        .collect();
+    let width = modules.first().map_or(0, Vec::len);
+    let columns = (width - if compact { 35 } else { 69 }) / 17;
+
+    Ok(MatrixSymbol {
+        symbology: QrSymbology::Pdf417,
+        version: columns as u8,
+        compact,
+        modules,
+    })
+}
+
+/// Aztec compacto: la marca de orientación superior izquierda está a 5 módulos del centro
+/// (en el completo esa posición es el anillo claro del patrón central)
+pub fn is_compact_aztec(modules: &[Vec<bool>]) -> bool {
+    let center = modules.len() / 2;
+    center >= 5
+        && get_aztec_layers(modules.len(), true).is_some()
+        && modules[center - 5][center - 5]
+}
+
+/// PDF417 compacto: las filas no terminan en el patrón de parada
+pub fn is_compact_pdf417(modules: &[Vec<bool>]) -> bool {
+    !modules.iter().all(|row| row.ends_with(&PDF417_STOP_PATTERN))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::zones::{
+        calculate_aztec_untouchable_zones, calculate_datamatrix_untouchable_zones, calculate_pdf417_untouchable_zones,
+        is_in_untouchable_zone, ZoneType,
+    };
+    use rxing::{BarcodeFormat, DecodeHints};
+
+    /// Decodifica la matriz con rxing (4 px por módulo, zona silenciosa mínima)
+    fn decode(symbol: &MatrixSymbol, format: BarcodeFormat) -> Option<String> {
+        let scale = 4;
+        let quiet_zone = symbol.symbology.min_quiet_zone().max(2);
+        let (w, h) = (symbol.width() + 2 * quiet_zone, symbol.height() + 2 * quiet_zone);
+        let mut luma = vec![255u8; w * h * scale * scale];
+        for (y, row) in symbol.modules.iter().enumerate() {
+            for (x, &dark) in row.iter().enumerate() {
+                if !dark {
+                    continue;
+                }
+                for py in 0..scale {
+                    for px in 0..scale {
+                        let index = ((y + quiet_zone) * scale + py) * w * scale + (x + quiet_zone) * scale + px;
+                        luma[index] = 0;
+                    }
+                }
+            }
+        }
+        let mut hints = DecodeHints::default().with(rxing::DecodeHintValue::TryHarder(true));
+        rxing::helpers::detect_in_luma_with_hints(luma, (w * scale) as u32, (h * scale) as u32, Some(format), &mut hints)
+            .ok()
+            .map(|result| result.getText().to_string())
+    }
+
+    fn options() -> MatrixCodeOptions {
+        MatrixCodeOptions::default()
+    }
+
+    #[test]
+    fn test_datamatrix_shapes_roundtrip() {
+        let long = "LOTE-A1B2C3".repeat(12);
+        for (data, shape, expected) in [
+            ("HELLO", DataMatrixShape::Square, (12, 12)),
+            ("https://qreable.com/dm", DataMatrixShape::Square, (20, 20)),
+            (long.as_str(), DataMatrixShape::Square, (40, 40)),
+            ("HELLO", DataMatrixShape::Rectangle, (18, 8)),
+            ("GTIN 09506000134352", DataMatrixShape::Dmre, (26, 12)),
+            (long.as_str(), DataMatrixShape::Dmre, (64, 24)),
+        ] {
+            let symbol = encode(
+                data,
+                QrSymbology::DataMatrix,
+                None,
+                &MatrixCodeOptions { datamatrix_shape: Some(shape), ..options() },
+            )
+            .unwrap();
+            assert_eq!((symbol.width(), symbol.height()), expected, "{:?} {:?}", shape, data);
+            assert_eq!(decode(&symbol, BarcodeFormat::DATA_MATRIX).as_deref(), Some(data), "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn test_reed_solomon_ecc200_matches_rxing() {
+        // Ejemplo del anexo R de ISO/IEC 16022
+        assert_eq!(reed_solomon_ecc200(&[142, 164, 186], 5), vec![114, 25, 5, 88, 102]);
+        let data: Vec<u8> = (1..=22).collect();
+        let info = SymbolInfoLookup::new().lookup(22).unwrap().unwrap();
+        let encoded: String = data.iter().map(|&b| char::from(b)).collect();
+        let expected: Vec<u8> = error_correction::encodeECC200(&encoded, info).unwrap().chars().skip(22).map(|c| c as u8).collect();
+        assert_eq!(reed_solomon_ecc200(&data, 18), expected);
+    }
+
+    #[test]
+    fn test_datamatrix_zones_frame_the_data() {
+        // Todo lo que no es zona intocable es área de colocación de datos
+        for version in [1u8, 10, 16, 25, 26, 32, 48] {
+            let (rows, cols, region_rows, region_cols) = get_datamatrix_size(version).unwrap();
+            let zones = calculate_datamatrix_untouchable_zones(cols as usize, rows as usize);
+            let free = (0..rows as u16)
+                .flat_map(|y| (0..cols as u16).map(move |x| (x, y)))
+                .filter(|&(x, y)| !is_in_untouchable_zone(x, y, &zones))
+                .count();
+            let regions = (rows as usize / (region_rows as usize + 2)) * (cols as usize / (region_cols as usize + 2));
+            assert_eq!(free, regions * region_rows as usize * region_cols as usize, "versión {}", version);
+        }
+        assert!(calculate_datamatrix_untouchable_zones(11, 11).is_empty());
+
+        // Las zonas coinciden con los patrones dibujados: L sólida y temporización alterna
+        let symbol = encode(&"LOTE-A1B2C3".repeat(12), QrSymbology::DataMatrix, None, &options()).unwrap();
+        let zones = calculate_datamatrix_untouchable_zones(symbol.width(), symbol.height());
+        assert!(zones.iter().any(|z| z.zone_type == ZoneType::AlignmentPattern));
+        let last = symbol.height() - 1;
+        assert!(symbol.modules[last].iter().all(|&m| m));
+        assert!(symbol.modules.iter().all(|row| row[0]));
+        assert!(symbol.modules[0].iter().enumerate().all(|(x, &m)| m == (x % 2 == 0)));
+    }
+
+    #[test]
+    fn test_aztec_compact_full_and_layers() {
+        let data = "https://qreable.com/aztec";
+
+        let auto = encode(data, QrSymbology::Aztec, None, &options()).unwrap();
+        assert!(auto.compact && is_compact_aztec(&auto.modules));
+        assert_eq!(decode(&auto, BarcodeFormat::AZTEC).as_deref(), Some(data));
+
+        let full = encode(data, QrSymbology::Aztec, None, &MatrixCodeOptions { aztec_compact: Some(false), ..options() }).unwrap();
+        assert!(!full.compact && !is_compact_aztec(&full.modules));
+        assert_eq!(decode(&full, BarcodeFormat::AZTEC).as_deref(), Some(data));
+
+        let layered = encode(data, QrSymbology::Aztec, None, &MatrixCodeOptions { aztec_layers: Some(6), ..options() }).unwrap();
+        assert_eq!((layered.version, layered.width()), (6, 41));
+        assert_eq!(decode(&layered, BarcodeFormat::AZTEC).as_deref(), Some(data));
+
+        // Más corrección exige un símbolo mayor; capas fuera de rango o insuficientes fallan
+        let high = encode(data, QrSymbology::Aztec, Some(ErrorCorrectionLevel::High), &options()).unwrap();
+        assert!(high.width() > auto.width());
+        let compact = |layers| MatrixCodeOptions { aztec_compact: Some(true), aztec_layers: Some(layers), ..options() };
+        assert!(encode(data, QrSymbology::Aztec, None, &compact(5)).is_err());
+        assert!(encode(&"A".repeat(200), QrSymbology::Aztec, None, &compact(1)).is_err());
+    }
+
+    #[test]
+    fn test_aztec_zones() {
+        let compact = encode("AZTEC", QrSymbology::Aztec, None, &options()).unwrap();
+        let zones = calculate_aztec_untouchable_zones(compact.width(), true);
+        assert!(!zones.iter().any(|z| z.zone_type == ZoneType::ReferenceGrid));
+        // El anillo de modo rodea el patrón central y deja fuera las capas de datos
+        let center = compact.width() as u16 / 2;
+        assert!(is_in_untouchable_zone(center, center, &zones));
+        assert!(is_in_untouchable_zone(center - 5, center + 2, &zones));
+        assert!(!is_in_untouchable_zone(center - 6, center, &zones));
+
+        let full = encode(&"AZTEC".repeat(60), QrSymbology::Aztec, None, &MatrixCodeOptions { aztec_compact: Some(false), ..options() }).unwrap();
+        let zones = calculate_aztec_untouchable_zones(full.width(), false);
+        let center = full.width() / 2;
+        // La rejilla alterna módulos oscuros y claros a lo largo de las líneas de referencia
+        for zone in zones.iter().filter(|z| z.zone_type == ZoneType::ReferenceGrid && z.height == 1) {
+            let y = zone.y as usize;
+            assert!((0..full.width()).all(|x| full.modules[y][x] == (x + center).is_multiple_of(2)), "fila {}", y);
+        }
+        assert!(calculate_aztec_untouchable_zones(20, false).is_empty());
+    }
+
+    #[test]
+    fn test_pdf417_dimensions_and_compact() {
+        let data = "PDF417 https://qreable.com/pdf417";
+
+        let symbol = encode(
+            data,
+            QrSymbology::Pdf417,
+            None,
+            &MatrixCodeOptions { pdf417_columns: Some(6), pdf417_rows: Some(12), pdf417_ecc_level: Some(4), ..options() },
+        )
+        .unwrap();
+        assert_eq!(symbol.width(), (6 + 4) * 17 + 1);
+        assert_eq!(symbol.height(), 12 * PDF417_ROW_HEIGHT);
+        assert!(!is_compact_pdf417(&symbol.modules));
+        assert_eq!(decode(&symbol, BarcodeFormat::PDF_417).as_deref(), Some(data));
+        let zones = calculate_pdf417_untouchable_zones(symbol.width(), symbol.height(), false);
+        assert_eq!(zones.iter().filter(|z| z.zone_type == ZoneType::RowIndicator).count(), 2);
+
+        let compact = encode(
+            data,
+            QrSymbology::Pdf417,
+            None,
+            &MatrixCodeOptions { pdf417_columns: Some(4), pdf417_compact: Some(true), ..options() },
+        )
+        .unwrap();
+        assert!(compact.compact && is_compact_pdf417(&compact.modules));
+        assert_eq!(compact.width(), (4 + 2) * 17 + 1);
+        assert_eq!(decode(&compact, BarcodeFormat::PDF_417).as_deref(), Some(data));
+        let zones = calculate_pdf417_untouchable_zones(compact.width(), compact.height(), true);
+        assert_eq!(zones.iter().filter(|z| z.zone_type == ZoneType::RowIndicator).count(), 1);
+
+        let invalid = |options: MatrixCodeOptions| encode(data, QrSymbology::Pdf417, None, &options).is_err();
+        assert!(invalid(MatrixCodeOptions { pdf417_ecc_level: Some(9), ..options() }));
+        assert!(invalid(MatrixCodeOptions { pdf417_columns: Some(31), ..options() }));
+        assert!(invalid(MatrixCodeOptions { pdf417_max_columns: Some(31), ..options() }));
+        assert!(invalid(MatrixCodeOptions { pdf417_min_columns: Some(8), pdf417_max_columns: Some(4), ..options() }));
+
+        // Con límites de columnas el codificador elige dentro del rango
+        let bounded = encode(
+            data,
+            QrSymbology::Pdf417,
+            None,
+            &MatrixCodeOptions { pdf417_min_columns: Some(3), pdf417_max_columns: Some(5), ..options() },
+        )
+        .unwrap();
+        assert!((3..=5).contains(&((bounded.width() - 1) / 17 - 4)));
+        assert_eq!(decode(&bounded, BarcodeFormat::PDF_417).as_deref(), Some(data));
+        assert!(invalid(MatrixCodeOptions { pdf417_columns: Some(1), pdf417_rows: Some(3), ..options() }));
+    }
+}
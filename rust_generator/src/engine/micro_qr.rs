@@ -0,0 +1,647 @@
+// engine/micro_qr.rs - Micro QR (ISO/IEC 18004, M1-M4) y rMQR (ISO/IEC 23941)
+// qrcodegen solo produce QR modelo 2: estas simbologías se codifican aquí sobre las
+// tablas de versiones de rxing, que es también quien las lee en la verificación
+
+use qrcodegen::{QrSegment, QrSegmentMode};
+use rxing::common::reedsolomon::{get_predefined_genericgf, PredefinedGenericGF, ReedSolomonEncoder};
+use rxing::qrcode::decoder::{ErrorCorrectionLevel as RxingEcl, Mode, Version as RxingVersion, VersionRef};
+
+use super::constants::{get_rmqr_alignment_columns, get_rmqr_size, RMQR_SIZES};
+use super::error::{QrError, QrResult};
+use super::segmenter::ContentSegmenter;
+use super::types::{ErrorCorrectionLevel, QrSymbology};
+
+/// Información de formato Micro QR: BCH(15,5) enmascarado
+const MICRO_FORMAT_POLY: u32 = 0x537;
+const MICRO_FORMAT_MASK: u32 = 0x4445;
+
+/// Información de formato rMQR: BCH(18,6), con una máscara distinta en cada lado
+const RMQR_FORMAT_POLY: u32 = 0x1F25;
+const RMQR_FORMAT_MASK_FINDER: u32 = 0x1FAB2;
+const RMQR_FORMAT_MASK_SUB: u32 = 0x20A7B;
+
+/// Máscaras Micro QR: los índices 0-3 corresponden a los patrones QR 1, 4, 6 y 7
+const MICRO_MASK_PATTERNS: [u8; 4] = [1, 4, 6, 7];
+
+/// rMQR aplica siempre el patrón QR 4
+const RMQR_MASK_PATTERN: u8 = 4;
+
+/// Asignación ECI para UTF-8 (solo rMQR admite ECI)
+const ECI_UTF8: u32 = 26;
+
+/// Símbolo Micro QR o rMQR codificado
+#[derive(Debug, Clone)]
+pub struct MicroSymbol {
+    pub symbology: QrSymbology,
+    /// Versión: 1-4 (M1-M4) o 1-32 (R7x43 … R17x139)
+    pub version: u8,
+    /// Nivel de corrección efectivo (M1 solo detecta errores y se informa como Low)
+    pub error_correction: ErrorCorrectionLevel,
+    /// Máscara aplicada, en la numeración propia de la simbología
+    pub mask: u8,
+    /// Módulos por filas (`modules[y][x]`)
+    pub modules: Vec<Vec<bool>>,
+}
+
+impl MicroSymbol {
+    pub fn width(&self) -> usize {
+        self.modules.first().map_or(0, Vec::len)
+    }
+
+    pub fn height(&self) -> usize {
+        self.modules.len()
+    }
+}
+
+/// Parámetros de codificación de una versión concreta
+struct Layout {
+    version: VersionRef,
+    number: u8,
+    level: RxingEcl,
+    /// Capacidad de datos en bits (M1 y M3 terminan en un codeword de 4 bits)
+    capacity_bits: usize,
+    data_codewords: usize,
+    half_last: bool,
+}
+
+/// Codifica `data` en la versión más pequeña de la simbología que lo admite
+pub fn encode(data: &str, symbology: QrSymbology, ecl: ErrorCorrectionLevel) -> QrResult<MicroSymbol> {
+    if data.is_empty() {
+        return Err(QrError::InvalidCharacters);
+    }
+    match symbology {
+        QrSymbology::MicroQr => encode_micro(data, ecl),
+        QrSymbology::Rmqr => encode_rmqr(data, ecl),
+        QrSymbology::Qr => Err(QrError::UnsupportedFormat(
+            "QR modelo 2 se genera con QrGenerator::generate".to_string(),
+        )),
+        QrSymbology::DataMatrix | QrSymbology::Aztec | QrSymbology::Pdf417 => Err(QrError::UnsupportedFormat(
+            "Data Matrix, Aztec y PDF417 se generan con matrix_codes::encode".to_string(),
+        )),
+    }
+}
+
+fn encode_micro(data: &str, ecl: ErrorCorrectionLevel) -> QrResult<MicroSymbol> {
+    let levels: &[(u8, RxingEcl)] = match ecl {
+        ErrorCorrectionLevel::Low => &[(1, RxingEcl::L), (2, RxingEcl::L), (3, RxingEcl::L), (4, RxingEcl::L)],
+        ErrorCorrectionLevel::Medium => &[(2, RxingEcl::M), (3, RxingEcl::M), (4, RxingEcl::M)],
+        ErrorCorrectionLevel::Quartile => &[(4, RxingEcl::Q)],
+        ErrorCorrectionLevel::High => {
+            return Err(QrError::ValidationError(
+                "Micro QR no admite el nivel de corrección H".to_string(),
+            ))
+        }
+    };
+    let candidates = segment_candidates(data, false)?;
+
+    for &(number, level) in levels {
+        let layout = micro_layout(number, level)?;
+        let Some(bits) = candidates.iter().find_map(|segments| stream_bits(segments, &layout, true)) else {
+            continue;
+        };
+        let codewords = final_codewords(&bits, &layout)?;
+        let dimension = 9 + 2 * number as usize;
+        let mut grid = Grid::new(dimension, dimension, &layout)?;
+        grid.draw_micro_patterns();
+        grid.place(&codewords, dimension - 1, layout.half_last.then_some(layout.data_codewords - 1));
+
+        // Máscara con mayor puntuación (ISO/IEC 18004, 7.8.3.2)
+        let (mask, modules) = (0..MICRO_MASK_PATTERNS.len() as u8)
+            .map(|mask| {
+                let mut masked = grid.clone();
+                masked.apply_mask(MICRO_MASK_PATTERNS[mask as usize]);
+                masked.draw_micro_format(micro_symbol_number(number, level), mask);
+                (mask, masked.modules)
+            })
+            .max_by_key(|(mask, modules)| (micro_mask_score(modules), std::cmp::Reverse(*mask)))
+            .expect("Micro QR define cuatro máscaras");
+
+        return Ok(MicroSymbol {
+            symbology: QrSymbology::MicroQr,
+            version: number,
+            error_correction: match level {
+                RxingEcl::M => ErrorCorrectionLevel::Medium,
+                RxingEcl::Q => ErrorCorrectionLevel::Quartile,
+                _ => ErrorCorrectionLevel::Low,
+            },
+            mask,
+            modules,
+        });
+    }
+
+    Err(QrError::EncodingError(format!(
+        "Los datos ({} bytes) no caben en Micro QR con corrección {:?}",
+        data.len(),
+        ecl
+    )))
+}
+
+fn encode_rmqr(data: &str, ecl: ErrorCorrectionLevel) -> QrResult<MicroSymbol> {
+    // rMQR solo define los niveles M y H
+    let (level, effective) = match ecl {
+        ErrorCorrectionLevel::Low | ErrorCorrectionLevel::Medium => (RxingEcl::M, ErrorCorrectionLevel::Medium),
+        ErrorCorrectionLevel::Quartile | ErrorCorrectionLevel::High => (RxingEcl::H, ErrorCorrectionLevel::High),
+    };
+    let candidates = segment_candidates(data, true)?;
+
+    // La versión de menor superficie que admite los datos
+    let mut versions: Vec<u8> = (1..=RMQR_SIZES.len() as u8).collect();
+    versions.sort_by_key(|&number| {
+        let (width, height) = RMQR_SIZES[number as usize - 1];
+        (width as usize * height as usize, height)
+    });
+
+    for number in versions {
+        let layout = rmqr_layout(number, level)?;
+        let Some(bits) = candidates.iter().find_map(|segments| stream_bits(segments, &layout, false)) else {
+            continue;
+        };
+        let codewords = final_codewords(&bits, &layout)?;
+        let (width, height) = get_rmqr_size(number).expect("versión rMQR válida");
+        let (width, height) = (width as usize, height as usize);
+        let mut grid = Grid::new(width, height, &layout)?;
+        grid.draw_rmqr_patterns();
+        grid.place(&codewords, width - 2, None);
+        grid.apply_mask(RMQR_MASK_PATTERN);
+        grid.draw_rmqr_format(level, number);
+
+        return Ok(MicroSymbol {
+            symbology: QrSymbology::Rmqr,
+            version: number,
+            error_correction: effective,
+            mask: RMQR_MASK_PATTERN,
+            modules: grid.modules,
+        });
+    }
+
+    Err(QrError::DataTooLong(data.len(), rmqr_max_bytes(level)))
+}
+
+/// Número de símbolo Micro QR codificado en la información de formato
+fn micro_symbol_number(version: u8, level: RxingEcl) -> u32 {
+    match (version, level) {
+        (1, _) => 0,
+        (2, RxingEcl::L) => 1,
+        (2, _) => 2,
+        (3, RxingEcl::L) => 3,
+        (3, _) => 4,
+        (4, RxingEcl::L) => 5,
+        (4, RxingEcl::M) => 6,
+        _ => 7,
+    }
+}
+
+fn micro_layout(number: u8, level: RxingEcl) -> QrResult<Layout> {
+    let version = RxingVersion::Micro(number as u32).map_err(|e| QrError::EncodingError(e.to_string()))?;
+    let data_codewords = data_codewords(version, level);
+    let half_last = number == 1 || number == 3;
+    Ok(Layout {
+        version,
+        number,
+        level,
+        capacity_bits: data_codewords * 8 - if half_last { 4 } else { 0 },
+        data_codewords,
+        half_last,
+    })
+}
+
+fn rmqr_layout(number: u8, level: RxingEcl) -> QrResult<Layout> {
+    let version = RxingVersion::rMQR(number as u32).map_err(|e| QrError::EncodingError(e.to_string()))?;
+    let data_codewords = data_codewords(version, level);
+    Ok(Layout {
+        version,
+        number,
+        level,
+        capacity_bits: data_codewords * 8,
+        data_codewords,
+        half_last: false,
+    })
+}
+
+fn data_codewords(version: VersionRef, level: RxingEcl) -> usize {
+    version
+        .getECBlocksForLevel(level)
+        .getECBlocks()
+        .iter()
+        .map(|block| (block.getCount() * block.getDataCodewords()) as usize)
+        .sum()
+}
+
+/// Capacidad en bytes de la mayor versión rMQR (para el mensaje de error)
+fn rmqr_max_bytes(level: RxingEcl) -> usize {
+    rmqr_layout(RMQR_SIZES.len() as u8, level)
+        .map(|layout| layout.data_codewords.saturating_sub(2))
+        .unwrap_or(0)
+}
+
+/// Segmentaciones alternativas: la mixta del segmentador y la de un único modo
+fn segment_candidates(data: &str, allow_eci: bool) -> QrResult<Vec<Vec<QrSegment>>> {
+    let mut mixed = ContentSegmenter::new()
+        .analyze_and_segment(data)
+        .map_err(QrError::EncodingError)?;
+    let mut single = QrSegment::make_segments(data);
+    if allow_eci && !data.is_ascii() {
+        mixed.insert(0, QrSegment::make_eci(ECI_UTF8));
+        single.insert(0, QrSegment::make_eci(ECI_UTF8));
+    }
+    Ok(vec![mixed, single])
+}
+
+/// Indicador de modo y su longitud en bits; `None` si la versión no admite el modo
+fn mode_indicator(mode: QrSegmentMode, layout: &Layout, micro: bool) -> Option<(u32, usize)> {
+    if micro {
+        let (value, min_version) = match mode {
+            QrSegmentMode::Numeric => (0, 1),
+            QrSegmentMode::Alphanumeric => (1, 2),
+            QrSegmentMode::Byte => (2, 3),
+            QrSegmentMode::Kanji => (3, 3),
+            QrSegmentMode::Eci => return None,
+        };
+        (layout.number >= min_version).then_some((value, layout.number as usize - 1))
+    } else {
+        let value = match mode {
+            QrSegmentMode::Numeric => 0b001,
+            QrSegmentMode::Alphanumeric => 0b010,
+            QrSegmentMode::Byte => 0b011,
+            QrSegmentMode::Kanji => 0b100,
+            QrSegmentMode::Eci => 0b111,
+        };
+        Some((value, 3))
+    }
+}
+
+/// Flujo de bits de datos completo (modo, contador, datos, terminador y relleno),
+/// o `None` si los segmentos no caben en la versión
+fn stream_bits(segments: &[QrSegment], layout: &Layout, micro: bool) -> Option<Vec<bool>> {
+    let mut bits = Vec::with_capacity(layout.capacity_bits);
+    for segment in segments {
+        let (indicator, indicator_len) = mode_indicator(segment.mode(), layout, micro)?;
+        append(&mut bits, indicator, indicator_len);
+        let rxing_mode = match segment.mode() {
+            QrSegmentMode::Numeric => Mode::NUMERIC,
+            QrSegmentMode::Alphanumeric => Mode::ALPHANUMERIC,
+            QrSegmentMode::Byte => Mode::BYTE,
+            QrSegmentMode::Kanji => Mode::KANJI,
+            QrSegmentMode::Eci => Mode::ECI,
+        };
+        if rxing_mode != Mode::ECI {
+            let count_bits = rxing_mode.CharacterCountBits(layout.version) as usize;
+            if segment.num_chars() >= 1 << count_bits {
+                return None;
+            }
+            append(&mut bits, segment.num_chars() as u32, count_bits);
+        }
+        bits.extend_from_slice(segment.data());
+        if bits.len() > layout.capacity_bits {
+            return None;
+        }
+    }
+
+    // Terminador (truncado si no hay espacio), alineación a byte y bytes de relleno
+    let terminator = if micro { 1 + 2 * layout.number as usize } else { 3 };
+    let terminator = terminator.min(layout.capacity_bits - bits.len());
+    bits.resize(bits.len() + terminator, false);
+    while bits.len() % 8 != 0 && bits.len() < layout.capacity_bits {
+        bits.push(false);
+    }
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if bits.len() + 8 > layout.capacity_bits {
+            break;
+        }
+        append(&mut bits, pad, 8);
+    }
+    bits.resize(layout.capacity_bits, false);
+    Some(bits)
+}
+
+fn append(bits: &mut Vec<bool>, value: u32, len: usize) {
+    bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+}
+
+/// Codewords de datos y de corrección, intercalados por bloques
+fn final_codewords(bits: &[bool], layout: &Layout) -> QrResult<Vec<u8>> {
+    // El codeword final de 4 bits de M1/M3 ocupa el nibble alto
+    let data: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .chain(std::iter::repeat(&false))
+                .take(8)
+                .fold(0u8, |acc, &bit| (acc << 1) | bit as u8)
+        })
+        .collect();
+    debug_assert_eq!(data.len(), layout.data_codewords);
+
+    let ec_blocks = layout.version.getECBlocksForLevel(layout.level);
+    let ec_len = ec_blocks.getECCodewordsPerBlock() as usize;
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    for group in ec_blocks.getECBlocks() {
+        for _ in 0..group.getCount() {
+            let len = group.getDataCodewords() as usize;
+            let block = &data[offset..offset + len];
+            blocks.push((block, reed_solomon(block, ec_len)?));
+            offset += len;
+        }
+    }
+
+    let max_len = blocks.iter().map(|(block, _)| block.len()).max().unwrap_or(0);
+    let mut codewords = Vec::with_capacity(data.len() + ec_len * blocks.len());
+    for i in 0..max_len {
+        codewords.extend(blocks.iter().filter_map(|(block, _)| block.get(i)));
+    }
+    for i in 0..ec_len {
+        codewords.extend(blocks.iter().map(|(_, ec)| ec[i]));
+    }
+    Ok(codewords)
+}
+
+fn reed_solomon(block: &[u8], ec_len: usize) -> QrResult<Vec<u8>> {
+    let mut buffer: Vec<i32> = block
+        .iter()
+        .map(|&byte| byte as i32)
+        .chain(std::iter::repeat_n(0, ec_len))
+        .collect();
+    ReedSolomonEncoder::new(get_predefined_genericgf(PredefinedGenericGF::QrCodeField256))
+        .and_then(|mut encoder| encoder.encode(&mut buffer, ec_len))
+        .map_err(|e| QrError::EncodingError(e.to_string()))?;
+    Ok(buffer[block.len()..].iter().map(|&value| value as u8).collect())
+}
+
+/// Puntuación de máscara Micro QR: módulos oscuros en el borde derecho e inferior
+fn micro_mask_score(modules: &[Vec<bool>]) -> usize {
+    let last = modules.len() - 1;
+    let right = (1..=last).filter(|&y| modules[y][last]).count();
+    let bottom = (1..=last).filter(|&x| modules[last][x]).count();
+    if right <= bottom {
+        right * 16 + bottom
+    } else {
+        bottom * 16 + right
+    }
+}
+
+/// Condición de inversión de los patrones de máscara QR (`x` columna, `y` fila)
+fn mask_bit(pattern: u8, x: usize, y: usize) -> bool {
+    match pattern {
+        0 => (y + x).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (y + x).is_multiple_of(3),
+        4 => (y / 2 + x / 3).is_multiple_of(2),
+        5 => (y * x) % 2 + (y * x) % 3 == 0,
+        6 => ((y * x) % 2 + (y * x) % 3).is_multiple_of(2),
+        _ => ((y + x) % 2 + (y * x) % 3).is_multiple_of(2),
+    }
+}
+
+/// BCH con el polinomio generador `poly` de grado `degree`
+fn bch(data: u32, poly: u32, degree: u32) -> u32 {
+    let mut value = data << degree;
+    let poly_len = 32 - poly.leading_zeros();
+    while 32 - value.leading_zeros() >= poly_len {
+        value ^= poly << (32 - value.leading_zeros() - poly_len);
+    }
+    (data << degree) | value
+}
+
+/// Matriz en construcción con el mapa de módulos de función de rxing
+#[derive(Clone)]
+struct Grid {
+    width: usize,
+    height: usize,
+    modules: Vec<Vec<bool>>,
+    function: Vec<Vec<bool>>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize, layout: &Layout) -> QrResult<Self> {
+        let pattern = layout
+            .version
+            .buildFunctionPattern()
+            .map_err(|e| QrError::EncodingError(e.to_string()))?;
+        let function = (0..height)
+            .map(|y| (0..width).map(|x| pattern.get(x as u32, y as u32)).collect())
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            modules: vec![vec![false; width]; height],
+            function,
+        })
+    }
+
+    fn set(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y][x] = dark;
+    }
+
+    /// Patrón de búsqueda 7x7 con esquina superior izquierda en (x, y)
+    fn draw_finder(&mut self, x0: usize, y0: usize) {
+        for dy in 0..7.min(self.height - y0) {
+            for dx in 0..7 {
+                let ring = dx.min(dy).min(6 - dx).min(6 - dy);
+                self.set(x0 + dx, y0 + dy, ring != 1);
+            }
+        }
+    }
+
+    fn draw_micro_patterns(&mut self) {
+        self.draw_finder(0, 0);
+        // Temporización en la fila y la columna 0 (el separador queda claro)
+        for i in 8..self.width {
+            self.set(i, 0, i % 2 == 0);
+            self.set(0, i, i % 2 == 0);
+        }
+    }
+
+    fn draw_rmqr_patterns(&mut self) {
+        let (w, h) = (self.width, self.height);
+        // Temporización en los cuatro bordes
+        for x in 0..w {
+            self.set(x, 0, x % 2 == 0);
+            self.set(x, h - 1, x % 2 == 0);
+        }
+        for y in 0..h {
+            self.set(0, y, y % 2 == 0);
+            self.set(w - 1, y, y % 2 == 0);
+        }
+        // Columnas de alineación: temporización vertical y patrones 3x3 en ambos bordes
+        for &cx in get_rmqr_alignment_columns(w as u8) {
+            let cx = cx as usize;
+            for y in 0..h {
+                self.set(cx, y, y % 2 == 0);
+            }
+            for x in [cx - 1, cx + 1] {
+                for y in [1, 2, h - 3, h - 2] {
+                    self.set(x, y, true);
+                }
+            }
+        }
+        // Patrones de esquina (el patrón de búsqueda y el separador prevalecen)
+        self.set(0, h - 2, true);
+        self.set(1, h - 2, false);
+        self.set(1, h - 1, true);
+        self.set(w - 2, 0, true);
+        self.set(w - 2, 1, true);
+        self.set(w - 1, 1, true);
+        self.draw_finder(0, 0);
+        // Subpatrón de búsqueda 5x5 en la esquina inferior derecha
+        for dy in 0..5 {
+            for dx in 0..5 {
+                let ring = dx.min(dy).min(4 - dx).min(4 - dy);
+                self.set(w - 5 + dx, h - 5 + dy, ring != 1);
+            }
+        }
+        // Separador
+        for y in 0..h.min(8) {
+            self.set(7, y, false);
+        }
+        if h > 7 {
+            for x in 0..8 {
+                self.set(x, 7, false);
+            }
+        }
+    }
+
+    /// Coloca los codewords en pares de columnas de derecha a izquierda, alternando
+    /// el sentido vertical; `half` es el índice del codeword de 4 bits, si lo hay
+    fn place(&mut self, codewords: &[u8], first_column: usize, half: Option<usize>) {
+        let bits: Vec<bool> = codewords
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &codeword)| {
+                let len = if Some(index) == half { 4 } else { 8 };
+                (8 - len..8).rev().map(move |i| (codeword >> i) & 1 == 1)
+            })
+            .collect();
+        let mut next = bits.into_iter();
+        let mut upward = true;
+        let mut x = first_column;
+        while x > 0 {
+            for row in 0..self.height {
+                let y = if upward { self.height - 1 - row } else { row };
+                for column in [x, x - 1] {
+                    if !self.function[y][column] {
+                        self.modules[y][column] = next.next().unwrap_or(false);
+                    }
+                }
+            }
+            upward = !upward;
+            x = x.saturating_sub(2);
+        }
+    }
+
+    fn apply_mask(&mut self, pattern: u8) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.function[y][x] && mask_bit(pattern, x, y) {
+                    self.modules[y][x] = !self.modules[y][x];
+                }
+            }
+        }
+    }
+
+    fn draw_micro_format(&mut self, symbol_number: u32, mask: u8) {
+        let format = bch((symbol_number << 2) | mask as u32, MICRO_FORMAT_POLY, 10) ^ MICRO_FORMAT_MASK;
+        let positions = (1..=8).map(|x| (x, 8)).chain((1..=7).rev().map(|y| (8, y)));
+        for (i, (x, y)) in positions.enumerate() {
+            self.set(x, y, (format >> (14 - i)) & 1 == 1);
+        }
+    }
+
+    fn draw_rmqr_format(&mut self, level: RxingEcl, number: u8) {
+        let (w, h) = (self.width, self.height);
+        let data = (u32::from(level == RxingEcl::H) << 5) | (number as u32 - 1);
+        let format = bch(data, RMQR_FORMAT_POLY, 12);
+
+        let finder_side = [(11, 3), (11, 2), (11, 1)]
+            .into_iter()
+            .chain([10, 9, 8].into_iter().flat_map(|x| (1..=5).rev().map(move |y| (x, y))));
+        for (i, (x, y)) in finder_side.enumerate() {
+            self.set(x, y, ((format ^ RMQR_FORMAT_MASK_FINDER) >> (17 - i)) & 1 == 1);
+        }
+
+        let sub_side = [(w - 3, h - 6), (w - 4, h - 6), (w - 5, h - 6)]
+            .into_iter()
+            .chain([w - 6, w - 7, w - 8].into_iter().flat_map(move |x| (2..=6).map(move |dy| (x, h - dy))));
+        for (i, (x, y)) in sub_side.enumerate() {
+            self.set(x, y, ((format ^ RMQR_FORMAT_MASK_SUB) >> (17 - i)) & 1 == 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rxing::{BarcodeFormat, DecodeHints};
+
+    /// Decodifica la matriz con rxing (4 px por módulo, zona silenciosa mínima)
+    fn decode(symbol: &MicroSymbol, format: BarcodeFormat) -> Option<String> {
+        let scale = 4;
+        let quiet_zone = symbol.symbology.min_quiet_zone();
+        let (w, h) = (symbol.width() + 2 * quiet_zone, symbol.height() + 2 * quiet_zone);
+        let mut luma = vec![255u8; w * h * scale * scale];
+        for (y, row) in symbol.modules.iter().enumerate() {
+            for (x, &dark) in row.iter().enumerate() {
+                if !dark {
+                    continue;
+                }
+                for py in 0..scale {
+                    for px in 0..scale {
+                        let index = ((y + quiet_zone) * scale + py) * w * scale + (x + quiet_zone) * scale + px;
+                        luma[index] = 0;
+                    }
+                }
+            }
+        }
+        let mut hints = DecodeHints::default();
+        rxing::helpers::detect_in_luma_with_hints(luma, (w * scale) as u32, (h * scale) as u32, Some(format), &mut hints)
+            .ok()
+            .map(|result| result.getText().to_string())
+    }
+
+    #[test]
+    fn test_micro_versions_roundtrip() {
+        for (data, ecl, version) in [
+            ("12345", ErrorCorrectionLevel::Low, 1),
+            ("HELLO", ErrorCorrectionLevel::Medium, 2),
+            ("hello", ErrorCorrectionLevel::Low, 3),
+            ("MICRO QR 2024", ErrorCorrectionLevel::Quartile, 4),
+        ] {
+            let symbol = encode(data, QrSymbology::MicroQr, ecl).unwrap();
+            assert_eq!(symbol.version, version, "versión para {:?}", data);
+            assert_eq!(symbol.width(), 9 + 2 * version as usize);
+            assert_eq!(decode(&symbol, BarcodeFormat::MICRO_QR_CODE).as_deref(), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_micro_rejects_unsupported_input() {
+        assert!(encode("abc", QrSymbology::MicroQr, ErrorCorrectionLevel::High).is_err());
+        assert!(encode(&"x".repeat(40), QrSymbology::MicroQr, ErrorCorrectionLevel::Low).is_err());
+    }
+
+    #[test]
+    fn test_rmqr_roundtrip() {
+        // El último caso ocupa varios bloques Reed-Solomon intercalados
+        let long = "LOTE-A1B2C3".repeat(8);
+        for (data, ecl) in [
+            ("12345", ErrorCorrectionLevel::Medium),
+            ("https://example.com/rmqr", ErrorCorrectionLevel::Medium),
+            ("Código rectangular", ErrorCorrectionLevel::High),
+            (long.as_str(), ErrorCorrectionLevel::Medium),
+        ] {
+            let symbol = encode(data, QrSymbology::Rmqr, ecl).unwrap();
+            assert!(symbol.width() > symbol.height());
+            assert_eq!(decode(&symbol, BarcodeFormat::RECTANGULAR_MICRO_QR_CODE).as_deref(), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_rmqr_format_information() {
+        // Valores de referencia de ISO/IEC 23941 para R7x59 nivel M
+        let format = bch(1, RMQR_FORMAT_POLY, 12);
+        assert_eq!(format ^ RMQR_FORMAT_MASK_FINDER, 0x1E597);
+        assert_eq!(format ^ RMQR_FORMAT_MASK_SUB, 0x2155E);
+    }
+}
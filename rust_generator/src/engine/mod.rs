@@ -13,6 +13,21 @@ pub mod zones;
 pub mod geometry;
 pub mod ecl_optimizer;
 pub mod segmenter;
+pub mod raster;
+pub mod vector;
+pub mod verifier;
+pub mod degrader;
+pub mod animation;
+pub mod bitstream;
+pub mod codeword_map;
+pub mod linear;
+pub mod structured_append;
+pub mod micro_qr;
+pub mod matrix_codes;
+pub mod payload;
+pub mod logo_store;
+pub mod svg_logo;
+pub mod mask_selector;
 
 // Re-exportar tipos principales
 pub use generator::QrGenerator;
@@ -20,6 +35,14 @@ pub use customizer::QrCustomizer;
 pub use validator::QrValidator;
 pub use optimizer::QrOptimizer;
 pub use router::ComplexityRouter;
+pub use verifier::ScanVerifier;
+pub use degrader::EffectDegrader;
+pub use animation::QrAnimator;
+pub use payload::QrPayload;
+pub use logo_store::LogoStore;
+pub use svg_logo::VectorLogo;
+pub use mask_selector::{DesignMaskSelector, MaskChoice};
+pub use ecl_optimizer::EclOptimizer;
 pub use types::ComplexityLevel;
 pub use types::*;
 pub use error::{QrError, QrResult};
@@ -30,14 +53,19 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::cache::distributed::{DistributedCache, DistributedCacheConfig, RedisMode};
 
-/// Motor principal del sistema QR
+/// Motor principal del sistema QR (clonarlo comparte sus componentes)
+#[derive(Clone)]
 pub struct QrEngine {
     generator: Arc<QrGenerator>,
     customizer: Arc<QrCustomizer>,
     validator: Arc<QrValidator>,
     optimizer: Arc<QrOptimizer>,
     router: Arc<ComplexityRouter>,
+    verifier: Arc<ScanVerifier>,
+    degrader: Arc<EffectDegrader>,
+    animator: Arc<QrAnimator>,
     cache: Arc<RwLock<DistributedCache>>,
+    logo_store: std::sync::OnceLock<Arc<LogoStore>>,
 }
 
 impl QrEngine {
@@ -59,10 +87,33 @@ impl QrEngine {
             validator: Arc::new(QrValidator::new()),
             optimizer: Arc::new(QrOptimizer::new()),
             router: Arc::new(ComplexityRouter::new()),
+            verifier: Arc::new(ScanVerifier::new()),
+            degrader: Arc::new(EffectDegrader::new()),
+            animator: Arc::new(QrAnimator::new()),
             cache: Arc::new(RwLock::new(cache)),
+            logo_store: std::sync::OnceLock::new(),
         }
     }
 
+    /// Usa el almacén de logos indicado (sin él, `logo_id` no está disponible)
+    pub fn with_logo_store(mut self, store: Arc<LogoStore>) -> Self {
+        self.logo_store = std::sync::OnceLock::from(store);
+        self
+    }
+
+    /// Inyecta el almacén de logos en un motor ya creado (p. ej. `QR_ENGINE`);
+    /// solo puede hacerse una vez
+    pub fn attach_logo_store(&self, store: Arc<LogoStore>) -> QrResult<()> {
+        self.logo_store
+            .set(store)
+            .map_err(|_| QrError::InternalError("El motor ya tiene almacén de logos".to_string()))
+    }
+
+    /// Almacén de logos (None si no se inyectó ninguno)
+    pub fn logo_store(&self) -> Option<&Arc<LogoStore>> {
+        self.logo_store.get()
+    }
+
     /// Carga la configuración del cache desde variables de entorno
     fn load_cache_config() -> DistributedCacheConfig {
         let mode = if let Ok(cluster_nodes) = std::env::var("REDIS_CLUSTER_NODES") {
@@ -109,60 +160,466 @@ impl QrEngine {
 
     /// Genera un código QR con routing automático por complejidad
     pub async fn generate(&self, request: QrRequest) -> QrResult<QrOutput> {
+        let request = Self::resolve_payload(request)?;
+        let linear = request.customization.as_ref().and_then(|c| c.barcode.clone());
+        if linear.is_some() {
+            Self::check_linear_request(&request)?;
+        }
+        
         // 1. Generar clave de cache
         let cache_key = self.generate_cache_key(&request);
         
         // 2. Verificar cache
         {
             let mut cache = self.cache.write().await;
-            if let Some(cached) = cache.get(&cache_key) {
+            if let Some(output) = cache.get(&cache_key).and_then(Self::cached_output) {
                 tracing::debug!("Cache hit for QR generation");
-                return Ok(QrOutput {
-                    data: cached.svg,
-                    format: OutputFormat::Svg,
-                    metadata: QrMetadata {
-                        generation_time_ms: 0, // Cached, no generation time
-                        complexity_level: self.router.determine_complexity(&request),
-                        features_used: vec!["cached".to_string()],
-                        quality_score: 1.0,
-                    },
-                });
+                return self.encode_output(output, &request);
             }
         }
         
         // 3. Determinar nivel de complejidad
         let complexity = self.router.determine_complexity(&request);
         
-        // 4. Rutear a la pipeline correspondiente
-        let output = match complexity {
-            ComplexityLevel::Basic => self.generate_basic(request).await,
-            ComplexityLevel::Medium => self.generate_medium(request).await,
-            ComplexityLevel::Advanced => self.generate_advanced(request).await,
-            ComplexityLevel::Ultra => self.generate_ultra(request).await,
-        }?;
+        // 4. Rutear a la pipeline correspondiente (todas producen SVG).
+        //    Los diseños ultra siempre pasan por la verificación de escaneo.
+        let verification = match (&request.verification, complexity) {
+            (Some(options), _) => Some(options.clone()),
+            (None, ComplexityLevel::Ultra) => Some(VerificationOptions::default()),
+            (None, _) => None,
+        };
+        //    Los códigos lineales tienen su propia pipeline y verificación.
+        let output = match (&linear, &verification) {
+            (Some(options), _) => self.generate_linear(&request, options)?,
+            (None, Some(options)) => self.generate_verified(request.clone(), complexity, options).await?,
+            (None, None) => self.run_pipeline(request.clone(), complexity, std::time::Instant::now()).await?,
+        };
         
         // 5. Guardar en cache
         {
             let mut cache = self.cache.write().await;
-            let cached_qr = crate::cache::redis::CachedQR {
-                svg: output.data.clone(),
-                metadata: crate::cache::redis::QRMetadata {
-                    version: 1,
-                    modules: 0, // TODO: Obtener de QR generado
-                    error_correction: "M".to_string(), // TODO: Obtener de request
-                    processing_time_ms: output.metadata.generation_time_ms,
+            match Self::cache_entry(&output) {
+                Ok(cached_qr) => {
+                    if let Err(e) = cache.set(&cache_key, &cached_qr) {
+                        tracing::warn!("Failed to cache QR code: {}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to serialize QR output for cache: {}", e),
+            }
+        }
+        
+        // 6. Convertir al formato solicitado
+        self.encode_output(output, &request)
+    }
+
+    /// Entrada de cache con la salida SVG completa en JSON: un acierto devuelve los
+    /// metadatos de la generación original, con su puntuación y su verificación
+    fn cache_entry(output: &QrOutput) -> serde_json::Result<crate::cache::redis::CachedQR> {
+        // El símbolo no se conserva: solo se conoce el ECL leído al verificar
+        let error_correction = output.metadata.verification.as_ref()
+            .and_then(|v| v.decoded_error_correction.clone());
+        Ok(crate::cache::redis::CachedQR {
+            svg: serde_json::to_string(output)?,
+            metadata: crate::cache::redis::QRMetadata {
+                version: None,
+                modules: None,
+                error_correction,
+                processing_time_ms: output.metadata.generation_time_ms,
+            },
+            generated_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Salida guardada por `cache_entry`; las entradas en otro formato se regeneran
+    fn cached_output(cached: crate::cache::redis::CachedQR) -> Option<QrOutput> {
+        let mut output = serde_json::from_str::<QrOutput>(&cached.svg).ok()?;
+        output.metadata.generation_time_ms = 0; // Cached, no generation time
+        output.metadata.features_used.push("cached".to_string());
+        Some(output)
+    }
+
+    /// Genera un mensaje que no cabe en un símbolo como conjunto Structured Append
+    /// (hasta 16 símbolos enlazados), en archivos separados o en una hoja en mosaico.
+    /// Cada símbolo admite la personalización de colores, gradientes, ojos y patrones.
+    /// Con `verification` se lee cada símbolo y `auto_correct` corrige el conjunto entero.
+    pub async fn generate_set(&self, request: QrRequest, options: &StructuredAppendOptions) -> QrResult<QrSymbolSet> {
+        let start = std::time::Instant::now();
+        let request = Self::resolve_payload(request)?;
+        if request.customization.as_ref().and_then(|c| c.symbology).is_some_and(|s| s != QrSymbology::Qr) {
+            return Err(QrError::UnsupportedFormat(
+                "Structured Append solo está definido para QR modelo 2".to_string(),
+            ));
+        }
+        if request.customization.as_ref().and_then(|c| c.gs1_mode).unwrap_or(false) {
+            return Err(QrError::UnsupportedFormat(
+                "Structured Append no admite el modo GS1".to_string(),
+            ));
+        }
+        if request.customization.as_ref().is_some_and(|c| c.barcode.is_some()) {
+            return Err(QrError::UnsupportedFormat(
+                "Structured Append no admite códigos de barras lineales".to_string(),
+            ));
+        }
+        let max_attempts = request.verification.as_ref()
+            .and_then(|v| v.max_attempts)
+            .unwrap_or(verifier::DEFAULT_MAX_ATTEMPTS);
+        let mut request = request;
+        let mut corrections = Vec::new();
+        let mut attempts = 0;
+
+        // Como en `generate_verified`, pero cada corrección regenera el conjunto
+        // completo: todos los símbolos comparten personalización y paridad
+        let (set, svgs, decoded) = loop {
+            let (set, svgs) = self.render_set(&request, options)?;
+            let Some(verification) = &request.verification else {
+                break (set, svgs, Vec::new());
+            };
+
+            let failure = match set.symbols.iter().zip(&svgs)
+                .map(|(symbol, svg)| self.verifier.verify(svg, &symbol.data).map_err(|e| format!(
+                    "el símbolo {} de {} no es legible: {}", symbol.index + 1, symbol.total, e
+                )))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(decoded) => break (set, svgs, decoded),
+                Err(failure) => failure,
+            };
+
+            tracing::debug!("Set verification failed (attempt {}): {}", attempts, failure);
+
+            let correction = if verification.auto_correct && attempts < max_attempts {
+                self.verifier.next_correction(&mut request)
+            } else {
+                None
+            };
+
+            match correction {
+                Some(correction) => {
+                    corrections.push(correction);
+                    attempts += 1;
+                }
+                None => {
+                    return Err(QrError::ValidationError(format!(
+                        "El conjunto no es legible tras {} correcciones: {}",
+                        corrections.len(),
+                        failure
+                    )));
+                }
+            }
+        };
+        let symbol_count = set.symbols.len();
+        let complexity = self.router.determine_complexity(&request);
+
+        let mut decoded = decoded.into_iter();
+        let mut outputs = Vec::with_capacity(symbol_count);
+        for (symbol, svg) in set.symbols.into_iter().zip(&svgs) {
+            let verification = decoded.next().map(|decoded| ScanVerification {
+                passed: true,
+                attempts,
+                corrections: corrections.clone(),
+                warnings: self.verifier.warnings(&decoded),
+                decoded_error_correction: Some(decoded.metadata.error_correction),
+                decode_score: Some(decoded.quality.overall_score),
+            });
+
+            let mut features_used = vec![
+                "structured_append".to_string(),
+                format!("structured_append_{}_of_{}", symbol.index + 1, symbol_count),
+            ];
+            if !corrections.is_empty() {
+                features_used.push("auto_corrected".to_string());
+            }
+            let output = QrOutput {
+                data: svg.clone(),
+                format: OutputFormat::Svg,
+                metadata: QrMetadata {
+                    generation_time_ms: start.elapsed().as_millis() as u64,
+                    complexity_level: complexity,
+                    features_used,
+                    quality_score: 1.0,
+                    verification,
                 },
-                generated_at: chrono::Utc::now().timestamp(),
+                bytes: None,
             };
-            
-            if let Err(e) = cache.set(&cache_key, &cached_qr) {
-                tracing::warn!("Failed to cache QR code: {}", e);
+            outputs.push((output, symbol.data));
+        }
+
+        let outputs = match options.layout {
+            SymbolSetLayout::Separate => outputs
+                .into_iter()
+                .map(|(output, data)| self.encode_output(output, &QrRequest { data, ..request.clone() }))
+                .collect::<QrResult<Vec<_>>>()?,
+            SymbolSetLayout::Sheet => {
+                if request.format == OutputFormat::Gif {
+                    return Err(QrError::UnsupportedFormat(
+                        "La hoja de símbolos no admite GIF".to_string(),
+                    ));
+                }
+                let columns = options.sheet_columns
+                    .map(|c| c as usize)
+                    .unwrap_or_else(|| (symbol_count as f64).sqrt().ceil() as usize);
+                let sheet = QrOutput {
+                    data: structured_append::tile_sheet(&svgs, columns)?,
+                    format: OutputFormat::Svg,
+                    metadata: QrMetadata {
+                        generation_time_ms: start.elapsed().as_millis() as u64,
+                        complexity_level: complexity,
+                        features_used: vec!["structured_append".to_string(), "symbol_sheet".to_string()],
+                        quality_score: 1.0,
+                        verification: None,
+                    },
+                    bytes: None,
+                };
+
+                // La hoja es estática y su ancho es el de una fila completa de símbolos
+                let columns = columns.clamp(1, symbol_count) as u32;
+                let mut sheet_request = QrRequest { size: request.size * columns, ..request.clone() };
+                if let Some(customization) = sheet_request.customization.as_mut() {
+                    customization.animation = None;
+                }
+                vec![self.encode_output(sheet, &sheet_request)?]
+            }
+        };
+
+        Ok(QrSymbolSet {
+            symbol_count,
+            parity: set.parity,
+            outputs,
+        })
+    }
+
+    /// Codifica el conjunto Structured Append de `request` y renderiza cada símbolo
+    fn render_set(
+        &self,
+        request: &QrRequest,
+        options: &StructuredAppendOptions,
+    ) -> QrResult<(StructuredAppendSet, Vec<String>)> {
+        let ecl = request.customization.as_ref()
+            .and_then(|c| c.error_correction)
+            .unwrap_or(ErrorCorrectionLevel::Medium);
+
+        let mut set = self.generator.generate_structured_append(&request.data, request.size, ecl, options)?;
+        let mut svgs = Vec::with_capacity(set.symbols.len());
+        for symbol in &mut set.symbols {
+            if let Some(quiet_zone) = request.customization.as_ref().and_then(|c| c.quiet_zone) {
+                self.generator.apply_quiet_zone(&mut symbol.qr, quiet_zone)?;
             }
+            symbol.qr.customization = request.customization.clone();
+            svgs.push(symbol.qr.to_svg());
         }
-        
+        Ok((set, svgs))
+    }
+
+    /// Los códigos lineales son estáticos: sin GIF ni animaciones
+    fn check_linear_request(request: &QrRequest) -> QrResult<()> {
+        let animated = request.customization.as_ref()
+            .and_then(|c| c.animation.as_ref())
+            .is_some_and(|a| !a.effects.is_empty());
+        if request.format == OutputFormat::Gif || animated {
+            return Err(QrError::UnsupportedFormat(
+                "Los códigos de barras lineales no admiten animación ni GIF".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Genera un código de barras lineal con los colores y la zona silenciosa de la
+    /// personalización; con verificación, el SVG se lee y se compara con el contenido
+    fn generate_linear(&self, request: &QrRequest, options: &LinearOptions) -> QrResult<QrOutput> {
+        let start = std::time::Instant::now();
+        let customization = request.customization.as_ref();
+        let barcode = linear::encode(&request.data, options)?;
+        let svg = barcode.to_svg(
+            linear::MODULE_UNITS,
+            options,
+            customization.and_then(|c| c.colors.as_ref()),
+            customization.and_then(|c| c.quiet_zone),
+        );
+
+        let verification = match &request.verification {
+            Some(_) => {
+                // El lector no devuelve los add-ons de 2 dígitos: solo se comparan los de 5
+                let (content, add_on) = linear::decode_svg(&svg, barcode.symbology)?;
+                let expected_add_on = barcode.add_on_text.clone().filter(|digits| digits.len() == 5);
+                if content != barcode.content || add_on != expected_add_on {
+                    return Err(QrError::ValidationError(format!(
+                        "El código de barras se leyó como '{}' en lugar de '{}'", content, barcode.content
+                    )));
+                }
+                Some(ScanVerification {
+                    passed: true,
+                    attempts: 1,
+                    corrections: Vec::new(),
+                    warnings: Vec::new(),
+                    decoded_error_correction: None,
+                    decode_score: None,
+                })
+            }
+            None => None,
+        };
+
+        Ok(QrOutput {
+            data: svg,
+            format: OutputFormat::Svg,
+            metadata: QrMetadata {
+                generation_time_ms: start.elapsed().as_millis() as u64,
+                complexity_level: ComplexityLevel::Basic,
+                features_used: self.get_used_features(request),
+                quality_score: 1.0,
+                verification,
+            },
+            bytes: None,
+        })
+    }
+
+    /// Ejecuta la pipeline del nivel de complejidad indicado; `started` es el inicio
+    /// de la solicitud completa, del que se descuenta el presupuesto de tiempo ultra
+    async fn run_pipeline(&self, request: QrRequest, complexity: ComplexityLevel, started: std::time::Instant) -> QrResult<QrOutput> {
+        match complexity {
+            ComplexityLevel::Basic => self.generate_basic(request).await,
+            ComplexityLevel::Medium => self.generate_medium(request).await,
+            ComplexityLevel::Advanced => self.generate_advanced(request).await,
+            ComplexityLevel::Ultra => self.generate_ultra(request, started).await,
+        }
+    }
+
+    /// Genera y decodifica el resultado; si no se lee, regenera aplicando
+    /// correcciones progresivas (o falla si la auto-corrección está desactivada)
+    async fn generate_verified(
+        &self,
+        mut request: QrRequest,
+        complexity: ComplexityLevel,
+        options: &VerificationOptions,
+    ) -> QrResult<QrOutput> {
+        let start = std::time::Instant::now();
+        let max_attempts = options.max_attempts.unwrap_or(verifier::DEFAULT_MAX_ATTEMPTS);
+        let mut corrections = Vec::new();
+        let mut attempts = 0;
+
+        loop {
+            // Todos los intentos comparten el mismo presupuesto de tiempo
+            let mut output = self.run_pipeline(request.clone(), complexity, start).await?;
+
+            let failure = match self.verifier.verify(&output.data, &request.data) {
+                Ok(decoded) => {
+                    // La puntuación combina las heurísticas del validador con la lectura real
+                    let decode_score = decoded.quality.overall_score;
+                    output.metadata.quality_score = (output.metadata.quality_score + decode_score) / 2.0;
+                    output.metadata.generation_time_ms = start.elapsed().as_millis() as u64;
+                    output.metadata.features_used.push("scan_verified".to_string());
+                    if !corrections.is_empty() {
+                        output.metadata.features_used.push("auto_corrected".to_string());
+                    }
+                    output.metadata.verification = Some(ScanVerification {
+                        passed: true,
+                        attempts,
+                        corrections,
+                        warnings: self.verifier.warnings(&decoded),
+                        decoded_error_correction: Some(decoded.metadata.error_correction),
+                        decode_score: Some(decode_score),
+                    });
+                    return Ok(output);
+                }
+                Err(e) => e,
+            };
+
+            tracing::debug!("Scan verification failed (attempt {}): {}", attempts, failure);
+
+            let correction = if options.auto_correct && attempts < max_attempts {
+                self.verifier.next_correction(&mut request)
+            } else {
+                None
+            };
+
+            match correction {
+                Some(correction) => {
+                    corrections.push(correction);
+                    attempts += 1;
+                }
+                None => {
+                    return Err(QrError::ValidationError(format!(
+                        "El código generado no es legible tras {} correcciones: {}",
+                        corrections.len(),
+                        failure
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Convierte la salida SVG de las pipelines al formato solicitado.
+    /// La animación se aplica aquí (SVG con SMIL o GIF); el resto de formatos son estáticos.
+    fn encode_output(&self, mut output: QrOutput, request: &QrRequest) -> QrResult<QrOutput> {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let animation = request.customization.as_ref()
+            .and_then(|c| c.animation.as_ref())
+            .filter(|a| !a.effects.is_empty());
+
+        let bytes = match request.format {
+            OutputFormat::Svg => {
+                if let Some(options) = animation {
+                    let (svg, report) = self.animator.animate_svg(&output.data, &request.data, options)?;
+                    output.data = svg;
+                    Self::record_animation(&mut output, &report, options);
+                }
+                return Ok(output);
+            }
+            OutputFormat::Gif => {
+                let static_options = AnimationOptions::default();
+                let (bytes, report) = self.animator.render_gif(
+                    &output.data,
+                    &request.data,
+                    animation.unwrap_or(&static_options),
+                    request.size,
+                )?;
+                if let Some(options) = animation {
+                    Self::record_animation(&mut output, &report, options);
+                }
+                bytes
+            }
+            OutputFormat::Png | OutputFormat::Jpeg | OutputFormat::Webp => raster::rasterize(
+                &output.data,
+                request.format,
+                request.size,
+                request.output.as_ref(),
+            )?,
+            OutputFormat::Pdf | OutputFormat::Eps => vector::export_vector(
+                &output.data,
+                request.format,
+                request.size,
+                request.output.as_ref(),
+            )?,
+        };
+
+        output.data = BASE64.encode(&bytes);
+        output.format = request.format;
+        output.bytes = Some(bytes);
         Ok(output)
     }
 
+    /// Registra la animación aplicada en los metadatos
+    fn record_animation(output: &mut QrOutput, report: &animation::AnimationReport, options: &AnimationOptions) {
+        output.metadata.features_used.push("animated".to_string());
+        output.metadata.features_used.push(format!("animation_frames_verified_{}", report.frames_verified));
+        if report.intensity < options.intensity.unwrap_or(1.0).clamp(0.0, 1.0) {
+            output.metadata.features_used.push("animation_intensity_reduced".to_string());
+        }
+    }
+
+    /// Sustituye `data` por la cadena construida a partir de `payload`, si se envió.
+    /// En modo GS1 los datos pasan a la cadena de elementos que contiene el símbolo
+    /// (separadores GS), que es lo que devuelve un lector y compara la verificación.
+    fn resolve_payload(mut request: QrRequest) -> QrResult<QrRequest> {
+        let payload = request.payload.take();
+        request.data = payload::resolve_data(request.data, payload.as_ref())?;
+        if request.customization.as_ref().and_then(|c| c.gs1_mode).unwrap_or(false) {
+            let elements = crate::standards::gs1::Gs1Parser::new().parse(&request.data)?;
+            request.data = crate::standards::gs1::Gs1Encoder::new().encode_element_string(&elements)?;
+        }
+        Ok(request)
+    }
+
     /// Genera clave de cache para una request
     fn generate_cache_key(&self, request: &QrRequest) -> String {
         // Generar hash único basado en los datos y opciones
@@ -177,6 +634,11 @@ impl QrEngine {
             let options_str = format!("{:?}", custom);
             hasher.update(options_str.as_bytes());
         }
+
+        // El SVG verificado puede diferir del original por las correcciones
+        if let Some(verification) = &request.verification {
+            hasher.update(format!("{:?}", verification).as_bytes());
+        }
         
         let result = hasher.finalize();
         let mut key = String::with_capacity(16);
@@ -191,28 +653,7 @@ impl QrEngine {
     async fn generate_basic(&self, request: QrRequest) -> QrResult<QrOutput> {
         let start = std::time::Instant::now();
         
-        // Verificar si hay tamaño fijo solicitado
-        let qr_code = if let Some(customization) = &request.customization {
-            if let Some(fixed_size) = customization.fixed_size {
-                // Generar con tamaño fijo
-                self.generator.generate_with_fixed_size(
-                    &request.data,
-                    request.size,
-                    fixed_size,
-                    customization.error_correction,
-                )?
-            } else {
-                // Generar normal con ECL si está especificado
-                if let Some(ecl) = customization.error_correction {
-                    self.generator.generate_with_ecl(&request.data, request.size, ecl)?
-                } else {
-                    self.generator.generate_basic(&request.data, request.size)?
-                }
-            }
-        } else {
-            // Generar QR básico
-            self.generator.generate_basic(&request.data, request.size)?
-        };
+        let qr_code = self.generate_base(&request, &[])?;
         
         // Convertir a output
         let output = QrOutput {
@@ -223,7 +664,9 @@ impl QrEngine {
                 complexity_level: ComplexityLevel::Basic,
                 features_used: vec!["basic_generation".to_string()],
                 quality_score: 1.0,
+                verification: None,
             },
+            bytes: None,
         };
         
         Ok(output)
@@ -233,23 +676,8 @@ impl QrEngine {
     async fn generate_medium(&self, request: QrRequest) -> QrResult<QrOutput> {
         let start = std::time::Instant::now();
         
-        // Generar base considerando tamaño fijo si está presente
-        let mut qr_code = if let Some(customization) = &request.customization {
-            if let Some(fixed_size) = customization.fixed_size {
-                self.generator.generate_with_fixed_size(
-                    &request.data,
-                    request.size,
-                    fixed_size,
-                    customization.error_correction,
-                )?
-            } else if let Some(ecl) = customization.error_correction {
-                self.generator.generate_with_ecl(&request.data, request.size, ecl)?
-            } else {
-                self.generator.generate_basic(&request.data, request.size)?
-            }
-        } else {
-            self.generator.generate_basic(&request.data, request.size)?
-        };
+        // Generar base considerando simbología y tamaño fijo
+        let mut qr_code = self.generate_base(&request, &[])?;
         
         // Aplicar personalizaciones medias
         if let Some(customization) = &request.customization {
@@ -294,7 +722,9 @@ impl QrEngine {
                 complexity_level: ComplexityLevel::Medium,
                 features_used: self.get_used_features(&request),
                 quality_score: validation.score,
+                verification: None,
             },
+            bytes: None,
         };
         
         Ok(output)
@@ -304,34 +734,31 @@ impl QrEngine {
     async fn generate_advanced(&self, request: QrRequest) -> QrResult<QrOutput> {
         let start = std::time::Instant::now();
         
-        // Pipeline paralela con rayon
-        let (qr_code, logo_result, frame_info, effects_info) = rayon::scope(|_s| -> QrResult<(QrCode, Option<crate::engine::customizer::LogoIntegrationResult>, Option<crate::engine::customizer::FrameInfo>, Option<crate::engine::customizer::EffectsInfo>)> {
-            // Generar base en paralelo con preparación de assets
-            let (qr_code, assets) = rayon::join(
-                || {
-                    // Generar considerando tamaño fijo si está presente
-                    if let Some(customization) = &request.customization {
-                        if let Some(fixed_size) = customization.fixed_size {
-                            self.generator.generate_with_fixed_size(
-                                &request.data,
-                                request.size,
-                                fixed_size,
-                                customization.error_correction,
-                            )
-                        } else if let Some(ecl) = customization.error_correction {
-                            self.generator.generate_with_ecl(&request.data, request.size, ecl)
-                        } else {
-                            self.generator.generate_basic(&request.data, request.size)
-                        }
-                    } else {
-                        self.generator.generate_basic(&request.data, request.size)
-                    }
-                },
-                || self.prepare_advanced_assets(&request),
-            );
-            
-            let qr_code = qr_code?;
-            let assets = assets?;
+        let (svg, quality_score) = self.render_advanced(&request)?;
+        
+        let output = QrOutput {
+            data: svg,
+            format: OutputFormat::Svg,
+            metadata: QrMetadata {
+                generation_time_ms: start.elapsed().as_millis() as u64,
+                complexity_level: ComplexityLevel::Advanced,
+                features_used: self.get_used_features(&request),
+                quality_score,
+                verification: None,
+            },
+            bytes: None,
+        };
+        
+        Ok(output)
+    }
+
+    /// Renderizado completo (logo, marco, efectos) compartido por las pipelines avanzada y ultra.
+    /// Retorna el SVG y la puntuación de validación.
+    fn render_advanced(&self, request: &QrRequest) -> QrResult<(String, f32)> {
+        let (qr_code, logo_results, frame_info, effects_info) = rayon::scope(|_s| -> QrResult<customizer::AdvancedCustomization> {
+            // El ECL depende de la silueta de los logos: los assets se preparan antes que la base
+            let assets = self.prepare_advanced_assets(request)?;
+            let qr_code = self.generate_base(request, &assets.logos)?;
             
             // Aplicar customizaciones avanzadas
             if let Some(customization) = &request.customization {
@@ -344,7 +771,7 @@ impl QrEngine {
                 )?;
                 Ok((customized_qr, logo_res, frame_inf, effects_inf))
             } else {
-                Ok((qr_code, None, None, None))
+                Ok((qr_code, Vec::new(), None, None))
             }
         })?;
         
@@ -383,30 +810,264 @@ impl QrEngine {
         // Generar SVG con logo, marco y efectos si existen
         let svg = qr_code.to_svg_with_options(
             10, 
-            logo_result.as_ref(), 
+            &logo_results, 
             frame_info.as_ref(),
             effects_info.as_ref(),
             request.customization.as_ref()
         );
         
-        let output = QrOutput {
+        Ok((svg, validation.score))
+    }
+
+    /// Generación ultra - Target: <200ms
+    ///
+    /// Renderiza dentro del presupuesto de tiempo del nivel (o `max_render_time_ms`);
+    /// con `auto_degrade_effects` elimina efectos empezando por los más costosos
+    /// en lugar de exceder el presupuesto. Si ya no queda nada que degradar se entrega
+    /// el diseño mínimo marcado con `time_budget_exceeded`. La verificación de escaneo
+    /// la aplica `generate`.
+    async fn generate_ultra(&self, mut request: QrRequest, start: std::time::Instant) -> QrResult<QrOutput> {
+
+        let limits = self.router.get_resource_limits(ComplexityLevel::Ultra);
+        let performance = request.customization.as_ref()
+            .and_then(|c| c.performance.clone())
+            .unwrap_or_default();
+        let budget_ms = performance.max_render_time_ms
+            .map(u64::from)
+            .unwrap_or(limits.timeout_ms);
+        let auto_degrade = performance.auto_degrade_effects.unwrap_or(false);
+        
+        let mut degraded = Vec::new();
+        if let Some(customization) = request.customization.as_mut() {
+            // Priorizar escaneabilidad: quitar efectos que deforman los módulos
+            if performance.prioritize_scanability.unwrap_or(false) {
+                degraded.extend(self.degrader.strip_scan_hazards(customization));
+            }
+            
+            // Degradar antes de renderizar si la estimación ya excede el presupuesto
+            if auto_degrade {
+                while self.degrader.estimate_cost_ms(customization, request.size) > budget_ms as f64 {
+                    match self.degrader.degrade_most_expensive(customization) {
+                        Some(effect) => degraded.push(effect),
+                        None => break,
+                    }
+                }
+            }
+        }
+        
+        // Renderizar fuera del runtime con el presupuesto restante como límite (sin
+        // presupuesto no se lanza); si se agota, degradar y reintentar o fallar. Un render
+        // abandonado termina en segundo plano sin que se espere su resultado.
+        let spawn_render = |request: &QrRequest| {
+            let engine = self.clone();
+            let request = request.clone();
+            tokio::task::spawn_blocking(move || engine.render_advanced(&request))
+        };
+        let mut budget_exceeded = false;
+        let (svg, quality_score) = loop {
+            let remaining = budget_ms.saturating_sub(start.elapsed().as_millis() as u64);
+            let mut pending = None;
+            if remaining > 0 {
+                let mut render = spawn_render(&request);
+                match tokio::time::timeout(std::time::Duration::from_millis(remaining), &mut render).await {
+                    Ok(rendered) => break Self::join_render(rendered)?,
+                    Err(_) => pending = Some(render),
+                }
+            }
+            
+            if !auto_degrade {
+                return Err(QrError::GenerationTimeout(start.elapsed().as_millis() as u64));
+            }
+            
+            match request.customization.as_mut().and_then(|c| self.degrader.degrade_most_expensive(c)) {
+                Some(effect) => degraded.push(effect),
+                None => {
+                    // Sin efectos que degradar: se entrega el diseño mínimo marcado como excedido
+                    tracing::warn!("Ultra pipeline exceeded {}ms budget without effects left to degrade", budget_ms);
+                    budget_exceeded = true;
+                    let render = pending.unwrap_or_else(|| spawn_render(&request));
+                    break Self::join_render(render.await)?;
+                }
+            }
+        };
+        
+        let mut features_used = self.get_used_features(&request);
+        features_used.push("ultra_pipeline".to_string());
+        for effect in &degraded {
+            features_used.push(format!("degraded_effect_{:?}", effect).to_lowercase());
+        }
+        if budget_exceeded {
+            features_used.push("time_budget_exceeded".to_string());
+        }
+        
+        // Cada efecto degradado reduce la fidelidad respecto al diseño solicitado
+        let quality_score = (quality_score - 0.05 * degraded.len() as f32).max(0.0);
+        
+        Ok(QrOutput {
             data: svg,
             format: OutputFormat::Svg,
             metadata: QrMetadata {
                 generation_time_ms: start.elapsed().as_millis() as u64,
-                complexity_level: ComplexityLevel::Advanced,
-                features_used: self.get_used_features(&request),
-                quality_score: validation.score,
+                complexity_level: ComplexityLevel::Ultra,
+                features_used,
+                quality_score,
+                verification: None,
             },
+            bytes: None,
+        })
+    }
+
+    /// Resultado de un render lanzado con `spawn_blocking`
+    fn join_render(
+        joined: Result<QrResult<(String, f32)>, tokio::task::JoinError>,
+    ) -> QrResult<(String, f32)> {
+        joined.map_err(|e| QrError::InternalError(format!("Render interrumpido: {}", e)))?
+    }
+
+    /// Genera el símbolo base y aplica la zona de silencio solicitada. `logos` son los
+    /// logos ya cargados de la personalización (vacío si la pipeline no los dibuja).
+    fn generate_base(&self, request: &QrRequest, logos: &[PreparedLogo]) -> QrResult<QrCode> {
+        let Some(customization) = &request.customization else {
+            return self.generator.generate_basic(&request.data, request.size);
         };
         
-        Ok(output)
+        let mut qr_code = self.generate_symbol(&request.data, request.size, customization, logos)?;
+        if let Some(quiet_zone) = customization.quiet_zone {
+            self.generator.apply_quiet_zone(&mut qr_code, quiet_zone)?;
+        }
+        Ok(qr_code)
+    }
+    
+    /// Codifica el símbolo según simbología, tamaño fijo y nivel de corrección
+    fn generate_symbol(
+        &self,
+        data: &str,
+        size: u32,
+        customization: &QrCustomization,
+        logos: &[PreparedLogo],
+    ) -> QrResult<QrCode> {
+        let mask_selection = customization.mask_selection.unwrap_or_default();
+        let explicit_layout = customization.version.is_some()
+            || customization.mask.is_some()
+            || mask_selection == MaskSelection::Design;
+        if explicit_layout
+            && (customization.symbology.unwrap_or_default() != QrSymbology::Qr || customization.gs1_mode.unwrap_or(false))
+        {
+            return Err(QrError::ValidationError(
+                "version, mask y mask_selection solo se aplican a QR sin modo GS1".to_string(),
+            ));
+        }
+        
+        match customization.symbology.unwrap_or_default() {
+            QrSymbology::Qr if customization.gs1_mode.unwrap_or(false) => {
+                return self.generator.generate_gs1(
+                    data,
+                    size,
+                    customization.error_correction,
+                    customization.fixed_size,
+                );
+            }
+            QrSymbology::Qr => {}
+            _ if customization.gs1_mode.unwrap_or(false) => {
+                return Err(QrError::ValidationError(
+                    "El modo GS1 solo está disponible para QR".to_string(),
+                ));
+            }
+            symbology @ (QrSymbology::DataMatrix | QrSymbology::Aztec | QrSymbology::Pdf417) => {
+                // Eligen su tamaño según los datos y sus propias opciones de dimensiones
+                let options = customization.matrix_code.clone().unwrap_or_default();
+                return self.generator.generate_matrix_symbol(
+                    data,
+                    size,
+                    symbology,
+                    customization.error_correction,
+                    &options,
+                );
+            }
+            symbology => {
+                // Micro QR y rMQR eligen su versión: fixed_size no aplica y sin ECL
+                // explícito se prioriza el símbolo más pequeño
+                let ecl = customization.error_correction.unwrap_or(ErrorCorrectionLevel::Low);
+                return self.generator.generate_micro(data, size, symbology, ecl);
+            }
+        }
+        
+        // Sin ECL explícito, los logos fijan el mínimo que recupera todos los bloques que tapan
+        let error_correction = match customization.error_correction {
+            Some(ecl) => Some(ecl),
+            // El nivel elegido pasa como explícito: `version`/`mask` ya no lo refuerzan
+            None if !logos.is_empty() => {
+                Some(self.logo_error_correction(data, customization, logos, !explicit_layout)?)
+            }
+            None => None,
+        };
+        
+        if explicit_layout {
+            let selector;
+            let mask = match (customization.mask, mask_selection) {
+                (Some(_), MaskSelection::Design) => {
+                    return Err(QrError::ValidationError(
+                        "mask fija y mask_selection: design son incompatibles".to_string(),
+                    ));
+                }
+                (Some(mask), _) => MaskChoice::Fixed(mask),
+                (None, MaskSelection::Design) => {
+                    selector = DesignMaskSelector::for_customization(customization);
+                    MaskChoice::Design(&selector)
+                }
+                (None, MaskSelection::Standard) => MaskChoice::Standard,
+            };
+            self.generator.generate_with_version_and_mask(
+                data,
+                size,
+                error_correction,
+                customization.fixed_size,
+                customization.version,
+                mask,
+            )
+        } else if let Some(fixed_size) = customization.fixed_size {
+            self.generator.generate_with_fixed_size(
+                data,
+                size,
+                fixed_size,
+                error_correction,
+            )
+        } else if let Some(ecl) = error_correction {
+            self.generator.generate_with_ecl(data, size, ecl)
+        } else {
+            self.generator.generate_basic(data, size)
+        }
     }
 
-    /// Generación ultra - Target: <200ms
-    async fn generate_ultra(&self, request: QrRequest) -> QrResult<QrOutput> {
-        // TODO: Implementar en fase 3
-        self.generate_advanced(request).await
+    /// ECL mínimo para los logos de la personalización, analizado sobre el símbolo que
+    /// se va a codificar (segmentos, rango de versiones y boost)
+    fn logo_error_correction(
+        &self,
+        data: &str,
+        customization: &QrCustomization,
+        logos: &[PreparedLogo],
+        boost_ecl: bool,
+    ) -> QrResult<ErrorCorrectionLevel> {
+        let optimizer = EclOptimizer::for_customization(customization, logos).with_boost(boost_ecl);
+        let (ecl, analysis) = optimizer.determine_optimal_ecl(data, optimizer.logo_size_ratio(), None)?;
+        tracing::debug!(
+            "ECL por logo: {:?} (versión {}, {} codewords afectados)",
+            ecl, analysis.qr_version, analysis.affected_codewords
+        );
+        Ok(ecl)
+    }
+
+    /// Carga el logo y los overlays de la personalización para una salida de `size`
+    /// píxeles, en el orden de `QrCustomization::logos`
+    pub fn prepare_logos(&self, customization: &QrCustomization, size: u32) -> QrResult<Vec<PreparedLogo>> {
+        customization
+            .logos()
+            .map(|logo| {
+                // El logo nunca ocupa más píxeles que su porcentaje del lado de salida
+                let target_px = (size as f32 * logo.size_percentage / 100.0).ceil().max(1.0) as u32;
+                self.load_logo(logo, target_px)
+            })
+            .collect()
     }
 
     /// Prepara assets para generación avanzada
@@ -414,10 +1075,8 @@ impl QrEngine {
         let mut assets = AdvancedAssets::default();
         
         if let Some(customization) = &request.customization {
-            // Preparar logo si existe
-            if let Some(logo_data) = &customization.logo {
-                assets.logo = Some(self.optimizer.prepare_logo(logo_data)?);
-            }
+            // Preparar logo y overlays si existen
+            assets.logos = self.prepare_logos(customization, request.size)?;
             
             // Pre-calcular gradientes
             if let Some(gradient) = &customization.gradient {
@@ -428,6 +1087,30 @@ impl QrEngine {
         Ok(assets)
     }
 
+    /// Carga el logo desde `data` o desde la biblioteca (`logo_id`) con su forma aplicada.
+    /// Los SVG devuelven además su versión vectorial; el raster queda para análisis
+    /// (p. ej. la silueta de exclusión) y salidas sin vector.
+    pub fn load_logo(&self, logo: &LogoOptions, target_px: u32) -> QrResult<PreparedLogo> {
+        let (image, vector) = match &logo.logo_id {
+            Some(_) if !logo.data.is_empty() => {
+                return Err(QrError::LogoError("Envíe `data` o `logo_id`, no ambos".to_string()));
+            }
+            Some(logo_id) => {
+                let store = self.logo_store.get().ok_or_else(|| {
+                    QrError::LogoError("La biblioteca de logos no está disponible".to_string())
+                })?;
+                let vector = store.load_vector(logo_id)?;
+                (self.optimizer.shape_logo(store.load(logo_id, target_px)?, logo.shape), vector)
+            }
+            None if VectorLogo::is_svg_data_url(&logo.data) => {
+                let vector = VectorLogo::from_data_url(&logo.data)?;
+                (self.optimizer.shape_logo(vector.rasterize(target_px)?, logo.shape), Some(vector))
+            }
+            None => (self.optimizer.prepare_logo(logo)?, None),
+        };
+        Ok(PreparedLogo { image, vector })
+    }
+
     /// Obtiene lista de características usadas
     fn get_used_features(&self, request: &QrRequest) -> Vec<String> {
         let mut features = vec!["basic_generation".to_string()];
@@ -459,6 +1142,46 @@ impl QrEngine {
             if let Some(fixed_size) = customization.fixed_size {
                 features.push(format!("fixed_size_{:?}", fixed_size).to_lowercase());
             }
+            if let Some(version) = customization.version {
+                features.push(format!("version_{}", version));
+            }
+            if let Some(mask) = customization.mask {
+                features.push(format!("mask_{}", mask));
+            } else if customization.mask_selection == Some(MaskSelection::Design) {
+                features.push("design_mask".to_string());
+            }
+            match customization.symbology {
+                Some(QrSymbology::MicroQr) => features.push("micro_qr".to_string()),
+                Some(QrSymbology::Rmqr) => features.push("rmqr".to_string()),
+                Some(QrSymbology::DataMatrix) => features.push("data_matrix".to_string()),
+                Some(QrSymbology::Aztec) => features.push("aztec".to_string()),
+                Some(QrSymbology::Pdf417) => features.push("pdf417".to_string()),
+                _ => {}
+            }
+            if let Some(options) = &customization.matrix_code {
+                if options.datamatrix_shape == Some(DataMatrixShape::Dmre) {
+                    features.push("dmre".to_string());
+                }
+                if options.aztec_compact == Some(true) || options.pdf417_compact == Some(true) {
+                    features.push("compact".to_string());
+                }
+            }
+            if customization.gs1_mode.unwrap_or(false) {
+                features.push("gs1".to_string());
+            }
+            if let Some(barcode) = &customization.barcode {
+                features.push("linear_barcode".to_string());
+                features.push(barcode.symbology.name().to_string());
+                if barcode.add_on.is_some() {
+                    features.push("add_on".to_string());
+                }
+                if barcode.bearer_bars.unwrap_or(barcode.symbology == LinearSymbology::Itf14) {
+                    features.push("bearer_bars".to_string());
+                }
+                if barcode.include_text.unwrap_or(true) {
+                    features.push("human_readable_text".to_string());
+                }
+            }
         }
         
         features
@@ -2,6 +2,7 @@
 
 use super::types::*;
 use super::error::{QrError, QrResult};
+use super::svg_logo::{VectorLogo, DEFAULT_SVG_LOGO_RASTER_PX};
 use image::DynamicImage;
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -221,6 +222,13 @@ impl QrOptimizer {
     pub fn prepare_logo(&self, logo_data: &LogoOptions) -> QrResult<DynamicImage> {
         use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
         
+        // Los SVG se sanean y rasterizan con resvg
+        if VectorLogo::is_svg_data_url(&logo_data.data) {
+            let logo = VectorLogo::from_data_url(&logo_data.data)?
+                .rasterize(DEFAULT_SVG_LOGO_RASTER_PX)?;
+            return Ok(self.shape_logo(logo, logo_data.shape));
+        }
+        
         // Decodificar logo desde base64
         if logo_data.data.starts_with("data:image") {
             let base64_start = logo_data.data.find(',').unwrap_or(0) + 1;
@@ -229,21 +237,24 @@ impl QrOptimizer {
             let decoded = BASE64.decode(base64_data)
                 .map_err(|e| QrError::LogoError(format!("Error decodificando base64: {}", e)))?;
             
-            let mut logo = image::load_from_memory(&decoded)
+            let logo = image::load_from_memory(&decoded)
                 .map_err(|e| QrError::LogoError(format!("Error cargando imagen: {}", e)))?;
             
-            // Aplicar forma al logo si es necesario
-            logo = self.apply_logo_shape(logo, logo_data.shape);
-            
-            // Optimizar para QR (reducir colores si es necesario)
-            logo = self.optimize_logo_for_qr(logo);
-            
-            Ok(logo)
+            Ok(self.shape_logo(logo, logo_data.shape))
         } else {
             Err(QrError::LogoError("Solo se soportan logos en formato base64".to_string()))
         }
     }
     
+    /// Aplica la forma y la optimización para QR a un logo ya cargado
+    pub fn shape_logo(&self, logo: DynamicImage, shape: LogoShape) -> DynamicImage {
+        // Aplicar forma al logo si es necesario
+        let logo = self.apply_logo_shape(logo, shape);
+        
+        // Optimizar para QR (reducir colores si es necesario)
+        self.optimize_logo_for_qr(logo)
+    }
+    
     /// Aplica forma al logo (circular, cuadrado redondeado, etc)
     fn apply_logo_shape(&self, logo: DynamicImage, shape: LogoShape) -> DynamicImage {
         use image::{Rgba, RgbaImage, GenericImageView};
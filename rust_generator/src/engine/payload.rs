@@ -0,0 +1,1047 @@
+// engine/payload.rs - Contenidos estructurados (WiFi, vCard, MeCard, evento, geo, SMS, e-mail)
+// Construye la cadena exacta que esperan los lectores a partir de campos tipados, con el
+// escapado propio de cada formato, para no depender de cadenas armadas a mano
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::error::{QrError, QrResult};
+
+/// Longitud máxima de línea en vCard e iCalendar antes de plegarla (octetos)
+const FOLD_LINE_OCTETS: usize = 75;
+
+/// Longitud máxima de un SSID (octetos)
+const MAX_SSID_BYTES: usize = 32;
+
+/// Contenido estructurado a codificar en lugar de `QrRequest::data`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QrPayload {
+    Wifi(WifiPayload),
+    #[serde(rename = "vcard")]
+    VCard(VCardPayload),
+    #[serde(rename = "mecard")]
+    MeCard(ContactInfo),
+    Event(EventPayload),
+    Geo(GeoPayload),
+    Sms(SmsPayload),
+    Email(EmailPayload),
+}
+
+impl QrPayload {
+    /// Construye la cadena a codificar
+    pub fn encode(&self) -> QrResult<String> {
+        match self {
+            QrPayload::Wifi(wifi) => wifi.encode(),
+            QrPayload::VCard(vcard) => vcard.encode(),
+            QrPayload::MeCard(contact) => contact.encode_mecard(),
+            QrPayload::Event(event) => event.encode(),
+            QrPayload::Geo(geo) => geo.encode(),
+            QrPayload::Sms(sms) => sms.encode(),
+            QrPayload::Email(email) => email.encode(),
+        }
+    }
+
+    /// Interpreta el contenido leído de un símbolo: inversa de `encode` para los formatos conocidos
+    pub fn parse(raw: &str) -> Option<QrPayload> {
+        let raw = raw.trim_start_matches('\u{feff}');
+        if let Some(fields) = strip_prefix_ci(raw, "WIFI:") {
+            WifiPayload::parse(fields).map(QrPayload::Wifi)
+        } else if let Some(fields) = strip_prefix_ci(raw, "MECARD:") {
+            ContactInfo::parse_mecard(fields).map(QrPayload::MeCard)
+        } else if strip_prefix_ci(raw, "BEGIN:VCARD").is_some() {
+            VCardPayload::parse(raw).map(QrPayload::VCard)
+        } else if raw.to_ascii_uppercase().contains("BEGIN:VEVENT") {
+            EventPayload::parse(raw).map(QrPayload::Event)
+        } else if let Some(uri) = strip_prefix_ci(raw, "geo:") {
+            GeoPayload::parse(uri).map(QrPayload::Geo)
+        } else if let Some(fields) = strip_prefix_ci(raw, "SMSTO:") {
+            SmsPayload::parse_smsto(fields).map(QrPayload::Sms)
+        } else if let Some(uri) = strip_prefix_ci(raw, "sms:") {
+            SmsPayload::parse_uri(uri).map(QrPayload::Sms)
+        } else if let Some(uri) = strip_prefix_ci(raw, "mailto:") {
+            EmailPayload::parse(uri).map(QrPayload::Email)
+        } else {
+            None
+        }
+    }
+}
+
+/// Cadena a codificar: `data` tal cual o la construida desde `payload`, que son excluyentes
+pub fn resolve_data(data: String, payload: Option<&QrPayload>) -> QrResult<String> {
+    match payload {
+        Some(_) if !data.is_empty() => Err(QrError::PayloadError(
+            "Envíe `data` o `payload`, no ambos".to_string(),
+        )),
+        Some(payload) => payload.encode(),
+        None => Ok(data),
+    }
+}
+
+/// Seguridad de la red WiFi
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WifiSecurity {
+    #[default]
+    Wpa,
+    Wep,
+    Nopass,
+}
+
+/// Red WiFi (formato `WIFI:` de ZXing)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WifiPayload {
+    pub ssid: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub security: WifiSecurity,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl WifiPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        if self.ssid.is_empty() || self.ssid.len() > MAX_SSID_BYTES {
+            return Err(QrError::PayloadError(format!(
+                "El SSID debe tener entre 1 y {} bytes", MAX_SSID_BYTES
+            )));
+        }
+
+        let password = self.password.as_deref().filter(|p| !p.is_empty());
+        let security = match (self.security, password) {
+            (WifiSecurity::Nopass, None) => "nopass",
+            (WifiSecurity::Nopass, Some(_)) => {
+                return Err(QrError::PayloadError("Una red abierta no lleva contraseña".to_string()));
+            }
+            (_, None) => {
+                return Err(QrError::PayloadError("La red protegida requiere contraseña".to_string()));
+            }
+            (WifiSecurity::Wpa, Some(p)) => {
+                // Frase WPA de 8-63 caracteres o clave PSK de 64 dígitos hexadecimales
+                let is_psk = p.len() == 64 && p.chars().all(|c| c.is_ascii_hexdigit());
+                if !is_psk && !(8..=63).contains(&p.chars().count()) {
+                    return Err(QrError::PayloadError(
+                        "La contraseña WPA debe tener entre 8 y 63 caracteres".to_string(),
+                    ));
+                }
+                "WPA"
+            }
+            (WifiSecurity::Wep, Some(p)) => {
+                let is_ascii = p.is_ascii() && matches!(p.len(), 5 | 13);
+                let is_hex = matches!(p.len(), 10 | 26) && p.chars().all(|c| c.is_ascii_hexdigit());
+                if !is_ascii && !is_hex {
+                    return Err(QrError::PayloadError(
+                        "La clave WEP debe tener 5 o 13 caracteres, o 10 o 26 dígitos hexadecimales".to_string(),
+                    ));
+                }
+                "WEP"
+            }
+        };
+
+        let mut encoded = format!("WIFI:T:{};S:{};", security, escape_wifi(&self.ssid));
+        if let Some(password) = password {
+            encoded.push_str(&format!("P:{};", escape_wifi(password)));
+        }
+        if self.hidden {
+            encoded.push_str("H:true;");
+        }
+        encoded.push(';');
+        Ok(encoded)
+    }
+
+    /// Campos tras el prefijo `WIFI:`
+    fn parse(fields: &str) -> Option<Self> {
+        let mut wifi = WifiPayload { ssid: String::new(), password: None, security: WifiSecurity::Wpa, hidden: false };
+        let mut security = None;
+        for field in split_escaped(fields, ';') {
+            let Some((key, value)) = field.split_once(':') else { continue };
+            let value = unescape(value);
+            match key.to_ascii_uppercase().as_str() {
+                "S" => wifi.ssid = value,
+                "P" => wifi.password = Some(value).filter(|p| !p.is_empty()),
+                "T" => security = Some(value.to_ascii_uppercase()),
+                "H" => wifi.hidden = value.eq_ignore_ascii_case("true"),
+                _ => {}
+            }
+        }
+        // Sin tipo explícito, la contraseña decide; cualquier variante WPA (WPA2, SAE...) cuenta como WPA
+        wifi.security = match security.as_deref() {
+            Some("WEP") => WifiSecurity::Wep,
+            Some("NOPASS") => WifiSecurity::Nopass,
+            Some("") | None if wifi.password.is_none() => WifiSecurity::Nopass,
+            _ => WifiSecurity::Wpa,
+        };
+        (!wifi.ssid.is_empty()).then_some(wifi)
+    }
+}
+
+/// Dirección postal de un contacto
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PostalAddress {
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+impl PostalAddress {
+    fn components(&self) -> [&str; 5] {
+        [&self.street, &self.city, &self.region, &self.postal_code, &self.country]
+            .map(|field| field.as_deref().unwrap_or(""))
+    }
+
+    /// Inversa de `components`; `None` si todos vienen vacíos
+    fn from_components(components: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut fields = components.into_iter().map(|c| Some(c).filter(|c| !c.is_empty()));
+        let address = PostalAddress {
+            street: fields.next().flatten(),
+            city: fields.next().flatten(),
+            region: fields.next().flatten(),
+            postal_code: fields.next().flatten(),
+            country: fields.next().flatten(),
+        };
+        (address != PostalAddress::default()).then_some(address)
+    }
+}
+
+/// Datos de contacto, comunes a vCard y MeCard
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContactInfo {
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub organization: Option<String>,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub phones: Vec<String>,
+    #[serde(default)]
+    pub emails: Vec<String>,
+    pub url: Option<String>,
+    pub address: Option<PostalAddress>,
+    pub note: Option<String>,
+}
+
+impl ContactInfo {
+    fn full_name(&self) -> String {
+        match self.last_name.as_deref().filter(|l| !l.is_empty()) {
+            Some(last) => format!("{} {}", self.first_name, last),
+            None => self.first_name.clone(),
+        }
+    }
+
+    fn validate(&self) -> QrResult<()> {
+        if self.first_name.trim().is_empty() {
+            return Err(QrError::PayloadError("El contacto requiere un nombre".to_string()));
+        }
+        for email in &self.emails {
+            validate_email(email)?;
+        }
+        Ok(())
+    }
+
+    /// Formato MECARD de NTT Docomo
+    pub fn encode_mecard(&self) -> QrResult<String> {
+        self.validate()?;
+
+        let mut fields = vec![format!(
+            "N:{}",
+            match self.last_name.as_deref().filter(|l| !l.is_empty()) {
+                Some(last) => format!("{},{}", escape_mecard(last), escape_mecard(&self.first_name)),
+                None => escape_mecard(&self.first_name),
+            }
+        )];
+        for phone in &self.phones {
+            fields.push(format!("TEL:{}", normalize_phone(phone)?));
+        }
+        for email in &self.emails {
+            fields.push(format!("EMAIL:{}", escape_mecard(email)));
+        }
+        if let Some(url) = &self.url {
+            fields.push(format!("URL:{}", escape_mecard(url)));
+        }
+        if let Some(address) = &self.address {
+            // Apartado, número interior, calle, ciudad, región, código postal y país
+            let components: Vec<String> = address.components().iter().map(|c| escape_mecard(c)).collect();
+            fields.push(format!("ADR:,,{}", components.join(",")));
+        }
+        if let Some(organization) = &self.organization {
+            fields.push(format!("ORG:{}", escape_mecard(organization)));
+        }
+        if let Some(note) = &self.note {
+            fields.push(format!("NOTE:{}", escape_mecard(note)));
+        }
+
+        Ok(format!("MECARD:{};;", fields.join(";")))
+    }
+
+    /// Campos tras el prefijo `MECARD:`
+    fn parse_mecard(fields: &str) -> Option<Self> {
+        let mut contact = ContactInfo::default();
+        for field in split_escaped(fields, ';') {
+            let Some((key, value)) = field.split_once(':') else { continue };
+            match key.to_ascii_uppercase().as_str() {
+                "N" => match split_escaped(value, ',').as_slice() {
+                    [last, first] => {
+                        contact.last_name = Some(unescape(last)).filter(|l| !l.is_empty());
+                        contact.first_name = unescape(first);
+                    }
+                    _ => contact.first_name = unescape(value),
+                },
+                "TEL" => contact.phones.push(unescape(value)),
+                "EMAIL" => contact.emails.push(unescape(value)),
+                "URL" => contact.url = Some(unescape(value)),
+                "ADR" => {
+                    // Se descartan el apartado y el número interior
+                    let components = split_escaped(value, ',').into_iter().skip(2).map(unescape);
+                    contact.address = PostalAddress::from_components(components);
+                }
+                "ORG" => contact.organization = Some(unescape(value)),
+                "NOTE" => contact.note = Some(unescape(value)),
+                _ => {}
+            }
+        }
+        (!contact.first_name.is_empty()).then_some(contact)
+    }
+}
+
+/// Versión de vCard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum VCardVersion {
+    #[default]
+    #[serde(rename = "3.0")]
+    V3,
+    #[serde(rename = "4.0")]
+    V4,
+}
+
+/// Tarjeta de contacto vCard (RFC 2426 para 3.0, RFC 6350 para 4.0)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VCardPayload {
+    #[serde(default)]
+    pub version: VCardVersion,
+    #[serde(flatten)]
+    pub contact: ContactInfo,
+}
+
+impl VCardPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        let contact = &self.contact;
+        contact.validate()?;
+
+        let mut lines = vec![
+            "BEGIN:VCARD".to_string(),
+            match self.version {
+                VCardVersion::V3 => "VERSION:3.0".to_string(),
+                VCardVersion::V4 => "VERSION:4.0".to_string(),
+            },
+            format!(
+                "N:{};{};;;",
+                escape_text(contact.last_name.as_deref().unwrap_or("")),
+                escape_text(&contact.first_name)
+            ),
+            format!("FN:{}", escape_text(&contact.full_name())),
+        ];
+        if let Some(organization) = &contact.organization {
+            lines.push(format!("ORG:{}", escape_text(organization)));
+        }
+        if let Some(title) = &contact.title {
+            lines.push(format!("TITLE:{}", escape_text(title)));
+        }
+        for phone in &contact.phones {
+            let phone = normalize_phone(phone)?;
+            lines.push(match self.version {
+                VCardVersion::V3 => format!("TEL;TYPE=VOICE:{}", phone),
+                VCardVersion::V4 => format!("TEL;VALUE=uri:tel:{}", phone),
+            });
+        }
+        for email in &contact.emails {
+            lines.push(match self.version {
+                VCardVersion::V3 => format!("EMAIL;TYPE=INTERNET:{}", email),
+                VCardVersion::V4 => format!("EMAIL:{}", email),
+            });
+        }
+        if let Some(url) = &contact.url {
+            lines.push(format!("URL:{}", url));
+        }
+        if let Some(address) = &contact.address {
+            let components: Vec<String> = address.components().iter().map(|c| escape_text(c)).collect();
+            lines.push(format!("ADR:;;{}", components.join(";")));
+        }
+        if let Some(note) = &contact.note {
+            lines.push(format!("NOTE:{}", escape_text(note)));
+        }
+        lines.push("END:VCARD".to_string());
+
+        Ok(join_folded(&lines))
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        let mut vcard = VCardPayload { version: VCardVersion::V3, contact: ContactInfo::default() };
+        let mut full_name = None;
+        for (name, value) in content_lines(raw) {
+            let contact = &mut vcard.contact;
+            match name.as_str() {
+                "VERSION" if value.trim() == "4.0" => vcard.version = VCardVersion::V4,
+                "N" => {
+                    let parts = split_escaped(&value, ';');
+                    contact.last_name = parts.first().map(|l| unescape_text(l)).filter(|l| !l.is_empty());
+                    contact.first_name = parts.get(1).map(|f| unescape_text(f)).unwrap_or_default();
+                }
+                "FN" => full_name = Some(unescape_text(&value)),
+                "ORG" => contact.organization = Some(unescape_text(&value)),
+                "TITLE" => contact.title = Some(unescape_text(&value)),
+                "TEL" => contact.phones.push(strip_prefix_ci(&value, "tel:").unwrap_or(&value).to_string()),
+                "EMAIL" => contact.emails.push(value),
+                "URL" => contact.url = Some(value),
+                "ADR" => {
+                    let components = split_escaped(&value, ';').into_iter().skip(2).map(unescape_text);
+                    contact.address = PostalAddress::from_components(components);
+                }
+                "NOTE" => contact.note = Some(unescape_text(&value)),
+                _ => {}
+            }
+        }
+        // Sin N estructurado el nombre completo hace de nombre
+        if vcard.contact.first_name.is_empty() {
+            vcard.contact.first_name = full_name.unwrap_or_default();
+        }
+        (!vcard.contact.first_name.is_empty()).then_some(vcard)
+    }
+}
+
+/// Momento de un evento: UTC, hora local flotante o día completo
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum EventTime {
+    Utc(DateTime<Utc>),
+    Local(NaiveDateTime),
+    Date(NaiveDate),
+}
+
+impl EventTime {
+    /// Propiedad iCalendar con el tipo de valor que corresponde
+    fn property(&self, name: &str) -> String {
+        match self {
+            EventTime::Utc(time) => format!("{}:{}", name, time.format("%Y%m%dT%H%M%SZ")),
+            EventTime::Local(time) => format!("{}:{}", name, time.format("%Y%m%dT%H%M%S")),
+            EventTime::Date(date) => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+        }
+    }
+
+    /// Compara dos momentos del mismo tipo de valor
+    fn is_before(&self, other: &EventTime) -> Option<bool> {
+        match (self, other) {
+            (EventTime::Utc(a), EventTime::Utc(b)) => Some(a < b),
+            (EventTime::Local(a), EventTime::Local(b)) => Some(a < b),
+            (EventTime::Date(a), EventTime::Date(b)) => Some(a < b),
+            _ => None,
+        }
+    }
+
+    /// Valor de DTSTART/DTEND: fecha-hora UTC con `Z`, fecha-hora flotante o fecha
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(utc) = value.strip_suffix('Z') {
+            NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                .ok()
+                .map(|time| EventTime::Utc(time.and_utc()))
+        } else if value.contains('T') {
+            NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(EventTime::Local)
+        } else {
+            NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(EventTime::Date)
+        }
+    }
+}
+
+/// Evento de calendario (VEVENT de RFC 5545)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventPayload {
+    pub summary: String,
+    pub start: EventTime,
+    pub end: Option<EventTime>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+}
+
+impl EventPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        if self.summary.trim().is_empty() {
+            return Err(QrError::PayloadError("El evento requiere un título".to_string()));
+        }
+
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("SUMMARY:{}", escape_text(&self.summary)),
+            self.start.property("DTSTART"),
+        ];
+        if let Some(end) = &self.end {
+            match end.is_before(&self.start) {
+                None => {
+                    return Err(QrError::PayloadError(
+                        "El inicio y el fin del evento deben ser del mismo tipo".to_string(),
+                    ));
+                }
+                Some(true) => {
+                    return Err(QrError::PayloadError("El evento termina antes de empezar".to_string()));
+                }
+                Some(false) => lines.push(end.property("DTEND")),
+            }
+        }
+        if let Some(location) = &self.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some(description) = &self.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+
+        Ok(join_folded(&lines))
+    }
+
+    /// Primer VEVENT del contenido, suelto o dentro de un VCALENDAR
+    fn parse(raw: &str) -> Option<Self> {
+        let (mut summary, mut start, mut end, mut location, mut description) = (None, None, None, None, None);
+        let mut in_event = false;
+        for (name, value) in content_lines(raw) {
+            match name.as_str() {
+                "BEGIN" if value.eq_ignore_ascii_case("VEVENT") => in_event = true,
+                "END" if value.eq_ignore_ascii_case("VEVENT") => break,
+                _ if !in_event => {}
+                "SUMMARY" => summary = Some(unescape_text(&value)),
+                "DTSTART" => start = EventTime::parse(&value),
+                "DTEND" => end = EventTime::parse(&value),
+                "LOCATION" => location = Some(unescape_text(&value)),
+                "DESCRIPTION" => description = Some(unescape_text(&value)),
+                _ => {}
+            }
+        }
+        Some(EventPayload { summary: summary?, start: start?, end, location, description })
+    }
+}
+
+/// Ubicación geográfica (URI `geo:` de RFC 5870, WGS-84)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeoPayload {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+}
+
+impl GeoPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(QrError::PayloadError(format!("Latitud fuera de rango: {}", self.latitude)));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(QrError::PayloadError(format!("Longitud fuera de rango: {}", self.longitude)));
+        }
+
+        Ok(match self.altitude {
+            Some(altitude) if altitude.is_finite() => {
+                format!("geo:{},{},{}", self.latitude, self.longitude, altitude)
+            }
+            Some(altitude) => {
+                return Err(QrError::PayloadError(format!("Altitud inválida: {}", altitude)));
+            }
+            None => format!("geo:{},{}", self.latitude, self.longitude),
+        })
+    }
+
+    /// Coordenadas tras `geo:`, ignorando parámetros (`;crs=`, `;u=`) y consulta
+    fn parse(uri: &str) -> Option<Self> {
+        let coordinates = uri.split([';', '?']).next().unwrap_or_default();
+        let values: Vec<f64> = coordinates
+            .split(',')
+            .map(|v| v.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        match values.as_slice() {
+            [latitude, longitude] => Some(GeoPayload { latitude: *latitude, longitude: *longitude, altitude: None }),
+            [latitude, longitude, altitude] => {
+                Some(GeoPayload { latitude: *latitude, longitude: *longitude, altitude: Some(*altitude) })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Mensaje SMS prellenado (formato `SMSTO:`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SmsPayload {
+    pub number: String,
+    pub message: Option<String>,
+}
+
+impl SmsPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        let number = normalize_phone(&self.number)?;
+        // El mensaje es todo lo que sigue al segundo separador: no requiere escapado
+        Ok(match self.message.as_deref().filter(|m| !m.is_empty()) {
+            Some(message) => format!("SMSTO:{}:{}", number, message),
+            None => format!("SMSTO:{}:", number),
+        })
+    }
+
+    /// Campos tras `SMSTO:`: número y mensaje separados por dos puntos
+    fn parse_smsto(fields: &str) -> Option<Self> {
+        let (number, message) = fields.split_once(':').unwrap_or((fields, ""));
+        Self::from_parts(number, message.to_string())
+    }
+
+    /// URI `sms:` de RFC 5724, con el mensaje en el parámetro `body`
+    fn parse_uri(uri: &str) -> Option<Self> {
+        let (number, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let message = query_param(query, "body").unwrap_or_default();
+        Self::from_parts(&percent_decode(number), message)
+    }
+
+    fn from_parts(number: &str, message: String) -> Option<Self> {
+        let number = number.trim();
+        (!number.is_empty()).then(|| SmsPayload {
+            number: number.to_string(),
+            message: Some(message).filter(|m| !m.is_empty()),
+        })
+    }
+}
+
+/// Correo prellenado (URI `mailto:` de RFC 6068)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailPayload {
+    pub to: String,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+impl EmailPayload {
+    pub fn encode(&self) -> QrResult<String> {
+        validate_email(&self.to)?;
+
+        let mut fields = Vec::new();
+        if let Some(subject) = self.subject.as_deref().filter(|s| !s.is_empty()) {
+            fields.push(format!("subject={}", percent_encode(subject)));
+        }
+        if let Some(body) = self.body.as_deref().filter(|b| !b.is_empty()) {
+            // Los saltos de línea del cuerpo se codifican como CRLF
+            let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+            fields.push(format!("body={}", percent_encode(&body)));
+        }
+
+        let mut encoded = format!("mailto:{}", percent_encode_address(&self.to));
+        if !fields.is_empty() {
+            encoded.push('?');
+            encoded.push_str(&fields.join("&"));
+        }
+        Ok(encoded)
+    }
+
+    /// URI tras `mailto:`
+    fn parse(uri: &str) -> Option<Self> {
+        let (to, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let to = percent_decode(to);
+        (!to.is_empty()).then(|| EmailPayload {
+            to,
+            subject: query_param(query, "subject"),
+            body: query_param(query, "body").map(|body| body.replace("\r\n", "\n")),
+        })
+    }
+}
+
+/// Escapa los caracteres especiales del formato `WIFI:`
+fn escape_wifi(value: &str) -> String {
+    escape_with(value, &['\\', ';', ',', '"', ':'])
+}
+
+/// Escapa los caracteres especiales de MECARD
+fn escape_mecard(value: &str) -> String {
+    escape_with(value, &['\\', ';', ',', ':'])
+}
+
+fn escape_with(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapa un valor de texto vCard/iCalendar (barra, punto y coma, coma y saltos de línea)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Une las líneas con CRLF, plegando las que superan 75 octetos
+fn join_folded(lines: &[String]) -> String {
+    let mut joined = String::new();
+    for line in lines {
+        let mut octets = 0;
+        for c in line.chars() {
+            // El plegado no puede partir un carácter multibyte
+            if octets + c.len_utf8() > FOLD_LINE_OCTETS {
+                joined.push_str("\r\n ");
+                octets = 1;
+            }
+            joined.push(c);
+            octets += c.len_utf8();
+        }
+        joined.push_str("\r\n");
+    }
+    joined
+}
+
+/// Separa por `separator` respetando los escapes con barra invertida, que se conservan
+fn split_escaped(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == separator => {
+                parts.push(&value[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Inversa de `escape_with`: la barra invertida toma literal el carácter siguiente
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Inversa de `escape_text`
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => unescaped.push('\n'),
+                next => unescaped.extend(next),
+            },
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Líneas de contenido vCard/iCalendar ya desplegadas, como (nombre en mayúsculas, valor)
+fn content_lines(raw: &str) -> Vec<(String, String)> {
+    let unfolded = raw.replace("\r\n ", "").replace("\r\n\t", "").replace("\n ", "").replace("\n\t", "");
+    unfolded
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            // Se descartan los parámetros (`TEL;TYPE=VOICE`) y el grupo (`item1.EMAIL`)
+            let name = name.split(';').next().unwrap_or_default();
+            let name = name.rsplit('.').next().unwrap_or_default();
+            Some((name.trim().to_ascii_uppercase(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Prefijo sin distinguir mayúsculas
+fn strip_prefix_ci<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &value[prefix.len()..])
+}
+
+/// Valor decodificado de un parámetro de consulta de URI
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| percent_decode(value))
+        .filter(|value| !value.is_empty())
+}
+
+/// Inversa de `percent_encode`; las secuencias inválidas se dejan tal cual
+pub(crate) fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Quita los separadores visuales de un teléfono y valida el resto
+fn normalize_phone(phone: &str) -> QrResult<String> {
+    let normalized: String = phone.chars().filter(|c| !c.is_whitespace()).collect();
+    let digits = normalized.chars().filter(|c| c.is_ascii_digit()).count();
+    let valid = digits > 0
+        && normalized.chars().enumerate().all(|(i, c)| {
+            c.is_ascii_digit() || matches!(c, '-' | '.' | '(' | ')') || (c == '+' && i == 0)
+        });
+    if !valid {
+        return Err(QrError::PayloadError(format!("Teléfono inválido: {}", phone)));
+    }
+    Ok(normalized)
+}
+
+fn validate_email(email: &str) -> QrResult<()> {
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.contains('@')
+                && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(QrError::PayloadError(format!("Dirección de correo inválida: {}", email)))
+    }
+}
+
+/// Codificación porcentual UTF-8 de todo lo que no es un carácter no reservado (RFC 3986)
+fn percent_encode(value: &str) -> String {
+    percent_encode_except(value, &[])
+}
+
+/// Como `percent_encode`, conservando la arroba de la dirección
+fn percent_encode_address(value: &str) -> String {
+    percent_encode_except(value, b"@")
+}
+
+fn percent_encode_except(value: &str, keep: &[u8]) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for &byte in value.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') || keep.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact() -> ContactInfo {
+        ContactInfo {
+            first_name: "Ana".to_string(),
+            last_name: Some("García, Ruiz".to_string()),
+            organization: Some("QReable; Labs".to_string()),
+            title: None,
+            phones: vec!["+34 600 123 456".to_string()],
+            emails: vec!["ana@qreable.com".to_string()],
+            url: None,
+            address: Some(PostalAddress {
+                street: Some("Gran Vía 1".to_string()),
+                city: Some("Madrid".to_string()),
+                ..Default::default()
+            }),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_wifi_escaping() {
+        let wifi = WifiPayload {
+            ssid: "Café;Bar".to_string(),
+            password: Some(r#"p"a:s,s\1;"#.to_string()),
+            security: WifiSecurity::Wpa,
+            hidden: true,
+        };
+        assert_eq!(wifi.encode().unwrap(), r#"WIFI:T:WPA;S:Café\;Bar;P:p\"a\:s\,s\\1\;;H:true;;"#);
+
+        let open = WifiPayload { ssid: "Invitados".to_string(), password: None, security: WifiSecurity::Nopass, hidden: false };
+        assert_eq!(open.encode().unwrap(), "WIFI:T:nopass;S:Invitados;;");
+
+        let short = WifiPayload { password: Some("corta".to_string()), ..wifi.clone() };
+        assert!(matches!(short.encode(), Err(QrError::PayloadError(_))));
+        let wep = WifiPayload { password: Some("0123456789".to_string()), security: WifiSecurity::Wep, ..wifi };
+        assert!(wep.encode().unwrap().starts_with("WIFI:T:WEP;"));
+    }
+
+    #[test]
+    fn test_vcard_versions() {
+        let v3 = VCardPayload { version: VCardVersion::V3, contact: contact() }.encode().unwrap();
+        assert_eq!(
+            v3,
+            "BEGIN:VCARD\r\nVERSION:3.0\r\nN:García\\, Ruiz;Ana;;;\r\nFN:Ana García\\, Ruiz\r\n\
+             ORG:QReable\\; Labs\r\nTEL;TYPE=VOICE:+34600123456\r\nEMAIL;TYPE=INTERNET:ana@qreable.com\r\n\
+             ADR:;;Gran Vía 1;Madrid;;;\r\nEND:VCARD\r\n"
+        );
+
+        let v4 = VCardPayload { version: VCardVersion::V4, contact: contact() }.encode().unwrap();
+        assert!(v4.contains("VERSION:4.0\r\n"));
+        assert!(v4.contains("TEL;VALUE=uri:tel:+34600123456\r\n"));
+
+        // Las líneas largas se pliegan a 75 octetos sin partir caracteres
+        let note = "ñ".repeat(60);
+        let long = VCardPayload { version: VCardVersion::V4, contact: ContactInfo { note: Some(note.clone()), ..contact() } }
+            .encode()
+            .unwrap();
+        assert!(long.split("\r\n").all(|line| line.len() <= FOLD_LINE_OCTETS));
+        assert!(long.replace("\r\n ", "").contains(&format!("NOTE:{}", note)));
+    }
+
+    #[test]
+    fn test_mecard() {
+        assert_eq!(
+            contact().encode_mecard().unwrap(),
+            "MECARD:N:García\\, Ruiz,Ana;TEL:+34600123456;EMAIL:ana@qreable.com;\
+             ADR:,,Gran Vía 1,Madrid,,,;ORG:QReable\\; Labs;;"
+        );
+        let invalid = ContactInfo { emails: vec!["sin-arroba".to_string()], ..contact() };
+        assert!(matches!(invalid.encode_mecard(), Err(QrError::PayloadError(_))));
+    }
+
+    #[test]
+    fn test_event() {
+        let payload: QrPayload = serde_json::from_str(
+            r#"{"type": "event", "summary": "Lanzamiento, v2", "start": "2025-10-17T09:00:00Z",
+                "end": "2025-10-17T11:30:00Z", "location": "Sala 1"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            payload.encode().unwrap(),
+            "BEGIN:VEVENT\r\nSUMMARY:Lanzamiento\\, v2\r\nDTSTART:20251017T090000Z\r\n\
+             DTEND:20251017T113000Z\r\nLOCATION:Sala 1\r\nEND:VEVENT\r\n"
+        );
+
+        let all_day: QrPayload =
+            serde_json::from_str(r#"{"type": "event", "summary": "Feria", "start": "2025-10-17"}"#).unwrap();
+        assert!(all_day.encode().unwrap().contains("DTSTART;VALUE=DATE:20251017\r\n"));
+
+        let mixed: QrPayload = serde_json::from_str(
+            r#"{"type": "event", "summary": "Feria", "start": "2025-10-17", "end": "2025-10-17T10:00:00"}"#,
+        )
+        .unwrap();
+        assert!(matches!(mixed.encode(), Err(QrError::PayloadError(_))));
+    }
+
+    #[test]
+    fn test_geo_sms_email() {
+        let geo = GeoPayload { latitude: 40.4168, longitude: -3.7038, altitude: None };
+        assert_eq!(geo.encode().unwrap(), "geo:40.4168,-3.7038");
+        assert!(GeoPayload { latitude: 91.0, ..geo }.encode().is_err());
+
+        let sms = SmsPayload { number: "+34 600 123 456".to_string(), message: Some("Hola: ¿llegas?".to_string()) };
+        assert_eq!(sms.encode().unwrap(), "SMSTO:+34600123456:Hola: ¿llegas?");
+        assert!(SmsPayload { number: "abc".to_string(), message: None }.encode().is_err());
+
+        let email = EmailPayload {
+            to: "soporte@qreable.com".to_string(),
+            subject: Some("Pedido #12 & envío".to_string()),
+            body: Some("Hola\nGracias".to_string()),
+        };
+        assert_eq!(
+            email.encode().unwrap(),
+            "mailto:soporte@qreable.com?subject=Pedido%20%2312%20%26%20env%C3%ADo&body=Hola%0D%0AGracias"
+        );
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        // Teléfono ya normalizado y nota larga para forzar el plegado
+        let contact = ContactInfo {
+            phones: vec!["+34600123456".to_string()],
+            note: Some("Línea uno\nñ".repeat(8)),
+            ..contact()
+        };
+        let payloads = vec![
+            QrPayload::Wifi(WifiPayload {
+                ssid: "Café;Bar".to_string(),
+                password: Some(r#"p"a:s,s\1;"#.to_string()),
+                security: WifiSecurity::Wpa,
+                hidden: true,
+            }),
+            QrPayload::Wifi(WifiPayload { ssid: "Invitados".to_string(), password: None, security: WifiSecurity::Nopass, hidden: false }),
+            QrPayload::VCard(VCardPayload { version: VCardVersion::V3, contact: contact.clone() }),
+            QrPayload::VCard(VCardPayload { version: VCardVersion::V4, contact: ContactInfo { note: None, ..contact.clone() } }),
+            QrPayload::MeCard(ContactInfo { note: Some("Hola; adiós".to_string()), ..contact }),
+            serde_json::from_str(
+                r#"{"type": "event", "summary": "Lanzamiento, v2", "start": "2025-10-17T09:00:00Z",
+                    "end": "2025-10-17T11:30:00Z", "location": "Sala 1", "description": "Planta 2\nAla B"}"#,
+            )
+            .unwrap(),
+            serde_json::from_str(r#"{"type": "event", "summary": "Feria", "start": "2025-10-17"}"#).unwrap(),
+            QrPayload::Geo(GeoPayload { latitude: 40.4168, longitude: -3.7038, altitude: Some(657.0) }),
+            QrPayload::Sms(SmsPayload { number: "+34600123456".to_string(), message: Some("Hola: ¿llegas?".to_string()) }),
+            QrPayload::Email(EmailPayload {
+                to: "soporte@qreable.com".to_string(),
+                subject: Some("Pedido #12 & envío".to_string()),
+                body: Some("Hola\nGracias".to_string()),
+            }),
+        ];
+        for payload in payloads {
+            let encoded = payload.encode().unwrap();
+            assert_eq!(QrPayload::parse(&encoded), Some(payload), "{}", encoded);
+        }
+    }
+
+    #[test]
+    fn test_parse_foreign_content() {
+        // Variantes que otros generadores producen y `encode` no
+        let wifi = QrPayload::parse("wifi:S:Casa;T:WPA2;P:secreto123;;").unwrap();
+        assert!(matches!(wifi, QrPayload::Wifi(WifiPayload { security: WifiSecurity::Wpa, .. })));
+
+        let vcard = QrPayload::parse("BEGIN:VCARD\nVERSION:2.1\nFN:Luis Pérez\nitem1.EMAIL;type=pref:luis@example.com\nEND:VCARD").unwrap();
+        match vcard {
+            QrPayload::VCard(vcard) => {
+                assert_eq!(vcard.contact.first_name, "Luis Pérez");
+                assert_eq!(vcard.contact.emails, vec!["luis@example.com"]);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        assert_eq!(
+            QrPayload::parse("sms:+34600123456?body=Hola%20all%C3%AD"),
+            Some(QrPayload::Sms(SmsPayload { number: "+34600123456".to_string(), message: Some("Hola allí".to_string()) }))
+        );
+        assert!(matches!(QrPayload::parse("geo:40.4,-3.7;u=35"), Some(QrPayload::Geo(_))));
+
+        assert_eq!(QrPayload::parse("https://qreable.com"), None);
+        assert_eq!(QrPayload::parse("geo:norte"), None);
+        assert_eq!(QrPayload::parse("WIFI:T:WPA;;"), None);
+    }
+}
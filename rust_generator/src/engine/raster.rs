@@ -0,0 +1,313 @@
+// engine/raster.rs - Rasterización del SVG final a PNG/JPEG/WebP
+// Usa resvg/tiny-skia para renderizar al tamaño solicitado y codifica
+// cada formato incluyendo metadatos de resolución (DPI)
+
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+
+use super::error::{QrError, QrResult};
+use super::types::{OutputFormat, OutputOptions};
+
+/// Resolución por defecto para salidas rasterizadas
+pub const DEFAULT_DPI: u32 = 300;
+
+/// Calidad JPEG por defecto
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// Base de fuentes del sistema para el texto de los marcos (se carga una sola vez)
+pub(crate) static FONT_DB: Lazy<usvg::fontdb::Database> = Lazy::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    db.load_system_fonts();
+    db
+});
+
+/// Lado máximo del lienzo rasterizado en píxeles
+pub const MAX_RASTER_SIDE: u32 = 16_384;
+
+/// Superficie máxima del lienzo rasterizado (64 Mpx, 256 MB en RGBA)
+pub const MAX_RASTER_PIXELS: u64 = 64 * 1024 * 1024;
+
+/// Imagen rasterizada en RGBA (sin premultiplicar)
+#[derive(Debug, Clone)]
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Opciones de usvg que no resuelven nada fuera del documento: sin directorio de
+/// recursos y con imágenes solo desde data URL (nunca rutas locales)
+pub(crate) fn restricted_options() -> usvg::Options {
+    usvg::Options {
+        resources_dir: None,
+        image_href_resolver: usvg::ImageHrefResolver {
+            resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+            resolve_string: Box::new(|_, _, _| None),
+        },
+        ..usvg::Options::default()
+    }
+}
+
+/// Parsea el SVG final a un árbol usvg (texto convertido a paths con las fuentes del sistema)
+pub fn parse_svg(svg: &str) -> QrResult<usvg::Tree> {
+    usvg::Tree::from_str(svg, &restricted_options(), &FONT_DB)
+        .map_err(|e| QrError::RenderError(format!("SVG inválido: {}", e)))
+}
+
+/// Rechaza lienzos por encima de `MAX_RASTER_SIDE` o `MAX_RASTER_PIXELS`
+pub fn check_raster_size(width: u64, height: u64) -> QrResult<()> {
+    let max_side = MAX_RASTER_SIDE as u64;
+    if width > max_side || height > max_side || width * height > MAX_RASTER_PIXELS {
+        return Err(QrError::ValidationError(format!(
+            "Lienzo de {}x{} fuera del límite ({} px por lado, {} px en total)",
+            width, height, MAX_RASTER_SIDE, MAX_RASTER_PIXELS
+        )));
+    }
+    Ok(())
+}
+
+/// Renderiza un SVG a RGBA con el ancho solicitado, conservando la proporción
+pub fn render_svg(svg: &str, target_width: u32) -> QrResult<RasterImage> {
+    if target_width == 0 {
+        return Err(QrError::RenderError("El ancho de salida debe ser mayor que 0".to_string()));
+    }
+
+    let tree = parse_svg(svg)?;
+
+    let svg_size = tree.size();
+    let scale = target_width as f32 / svg_size.width();
+    // La proporción la fija el SVG: se limita antes de reservar el lienzo
+    let target_height = (svg_size.height() as f64 * scale as f64).round().max(1.0);
+    check_raster_size(target_width as u64, target_height.min(u64::MAX as f64) as u64)?;
+    let target_height = target_height as u32;
+
+    let mut pixmap = tiny_skia::Pixmap::new(target_width, target_height)
+        .ok_or_else(|| QrError::RenderError(format!(
+            "No se pudo crear lienzo de {}x{}", target_width, target_height
+        )))?;
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia trabaja con alfa premultiplicado; los codificadores esperan RGBA directo
+    let mut pixels = Vec::with_capacity((target_width * target_height * 4) as usize);
+    for pixel in pixmap.pixels() {
+        let c = pixel.demultiply();
+        pixels.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+    }
+
+    Ok(RasterImage {
+        width: target_width,
+        height: target_height,
+        pixels,
+    })
+}
+
+/// Rasteriza el SVG y lo codifica en el formato solicitado
+pub fn rasterize(
+    svg: &str,
+    format: OutputFormat,
+    width: u32,
+    options: Option<&OutputOptions>,
+) -> QrResult<Vec<u8>> {
+    let dpi = options.and_then(|o| o.dpi).unwrap_or(DEFAULT_DPI);
+    if dpi == 0 || dpi > 10_000 {
+        return Err(QrError::ValidationError(format!("DPI fuera de rango: {}", dpi)));
+    }
+
+    let image = render_svg(svg, width)?;
+
+    match format {
+        OutputFormat::Png => encode_png(&image, dpi),
+        OutputFormat::Jpeg => {
+            let quality = options
+                .and_then(|o| o.jpeg_quality)
+                .unwrap_or(DEFAULT_JPEG_QUALITY)
+                .clamp(1, 100);
+            encode_jpeg(&image, dpi, quality)
+        }
+        OutputFormat::Webp => encode_webp(&image, dpi),
+        other => Err(QrError::UnsupportedFormat(format!(
+            "{:?} no es un formato raster", other
+        ))),
+    }
+}
+
+/// Codifica PNG con chunk pHYs (píxeles por metro)
+fn encode_png(image: &RasterImage, dpi: u32) -> QrResult<Vec<u8>> {
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buffer, image.width, image.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let ppm = dpi_to_pixels_per_meter(dpi);
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: ppm,
+            yppu: ppm,
+            unit: png::Unit::Meter,
+        }));
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| QrError::RenderError(format!("Error PNG: {}", e)))?;
+        writer
+            .write_image_data(&image.pixels)
+            .map_err(|e| QrError::RenderError(format!("Error PNG: {}", e)))?;
+    }
+    Ok(buffer)
+}
+
+/// Codifica JPEG con densidad JFIF en DPI, aplanando el alfa sobre blanco
+fn encode_jpeg(image: &RasterImage, dpi: u32, quality: u8) -> QrResult<Vec<u8>> {
+    use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+
+    let rgb = flatten_on_white(&image.pixels);
+    let mut buffer = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut buffer, quality);
+    encoder.set_pixel_density(PixelDensity::dpi(dpi.min(u16::MAX as u32) as u16));
+    encoder.encode(&rgb, image.width, image.height, image::ColorType::Rgb8)?;
+    Ok(buffer)
+}
+
+/// Codifica WebP sin pérdida; la resolución se declara en un bloque EXIF
+fn encode_webp(image: &RasterImage, dpi: u32) -> QrResult<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut encoder = image_webp::WebPEncoder::new(&mut buffer);
+    encoder.set_exif_metadata(exif_resolution_block(dpi));
+    encoder
+        .encode(&image.pixels, image.width, image.height, image_webp::ColorType::Rgba8)
+        .map_err(|e| QrError::RenderError(format!("Error WebP: {}", e)))?;
+    Ok(buffer)
+}
+
+/// Convierte DPI a píxeles por metro (unidad del chunk pHYs)
+fn dpi_to_pixels_per_meter(dpi: u32) -> u32 {
+    (dpi as f64 / 0.0254).round() as u32
+}
+
+/// Compone RGBA sobre fondo blanco y retorna RGB
+pub(crate) fn flatten_on_white(rgba: &[u8]) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(rgba.len() / 4 * 3);
+    for px in rgba.chunks_exact(4) {
+        let alpha = px[3] as u32;
+        for &channel in &px[..3] {
+            rgb.push(((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8);
+        }
+    }
+    rgb
+}
+
+/// Bloque EXIF mínimo (TIFF little-endian) con XResolution, YResolution y ResolutionUnit
+fn exif_resolution_block(dpi: u32) -> Vec<u8> {
+    const ENTRY_COUNT: u16 = 3;
+    // Cabecera (8) + contador (2) + entradas (12 c/u) + siguiente IFD (4)
+    let rational_offset = 8 + 2 + 12 * ENTRY_COUNT as u32 + 4;
+
+    let mut exif = Vec::with_capacity(rational_offset as usize + 16);
+    exif.extend_from_slice(b"II");
+    exif.extend_from_slice(&42u16.to_le_bytes());
+    exif.extend_from_slice(&8u32.to_le_bytes());
+    exif.extend_from_slice(&ENTRY_COUNT.to_le_bytes());
+
+    // XResolution (0x011A) y YResolution (0x011B): RATIONAL apuntando al área de datos
+    for (tag, offset) in [(0x011Au16, rational_offset), (0x011B, rational_offset + 8)] {
+        exif.extend_from_slice(&tag.to_le_bytes());
+        exif.extend_from_slice(&5u16.to_le_bytes());
+        exif.extend_from_slice(&1u32.to_le_bytes());
+        exif.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    // ResolutionUnit (0x0128): SHORT = 2 (pulgadas)
+    exif.extend_from_slice(&0x0128u16.to_le_bytes());
+    exif.extend_from_slice(&3u16.to_le_bytes());
+    exif.extend_from_slice(&1u32.to_le_bytes());
+    exif.extend_from_slice(&2u32.to_le_bytes());
+
+    exif.extend_from_slice(&0u32.to_le_bytes());
+
+    for _ in 0..2 {
+        exif.extend_from_slice(&dpi.to_le_bytes());
+        exif.extend_from_slice(&1u32.to_le_bytes());
+    }
+
+    exif
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 20" width="10" height="20"><rect width="10" height="20" fill="#FFFFFF"/><rect width="5" height="5" fill="#000000"/></svg>"##;
+
+    #[test]
+    fn test_render_svg_scales_to_width() {
+        let image = render_svg(SAMPLE_SVG, 100).unwrap();
+        assert_eq!(image.width, 100);
+        assert_eq!(image.height, 200);
+        // Esquina superior izquierda negra, esquina inferior derecha blanca
+        assert_eq!(&image.pixels[0..4], &[0, 0, 0, 255]);
+        assert_eq!(&image.pixels[image.pixels.len() - 4..], &[255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_render_svg_limits_canvas() {
+        // Una proporción extrema no debe reservar gigabytes
+        let tall = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 1 10000000" width="1" height="10000000"/>"#;
+        assert!(matches!(render_svg(tall, 1024), Err(QrError::ValidationError(_))));
+        assert!(matches!(render_svg(SAMPLE_SVG, MAX_RASTER_SIDE + 1), Err(QrError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_render_svg_ignores_local_files() {
+        let path = std::env::temp_dir().join(format!("qreable_raster_{}.png", uuid::Uuid::new_v4()));
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 0, 255])).save(&path).unwrap();
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10" width="10" height="10"><image href="{}" width="10" height="10"/></svg>"#,
+            path.display()
+        );
+
+        // La imagen local no se resuelve: el lienzo queda transparente
+        let image = render_svg(&svg, 20).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(image.pixels.chunks(4).all(|pixel| pixel[3] == 0));
+    }
+
+    #[test]
+    fn test_png_includes_dpi() {
+        let options = OutputOptions { dpi: Some(600), ..Default::default() };
+        let bytes = rasterize(SAMPLE_SVG, OutputFormat::Png, 50, Some(&options)).unwrap();
+        assert_eq!(&bytes[1..4], b"PNG");
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let reader = decoder.read_info().unwrap();
+        let dims = reader.info().pixel_dims.unwrap();
+        assert_eq!(dims.xppu, dpi_to_pixels_per_meter(600));
+        assert_eq!(reader.info().width, 50);
+        assert_eq!(reader.info().height, 100);
+    }
+
+    #[test]
+    fn test_jpeg_and_webp_signatures() {
+        let jpeg = rasterize(SAMPLE_SVG, OutputFormat::Jpeg, 40, None).unwrap();
+        assert_eq!(&jpeg[0..2], &[0xFF, 0xD8]);
+        // JFIF: unidad 1 (DPI) y densidad 300 en el segmento APP0
+        assert_eq!(&jpeg[6..11], b"JFIF\0");
+        assert_eq!(jpeg[13], 1);
+        assert_eq!(u16::from_be_bytes([jpeg[14], jpeg[15]]), 300);
+
+        let webp = rasterize(SAMPLE_SVG, OutputFormat::Webp, 40, None).unwrap();
+        assert_eq!(&webp[0..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+    }
+
+    #[test]
+    fn test_non_raster_format_rejected() {
+        assert!(matches!(
+            rasterize(SAMPLE_SVG, OutputFormat::Svg, 40, None),
+            Err(QrError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -4,6 +4,8 @@ use super::types::*;
 use super::error::QrResult;
 use crate::standards::validator::{ValidationResult, ValidationSeverity};
 use crate::standards::decoder::{DecodedData, DamageLevel};
+use crate::standards::robustness::RobustnessReport;
+use crate::standards::print_quality::{Grade, PrintQualityReport};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
@@ -53,6 +55,10 @@ pub struct QualityReport {
     pub validation_results: HashMap<String, ValidationSummary>,
     /// Resultados de decodificación
     pub decode_results: Option<DecodeSummary>,
+    /// Curvas de supervivencia ante degradación simulada
+    pub robustness: Option<RobustnessReport>,
+    /// Grados ISO/IEC 15415 medidos sobre la imagen del símbolo
+    pub print_quality: Option<PrintQualityReport>,
     /// Puntuación general
     pub overall_score: QualityScore,
     /// Recomendaciones
@@ -68,7 +74,8 @@ pub struct QualityReport {
 pub struct QrInfo {
     pub data_content: String,
     pub size: u32,
-    pub version: u8,
+    /// None si el símbolo escaneado no informa su versión
+    pub version: Option<u8>,
     pub error_correction: ErrorCorrectionLevel,
     pub customization_features: Vec<String>,
 }
@@ -97,6 +104,9 @@ pub struct DecodeSummary {
 /// Puntuación de calidad general
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityScore {
+    /// 30% validación, 30% cumplimiento y 40% medidas (técnica 20%, legibilidad 10%,
+    /// durabilidad 10%). Un informe de escaneo no tiene validaciones de perfil: las
+    /// medidas se reescalan a 0-1 y pasan a ser el 100% de la puntuación.
     pub overall: f32,
     pub technical: f32,
     pub compliance: f32,
@@ -183,9 +193,65 @@ impl QualityReporter {
         data: &str,
         validation_results: Vec<(&str, ValidationResult)>,
         decode_result: Option<DecodedData>,
+        robustness_result: Option<RobustnessReport>,
+        print_quality: Option<PrintQualityReport>,
     ) -> QrResult<QualityReport> {
         let start_time = std::time::Instant::now();
         
+        // Información del QR
+        let qr_info = QrInfo {
+            data_content: truncate_content(data),
+            size: qr.size as u32,
+            version: Some(qr.get_version()),
+            error_correction: qr.customization.as_ref()
+                .and_then(|c| c.error_correction)
+                .unwrap_or(ErrorCorrectionLevel::Medium),
+            customization_features: self.get_customization_features(qr),
+        };
+        
+        self.build_report(qr_info, validation_results, decode_result, robustness_result, print_quality, true, start_time)
+    }
+    
+    /// Genera el reporte de un símbolo escaneado: sin `QrCode` ni validaciones de perfil,
+    /// solo con la lectura y, si se midió, el grado de impresión
+    pub fn generate_scan_report(
+        &self,
+        decoded: &DecodedData,
+        print_quality: Option<PrintQualityReport>,
+    ) -> QrResult<QualityReport> {
+        let start_time = std::time::Instant::now();
+        
+        let version = decoded.metadata.qr_version;
+        let qr_info = QrInfo {
+            data_content: truncate_content(&decoded.raw_data),
+            // Sin cuadrícula medida no se conoce la versión ni, por tanto, el tamaño
+            size: version.map_or(0, |version| 17 + 4 * version as u32),
+            version,
+            error_correction: match decoded.metadata.error_correction.as_str() {
+                "L" => ErrorCorrectionLevel::Low,
+                "Q" => ErrorCorrectionLevel::Quartile,
+                "H" => ErrorCorrectionLevel::High,
+                _ => ErrorCorrectionLevel::Medium,
+            },
+            customization_features: vec![format!("Scanned {:?}", decoded.metadata.symbology)],
+        };
+        
+        self.build_report(qr_info, Vec::new(), Some(decoded.clone()), None, print_quality, false, start_time)
+    }
+    
+    /// Puntúa los resultados y arma el reporte; `structure_analyzed` indica si se
+    /// inspeccionó la matriz generada (módulos, zona silenciosa, corrección)
+    #[allow(clippy::too_many_arguments)]
+    fn build_report(
+        &self,
+        qr_info: QrInfo,
+        validation_results: Vec<(&str, ValidationResult)>,
+        decode_result: Option<DecodedData>,
+        robustness_result: Option<RobustnessReport>,
+        print_quality: Option<PrintQualityReport>,
+        structure_analyzed: bool,
+        start_time: std::time::Instant,
+    ) -> QrResult<QualityReport> {
         // Procesar resultados de validación
         let mut validation_summaries = HashMap::new();
         let mut total_score = 0.0;
@@ -234,27 +300,35 @@ impl QualityReporter {
         let avg_validation_score = total_score / num_validations;
         let avg_compliance = total_compliance / num_validations;
         
-        let technical_score = decode_summary.as_ref()
-            .map(|d| d.quality_score)
+        // El grado de impresión medido prevalece sobre la estimación del decodificador
+        let technical_score = print_quality.as_ref()
+            .map(|pq| pq.overall.value() as f32 / Grade::A.value() as f32)
+            .or_else(|| decode_summary.as_ref().map(|d| d.quality_score))
             .unwrap_or(0.8);
         
         let readability_score = if decode_summary.is_some() { 1.0 } else { 0.0 };
         
-        let durability_score = match decode_summary.as_ref()
-            .map(|d| d.damage_level.as_str())
-            .unwrap_or("None") {
-            "None" => 1.0,
-            "Minor" => 0.8,
-            "Moderate" => 0.5,
-            "Severe" => 0.2,
-            _ => 0.0,
+        // La durabilidad se mide con la simulación de degradación si está disponible
+        let durability_score = match &robustness_result {
+            Some(robustness) => robustness.overall_survival,
+            None => match decode_summary.as_ref()
+                .map(|d| d.damage_level.as_str())
+                .unwrap_or("None") {
+                "None" => 1.0,
+                "Minor" => 0.8,
+                "Moderate" => 0.5,
+                "Severe" => 0.2,
+                _ => 0.0,
+            },
         };
         
-        let overall_score = (avg_validation_score * 0.3 + 
-                            avg_compliance * 0.3 + 
-                            technical_score * 0.2 + 
-                            readability_score * 0.1 + 
-                            durability_score * 0.1).min(1.0).max(0.0);
+        // Sin validaciones de perfil el peso se reparte entre las medidas disponibles
+        let measured_score = technical_score * 0.2 + readability_score * 0.1 + durability_score * 0.1;
+        let overall_score = if validation_results.is_empty() {
+            measured_score / 0.4
+        } else {
+            avg_validation_score * 0.3 + avg_compliance * 0.3 + measured_score
+        }.clamp(0.0, 1.0);
         
         let grade = self.calculate_grade(overall_score);
         
@@ -262,25 +336,16 @@ impl QualityReporter {
         let recommendations = self.generate_recommendations(
             &validation_results,
             decode_result.as_ref(),
+            robustness_result.as_ref(),
             overall_score
         );
         
         // Generar certificaciones
-        let certifications = self.generate_certifications(&validation_results);
-        
-        // Información del QR
-        let qr_info = QrInfo {
-            data_content: data.chars().take(50).collect::<String>() + 
-                         if data.len() > 50 { "..." } else { "" },
-            size: qr.size as u32,
-            version: (qr.size / 4 - 4) as u8, // Aproximación
-            error_correction: qr.customization.as_ref()
-                .and_then(|c| c.error_correction)
-                .unwrap_or(ErrorCorrectionLevel::Medium),
-            customization_features: self.get_customization_features(qr),
-        };
+        let certifications = self.generate_certifications(&validation_results, print_quality.as_ref());
         
         let analysis_duration_ms = start_time.elapsed().as_millis() as u64;
+        let robustness_performed = robustness_result.is_some();
+        let print_quality_performed = print_quality.is_some();
         
         Ok(QualityReport {
             report_id: uuid::Uuid::new_v4().to_string(),
@@ -288,6 +353,8 @@ impl QualityReporter {
             qr_info,
             validation_results: validation_summaries,
             decode_results: decode_summary,
+            robustness: robustness_result,
+            print_quality,
             overall_score: QualityScore {
                 overall: overall_score,
                 technical: technical_score,
@@ -301,7 +368,12 @@ impl QualityReporter {
             metadata: ReportMetadata {
                 generator_version: env!("CARGO_PKG_VERSION").to_string(),
                 analysis_duration_ms,
-                tests_performed: self.get_tests_performed(&validation_results),
+                tests_performed: self.get_tests_performed(
+                    &validation_results,
+                    structure_analyzed,
+                    robustness_performed,
+                    print_quality_performed,
+                ),
                 environment: "Production".to_string(),
             },
         })
@@ -323,6 +395,7 @@ impl QualityReporter {
         &self,
         validation_results: &[(&str, ValidationResult)],
         decode_result: Option<&DecodedData>,
+        robustness_result: Option<&RobustnessReport>,
         overall_score: f32,
     ) -> Vec<Recommendation> {
         let mut recommendations = Vec::new();
@@ -381,6 +454,26 @@ impl QualityReporter {
             }
         }
         
+        // Recomendaciones basadas en la simulación de degradación
+        if let Some(robustness) = robustness_result {
+            for curve in robustness.curves.iter().filter(|c| c.survival_rate < 0.5) {
+                let breaking = curve.breaking_level
+                    .map(|level| format!(" (falla desde {} {})", level, curve.perturbation.unit()))
+                    .unwrap_or_default();
+                recommendations.push(Recommendation {
+                    category: RecommendationCategory::Design,
+                    priority: if curve.survival_rate == 0.0 {
+                        RecommendationPriority::High
+                    } else {
+                        RecommendationPriority::Medium
+                    },
+                    message: format!("Baja tolerancia a {}{}", curve.perturbation.name().to_lowercase(), breaking),
+                    action: "Aumentar el nivel de corrección de errores, el tamaño de módulo o el contraste".to_string(),
+                    impact: "Mejorará la lectura en condiciones reales de impresión y cámara".to_string(),
+                });
+            }
+        }
+        
         // Recomendaciones generales
         if overall_score < 0.7 {
             recommendations.push(Recommendation {
@@ -396,20 +489,27 @@ impl QualityReporter {
     }
     
     /// Genera certificaciones basadas en los resultados
-    fn generate_certifications(&self, validation_results: &[(&str, ValidationResult)]) -> Vec<Certification> {
+    fn generate_certifications(
+        &self,
+        validation_results: &[(&str, ValidationResult)],
+        print_quality: Option<&PrintQualityReport>,
+    ) -> Vec<Certification> {
         let mut certifications = Vec::new();
         
+        // ISO 15415: solo con grados medidos sobre una imagen; C es el mínimo habitual de aceptación
+        if let Some(pq) = print_quality {
+            let parameters: Vec<String> = pq.parameters().iter()
+                .map(|(name, parameter)| format!("{} {}", name, parameter.grade))
+                .collect();
+            certifications.push(Certification {
+                standard: "ISO/IEC 15415".to_string(),
+                level: format!("Grade {}", pq.overall),
+                compliant: pq.overall >= Grade::C,
+                details: format!("{} (apertura {:.1} px)", parameters.join(", "), pq.aperture_px),
+            });
+        }
+        
         for (profile, result) in validation_results {
-            // ISO 15415
-            if result.compliance.get("ISO15415").copied().unwrap_or(false) {
-                certifications.push(Certification {
-                    standard: "ISO/IEC 15415".to_string(),
-                    level: if result.score > 0.9 { "Grade A" } else { "Grade B" }.to_string(),
-                    compliant: true,
-                    details: "Cumple con estándares de calidad de impresión 2D".to_string(),
-                });
-            }
-            
             // GS1
             if profile.contains("GS1") && result.is_valid {
                 certifications.push(Certification {
@@ -467,13 +567,22 @@ impl QualityReporter {
     }
     
     /// Obtiene la lista de pruebas realizadas
-    fn get_tests_performed(&self, validation_results: &[(&str, ValidationResult)]) -> Vec<String> {
-        let mut tests = vec![
-            "Structure Analysis".to_string(),
-            "Module Size Verification".to_string(),
-            "Quiet Zone Check".to_string(),
-            "Error Correction Validation".to_string(),
-        ];
+    fn get_tests_performed(
+        &self,
+        validation_results: &[(&str, ValidationResult)],
+        structure_analyzed: bool,
+        robustness_performed: bool,
+        print_quality_performed: bool,
+    ) -> Vec<String> {
+        let mut tests = Vec::new();
+        if structure_analyzed {
+            tests.extend([
+                "Structure Analysis".to_string(),
+                "Module Size Verification".to_string(),
+                "Quiet Zone Check".to_string(),
+                "Error Correction Validation".to_string(),
+            ]);
+        }
         
         for (profile, _) in validation_results {
             tests.push(format!("{} Profile Validation", profile));
@@ -481,6 +590,12 @@ impl QualityReporter {
         
         tests.push("Decode Verification".to_string());
         tests.push("Quality Metrics Analysis".to_string());
+        if robustness_performed {
+            tests.push("Degradation Robustness Simulation".to_string());
+        }
+        if print_quality_performed {
+            tests.push("ISO/IEC 15415 Print Quality Grading".to_string());
+        }
         
         tests
     }
@@ -508,7 +623,10 @@ impl QualityReporter {
         output.push_str("\n");
         output.push_str(&format!("Contenido: {}\n", report.qr_info.data_content));
         output.push_str(&format!("Tamaño: {}x{}\n", report.qr_info.size, report.qr_info.size));
-        output.push_str(&format!("Versión: {}\n", report.qr_info.version));
+        output.push_str(&format!(
+            "Versión: {}\n",
+            report.qr_info.version.map_or_else(|| "desconocida".to_string(), |version| version.to_string())
+        ));
         output.push_str(&format!("Corrección de Errores: {:?}\n", report.qr_info.error_correction));
         output.push_str(&format!("Características: {}\n", report.qr_info.customization_features.join(", ")));
         output.push_str("\n");
@@ -546,13 +664,47 @@ impl QualityReporter {
             output.push_str("\n");
         }
         
+        // Robustez ante degradación
+        if let Some(robustness) = &report.robustness {
+            output.push_str("ROBUSTEZ ANTE DEGRADACIÓN:\n");
+            output.push_str(&"-".repeat(40));
+            output.push('\n');
+            output.push_str(&format!("Supervivencia media: {:.1}%\n", robustness.overall_survival * 100.0));
+            for curve in &robustness.curves {
+                let profile: String = curve.points.iter()
+                    .map(|p| if p.decoded { '■' } else { '□' })
+                    .collect();
+                output.push_str(&format!("{:<16} {} {:>5.1}%", curve.perturbation.name(), profile, curve.survival_rate * 100.0));
+                if let Some(level) = curve.breaking_level {
+                    output.push_str(&format!("  (falla desde {} {})", level, curve.perturbation.unit()));
+                }
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+        
+        // Calidad de impresión medida
+        if let Some(pq) = &report.print_quality {
+            output.push_str("CALIDAD DE IMPRESIÓN ISO/IEC 15415:\n");
+            output.push_str(&"-".repeat(40));
+            output.push('\n');
+            output.push_str(&format!("Grado del símbolo: {}\n", pq.overall));
+            output.push_str(&format!("  Decodificación: {}\n", pq.decode));
+            for (name, parameter) in pq.parameters() {
+                output.push_str(&format!("  {:<4} {}  ({:.2})\n", name, parameter.grade, parameter.value));
+            }
+            output.push_str(&format!("Apertura: {:.1} px (módulo {:.1} px)\n", pq.aperture_px, pq.module_size_px));
+            output.push('\n');
+        }
+        
         // Certificaciones
         if !report.certifications.is_empty() {
             output.push_str("CERTIFICACIONES OBTENIDAS:\n");
             output.push_str(&"-".repeat(40));
             output.push_str("\n");
             for cert in &report.certifications {
-                output.push_str(&format!("✓ {} - {}\n", cert.standard, cert.level));
+                let mark = if cert.compliant { '✓' } else { '✗' };
+                output.push_str(&format!("{} {} - {}\n", mark, cert.standard, cert.level));
                 output.push_str(&format!("  {}\n", cert.details));
             }
             output.push_str("\n");
@@ -641,6 +793,11 @@ impl Default for QualityReporter {
     }
 }
 
+/// Primeros 50 caracteres del contenido para el encabezado del reporte
+fn truncate_content(data: &str) -> String {
+    data.chars().take(50).collect::<String>() + if data.chars().count() > 50 { "..." } else { "" }
+}
+
 // Necesitamos agregar uuid a las dependencias
 use uuid;
 
@@ -660,6 +817,8 @@ mod tests {
             quiet_zone: 4,
             customization: None,
             logo_zone: None,
+            symbology: QrSymbology::Qr,
+            gs1_mode: false,
         };
         
         // Simular resultados de validación
@@ -670,6 +829,8 @@ mod tests {
             &qr,
             "Test data",
             vec![("General", validation_result)],
+            None,
+            None,
             None
         );
         
@@ -679,6 +840,80 @@ mod tests {
         assert!(report.overall_score.overall >= 0.0 && report.overall_score.overall <= 1.0);
     }
     
+    #[test]
+    fn test_report_with_robustness_section() {
+        use crate::standards::robustness::{Perturbation, RobustnessAnalyzer};
+        
+        let reporter = QualityReporter::new();
+        let qr = crate::engine::QrGenerator::new().generate_basic("Test data", 300).unwrap();
+        let robustness = RobustnessAnalyzer::new()
+            .with_perturbations(vec![Perturbation::Occlusion])
+            .analyze_svg(&qr.to_svg(), "Test data")
+            .unwrap();
+        let survival = robustness.overall_survival;
+        
+        let report = reporter.generate_report(&qr, "Test data", vec![], None, Some(robustness), None).unwrap();
+        
+        assert_eq!(report.overall_score.durability, survival);
+        assert!(report.metadata.tests_performed.contains(&"Degradation Robustness Simulation".to_string()));
+        let text = reporter.format_text_report(&report);
+        assert!(text.contains("ROBUSTEZ ANTE DEGRADACIÓN"));
+        assert!(text.contains("Oclusión"));
+    }
+    
+    #[test]
+    fn test_print_quality_certification() {
+        use crate::standards::print_quality::PrintQualityGrader;
+        
+        let reporter = QualityReporter::new();
+        let qr = crate::engine::QrGenerator::new().generate_basic("Test data", 300).unwrap();
+        let validator = StandardValidator::new();
+        let validation_result = validator.validate(&qr, ValidationProfile::Manufacturing, "Test data").unwrap();
+        
+        // Sin imagen no hay grado ISO/IEC 15415 que certificar
+        let report = reporter.generate_report(&qr, "Test data", vec![("Manufacturing", validation_result.clone())], None, None, None).unwrap();
+        assert!(report.certifications.iter().all(|c| c.standard != "ISO/IEC 15415"));
+        
+        let print_quality = PrintQualityGrader::new().grade_svg(&qr.to_svg()).unwrap();
+        let report = reporter.generate_report(&qr, "Test data", vec![("Manufacturing", validation_result)], None, None, Some(print_quality)).unwrap();
+        let certification = report.certifications.iter().find(|c| c.standard == "ISO/IEC 15415").unwrap();
+        assert_eq!(certification.level, "Grade A");
+        assert!(certification.compliant);
+        assert!(certification.details.contains("UEC A"));
+        assert_eq!(report.overall_score.technical, 1.0);
+        assert!(report.metadata.tests_performed.contains(&"ISO/IEC 15415 Print Quality Grading".to_string()));
+        assert!(reporter.format_text_report(&report).contains("CALIDAD DE IMPRESIÓN ISO/IEC 15415"));
+    }
+    
+    #[test]
+    fn test_scan_report() {
+        use crate::standards::decoder::QrDecoder;
+        use crate::standards::print_quality::PrintQualityGrader;
+        use image::DynamicImage;
+        
+        let reporter = QualityReporter::new();
+        let qr = crate::engine::QrGenerator::new().generate_basic("Test data", 300).unwrap();
+        let raster = crate::engine::raster::render_svg(&qr.to_svg(), 300).unwrap();
+        let rgb = crate::engine::raster::flatten_on_white(&raster.pixels);
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_raw(raster.width, raster.height, rgb).unwrap());
+        let decoded = QrDecoder::new().decode_image(&image).unwrap();
+        let print_quality = PrintQualityGrader::new().grade_image(&image).unwrap();
+        
+        let report = reporter.generate_scan_report(&decoded, Some(print_quality)).unwrap();
+        assert_eq!(report.qr_info.data_content, "Test data");
+        assert_eq!(report.qr_info.version, Some(1));
+        assert_eq!(report.qr_info.size, 21);
+        assert!(report.validation_results.is_empty());
+        // Sin validaciones la puntuación no queda limitada por el peso de cumplimiento
+        assert!(report.overall_score.overall > 0.6, "{}", report.overall_score.overall);
+        
+        let tests = &report.metadata.tests_performed;
+        assert!(!tests.contains(&"Quiet Zone Check".to_string()));
+        assert!(tests.contains(&"Decode Verification".to_string()));
+        assert!(tests.contains(&"ISO/IEC 15415 Print Quality Grading".to_string()));
+        assert!(reporter.format_text_report(&report).contains("Scanned Qr"));
+    }
+    
     #[test]
     fn test_grade_calculation() {
         let reporter = QualityReporter::new();
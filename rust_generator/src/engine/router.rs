@@ -27,8 +27,8 @@ impl Default for ComplexityThresholds {
     fn default() -> Self {
         Self {
             basic_max_size: 600,
-            medium_features_count: 2,
-            advanced_features_count: 4,
+            medium_features_count: 3,
+            advanced_features_count: 5,
             ultra_features: vec![
                 "complex_frame".to_string(),
                 "multiple_effects".to_string(),
@@ -70,8 +70,9 @@ impl ComplexityRouter {
             
             // Por número de características
             (Some(custom), count, false) => {
-                // Efectos siempre requieren nivel avanzado como mínimo
-                if custom.effects.is_some() && custom.effects.as_ref().unwrap().len() > 0 {
+                // Efectos y logos siempre requieren nivel avanzado como mínimo
+                let has_effects = custom.effects.as_ref().is_some_and(|effects| !effects.is_empty());
+                if has_effects || custom.logos().next().is_some() {
                     if count > self.thresholds.advanced_features_count {
                         ComplexityLevel::Ultra
                     } else {
@@ -161,6 +162,11 @@ impl ComplexityRouter {
                 }
             }
             
+            // Animación (cada fotograma se verifica)
+            if custom.animation.as_ref().is_some_and(|a| !a.effects.is_empty()) {
+                return true;
+            }
+            
             // Gradientes muy complejos
             if let Some(gradient) = &custom.gradient {
                 if gradient.colors.len() > 3 {
@@ -239,7 +245,7 @@ mod tests {
             data: "https://example.com".to_string(),
             size: 400,
             format: OutputFormat::Svg,
-            customization: None,
+            ..Default::default()
         };
         
         assert_eq!(router.determine_complexity(&request), ComplexityLevel::Basic);
@@ -256,18 +262,10 @@ mod tests {
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
                 eye_shape: Some(EyeShape::RoundedSquare),
-                eye_border_style: None,
-                eye_center_style: None,
                 data_pattern: Some(DataPattern::Dots),
-                colors: None,
-                gradient: None,
-                logo: None,
-                frame: None,
-                effects: None,
-                error_correction: None,
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         assert_eq!(router.determine_complexity(&request), ComplexityLevel::Medium);
@@ -284,24 +282,18 @@ mod tests {
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
                 eye_shape: Some(EyeShape::RoundedSquare),
-                eye_border_style: None,
-                eye_center_style: None,
-                data_pattern: None,
-                colors: None,
-                gradient: None,
                 logo: Some(LogoOptions {
                     data: "base64...".to_string(),
+                    logo_id: None,
                     size_percentage: 20.0,
                     padding: 5,
                     background: None,
                     shape: LogoShape::Circle,
+                    placement: None,
                 }),
-                frame: None,
-                effects: None,
-                error_correction: None,
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         assert_eq!(router.determine_complexity(&request), ComplexityLevel::Advanced);
@@ -4,6 +4,9 @@
 
 use qrcodegen::QrSegment;
 
+/// Separador de grupo GS1 (FNC1 dentro de los datos)
+const GROUP_SEPARATOR: char = '\u{1D}';
+
 /// Tipo de segmento detectado
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum SegmentType {
@@ -25,6 +28,8 @@ struct DataSegment {
 pub struct ContentSegmenter {
     /// Umbral mínimo de caracteres para crear un segmento
     min_segment_length: usize,
+    /// Datos GS1 en modo FNC1: el separador GS y el '%' literal van en segmentos de bytes
+    gs1_mode: bool,
 }
 
 impl ContentSegmenter {
@@ -32,6 +37,17 @@ impl ContentSegmenter {
     pub fn new() -> Self {
         Self {
             min_segment_length: 3, // Mínimo 3 caracteres para que valga la pena segmentar
+            gs1_mode: false,
+        }
+    }
+
+    /// Segmentador para cadenas de elementos GS1 con separadores GS (0x1D).
+    /// En modo FNC1 un '%' alfanumérico se lee como separador; para no depender de
+    /// cómo cada lector resuelve el escape '%%', GS y '%' se codifican como bytes.
+    pub fn for_gs1() -> Self {
+        Self {
+            gs1_mode: true,
+            ..Self::new()
         }
     }
 
@@ -77,15 +93,16 @@ impl ContentSegmenter {
                                 end_index: index - 1,
                             });
                         } else if let Some(last) = segments.last_mut() {
-                            // Fusionar con el segmento anterior si es muy corto
+                            // Fusionar con el segmento anterior si es muy corto, en el
+                            // modo más compacto que admite ambos
                             last.content.push_str(&current_segment);
                             last.end_index = index - 1;
-                            last.segment_type = SegmentType::Byte; // Degradar a byte si es mixto
+                            last.segment_type = Self::merged_type(last.segment_type, seg_type);
                         } else {
-                            // Primer segmento muy corto, guardarlo como byte
+                            // Primer segmento muy corto: no hay con qué fusionarlo
                             segments.push(DataSegment {
                                 content: current_segment.clone(),
-                                segment_type: SegmentType::Byte,
+                                segment_type: seg_type,
                                 start_index,
                                 end_index: index - 1,
                             });
@@ -115,11 +132,11 @@ impl ContentSegmenter {
                 } else if let Some(last) = segments.last_mut() {
                     last.content.push_str(&current_segment);
                     last.end_index = data.len() - 1;
-                    last.segment_type = SegmentType::Byte;
+                    last.segment_type = Self::merged_type(last.segment_type, seg_type);
                 } else {
                     segments.push(DataSegment {
                         content: current_segment,
-                        segment_type: SegmentType::Byte,
+                        segment_type: seg_type,
                         start_index,
                         end_index: data.len() - 1,
                     });
@@ -133,7 +150,9 @@ impl ContentSegmenter {
 
     /// Clasifica un carácter según el modo QR más eficiente
     fn classify_character(&self, ch: char) -> SegmentType {
-        if ch.is_ascii_digit() {
+        if self.gs1_mode && (ch == '%' || ch == GROUP_SEPARATOR) {
+            SegmentType::Byte
+        } else if ch.is_ascii_digit() {
             SegmentType::Numeric
         } else if self.is_alphanumeric_qr(ch) {
             SegmentType::Alphanumeric
@@ -153,11 +172,21 @@ impl ContentSegmenter {
     fn should_segment(&self, content: &str, segment_type: &SegmentType) -> bool {
         match segment_type {
             SegmentType::Numeric => content.len() >= self.min_segment_length,
-            SegmentType::Alphanumeric => content.len() >= self.min_segment_length + 1,
+            SegmentType::Alphanumeric => content.len() >= self.min_segment_length,
             SegmentType::Byte => true, // Siempre segmentar bytes
         }
     }
 
+    /// Modo más compacto capaz de codificar dos segmentos fusionados:
+    /// numérico ⊂ alfanumérico ⊂ bytes
+    fn merged_type(a: SegmentType, b: SegmentType) -> SegmentType {
+        match (a, b) {
+            (SegmentType::Byte, _) | (_, SegmentType::Byte) => SegmentType::Byte,
+            (SegmentType::Numeric, SegmentType::Numeric) => SegmentType::Numeric,
+            _ => SegmentType::Alphanumeric,
+        }
+    }
+
     /// Optimiza los segmentos fusionando los adyacentes del mismo tipo
     fn optimize_segments(&self, segments: Vec<DataSegment>) -> Vec<DataSegment> {
         if segments.is_empty() {
@@ -179,6 +208,48 @@ impl ContentSegmenter {
         }
         
         optimized.push(current);
+
+        // Fusionar vecinos mientras ahorre bits: la cabecera de modo y longitud
+        // de un segmento corto cuesta más de lo que ahorra su modo compacto
+        loop {
+            let best = optimized.windows(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    let merged_type = Self::merged_type(pair[0].segment_type, pair[1].segment_type);
+                    let separate = self.calculate_bit_size(&pair[0].content, &pair[0].segment_type)
+                        + self.calculate_bit_size(&pair[1].content, &pair[1].segment_type);
+                    let merged = self.calculate_bit_size(
+                        &format!("{}{}", pair[0].content, pair[1].content),
+                        &merged_type,
+                    );
+                    (i, merged_type, separate as isize - merged as isize)
+                })
+                .filter(|(_, _, saving)| *saving >= 0)
+                .max_by_key(|(_, _, saving)| *saving);
+
+            let Some((i, merged_type, _)) = best else { break };
+            let next = optimized.remove(i + 1);
+            let segment = &mut optimized[i];
+            segment.content.push_str(&next.content);
+            segment.end_index = next.end_index;
+            segment.segment_type = merged_type;
+        }
+
+        // Nunca peor que codificar todo como bytes
+        let total: usize = optimized.iter()
+            .map(|s| self.calculate_bit_size(&s.content, &s.segment_type))
+            .sum();
+        let content: String = optimized.iter().map(|s| s.content.as_str()).collect();
+        if optimized.len() > 1 && total > self.calculate_bit_size(&content, &SegmentType::Byte) {
+            let end_index = optimized.last().map_or(0, |s| s.end_index);
+            return vec![DataSegment {
+                content,
+                segment_type: SegmentType::Byte,
+                start_index: 0,
+                end_index,
+            }];
+        }
+
         optimized
     }
 
@@ -199,7 +270,8 @@ impl ContentSegmenter {
                 },
                 SegmentType::Alphanumeric => {
                     // Validar que todos los caracteres sean alfanuméricos QR válidos
-                    if segment.content.chars().all(|c| self.is_alphanumeric_qr(c)) {
+                    let escapes_fnc1 = self.gs1_mode && segment.content.contains('%');
+                    if segment.content.chars().all(|c| self.is_alphanumeric_qr(c)) && !escapes_fnc1 {
                         QrSegment::make_alphanumeric(&segment.content)
                     } else {
                         return Err(format!("Segmento alfanumérico contiene caracteres inválidos: {}", segment.content));
@@ -287,15 +359,21 @@ mod tests {
     #[test]
     fn test_mixed_segmentation() {
         let segmenter = ContentSegmenter::new();
-        let segments = segmenter.detect_segments("ABC123XYZ");
+        let segments = segmenter.detect_segments("ABC12345678901234XYZ");
         
         assert_eq!(segments.len(), 3);
         assert_eq!(segments[0].segment_type, SegmentType::Alphanumeric);
         assert_eq!(segments[0].content, "ABC");
         assert_eq!(segments[1].segment_type, SegmentType::Numeric);
-        assert_eq!(segments[1].content, "123");
+        assert_eq!(segments[1].content, "12345678901234");
         assert_eq!(segments[2].segment_type, SegmentType::Alphanumeric);
         assert_eq!(segments[2].content, "XYZ");
+        
+        // Tres dígitos no pagan la cabecera de un segmento numérico:
+        // un único alfanumérico ocupa 63 bits frente a 84 de tres segmentos
+        let segments = segmenter.detect_segments("ABC123XYZ");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].segment_type, SegmentType::Alphanumeric);
     }
 
     #[test]
@@ -309,6 +387,24 @@ mod tests {
         assert!(last_segment.content.contains("12345"));
     }
 
+    #[test]
+    fn test_gs1_separators_use_byte_mode() {
+        let data = "010950110153000310ABCD%EFGH\u{1D}21SERIALNUMBER1234";
+        let segments = ContentSegmenter::for_gs1().detect_segments(data);
+        
+        assert_eq!(segments.iter().map(|s| s.content.as_str()).collect::<String>(), data);
+        for segment in &segments {
+            if segment.content.contains('%') || segment.content.contains('\u{1D}') {
+                assert_eq!(segment.segment_type, SegmentType::Byte);
+            }
+        }
+        assert!(ContentSegmenter::for_gs1().analyze_and_segment(data).is_ok());
+        
+        // Fuera del modo GS1 el '%' es alfanumérico
+        let segments = ContentSegmenter::new().detect_segments("ABCD%EFGH");
+        assert_eq!(segments[0].segment_type, SegmentType::Alphanumeric);
+    }
+
     #[test]
     fn test_savings_calculation() {
         let segmenter = ContentSegmenter::new();
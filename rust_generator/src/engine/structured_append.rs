@@ -0,0 +1,218 @@
+// engine/structured_append.rs - Structured Append (ISO/IEC 18004, sección 8)
+// Reparte un mensaje que no cabe en un símbolo entre hasta 16 símbolos enlazados;
+// cada uno lleva su posición en la secuencia y la paridad del mensaje completo
+
+use qrcodegen::{QrCode as QrCodeGen, QrCodeEcc, QrSegment, Version};
+
+use super::bitstream::{data_codewords, MODE_STRUCTURED_APPEND};
+use super::error::{QrError, QrResult};
+use super::segmenter::ContentSegmenter;
+
+/// Máximo de símbolos por conjunto (4 bits de total en la cabecera)
+pub const MAX_SYMBOLS: usize = 16;
+
+/// Bits de cabecera: modo (4) + posición (4) + total (4) + paridad (8)
+const HEADER_BITS: usize = 20;
+
+/// Asignación ECI para UTF-8: cada símbolo se lee por separado y debe declarar su juego de caracteres
+const ECI_UTF8: u32 = 26;
+
+/// Símbolo codificado de un conjunto
+pub struct EncodedSymbol {
+    /// Fragmento del mensaje contenido en el símbolo
+    pub data: String,
+    pub qr: QrCodeGen,
+}
+
+/// Conjunto codificado con su paridad compartida
+pub struct EncodedSet {
+    pub parity: u8,
+    pub symbols: Vec<EncodedSymbol>,
+}
+
+/// Paridad Structured Append: XOR de todos los bytes del mensaje
+pub fn parity(data: &[u8]) -> u8 {
+    data.iter().fold(0, |acc, byte| acc ^ byte)
+}
+
+/// Codifica el mensaje en el menor número de símbolos (hasta `max_symbols`)
+/// sin superar `max_version`, repartiendo los datos de forma equilibrada
+pub fn encode(data: &str, ecl: QrCodeEcc, max_symbols: usize, max_version: u8) -> QrResult<EncodedSet> {
+    if data.is_empty() {
+        return Err(QrError::InvalidCharacters);
+    }
+    let max_symbols = max_symbols.clamp(1, MAX_SYMBOLS);
+    let max_version = max_version.clamp(Version::MIN.value(), Version::MAX.value());
+
+    let chunks = split(data, ecl, max_symbols, max_version)?;
+    let total = chunks.len();
+    let parity = parity(data.as_bytes());
+
+    let symbols = chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let segments = segments_for(chunk)?;
+            let version = smallest_version(&segments, ecl, max_version).ok_or_else(|| {
+                QrError::EncodingError(format!("El fragmento {} no cabe en la versión {}", index + 1, max_version))
+            })?;
+            let header = [(MODE_STRUCTURED_APPEND, 4), (index as u32, 4), (total as u32 - 1, 4), (parity as u32, 8)];
+            let codewords = data_codewords(&header, &segments, version, ecl);
+            Ok(EncodedSymbol {
+                data: chunk.to_string(),
+                qr: QrCodeGen::encode_codewords(Version::new(version), ecl, &codewords, None),
+            })
+        })
+        .collect::<QrResult<Vec<_>>>()?;
+
+    Ok(EncodedSet { parity, symbols })
+}
+
+/// Compone los SVG de un conjunto en una hoja en mosaico (de izquierda a derecha y de arriba abajo)
+pub fn tile_sheet(svgs: &[String], columns: usize) -> QrResult<String> {
+    let sizes = svgs
+        .iter()
+        .map(|svg| super::raster::parse_svg(svg).map(|tree| tree.size()))
+        .collect::<QrResult<Vec<_>>>()?;
+    let cell = sizes.iter().map(|size| size.width().max(size.height())).fold(0.0f32, f32::max);
+    let columns = columns.clamp(1, svgs.len().max(1));
+    let rows = svgs.len().div_ceil(columns);
+    let (width, height) = (cell * columns as f32, cell * rows as f32);
+
+    let mut sheet = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}"><rect width="{w}" height="{h}" fill="white"/>"#,
+        w = width, h = height
+    );
+    for (index, (svg, size)) in svgs.iter().zip(&sizes).enumerate() {
+        // Cada símbolo conserva su SVG completo, anidado y centrado en su celda
+        let x = (index % columns) as f32 * cell + (cell - size.width()) / 2.0;
+        let y = (index / columns) as f32 * cell + (cell - size.height()) / 2.0;
+        let start = svg.find("<svg").ok_or_else(|| QrError::RenderError("SVG de símbolo inválido".to_string()))?;
+        sheet.push_str(&svg[..start]);
+        sheet.push_str(&format!(r#"<svg x="{}" y="{}""#, x, y));
+        sheet.push_str(&svg[start + 4..]);
+    }
+    sheet.push_str("</svg>");
+    Ok(sheet)
+}
+
+/// Divide el mensaje en fragmentos que caben cada uno en un símbolo.
+/// El reparto voraz fija el número de símbolos; después se intenta un reparto equilibrado.
+fn split(data: &str, ecl: QrCodeEcc, max_symbols: usize, max_version: u8) -> QrResult<Vec<&str>> {
+    // Los cortes solo caen en límites de carácter para que cada símbolo sea texto válido
+    let boundaries: Vec<usize> = data.char_indices().map(|(i, _)| i).chain([data.len()]).collect();
+    let fits = |start: usize, end: usize| {
+        segments_for(&data[start..end])
+            .map(|segments| smallest_version(&segments, ecl, max_version).is_some())
+            .unwrap_or(false)
+    };
+
+    let mut greedy = Vec::new();
+    let mut start = 0;
+    while start < boundaries.len() - 1 {
+        // Búsqueda binaria del corte más lejano que aún cabe
+        let (mut low, mut high) = (start + 1, boundaries.len() - 1);
+        if !fits(boundaries[start], boundaries[low]) {
+            return Err(QrError::EncodingError(
+                "Un carácter no cabe en un símbolo de la versión máxima".to_string(),
+            ));
+        }
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            if fits(boundaries[start], boundaries[mid]) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        greedy.push((boundaries[start], boundaries[low]));
+        start = low;
+
+        if greedy.len() == max_symbols && start < boundaries.len() - 1 {
+            return Err(QrError::DataTooLong(data.len(), boundaries[start]));
+        }
+    }
+
+    // Reparto equilibrado: mismo número de símbolos, fragmentos de tamaño similar
+    let count = greedy.len();
+    let target = data.len().div_ceil(count);
+    let mut balanced = Vec::with_capacity(count);
+    let mut cut = 0;
+    for index in 1..=count {
+        let end = if index == count {
+            data.len()
+        } else {
+            let goal = (target * index).min(data.len());
+            boundaries.iter().copied().find(|&b| b >= goal).unwrap_or(data.len())
+        };
+        balanced.push((cut, end));
+        cut = end;
+    }
+
+    let ranges = if balanced.iter().all(|&(s, e)| s < e && fits(s, e)) {
+        balanced
+    } else {
+        greedy
+    };
+    Ok(ranges.into_iter().map(|(s, e)| &data[s..e]).collect())
+}
+
+/// Segmentos de un fragmento (ECI UTF-8 si no es ASCII)
+fn segments_for(chunk: &str) -> QrResult<Vec<QrSegment>> {
+    let mut segments = Vec::new();
+    if !chunk.is_ascii() {
+        segments.push(QrSegment::make_eci(ECI_UTF8));
+    }
+    segments.extend(
+        ContentSegmenter::new()
+            .analyze_and_segment(chunk)
+            .map_err(QrError::EncodingError)?,
+    );
+    Ok(segments)
+}
+
+/// Menor versión en la que caben la cabecera y los segmentos
+fn smallest_version(segments: &[QrSegment], ecl: QrCodeEcc, max_version: u8) -> Option<u8> {
+    super::bitstream::smallest_version(HEADER_BITS, segments, ecl, Version::MIN.value(), max_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(len: usize) -> String {
+        (0..len).map(|i| char::from(b'A' + (i % 26) as u8)).collect::<String>().to_lowercase()
+    }
+
+    #[test]
+    fn test_splits_oversized_payload() {
+        let data = payload(6000);
+        let set = encode(&data, QrCodeEcc::Medium, MAX_SYMBOLS, 40).unwrap();
+
+        assert_eq!(set.parity, parity(data.as_bytes()));
+        assert_eq!(set.symbols.len(), 3);
+        assert_eq!(set.symbols.iter().map(|s| s.data.as_str()).collect::<String>(), data);
+        // Reparto equilibrado: todos los símbolos de la misma versión
+        assert!(set.symbols.iter().all(|s| s.qr.version().value() == set.symbols[0].qr.version().value()));
+    }
+
+    #[test]
+    fn test_respects_symbol_and_version_limits() {
+        let data = payload(600);
+        let set = encode(&data, QrCodeEcc::Low, MAX_SYMBOLS, 5).unwrap();
+        assert!(set.symbols.iter().all(|s| s.qr.version().value() <= 5));
+
+        assert!(matches!(
+            encode(&data, QrCodeEcc::Low, 2, 5),
+            Err(QrError::DataTooLong(600, _))
+        ));
+    }
+
+    #[test]
+    fn test_multibyte_chunks_stay_valid() {
+        let data = "añoñú€".repeat(120);
+        let set = encode(&data, QrCodeEcc::High, MAX_SYMBOLS, 10).unwrap();
+        assert!(set.symbols.len() > 1);
+        assert_eq!(set.symbols.iter().map(|s| s.data.as_str()).collect::<String>(), data);
+    }
+}
@@ -0,0 +1,209 @@
+// engine/svg_logo.rs - Logos vectoriales (image/svg+xml)
+// El SVG del cliente se parsea con usvg sin acceso a archivos locales y se vuelve a
+// serializar desde el árbol: scripts, manejadores de eventos, foreignObject y
+// referencias externas no tienen representación en usvg, así que no sobreviven.
+// El resultado se incrusta como vector en el SVG de salida y solo se rasteriza
+// (con resvg) cuando la salida es raster.
+
+use image::{DynamicImage, RgbaImage};
+use resvg::usvg;
+use sha2::{Digest, Sha256};
+
+use super::error::{QrError, QrResult};
+use super::raster::{self, FONT_DB};
+use super::types::LogoShape;
+
+/// Tamaño máximo del SVG de un logo
+pub const MAX_SVG_LOGO_BYTES: usize = 2 * 1024 * 1024;
+
+/// Lado mayor con el que se rasteriza un logo vectorial cuando no hay tamaño de salida
+pub const DEFAULT_SVG_LOGO_RASTER_PX: u32 = 512;
+
+/// Prefijo MIME de los logos vectoriales en data URLs
+const SVG_DATA_URL_PREFIX: &str = "data:image/svg+xml";
+
+/// Logo vectorial saneado, listo para incrustarse en el SVG de salida
+#[derive(Debug, Clone)]
+pub struct VectorLogo {
+    /// Documento `<svg>` re-serializado por usvg
+    pub markup: String,
+    pub width: f32,
+    pub height: f32,
+    /// Prefijo de los IDs internos, para no colisionar con los del QR
+    pub id_prefix: String,
+}
+
+impl VectorLogo {
+    /// Indica si el logo llega como data URL `image/svg+xml`
+    pub fn is_svg_data_url(data: &str) -> bool {
+        data.get(..SVG_DATA_URL_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(SVG_DATA_URL_PREFIX))
+    }
+
+    /// Decodifica un data URL `image/svg+xml` (base64 o percent-encoded) y lo sanea
+    pub fn from_data_url(data: &str) -> QrResult<Self> {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        if !Self::is_svg_data_url(data) {
+            return Err(QrError::LogoError("El logo no es un data URL image/svg+xml".to_string()));
+        }
+        let (header, body) = data.split_once(',')
+            .ok_or_else(|| QrError::LogoError("Data URL sin contenido".to_string()))?;
+
+        let bytes = if header.split(';').any(|param| param.eq_ignore_ascii_case("base64")) {
+            BASE64.decode(body.trim())
+                .map_err(|e| QrError::LogoError(format!("Error decodificando base64: {}", e)))?
+        } else {
+            crate::engine::payload::percent_decode(body).into_bytes()
+        };
+
+        Self::sanitize(&bytes)
+    }
+
+    /// Sanea un SVG: solo se conserva lo que usvg sabe representar y las imágenes
+    /// embebidas como data URL; nada se resuelve fuera del documento
+    pub fn sanitize(svg: &[u8]) -> QrResult<Self> {
+        if svg.len() > MAX_SVG_LOGO_BYTES {
+            return Err(QrError::LogoError(format!(
+                "El logo SVG excede {} bytes", MAX_SVG_LOGO_BYTES
+            )));
+        }
+
+        let tree = usvg::Tree::from_data(svg, &raster::restricted_options(), &FONT_DB)
+            .map_err(|e| QrError::LogoError(format!("SVG de logo inválido: {}", e)))?;
+
+        // Prefijo estable por contenido: la misma entrada produce el mismo SVG (cacheable)
+        let digest = Sha256::digest(svg);
+        let id_prefix = format!(
+            "logo-{}-",
+            digest[..4].iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        );
+        let markup = tree.to_string(&usvg::WriteOptions {
+            id_prefix: Some(id_prefix.clone()),
+            ..usvg::WriteOptions::default()
+        });
+
+        Ok(Self {
+            markup,
+            width: tree.size().width(),
+            height: tree.size().height(),
+            id_prefix,
+        })
+    }
+
+    /// Rasteriza el logo con resvg; `max_side` es el lado mayor resultante
+    pub fn rasterize(&self, max_side: u32) -> QrResult<DynamicImage> {
+        let width = if self.width >= self.height {
+            max_side
+        } else {
+            (max_side as f32 * self.width / self.height).round().max(1.0) as u32
+        };
+        let image = raster::render_svg(&self.markup, width)?;
+        RgbaImage::from_raw(image.width, image.height, image.pixels)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| QrError::InternalError("Buffer RGBA del logo incompleto".to_string()))
+    }
+
+    /// Elemento SVG que coloca el logo centrado en la caja indicada (equivalente a
+    /// `xMidYMid meet`) y recortado a la forma pedida
+    pub fn to_svg_element(&self, x: f32, y: f32, width: f32, height: f32, shape: LogoShape) -> String {
+        let scale = (width / self.width).min(height / self.height);
+        let offset_x = x + (width - self.width * scale) / 2.0;
+        let offset_y = y + (height - self.height * scale) / 2.0;
+        let placed = format!(
+            r#"<g transform="translate({:.2} {:.2}) scale({:.4})">{}</g>"#,
+            offset_x, offset_y, scale, self.markup
+        );
+
+        // El mismo logo puede aparecer varias veces: el recorte depende de la posición
+        let clip_id = format!("{}clip-{}-{}", self.id_prefix, x.round(), y.round());
+        let clip_shape = match shape {
+            LogoShape::Square => return placed,
+            LogoShape::Circle => format!(
+                r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}"/>"#,
+                x + width / 2.0, y + height / 2.0, width.min(height) / 2.0
+            ),
+            // Mismo radio que el recorte raster de `QrOptimizer::apply_logo_shape`
+            LogoShape::RoundedSquare => {
+                let radius = width.min(height) * 0.2;
+                format!(
+                    r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" rx="{:.2}" ry="{:.2}"/>"#,
+                    x, y, width, height, radius, radius
+                )
+            }
+        };
+
+        format!(
+            r#"<defs><clipPath id="{}">{}</clipPath></defs><g clip-path="url(#{})">{}</g>"#,
+            clip_id, clip_shape, clip_id, placed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTILE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="40" height="20" onload="alert(1)">
+        <script>alert('xss')</script>
+        <foreignObject width="40" height="20"><div xmlns="http://www.w3.org/1999/xhtml">hola</div></foreignObject>
+        <image href="https://evil.example/pixel.png" width="10" height="10"/>
+        <image xlink:href="/etc/passwd" width="10" height="10"/>
+        <use href="https://evil.example/sprite.svg#icon"/>
+        <a href="javascript:alert(1)"><rect id="mark" width="40" height="20" fill="#E11D48" onclick="steal()"/></a>
+    </svg>"##;
+
+    #[test]
+    fn test_sanitize_strips_active_content() {
+        let logo = VectorLogo::sanitize(HOSTILE_SVG.as_bytes()).unwrap();
+        let markup = logo.markup.to_lowercase();
+
+        for forbidden in ["script", "onload", "onclick", "foreignobject", "javascript:", "evil.example", "/etc/passwd", "alert"] {
+            assert!(!markup.contains(forbidden), "{} sobrevivió al saneado: {}", forbidden, logo.markup);
+        }
+        // El contenido vectorial se conserva y los IDs quedan con prefijo
+        assert!(markup.contains("#e11d48"));
+        assert!(logo.markup.contains(&format!("{}mark", logo.id_prefix)));
+        assert_eq!((logo.width, logo.height), (40.0, 20.0));
+
+        assert!(matches!(VectorLogo::sanitize(b"<html>no</html>"), Err(QrError::LogoError(_))));
+    }
+
+    #[test]
+    fn test_from_data_url() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><circle cx="5" cy="5" r="5" fill="#0F766E"/></svg>"##;
+        let encoded = format!("data:image/svg+xml;base64,{}", BASE64.encode(svg));
+        let percent = format!("data:image/svg+xml;charset=utf-8,{}", svg.replace('#', "%23").replace('<', "%3C"));
+
+        let from_base64 = VectorLogo::from_data_url(&encoded).unwrap();
+        let from_percent = VectorLogo::from_data_url(&percent).unwrap();
+        assert_eq!(from_base64.markup, from_percent.markup);
+        assert_eq!(from_base64.id_prefix, VectorLogo::from_data_url(&encoded).unwrap().id_prefix);
+
+        assert!(!VectorLogo::is_svg_data_url("data:image/png;base64,AAAA"));
+        assert!(VectorLogo::from_data_url("data:image/png;base64,AAAA").is_err());
+    }
+
+    #[test]
+    fn test_rasterize_and_clip() {
+        let logo = VectorLogo::sanitize(HOSTILE_SVG.as_bytes()).unwrap();
+
+        let image = logo.rasterize(100).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (100, 50));
+        assert_eq!(image.get_pixel(50, 25).0, [0xE1, 0x1D, 0x48, 255]);
+
+        let square = logo.to_svg_element(10.0, 10.0, 80.0, 80.0, LogoShape::Square);
+        assert!(!square.contains("clipPath"));
+        // Centrado vertical: 40x20 escalado x2 ocupa 80x40 dentro de la caja
+        assert!(square.contains("translate(10.00 30.00) scale(2.0000)"));
+
+        let circle = logo.to_svg_element(10.0, 10.0, 80.0, 80.0, LogoShape::Circle);
+        assert!(circle.contains(r#"<circle cx="50.00" cy="50.00" r="40.00"/>"#));
+        assert!(circle.contains(&format!(r#"clip-path="url(#{}clip-10-10)""#, logo.id_prefix)));
+        // Dos copias del mismo logo no comparten el recorte
+        let other = logo.to_svg_element(120.0, 10.0, 80.0, 80.0, LogoShape::Circle);
+        assert!(other.contains(&format!(r#"<clipPath id="{}clip-120-10">"#, logo.id_prefix)));
+    }
+}
@@ -14,7 +14,7 @@ mod tests {
             data: "https://qreable.com".to_string(),
             size: 400,
             format: OutputFormat::Svg,
-            customization: None,
+            ..Default::default()
         };
         
         let start = Instant::now();
@@ -34,6 +34,57 @@ mod tests {
         assert!(output.data.contains("<svg"));
     }
 
+    #[tokio::test]
+    async fn test_raster_output_formats() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let engine = QrEngine::new();
+
+        for format in [OutputFormat::Png, OutputFormat::Jpeg, OutputFormat::Webp] {
+            let request = QrRequest {
+                data: "https://qreable.com/raster".to_string(),
+                size: 300,
+                format,
+                output: Some(OutputOptions { dpi: Some(600), ..Default::default() }),
+                ..Default::default()
+            };
+
+            let output = engine.generate(request).await.unwrap();
+            assert_eq!(output.format, format);
+
+            // data en base64 y bytes crudos deben coincidir
+            let bytes = output.bytes.expect("los formatos raster incluyen bytes");
+            assert_eq!(BASE64.decode(&output.data).unwrap(), bytes);
+
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!(decoded.width(), 300);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vector_output_formats() {
+        let engine = QrEngine::new();
+
+        for (format, signature) in [(OutputFormat::Pdf, "%PDF"), (OutputFormat::Eps, "%!PS")] {
+            let request = QrRequest {
+                data: "https://qreable.com/print".to_string(),
+                size: 300,
+                format,
+                output: Some(OutputOptions {
+                    size_mm: Some(30.0),
+                    color_space: Some(ColorSpace::Cmyk),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let output = engine.generate(request).await.unwrap();
+            assert_eq!(output.format, format);
+            let bytes = output.bytes.unwrap();
+            assert!(bytes.starts_with(signature.as_bytes()));
+        }
+    }
+
     #[tokio::test]
     async fn test_medium_qr_with_customization() {
         let engine = QrEngine::new();
@@ -45,22 +96,16 @@ mod tests {
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
                 eye_shape: Some(EyeShape::RoundedSquare),
-                eye_border_style: None,
-                eye_center_style: None,
                 data_pattern: Some(DataPattern::Dots),
                 colors: Some(ColorOptions {
                     foreground: "#2563EB".to_string(),
                     background: "#FFFFFF".to_string(),
                     eye_colors: None,
                 }),
-                gradient: None,
-                logo: None,
-                frame: None,
-                effects: None,
                 error_correction: Some(ErrorCorrectionLevel::High),
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         let start = Instant::now();
@@ -79,6 +124,831 @@ mod tests {
         assert!(output.metadata.features_used.contains(&"custom_pattern".to_string()));
     }
 
+    fn ultra_request(performance: PerformanceConfig) -> QrRequest {
+        let shadow = EffectOptions {
+            effect_type: Effect::Shadow,
+            config: EffectConfiguration::Shadow {
+                offset_x: None,
+                offset_y: None,
+                blur_radius: None,
+                color: None,
+                opacity: None,
+            },
+        };
+        let glow = EffectOptions {
+            effect_type: Effect::Glow,
+            config: EffectConfiguration::Glow { intensity: None, color: None },
+        };
+        let distort = EffectOptions {
+            effect_type: Effect::Distort,
+            config: EffectConfiguration::Distort { strength: None, frequency: None, direction: None },
+        };
+        
+        QrRequest {
+            data: "https://qreable.com/campaign".to_string(),
+            size: 500,
+            format: OutputFormat::Svg,
+            customization: Some(QrCustomization {
+                frame: Some(FrameOptions {
+                    frame_type: FrameType::Speech,
+                    text: Some("ESCANEAME".to_string()),
+                    color: "#000000".to_string(),
+                    text_position: TextPosition::Bottom,
+                }),
+                effects: Some(vec![shadow, glow, distort]),
+                error_correction: Some(ErrorCorrectionLevel::High),
+                performance: Some(performance),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ultra_pipeline_budget() {
+        let engine = QrEngine::new();
+        
+        // Presupuesto generoso (cubre también las lecturas de verificación): se renderiza
+        // con todos los efectos y se verifica
+        let request = ultra_request(PerformanceConfig {
+            max_render_time_ms: Some(600_000),
+            ..Default::default()
+        });
+        assert_eq!(engine.router.determine_complexity(&request), ComplexityLevel::Ultra);
+        let output = engine.generate(request).await.unwrap();
+        assert_eq!(output.metadata.complexity_level, ComplexityLevel::Ultra);
+        assert!(output.metadata.features_used.contains(&"ultra_pipeline".to_string()));
+        assert!(output.metadata.verification.expect("ultra siempre verifica").passed);
+        
+        // Presupuesto agotado sin degradación: timeout
+        let result = engine.generate(ultra_request(PerformanceConfig {
+            max_render_time_ms: Some(0),
+            ..Default::default()
+        })).await;
+        assert!(matches!(result, Err(QrError::GenerationTimeout(_))));
+        
+        // Con degradación automática los efectos se eliminan del más costoso al menos costoso
+        let output = engine.generate(ultra_request(PerformanceConfig {
+            max_render_time_ms: Some(0),
+            auto_degrade_effects: Some(true),
+            ..Default::default()
+        })).await.unwrap();
+        let features = &output.metadata.features_used;
+        for effect in ["degraded_effect_distort", "degraded_effect_glow", "degraded_effect_shadow"] {
+            assert!(features.contains(&effect.to_string()), "{:?}", features);
+        }
+        assert!(!output.data.contains("<filter"));
+        // Sin nada más que degradar, el diseño mínimo se entrega marcado como fuera de plazo
+        assert!(features.contains(&"time_budget_exceeded".to_string()), "{:?}", features);
+        
+        // El presupuesto se mide desde el inicio de la solicitud, no de cada reintento
+        let already_spent = std::time::Instant::now() - std::time::Duration::from_millis(100);
+        let result = engine.generate_ultra(ultra_request(PerformanceConfig {
+            max_render_time_ms: Some(50),
+            ..Default::default()
+        }), already_spent).await;
+        assert!(matches!(result, Err(QrError::GenerationTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_ultra_prioritizes_scanability() {
+        let engine = QrEngine::new();
+        
+        let output = engine.generate(ultra_request(PerformanceConfig {
+            prioritize_scanability: Some(true),
+            ..Default::default()
+        })).await.unwrap();
+        let features = &output.metadata.features_used;
+        assert!(features.contains(&"degraded_effect_distort".to_string()));
+        assert!(!features.contains(&"degraded_effect_shadow".to_string()));
+    }
+
+    fn animated_request(format: OutputFormat) -> QrRequest {
+        QrRequest {
+            data: "https://qreable.com/social".to_string(),
+            size: 300,
+            format,
+            customization: Some(QrCustomization {
+                eye_shape: None,
+                eye_border_style: None,
+                eye_center_style: None,
+                data_pattern: None,
+                colors: None,
+                gradient: Some(GradientOptions {
+                    enabled: true,
+                    gradient_type: GradientType::Linear,
+                    colors: vec!["#1D4ED8".to_string(), "#111827".to_string()],
+                    angle: Some(45.0),
+                    apply_to_eyes: true,
+                    apply_to_data: true,
+                    per_module: false,
+                    stroke_style: None,
+                }),
+                eye_border_gradient: None,
+                eye_center_gradient: None,
+                logo: None,
+                overlays: None,
+                frame: None,
+                effects: None,
+                selective_effects: None,
+                error_correction: None,
+                logo_size_ratio: None,
+                fixed_size: None,
+                performance: None,
+                animation: Some(AnimationOptions {
+                    effects: vec![
+                        AnimationEffect::GradientRotation,
+                        AnimationEffect::EyePulse,
+                        AnimationEffect::ModuleReveal,
+                        AnimationEffect::GlowSweep,
+                    ],
+                    frame_count: Some(8),
+                    ..Default::default()
+                }),
+                symbology: None,
+                quiet_zone: None,
+                gs1_mode: None,
+                version: None,
+                mask: None,
+                mask_selection: None,
+                barcode: None,
+                matrix_code: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_animated_outputs() {
+        let engine = QrEngine::new();
+
+        let request = animated_request(OutputFormat::Svg);
+        assert_eq!(engine.router.determine_complexity(&request), ComplexityLevel::Ultra);
+        let output = engine.generate(request).await.unwrap();
+        assert!(output.data.contains("<animateTransform"));
+        assert!(output.data.contains("<animate "));
+        let features = &output.metadata.features_used;
+        assert!(features.contains(&"animated".to_string()));
+        assert!(features.contains(&"animation_frames_verified_8".to_string()), "{:?}", features);
+
+        let output = engine.generate(animated_request(OutputFormat::Gif)).await.unwrap();
+        assert_eq!(output.format, OutputFormat::Gif);
+        let bytes = output.bytes.expect("GIF incluye bytes");
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert!(output.metadata.features_used.contains(&"animated".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_structured_append_set() {
+        use crate::standards::decoder::QrDecoder;
+
+        let engine = QrEngine::new();
+        let decoder = QrDecoder::new();
+        let data: String = (0..150).map(|i| format!("PALLET-{:05}|", i)).collect();
+        let request = QrRequest {
+            data: data.clone(),
+            size: 400,
+            format: OutputFormat::Svg,
+            verification: Some(VerificationOptions::default()),
+            ..Default::default()
+        };
+        let mut options = StructuredAppendOptions {
+            max_version: Some(15),
+            ..Default::default()
+        };
+
+        // Archivos separados: cada símbolo se lee solo y el conjunto se reúne en cualquier orden
+        let set = engine.generate_set(request.clone(), &options).await.unwrap();
+        assert!(set.symbol_count > 1);
+        assert_eq!(set.outputs.len(), set.symbol_count);
+        assert!(set.outputs.iter().all(|o| o.metadata.verification.as_ref().is_some_and(|v| v.passed)));
+        let mut decoded: Vec<_> = set.outputs.iter().map(|o| decoder.decode_svg(&o.data).unwrap()).collect();
+        decoded.reverse();
+        assert_eq!(decoded[0].metadata.structured_append.unwrap().index as usize, set.symbol_count - 1);
+        assert_eq!(decoder.reassemble(&decoded).unwrap(), data);
+        assert!(decoder.reassemble(&decoded[1..]).is_err());
+        assert!(set.outputs.iter().all(|o| o.metadata.verification.as_ref().is_some_and(|v| v.attempts == 0)));
+
+        // Con puntos y ojos circulares el conjunto se corrige como un código suelto
+        let styled = QrRequest { data: data.clone(), ..verified_request("#1E293B", true) };
+        let set = engine.generate_set(styled.clone(), &options).await.unwrap();
+        for output in &set.outputs {
+            let verification = output.metadata.verification.as_ref().expect("se solicitó verificación");
+            assert!(verification.passed && verification.attempts > 0);
+            assert_eq!(verification.attempts as usize, verification.corrections.len());
+            assert!(output.metadata.features_used.contains(&"auto_corrected".to_string()));
+        }
+        let manual = QrRequest { verification: Some(VerificationOptions { auto_correct: false, max_attempts: None }), ..styled };
+        assert!(matches!(engine.generate_set(manual, &options).await, Err(QrError::ValidationError(_))));
+
+        // Hoja en mosaico: una sola imagen con todo el conjunto
+        options.layout = SymbolSetLayout::Sheet;
+        options.sheet_columns = Some(3);
+        let sheet = engine.generate_set(QrRequest { format: OutputFormat::Png, ..request }, &options).await.unwrap();
+        assert_eq!(sheet.outputs.len(), 1);
+        let image = image::load_from_memory(sheet.outputs[0].bytes.as_ref().unwrap()).unwrap();
+        assert_eq!(image.width(), 1200);
+        assert_eq!(decoder.decode_structured_append_image(&image).unwrap(), data);
+    }
+
+    fn verified_request(foreground: &str, auto_correct: bool) -> QrRequest {
+        QrRequest {
+            data: "https://qreable.com/verified".to_string(),
+            size: 400,
+            format: OutputFormat::Svg,
+            customization: Some(QrCustomization {
+                eye_border_style: Some(EyeBorderStyle::Circle),
+                data_pattern: Some(DataPattern::Dots),
+                colors: Some(ColorOptions {
+                    foreground: foreground.to_string(),
+                    background: "#FFFFFF".to_string(),
+                    eye_colors: None,
+                }),
+                ..Default::default()
+            }),
+            verification: Some(VerificationOptions { auto_correct, max_attempts: None }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_verification() {
+        let engine = QrEngine::new();
+
+        let output = engine.generate(verified_request("#1E293B", true)).await.unwrap();
+        let verification = output.metadata.verification.clone().expect("se solicitó verificación");
+        assert!(verification.passed);
+        assert!(verification.decode_score.unwrap() > 0.0);
+        assert!(output.metadata.features_used.contains(&"scan_verified".to_string()));
+
+        // Un acierto de cache devuelve la verificación y la puntuación originales
+        let entry = QrEngine::cache_entry(&output).unwrap();
+        assert_eq!(entry.metadata.error_correction, verification.decoded_error_correction);
+        let cached = QrEngine::cached_output(entry).unwrap();
+        assert_eq!(cached.data, output.data);
+        assert_eq!(cached.metadata.quality_score, output.metadata.quality_score);
+        let cached_verification = cached.metadata.verification.unwrap();
+        assert_eq!(cached_verification.corrections, verification.corrections);
+        assert_eq!(cached_verification.decode_score, verification.decode_score);
+        assert!(cached.metadata.features_used.contains(&"cached".to_string()));
+
+        // Sin contraste el código no se puede leer ni corregir
+        let result = engine.generate(verified_request("#FFFFFF", false)).await;
+        assert!(matches!(result, Err(QrError::ValidationError(_))));
+        let result = engine.generate(verified_request("#FFFFFF", true)).await;
+        assert!(matches!(result, Err(QrError::ValidationError(_))));
+    }
+
+    fn symbology_request(data: &str, symbology: QrSymbology) -> QrRequest {
+        let mut request = verified_request("#1E293B", true);
+        request.data = data.to_string();
+        if let Some(customization) = request.customization.as_mut() {
+            customization.symbology = Some(symbology);
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_micro_and_rmqr_generation() {
+        let engine = QrEngine::new();
+        let generator = QrGenerator::new();
+
+        // El diseño estilizado se verifica con el lector real, igual que un QR
+        for (data, symbology, feature) in [
+            ("VIAL-0042", QrSymbology::MicroQr, "micro_qr"),
+            ("https://qreable.com/lote/A1", QrSymbology::Rmqr, "rmqr"),
+        ] {
+            let output = engine.generate(symbology_request(data, symbology)).await.unwrap();
+            let verification = output.metadata.verification.expect("se solicitó verificación");
+            assert!(verification.passed, "{:?}", symbology);
+            assert!(verification.decode_score.unwrap() > 0.0);
+            assert!(output.metadata.features_used.contains(&feature.to_string()));
+
+            // Micro QR y rMQR tienen un único patrón de búsqueda estilizable
+            let qr = generator.generate_micro(data, 400, symbology, ErrorCorrectionLevel::Medium).unwrap();
+            assert_eq!(qr.quiet_zone, 2);
+            let eyes = qr.to_enhanced_data().paths.eyes;
+            assert!(!eyes.is_empty() && eyes.iter().all(|eye| eye.eye_type == "top_left"));
+        }
+
+        let rmqr = generator.generate_micro("https://qreable.com/lote/A1", 400, QrSymbology::Rmqr, ErrorCorrectionLevel::High).unwrap();
+        assert!(rmqr.width() > rmqr.height());
+        assert!(rmqr.get_version() >= 1);
+
+        // Sin margen de corrección para ocultar módulos bajo un logo
+        let mut request = symbology_request("VIAL-0042", QrSymbology::MicroQr);
+        request.customization.as_mut().unwrap().logo = Some(LogoOptions {
+            data: "base64...".to_string(),
+            logo_id: None,
+            size_percentage: 20.0,
+            padding: 5,
+            background: None,
+            shape: LogoShape::Circle,
+            placement: None,
+        });
+        assert!(matches!(engine.generate(request).await, Err(QrError::LogoError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_code_generation() {
+        let engine = QrEngine::new();
+
+        // Patrón de datos y colores sobre los datos; las zonas fijas siguen legibles
+        for (data, symbology, feature) in [
+            ("https://qreable.com/dm/lote/A1B2C3", QrSymbology::DataMatrix, "data_matrix"),
+            ("https://qreable.com/aztec", QrSymbology::Aztec, "aztec"),
+            ("https://qreable.com/pdf417", QrSymbology::Pdf417, "pdf417"),
+        ] {
+            let output = engine.generate(symbology_request(data, symbology)).await.unwrap();
+            let verification = output.metadata.verification.expect("se solicitó verificación");
+            assert!(verification.passed, "{:?}", symbology);
+            assert!(output.metadata.features_used.contains(&feature.to_string()));
+        }
+
+        // DMRE y Aztec compacto con capas fijas
+        let mut request = symbology_request("GTIN 09506000134352", QrSymbology::DataMatrix);
+        request.customization.as_mut().unwrap().matrix_code = Some(MatrixCodeOptions {
+            datamatrix_shape: Some(DataMatrixShape::Dmre),
+            ..Default::default()
+        });
+        let output = engine.generate(request).await.unwrap();
+        assert!(output.metadata.features_used.contains(&"dmre".to_string()));
+        assert!(output.metadata.verification.unwrap().passed);
+
+        let options = MatrixCodeOptions { aztec_compact: Some(true), aztec_layers: Some(3), ..Default::default() };
+        let aztec = QrGenerator::new()
+            .generate_matrix_symbol("AZTEC", 400, QrSymbology::Aztec, None, &options)
+            .unwrap();
+        assert_eq!((aztec.size, aztec.get_version(), aztec.quiet_zone), (23, 3, 0));
+        assert!(aztec.to_enhanced_data().paths.eyes.is_empty());
+
+        // Aztec no admite logo; DataMatrix sí mientras no tape sus patrones
+        use base64::Engine as _;
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(8, 8, image::Rgba([200, 30, 30, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let logo = LogoOptions {
+            data: format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png)),
+            logo_id: None,
+            size_percentage: 20.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Circle,
+            placement: None,
+        };
+        let mut request = symbology_request("https://qreable.com/aztec", QrSymbology::Aztec);
+        request.customization.as_mut().unwrap().logo = Some(logo.clone());
+        assert!(matches!(engine.generate(request).await, Err(QrError::LogoError(_))));
+
+        // 40x40 con cuatro regiones: el centro es el cruce de los patrones de alineación
+        let mut request = symbology_request(&"LOTE-A1B2C3".repeat(12), QrSymbology::DataMatrix);
+        request.customization.as_mut().unwrap().logo = Some(logo.clone());
+        assert!(matches!(
+            engine.generate(request).await,
+            Err(QrError::LogoError(message)) if message.contains("patrones fijos")
+        ));
+        let mut request = symbology_request("https://qreable.com/dm", QrSymbology::DataMatrix);
+        request.customization.as_mut().unwrap().logo = Some(logo);
+        assert!(engine.generate(request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_logo_from_store() {
+        let root = std::env::temp_dir().join(format!("qreable_logos_{}", uuid::Uuid::new_v4()));
+        let store = std::sync::Arc::new(crate::engine::LogoStore::open(&root).unwrap());
+        let engine = QrEngine::new().with_logo_store(store.clone());
+
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(400, 400, image::Rgba([200, 30, 30, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let record = store.put(&png).unwrap().record;
+
+        let mut logo = LogoOptions {
+            data: String::new(),
+            logo_id: Some(record.logo_id.clone()),
+            size_percentage: 20.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Circle,
+            placement: None,
+        };
+        let mut request = symbology_request("https://qreable.com/logo", QrSymbology::Qr);
+        request.customization.as_mut().unwrap().logo = Some(logo.clone());
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(output.data.contains("<image"));
+
+        // Un SVG de la biblioteca se incrusta como vector, igual que desde `data`
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M2 2h20v20H2z" fill="#E11D48"/></svg>"##;
+        let vector_id = store.put(svg.as_bytes()).unwrap().record.logo_id;
+        let mut vector_request = request.clone();
+        vector_request.customization.as_mut().unwrap().logo = Some(LogoOptions {
+            logo_id: Some(vector_id),
+            ..logo.clone()
+        });
+        let output = engine.generate(vector_request).await.unwrap();
+        assert!(!output.data.contains("<image"));
+        assert!(output.data.contains("<clipPath id=\"logo-"));
+
+        // La clave de cache depende de la referencia, no de los bytes del logo
+        let key = engine.generate_cache_key(&request);
+
+        logo.data = "data:image/png;base64,AAAA".to_string();
+        request.customization.as_mut().unwrap().logo = Some(logo.clone());
+        assert!(matches!(engine.generate(request.clone()).await, Err(QrError::LogoError(_))));
+
+        logo.data = String::new();
+        logo.logo_id = Some("0".repeat(64));
+        request.customization.as_mut().unwrap().logo = Some(logo);
+        assert_ne!(engine.generate_cache_key(&request), key);
+        assert!(matches!(engine.generate(request).await, Err(QrError::LogoError(_))));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_svg_logo_kept_as_vector() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let engine = QrEngine::new();
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" onload="alert(1)"><script>alert(1)</script><path d="M2 2h20v20H2z" fill="#E11D48"/></svg>"##;
+        let mut request = symbology_request("https://qreable.com/vector-logo", QrSymbology::Qr);
+        request.customization.as_mut().unwrap().logo = Some(LogoOptions {
+            data: format!("data:image/svg+xml;base64,{}", BASE64.encode(svg)),
+            logo_id: None,
+            size_percentage: 20.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Circle,
+            placement: None,
+        });
+
+        // El logo llega al SVG como paths recortados, sin PNG ni contenido activo
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(!output.data.contains("data:image/png"));
+        assert!(!output.data.contains("script") && !output.data.contains("onload"));
+        assert!(output.data.contains("#e11d48") || output.data.contains("#E11D48"));
+        assert!(output.data.contains("<clipPath id=\"logo-"));
+        assert!(output.metadata.verification.expect("se solicitó verificación").passed);
+
+        // Las salidas raster lo rasterizan con resvg
+        let png = QrRequest { format: OutputFormat::Png, ..request };
+        let bytes = engine.generate(png).await.unwrap().bytes.expect("bytes raster");
+        let image = image::load_from_memory(&bytes).unwrap().to_rgba8();
+        let center = image.get_pixel(image.width() / 2, image.height() / 2).0;
+        assert_eq!(&center[..3], &[0xE1, 0x1D, 0x48]);
+    }
+
+    #[tokio::test]
+    async fn test_logo_overlays() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let engine = QrEngine::new();
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(200, 200, image::Rgba([15, 118, 110, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let logo = |size_percentage, placement| LogoOptions {
+            data: format!("data:image/png;base64,{}", BASE64.encode(&png)),
+            logo_id: None,
+            size_percentage,
+            padding: 0,
+            background: None,
+            shape: LogoShape::Square,
+            placement,
+        };
+
+        // Logo centrado más una insignia en la esquina inferior derecha
+        let mut request = symbology_request("https://qreable.com/insignia", QrSymbology::Qr);
+        let customization = request.customization.as_mut().unwrap();
+        customization.error_correction = Some(ErrorCorrectionLevel::High);
+        customization.logo = Some(logo(18.0, None));
+        customization.overlays = Some(vec![logo(10.0, Some(LogoPlacement::Anchor(LogoAnchor::BottomRight)))]);
+
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert_eq!(output.data.matches("<image").count(), 2);
+        assert!(output.metadata.verification.expect("se solicitó verificación").passed);
+
+        // Solo overlays y sin ECL explícito: su oclusión sigue decidiendo el nivel
+        let mut badges = symbology_request("https://qreable.com/insignia", QrSymbology::Qr);
+        let badges_customization = badges.customization.as_mut().unwrap();
+        (badges_customization.data_pattern, badges_customization.eye_border_style) = (None, None);
+        badges_customization.overlays = Some(vec![
+            logo(20.0, Some(LogoPlacement::Anchor(LogoAnchor::BottomRight))),
+            logo(20.0, Some(LogoPlacement::Anchor(LogoAnchor::TopRight))),
+        ]);
+        let badges_customization = badges.customization.clone().unwrap();
+        let logos = engine.prepare_logos(&badges_customization, badges.size).unwrap();
+        let optimizer = ecl_optimizer::EclOptimizer::for_customization(&badges_customization, &logos);
+        assert_eq!(optimizer.logo_size_ratio(), 0.0);
+        let (ecl, analysis) = optimizer.determine_optimal_ecl(&badges.data, optimizer.logo_size_ratio(), None).unwrap();
+        assert!(analysis.occluded_modules > 0 && ecl > ErrorCorrectionLevel::Low);
+        let output = engine.generate(badges).await.unwrap();
+        assert!(output.metadata.verification.expect("se solicitó verificación").passed);
+
+        // Coordenadas explícitas sobre el finder superior izquierdo
+        request.customization.as_mut().unwrap().overlays =
+            Some(vec![logo(10.0, Some(LogoPlacement::Modules { x: 0, y: 0 }))]);
+        assert!(matches!(engine.generate(request).await, Err(QrError::LogoError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_logo_selects_error_correction() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let engine = QrEngine::new();
+        let data = "LOTE-2026-0042/caja-17";
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(200, 200, image::Rgba([15, 118, 110, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        // Sin estilos de módulos: solo el logo tapa datos
+        let mut request = symbology_request(data, QrSymbology::Qr);
+        let customization = request.customization.as_mut().unwrap();
+        (customization.data_pattern, customization.eye_border_style) = (None, None);
+        customization.logo = Some(LogoOptions {
+            data: format!("data:image/png;base64,{}", BASE64.encode(&png)),
+            logo_id: None,
+            size_percentage: 15.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Square,
+            placement: None,
+        });
+
+        // Sin ECL explícito decide el análisis de bloques sobre el símbolo real
+        let customization = request.customization.clone().unwrap();
+        let logos = engine.prepare_logos(&customization, request.size).unwrap();
+        let (ecl, analysis) = ecl_optimizer::EclOptimizer::for_customization(&customization, &logos)
+            .determine_optimal_ecl(data, 0.15, None)
+            .unwrap();
+        assert!(analysis.recoverable);
+        assert!(ecl > ErrorCorrectionLevel::Low);
+
+        let output = engine.generate(request.clone()).await.unwrap();
+        let verification = output.metadata.verification.expect("se solicitó verificación");
+        assert!(verification.passed && verification.corrections.is_empty());
+        assert!(output.data.contains("<image"));
+        // El símbolo es el que produce el generador con ese nivel (y su boost)
+        let reference = QrGenerator::new().generate_with_ecl(data, request.size, ecl).unwrap();
+        let expected = crate::standards::decoder::QrDecoder::new().decode_svg(&reference.to_svg()).unwrap();
+        assert_eq!(verification.decoded_error_correction, Some(expected.metadata.error_correction));
+
+        // Un ECL explícito se respeta
+        request.customization.as_mut().unwrap().error_correction = Some(ErrorCorrectionLevel::High);
+        let output = engine.generate(request).await.unwrap();
+        assert_eq!(output.metadata.verification.unwrap().decoded_error_correction.as_deref(), Some("H"));
+    }
+
+    #[test]
+    fn test_logo_clears_only_its_silhouette() {
+        // Disco opaco sobre fondo transparente: las esquinas del área conservan sus módulos
+        let disc = image::RgbaImage::from_fn(200, 200, |x, y| {
+            let (dx, dy) = (x as f32 - 99.5, y as f32 - 99.5);
+            image::Rgba([15, 118, 110, if dx * dx + dy * dy <= 100.0 * 100.0 { 255 } else { 0 }])
+        });
+        let options = LogoOptions {
+            data: String::new(),
+            logo_id: None,
+            size_percentage: 30.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Circle,
+            placement: None,
+        };
+        let mut matrix = vec![vec![true; 41]; 41];
+        let prepared = PreparedLogo { image: image::DynamicImage::ImageRgba8(disc), vector: None };
+        let result = QrCustomizer::new().integrate_logo(&mut matrix, &options, Some(prepared), &[]).unwrap();
+
+        let area = &result.logo_area;
+        let cleared = matrix.iter().flatten().filter(|dark| !**dark).count();
+        assert!(cleared > 0 && cleared < area.width * area.height);
+        assert!(matrix[area.y][area.x] && matrix[area.y + area.height - 1][area.x + area.width - 1]);
+        assert!(!matrix[area.y + area.height / 2][area.x + area.width / 2]);
+        // Nada fuera del área se toca
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, dark) in row.iter().enumerate() {
+                let inside = (area.x..area.x + area.width).contains(&x) && (area.y..area.y + area.height).contains(&y);
+                assert!(*dark || inside);
+                assert_eq!(!*dark, result.logo_zone.contains_module(x as u16, y as u16));
+            }
+        }
+        assert!((result.capacity_loss_percentage - cleared as f32 / (41.0 * 41.0) * 100.0).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_version_and_mask() {
+        let engine = QrEngine::new();
+        let decoder = crate::standards::decoder::QrDecoder::new();
+        let data = "https://qreable.com/version";
+
+        let mut request = symbology_request(data, QrSymbology::Qr);
+        let customization = request.customization.as_mut().unwrap();
+        customization.version = Some(7);
+        customization.mask = Some(5);
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(output.metadata.verification.unwrap().passed);
+        assert!(output.metadata.features_used.contains(&"version_7".to_string()));
+        let decoded = decoder.decode_svg(&output.data).unwrap();
+        assert_eq!(decoded.raw_data, data);
+        assert_eq!((decoded.metadata.qr_version, decoded.metadata.mask_pattern), (Some(7), Some(5)));
+
+        // Un ECL explícito se respeta aunque la versión fija deje espacio para reforzarlo
+        let mut low_request = request.clone();
+        let customization = low_request.customization.as_mut().unwrap();
+        customization.error_correction = Some(ErrorCorrectionLevel::Low);
+        customization.data_pattern = None;
+        customization.eye_border_style = None;
+        low_request.verification = None;
+        let output = engine.generate(low_request).await.unwrap();
+        let decoded = decoder.decode_svg(&output.data).unwrap();
+        assert_eq!(decoded.metadata.error_correction, "L");
+
+        // Valores fuera de rango, versiones donde no caben los datos y combinaciones incompatibles
+        for (version, mask, selection, fixed_size) in [
+            (Some(41), None, None, None),
+            (None, Some(8), None, None),
+            (Some(1), None, None, None),
+            (Some(7), None, None, Some(QrSize::Small)),
+            (None, Some(2), Some(MaskSelection::Design), None),
+        ] {
+            let customization = request.customization.as_mut().unwrap();
+            customization.error_correction = Some(ErrorCorrectionLevel::High);
+            (customization.version, customization.mask, customization.mask_selection, customization.fixed_size) =
+                (version, mask, selection, fixed_size);
+            let result = engine.generate(request.clone()).await;
+            assert!(matches!(result, Err(QrError::ValidationError(_))), "{:?} {:?} {:?}", version, mask, selection);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_design_mask_selection() {
+        let engine = QrEngine::new();
+        let data = "https://qreable.com/mascara-de-diseno";
+        let logo = LogoOptions {
+            data: "data:image/svg+xml;utf8,<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 4 4'><rect width='4' height='4' fill='%230F766E'/></svg>".to_string(),
+            logo_id: None,
+            size_percentage: 22.0,
+            padding: 1,
+            background: None,
+            shape: LogoShape::Square,
+            placement: None,
+        };
+
+        let mut request = symbology_request(data, QrSymbology::Qr);
+        let customization = request.customization.as_mut().unwrap();
+        customization.error_correction = Some(ErrorCorrectionLevel::High);
+        customization.logo = Some(logo);
+        customization.mask_selection = Some(MaskSelection::Design);
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(output.metadata.verification.unwrap().passed);
+        assert!(output.metadata.features_used.contains(&"design_mask".to_string()));
+
+        // La máscara elegida no deja más módulos bajo el logo ni más motas que la estándar
+        let customization = request.customization.as_ref().unwrap();
+        let selector = DesignMaskSelector::for_customization(customization);
+        let generator = QrGenerator::new();
+        let generate = |mask| {
+            generator.generate_with_version_and_mask(data, 400, customization.error_correction, None, None, mask).unwrap()
+        };
+        let standard = generate(MaskChoice::Standard);
+        let design = generate(MaskChoice::Design(&selector));
+        let areas = selector.logo_areas(design.size).unwrap();
+        let standard_score = selector.score(0, &standard.matrix, &areas);
+        let design_score = selector.score(0, &design.matrix, &areas);
+        assert!(design_score.design_score <= standard_score.design_score);
+        assert!(design_score.penalty as f32 <= standard_score.penalty as f32 * 1.15);
+    }
+
+    #[tokio::test]
+    async fn test_structured_payload() {
+        let engine = QrEngine::new();
+        let payload: QrPayload = serde_json::from_str(
+            r#"{"type": "wifi", "ssid": "Café;Bar", "password": "clave:segura", "hidden": true}"#,
+        )
+        .unwrap();
+        let mut request = verified_request("#1E293B", true);
+        request.data = String::new();
+        request.payload = Some(payload.clone());
+
+        // El lector devuelve la cadena escapada, idéntica a la construida
+        let output = engine.generate(request.clone()).await.unwrap();
+        let decoded = crate::standards::decoder::QrDecoder::new().decode_svg(&output.data).unwrap();
+        assert_eq!(decoded.raw_data, r#"WIFI:T:WPA;S:Café\;Bar;P:clave\:segura;H:true;;"#);
+        assert_eq!(decoded.content_type, crate::standards::decoder::ContentType::Wifi);
+
+        request.data = "https://qreable.com".to_string();
+        assert!(matches!(engine.generate(request).await, Err(QrError::PayloadError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_gs1_generation() {
+        let engine = QrEngine::new();
+        let mut request = verified_request("#1E293B", true);
+        request.data = "(01)01234567890128(10)ABC123(17)251231".to_string();
+        request.customization.as_mut().unwrap().gs1_mode = Some(true);
+
+        // El lector recibe el símbolo marcado como GS1, con GS tras el lote de longitud variable
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(output.metadata.verification.unwrap().passed);
+        assert!(output.metadata.features_used.contains(&"gs1".to_string()));
+        let decoded = crate::standards::decoder::QrDecoder::new().decode_svg(&output.data).unwrap();
+        assert!(decoded.metadata.gs1_mode);
+        assert_eq!(decoded.raw_data, "010123456789012810ABC123\u{1D}17251231");
+        assert_eq!(decoded.content_type, crate::standards::decoder::ContentType::Gs1);
+        assert_eq!(decoded.gs1_elements.unwrap().len(), 3);
+
+        // El generador marca el símbolo y el validador lo comprueba
+        let qr = QrGenerator::new().generate_gs1(&request.data, 400, None, None).unwrap();
+        assert!(qr.gs1_mode);
+        let validation = crate::standards::validator::StandardValidator::new()
+            .validate(&qr, crate::standards::validator::ValidationProfile::Retail, &request.data)
+            .unwrap();
+        assert_eq!(validation.compliance.get("gs1_mode"), Some(&true));
+
+        // Datos que no son GS1 válidos y simbologías sin modo FNC1
+        request.data = "(10)ABC123".to_string();
+        assert!(engine.generate(request.clone()).await.is_err());
+        request.data = "(01)01234567890128".to_string();
+        request.customization.as_mut().unwrap().symbology = Some(QrSymbology::MicroQr);
+        assert!(matches!(engine.generate(request).await, Err(QrError::ValidationError(_))));
+    }
+
+    fn svg_width(svg: &str) -> f32 {
+        let tree = crate::engine::raster::parse_svg(svg).unwrap();
+        tree.size().width()
+    }
+
+    #[tokio::test]
+    async fn test_request_quiet_zone() {
+        let engine = QrEngine::new();
+        let request_with = |quiet_zone: Option<usize>| {
+            let mut request = verified_request("#1E293B", false);
+            request.verification = None;
+            request.customization.as_mut().unwrap().quiet_zone = quiet_zone;
+            request
+        };
+
+        // Cada módulo de margen añade lo mismo a ambos lados
+        let embedded = svg_width(&engine.generate(request_with(Some(0))).await.unwrap().data);
+        let standard = svg_width(&engine.generate(request_with(None)).await.unwrap().data);
+        let print = svg_width(&engine.generate(request_with(Some(8))).await.unwrap().data);
+        assert!(embedded < standard && standard < print);
+        assert_eq!(print - standard, standard - embedded);
+
+        // Sin margen propio el código se lee sobre el fondo claro que lo rodea: la falta
+        // de zona de silencio es una advertencia y no añade correcciones
+        let verify = |quiet_zone: Option<usize>| {
+            let mut request = request_with(quiet_zone);
+            request.verification = Some(VerificationOptions::default());
+            let engine = &engine;
+            async move { engine.generate(request).await.unwrap().metadata.verification.unwrap() }
+        };
+        let with_margin = verify(None).await;
+        let without_margin = verify(Some(0)).await;
+        assert!(with_margin.passed && without_margin.passed);
+        assert_eq!(without_margin.corrections, with_margin.corrections);
+        assert!(with_margin.warnings.is_empty());
+        assert!(without_margin.warnings.iter().any(|w| w.code == "QZ002"));
+
+        assert!(matches!(
+            engine.generate(request_with(Some(100))).await,
+            Err(QrError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_linear_barcode_generation() {
+        let engine = QrEngine::new();
+        let mut request = verified_request("#1E293B", false);
+        request.data = "978020137962".to_string();
+        request.verification = Some(VerificationOptions::default());
+        request.customization.as_mut().unwrap().barcode = Some(LinearOptions {
+            symbology: LinearSymbology::Ean13,
+            add_on: Some("90000".to_string()),
+            ..Default::default()
+        });
+
+        // Dígito de control calculado, add-on legible y colores de la personalización
+        let output = engine.generate(request.clone()).await.unwrap();
+        assert!(output.metadata.verification.unwrap().passed);
+        assert!(output.data.contains(">9780201379624</text>"));
+        assert!(output.data.contains(">90000</text>"));
+        assert!(output.data.contains("#1E293B"));
+        for feature in ["linear_barcode", "ean13", "add_on", "human_readable_text"] {
+            assert!(output.metadata.features_used.contains(&feature.to_string()), "{}", feature);
+        }
+
+        let png = QrRequest { format: OutputFormat::Png, ..request.clone() };
+        assert!(engine.generate(png).await.unwrap().bytes.is_some());
+        let gif = QrRequest { format: OutputFormat::Gif, ..request.clone() };
+        assert!(matches!(engine.generate(gif).await, Err(QrError::UnsupportedFormat(_))));
+
+        request.data = "9780201379620".to_string();
+        assert!(matches!(engine.generate(request).await, Err(QrError::ValidationError(_))));
+    }
+
     #[test]
     fn test_complexity_routing() {
         let router = ComplexityRouter::new();
@@ -88,7 +958,7 @@ mod tests {
             data: "test".to_string(),
             size: 400,
             format: OutputFormat::Svg,
-            customization: None,
+            ..Default::default()
         };
         assert_eq!(router.determine_complexity(&basic_request), ComplexityLevel::Basic);
         
@@ -98,25 +968,18 @@ mod tests {
             size: 400,
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
-                eye_shape: None,
-                eye_border_style: None,
-                eye_center_style: None,
-                data_pattern: None,
-                colors: None,
-                gradient: None,
                 logo: Some(LogoOptions {
                     data: "base64...".to_string(),
+                    logo_id: None,
                     size_percentage: 20.0,
                     padding: 5,
                     background: None,
                     shape: LogoShape::Circle,
+                    placement: None,
                 }),
-                frame: None,
-                effects: None,
-                error_correction: None,
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         assert_eq!(router.determine_complexity(&advanced_request), ComplexityLevel::Advanced);
     }
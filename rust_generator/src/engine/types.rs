@@ -3,10 +3,16 @@
 use serde::{Deserialize, Serialize};
 use image::DynamicImage;
 
+use super::payload::QrPayload;
+
+/// Tamaño en píxeles cuando el llamador no indica uno
+pub const DEFAULT_QR_SIZE: u32 = 300;
+
 /// Solicitud principal de generación QR
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrRequest {
-    /// Datos a codificar
+    /// Datos a codificar (vacío si se envía `payload`)
+    #[serde(default)]
     pub data: String,
     
     /// Tamaño en píxeles
@@ -17,6 +23,32 @@ pub struct QrRequest {
     
     /// Opciones de personalización
     pub customization: Option<QrCustomization>,
+
+    /// Opciones de salida (DPI, calidad) para formatos no vectoriales
+    #[serde(default)]
+    pub output: Option<OutputOptions>,
+
+    /// Verificación de escaneo del resultado (decodificación real del SVG renderizado)
+    #[serde(default)]
+    pub verification: Option<VerificationOptions>,
+
+    /// Contenido estructurado (WiFi, vCard, evento...) que sustituye a `data`
+    #[serde(default)]
+    pub payload: Option<QrPayload>,
+}
+
+impl Default for QrRequest {
+    fn default() -> Self {
+        Self {
+            data: String::new(),
+            size: DEFAULT_QR_SIZE,
+            format: OutputFormat::default(),
+            customization: None,
+            output: None,
+            verification: None,
+            payload: None,
+        }
+    }
 }
 
 /// Tamaño fijo para QR codes
@@ -60,7 +92,7 @@ impl QrSize {
 }
 
 /// Opciones de personalización
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QrCustomization {
     /// Forma de los ojos (LEGACY - usar eye_border_style y eye_center_style)
     pub eye_shape: Option<EyeShape>,
@@ -89,6 +121,10 @@ pub struct QrCustomization {
     /// Logo
     pub logo: Option<LogoOptions>,
     
+    /// Logos adicionales (sellos, insignias) superpuestos junto al principal
+    #[serde(default)]
+    pub overlays: Option<Vec<LogoOptions>>,
+    
     /// Marco
     pub frame: Option<FrameOptions>,
     
@@ -106,6 +142,53 @@ pub struct QrCustomization {
     
     /// Tamaño fijo del QR (para batch uniforme)
     pub fixed_size: Option<QrSize>,
+    
+    /// Presupuesto de tiempo y degradación de efectos (pipeline ultra)
+    #[serde(default)]
+    pub performance: Option<PerformanceConfig>,
+    
+    /// Animación para salida GIF o SVG animado
+    #[serde(default)]
+    pub animation: Option<AnimationOptions>,
+    
+    /// Simbología del código (QR, Micro QR, rMQR, Data Matrix, Aztec o PDF417)
+    #[serde(default)]
+    pub symbology: Option<QrSymbology>,
+    
+    /// Zona de silencio en módulos (0 para incrustar, más de 4 para impresión)
+    #[serde(default)]
+    pub quiet_zone: Option<usize>,
+    
+    /// Generar como símbolo GS1 (FNC1 en primera posición)
+    #[serde(default)]
+    pub gs1_mode: Option<bool>,
+    
+    /// Versión QR explícita (1-40); sin ella, la menor en la que caben los datos
+    #[serde(default)]
+    pub version: Option<u8>,
+    
+    /// Máscara explícita (0-7); sin ella se elige según `mask_selection`
+    #[serde(default)]
+    pub mask: Option<u8>,
+    
+    /// Criterio para elegir la máscara cuando no se fija una
+    #[serde(default)]
+    pub mask_selection: Option<MaskSelection>,
+    
+    /// Código de barras lineal (1D) en lugar de un símbolo QR
+    #[serde(default)]
+    pub barcode: Option<LinearOptions>,
+    
+    /// Opciones propias de DataMatrix, Aztec y PDF417 (forma, capas, columnas...)
+    #[serde(default)]
+    pub matrix_code: Option<MatrixCodeOptions>,
+}
+
+impl QrCustomization {
+    /// Logo principal seguido de los overlays, en orden de colocación
+    pub fn logos(&self) -> impl Iterator<Item = &LogoOptions> {
+        self.logo.iter().chain(self.overlays.iter().flatten())
+    }
 }
 
 /// Formas de ojos disponibles (LEGACY - mantener para compatibilidad)
@@ -290,11 +373,43 @@ pub enum GradientType {
 /// Opciones de logo
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogoOptions {
+    #[serde(default)]
     pub data: String,  // Base64 o URL
+    /// Logo subido a la biblioteca (`LogoStore`) en lugar de `data`
+    #[serde(default)]
+    pub logo_id: Option<String>,
     pub size_percentage: f32,  // 10-30%
     pub padding: u32,
     pub background: Option<String>,  // Hex color
     pub shape: LogoShape,
+    /// Posición del área del logo; sin ella se centra
+    #[serde(default)]
+    pub placement: Option<LogoPlacement>,
+}
+
+/// Colocación de un logo: un ancla con nombre o la esquina superior izquierda
+/// del área (padding incluido) en módulos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum LogoPlacement {
+    Anchor(LogoAnchor),
+    Modules { x: usize, y: usize },
+}
+
+/// Anclas con nombre; el logo se coloca en la posición libre más cercana al ancla
+/// que no tape patrones de función
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoAnchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -517,7 +632,7 @@ pub struct CompatibilityRules {
 }
 
 /// Configuración de performance para efectos
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PerformanceConfig {
     /// Límite de tiempo de renderizado en ms
     pub max_render_time_ms: Option<u32>,
@@ -532,6 +647,60 @@ pub struct PerformanceConfig {
     pub auto_degrade_effects: Option<bool>,
 }
 
+/// Opciones de Structured Append: un mensaje repartido en varios símbolos enlazados
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StructuredAppendOptions {
+    /// Máximo de símbolos del conjunto, 1-16 (default: 16)
+    pub max_symbols: Option<u8>,
+    /// Versión máxima de cada símbolo, 1-40 (default: 40)
+    pub max_version: Option<u8>,
+    /// Entrega del conjunto (default: un archivo por símbolo)
+    #[serde(default)]
+    pub layout: SymbolSetLayout,
+    /// Columnas de la hoja en mosaico (default: raíz cuadrada del número de símbolos)
+    pub sheet_columns: Option<u32>,
+}
+
+/// Entrega de un conjunto de símbolos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolSetLayout {
+    /// Un archivo por símbolo, en orden de secuencia
+    #[default]
+    Separate,
+    /// Todos los símbolos en una hoja en mosaico
+    Sheet,
+}
+
+/// Opciones de animación del código
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AnimationOptions {
+    /// Efectos animados, combinados en cada fotograma
+    pub effects: Vec<AnimationEffect>,
+    /// Duración de un ciclo en ms (default: 2000)
+    pub duration_ms: Option<u32>,
+    /// Fotogramas por ciclo del GIF y de la verificación (default: 12)
+    pub frame_count: Option<u32>,
+    /// Intensidad 0.0-1.0 de los efectos que alteran el contraste (default: 1.0)
+    pub intensity: Option<f32>,
+    /// Repeticiones del GIF, 0 = infinito (default: 0)
+    pub loop_count: Option<u16>,
+}
+
+/// Efectos de animación disponibles
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationEffect {
+    /// Rotación de los gradientes del diseño
+    GradientRotation,
+    /// Pulso de brillo sobre los tres ojos
+    EyePulse,
+    /// Barrido que revela los módulos de izquierda a derecha
+    ModuleReveal,
+    /// Franja de brillo que recorre el código en diagonal
+    GlowSweep,
+}
+
 /// Nivel de corrección de errores
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ErrorCorrectionLevel {
@@ -541,33 +710,393 @@ pub enum ErrorCorrectionLevel {
     High,    // 30%
 }
 
+/// Criterio de selección de máscara QR
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskSelection {
+    /// Menor penalización de ISO/IEC 18004
+    #[default]
+    Standard,
+    /// Mejor resultado para el patrón de datos y los logos entre las máscaras
+    /// cuya penalización no excede el techo permitido
+    Design,
+}
+
+/// Simbología del código generado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QrSymbology {
+    /// QR Code modelo 2 (versiones 1-40)
+    #[default]
+    Qr,
+    /// Micro QR (M1-M4): un único patrón de búsqueda
+    MicroQr,
+    /// Rectangular Micro QR (ISO/IEC 23941): patrón de búsqueda y subpatrón en esquinas opuestas
+    Rmqr,
+    /// Data Matrix ECC 200 (ISO/IEC 16022), cuadrado, rectangular o DMRE (ISO/IEC 21471)
+    DataMatrix,
+    /// Aztec (ISO/IEC 24778), compacto o completo
+    Aztec,
+    /// PDF417 (ISO/IEC 15438), completo o compacto (truncado)
+    Pdf417,
+}
+
+impl QrSymbology {
+    /// Zona silenciosa mínima exigida por la norma (módulos)
+    pub fn min_quiet_zone(&self) -> usize {
+        match self {
+            QrSymbology::Qr => 4,
+            QrSymbology::MicroQr | QrSymbology::Rmqr | QrSymbology::Pdf417 => 2,
+            QrSymbology::DataMatrix => 1,
+            // El patrón central de Aztec no necesita margen
+            QrSymbology::Aztec => 0,
+        }
+    }
+    
+    /// Ancho mínimo en módulos (QR versión 1, M1, rMQR R11x27, Data Matrix 10x10,
+    /// Aztec compacto de una capa y PDF417 compacto de una columna)
+    pub fn min_size(&self) -> usize {
+        match self {
+            QrSymbology::Qr => 21,
+            QrSymbology::MicroQr => 11,
+            QrSymbology::Rmqr => 27,
+            QrSymbology::DataMatrix => 10,
+            QrSymbology::Aztec => 15,
+            QrSymbology::Pdf417 => 52,
+        }
+    }
+    
+    /// Nivel de corrección más alto disponible (Micro QR llega a Q en M4). Data Matrix
+    /// tiene corrección fija por tamaño; en Aztec y PDF417 se traduce a porcentaje y nivel.
+    pub fn max_error_correction(&self) -> ErrorCorrectionLevel {
+        match self {
+            QrSymbology::MicroQr => ErrorCorrectionLevel::Quartile,
+            _ => ErrorCorrectionLevel::High,
+        }
+    }
+    
+    /// Simbologías de la familia QR (QR, Micro QR y rMQR)
+    pub fn is_qr_family(&self) -> bool {
+        matches!(self, QrSymbology::Qr | QrSymbology::MicroQr | QrSymbology::Rmqr)
+    }
+    
+    /// Reconoce los nombres de tipo de la API legacy para las simbologías 2D
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect();
+        match normalized.as_str() {
+            "qr" | "qrcode" => Some(QrSymbology::Qr),
+            "microqr" => Some(QrSymbology::MicroQr),
+            "rmqr" => Some(QrSymbology::Rmqr),
+            "datamatrix" => Some(QrSymbology::DataMatrix),
+            "aztec" => Some(QrSymbology::Aztec),
+            "pdf417" => Some(QrSymbology::Pdf417),
+            _ => None,
+        }
+    }
+}
+
+/// Forma del símbolo Data Matrix
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DataMatrixShape {
+    /// Cuadrado de 10x10 a 144x144
+    #[default]
+    Square,
+    /// Rectangular de ISO/IEC 16022 (8x18 a 16x48)
+    Rectangle,
+    /// Rectangular incluyendo los tamaños extendidos DMRE (8x48 a 26x64)
+    Dmre,
+}
+
+/// Opciones de codificación de DataMatrix, Aztec y PDF417. Las que no corresponden a la
+/// simbología del símbolo se ignoran.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatrixCodeOptions {
+    /// Forma Data Matrix (por defecto cuadrada)
+    #[serde(default)]
+    pub datamatrix_shape: Option<DataMatrixShape>,
+    
+    /// Aztec compacto (1-4 capas) o completo (1-32 capas); por defecto el más pequeño
+    #[serde(default)]
+    pub aztec_compact: Option<bool>,
+    
+    /// Número de capas Aztec (por defecto el mínimo que admite los datos)
+    #[serde(default)]
+    pub aztec_layers: Option<u8>,
+    
+    /// Porcentaje mínimo de palabras de corrección Aztec (por defecto según `error_correction`)
+    #[serde(default)]
+    pub aztec_ec_percent: Option<u8>,
+    
+    /// Columnas de datos PDF417 (1-30); fija ambos límites
+    #[serde(default)]
+    pub pdf417_columns: Option<u8>,
+    
+    /// Mínimo de columnas de datos PDF417 cuando no se fija `pdf417_columns` (por defecto 1)
+    #[serde(default)]
+    pub pdf417_min_columns: Option<u8>,
+    
+    /// Máximo de columnas de datos PDF417 cuando no se fija `pdf417_columns` (por defecto 30)
+    #[serde(default)]
+    pub pdf417_max_columns: Option<u8>,
+    
+    /// Filas PDF417 (3-90)
+    #[serde(default)]
+    pub pdf417_rows: Option<u8>,
+    
+    /// Nivel de corrección PDF417 (0-8; por defecto según `error_correction`)
+    #[serde(default)]
+    pub pdf417_ecc_level: Option<u8>,
+    
+    /// PDF417 compacto (truncado): sin indicador derecho y con barra de parada de un módulo
+    #[serde(default)]
+    pub pdf417_compact: Option<bool>,
+}
+
+/// Simbología de código de barras lineal (1D)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LinearSymbology {
+    #[default]
+    Code128,
+    Ean8,
+    Ean13,
+    UpcA,
+    UpcE,
+    /// Interleaved 2 of 5 de longitud par
+    Itf,
+    /// ITF-14 (GTIN-14 sobre Interleaved 2 of 5)
+    Itf14,
+    Code39,
+    Code93,
+    Codabar,
+}
+
+impl LinearSymbology {
+    /// Reconoce los nombres de tipo de la API legacy ("ean-13", "upc_a", "code 128"...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect();
+        match normalized.as_str() {
+            "code128" => Some(LinearSymbology::Code128),
+            "ean8" => Some(LinearSymbology::Ean8),
+            "ean13" => Some(LinearSymbology::Ean13),
+            "upca" => Some(LinearSymbology::UpcA),
+            "upce" => Some(LinearSymbology::UpcE),
+            "itf" => Some(LinearSymbology::Itf),
+            "itf14" => Some(LinearSymbology::Itf14),
+            "code39" => Some(LinearSymbology::Code39),
+            "code93" => Some(LinearSymbology::Code93),
+            "codabar" => Some(LinearSymbology::Codabar),
+            _ => None,
+        }
+    }
+    
+    /// Nombre canónico (también usado en métricas y features)
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinearSymbology::Code128 => "code128",
+            LinearSymbology::Ean8 => "ean8",
+            LinearSymbology::Ean13 => "ean13",
+            LinearSymbology::UpcA => "upca",
+            LinearSymbology::UpcE => "upce",
+            LinearSymbology::Itf => "itf",
+            LinearSymbology::Itf14 => "itf14",
+            LinearSymbology::Code39 => "code39",
+            LinearSymbology::Code93 => "code93",
+            LinearSymbology::Codabar => "codabar",
+        }
+    }
+    
+    /// Familia EAN/UPC: guardas prolongadas, texto por mitades y add-ons
+    pub fn is_ean_upc(&self) -> bool {
+        matches!(
+            self,
+            LinearSymbology::Ean8 | LinearSymbology::Ean13 | LinearSymbology::UpcA | LinearSymbology::UpcE
+        )
+    }
+    
+    /// Zona silenciosa mínima por lado (módulos); en EAN/UPC, la izquierda, que es la mayor
+    pub fn min_quiet_zone(&self) -> usize {
+        match self {
+            LinearSymbology::Ean13 => 11,
+            LinearSymbology::UpcA | LinearSymbology::UpcE => 9,
+            LinearSymbology::Ean8 => 7,
+            _ => 10,
+        }
+    }
+    
+    /// Alto nominal de las barras en módulos (EAN-13: 22.85 mm con X = 0.33 mm)
+    pub fn default_bar_height(&self) -> f32 {
+        match self {
+            LinearSymbology::Ean13 | LinearSymbology::UpcA | LinearSymbology::UpcE => 69.0,
+            LinearSymbology::Ean8 => 55.0,
+            LinearSymbology::Itf14 => 32.0,
+            _ => 50.0,
+        }
+    }
+}
+
+/// Opciones de un código de barras lineal
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinearOptions {
+    pub symbology: LinearSymbology,
+    
+    /// Alto de las barras en módulos (por defecto el nominal de la simbología)
+    #[serde(default)]
+    pub bar_height: Option<f32>,
+    
+    /// Interpretación legible bajo las barras (por defecto sí)
+    #[serde(default)]
+    pub include_text: Option<bool>,
+    
+    /// Add-on EAN/UPC de 2 o 5 dígitos (EAN-13, UPC-A y UPC-E)
+    #[serde(default)]
+    pub add_on: Option<String>,
+    
+    /// Barras portadoras alrededor del símbolo (por defecto solo en ITF-14)
+    #[serde(default)]
+    pub bearer_bars: Option<bool>,
+}
+
 /// Formato de salida
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
+    #[default]
     Svg,
     Png,
     Jpeg,
     Webp,
     Eps,
     Pdf,
+    Gif,
+}
+
+impl OutputFormat {
+    /// Indica si el formato se genera rasterizando el SVG final
+    pub fn is_raster(&self) -> bool {
+        matches!(self, OutputFormat::Png | OutputFormat::Jpeg | OutputFormat::Webp | OutputFormat::Gif)
+    }
+
+    /// Tipo MIME del formato
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "image/svg+xml",
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Webp => "image/webp",
+            OutputFormat::Eps => "application/postscript",
+            OutputFormat::Pdf => "application/pdf",
+            OutputFormat::Gif => "image/gif",
+        }
+    }
+}
+
+/// Opciones de salida para formatos rasterizados y vectoriales de impresión
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OutputOptions {
+    /// Resolución en puntos por pulgada (default: 300)
+    pub dpi: Option<u32>,
+    /// Calidad JPEG 1-100 (default: 90)
+    pub jpeg_quality: Option<u8>,
+    /// Ancho físico en milímetros para PDF/EPS (default: `size` en píxeles a los DPI indicados)
+    pub size_mm: Option<f32>,
+    /// Espacio de color para PDF/EPS (default: RGB)
+    pub color_space: Option<ColorSpace>,
+}
+
+/// Espacio de color de los documentos vectoriales
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace {
+    #[default]
+    Rgb,
+    Cmyk,
 }
 
 /// Resultado de generación
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrOutput {
     pub data: String,  // SVG string o base64 para imágenes
     pub format: OutputFormat,
     pub metadata: QrMetadata,
+    /// Bytes crudos del archivo para formatos binarios (no se serializan)
+    #[serde(skip)]
+    pub bytes: Option<Vec<u8>>,
 }
 
 /// Metadatos del QR generado
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrMetadata {
     pub generation_time_ms: u64,
     pub complexity_level: ComplexityLevel,
     pub features_used: Vec<String>,
     pub quality_score: f32,  // 0.0 - 1.0
+    /// Resultado de la verificación de escaneo (solo si se solicitó)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ScanVerification>,
+}
+
+/// Conjunto Structured Append generado
+#[derive(Debug, Clone, Serialize)]
+pub struct QrSymbolSet {
+    /// Número de símbolos del conjunto
+    pub symbol_count: usize,
+    /// Paridad compartida por todos los símbolos
+    pub parity: u8,
+    /// Un archivo por símbolo en orden de secuencia, o la hoja en mosaico
+    pub outputs: Vec<QrOutput>,
+}
+
+/// Opciones de verificación de escaneo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationOptions {
+    /// Corregir automáticamente si el código no se lee (subir ECL, reducir logo,
+    /// simplificar estilos); si es `false` la generación falla
+    #[serde(default = "default_auto_correct")]
+    pub auto_correct: bool,
+    /// Número máximo de regeneraciones al auto-corregir (default: `DEFAULT_MAX_ATTEMPTS`, 5)
+    pub max_attempts: Option<u32>,
+}
+
+fn default_auto_correct() -> bool {
+    true
+}
+
+impl Default for VerificationOptions {
+    fn default() -> Self {
+        Self {
+            auto_correct: true,
+            max_attempts: None,
+        }
+    }
+}
+
+/// Resultado de decodificar el código generado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanVerification {
+    /// El contenido decodificado coincide con `request.data`
+    pub passed: bool,
+    /// Regeneraciones realizadas hasta obtener un código legible
+    pub attempts: u32,
+    /// Correcciones aplicadas, en orden
+    pub corrections: Vec<String>,
+    /// Advertencias de la lectura que no impiden escanear (p. ej. QZ002, zona de silencio
+    /// por debajo del mínimo de la simbología en un código incrustado)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<crate::standards::validator::ValidationWarning>,
+    /// Nivel de corrección leído del código final
+    pub decoded_error_correction: Option<String>,
+    /// Puntuación de calidad medida por el decodificador (0.0 - 1.0)
+    pub decode_score: Option<f32>,
 }
 
 /// Nivel de complejidad
@@ -587,17 +1116,48 @@ pub struct QrCode {
     pub quiet_zone: usize,
     pub customization: Option<QrCustomization>,
     pub logo_zone: Option<crate::engine::geometry::LogoExclusionZone>,
+    pub symbology: QrSymbology,
+    /// Símbolo marcado como datos GS1 (FNC1 en primera posición)
+    pub gs1_mode: bool,
 }
 
 // La implementación de to_svg está en generator.rs
 
+/// Símbolo de un conjunto Structured Append
+#[derive(Debug)]
+pub struct StructuredAppendSymbol {
+    /// Posición en la secuencia (desde 0)
+    pub index: u8,
+    /// Número total de símbolos del conjunto
+    pub total: u8,
+    /// Fragmento del mensaje contenido en este símbolo
+    pub data: String,
+    pub qr: QrCode,
+}
+
+/// Conjunto de símbolos Structured Append que forman un único mensaje
+#[derive(Debug)]
+pub struct StructuredAppendSet {
+    /// Paridad del mensaje completo (XOR de todos sus bytes)
+    pub parity: u8,
+    pub symbols: Vec<StructuredAppendSymbol>,
+}
+
 /// Assets pre-procesados para generación avanzada
 #[derive(Default)]
 pub struct AdvancedAssets {
-    pub logo: Option<DynamicImage>,
+    /// Logos en el orden de `QrCustomization::logos`
+    pub logos: Vec<PreparedLogo>,
     pub gradient_data: Option<GradientData>,
 }
 
+/// Logo cargado y con su forma aplicada
+pub struct PreparedLogo {
+    pub image: DynamicImage,
+    /// Logo SVG saneado, incrustado como vector en la salida
+    pub vector: Option<super::svg_logo::VectorLogo>,
+}
+
 /// Datos pre-computados de gradiente
 pub struct GradientData {
     pub colors: Vec<[u8; 4]>,  // RGBA
@@ -718,6 +1278,27 @@ pub struct ExclusionInfo {
     pub selected_ecl: String,
     /// Si se usó override de ECL
     pub ecl_override: bool,
+    /// Si todos los bloques Reed-Solomon pueden corregir los codewords tapados
+    #[serde(default = "default_recoverable")]
+    pub recoverable: bool,
+    /// Daño en cada bloque Reed-Solomon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_damage: Vec<BlockDamage>,
+}
+
+fn default_recoverable() -> bool {
+    true
+}
+
+/// Codewords que el logo daña en un bloque Reed-Solomon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockDamage {
+    /// Índice del bloque en el orden de ISO/IEC 18004
+    pub block: usize,
+    /// Codewords distintos con algún módulo tapado
+    pub damaged_codewords: usize,
+    /// Codewords erróneos que el bloque puede corregir
+    pub correction_capacity: usize,
 }
 
 /// Información sobre una zona intocable
@@ -968,25 +1549,28 @@ mod tests {
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
                 eye_shape: Some(EyeShape::RoundedSquare),
-                eye_border_style: None,
-                eye_center_style: None,
                 data_pattern: Some(DataPattern::Dots),
                 colors: Some(ColorOptions {
                     foreground: "#000000".to_string(),
                     background: "#FFFFFF".to_string(),
                     eye_colors: None,
                 }),
-                gradient: None,
-                logo: None,
-                frame: None,
-                effects: None,
                 error_correction: Some(ErrorCorrectionLevel::Medium),
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         let json = serde_json::to_string(&request).unwrap();
         let _decoded: QrRequest = serde_json::from_str(&json).unwrap();
     }
+
+    #[test]
+    fn test_logo_placement_deserialization() {
+        let anchor: LogoPlacement = serde_json::from_str(r#""bottom_right""#).unwrap();
+        assert_eq!(anchor, LogoPlacement::Anchor(LogoAnchor::BottomRight));
+        let modules: LogoPlacement = serde_json::from_str(r#"{"x": 20, "y": 4}"#).unwrap();
+        assert_eq!(modules, LogoPlacement::Modules { x: 20, y: 4 });
+        assert!(serde_json::from_str::<LogoPlacement>(r#""middle""#).is_err());
+    }
 }
\ No newline at end of file
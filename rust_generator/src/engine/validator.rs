@@ -23,7 +23,7 @@ impl QrValidator {
         let mut score = 1.0;
         
         // Verificar tamaño mínimo
-        if qr.size < 21 {  // QR v1 mínimo
+        if qr.size < qr.symbology.min_size() {
             issues.push(ValidationIssue {
                 severity: IssueSeverity::Error,
                 message: "QR code demasiado pequeño".to_string(),
@@ -0,0 +1,934 @@
+// engine/vector.rs - Exportación vectorial PDF/EPS para flujos de impresión
+// Recorre el árbol usvg del SVG final (los mismos paths que emite to_svg_with_options,
+// con el texto de los marcos ya convertido a contornos) y lo traduce a operadores
+// PDF o PostScript nivel 3, en RGB o CMYK. Los filtros de efectos no tienen
+// equivalente vectorial y se omiten; el contenido filtrado se dibuja sin efecto.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use resvg::{tiny_skia, usvg};
+
+use super::error::{QrError, QrResult};
+use super::raster::{self, DEFAULT_DPI};
+use super::types::{ColorSpace, OutputFormat, OutputOptions};
+
+/// Puntos tipográficos por milímetro
+pub const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// Ancho físico máximo permitido (1 metro)
+const MAX_SIZE_MM: f32 = 1000.0;
+
+/// Dialecto de salida
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dialect {
+    Pdf,
+    PostScript,
+}
+
+/// Imagen incrustada (logo) ya decodificada
+struct EmbeddedImage {
+    width: u32,
+    height: u32,
+    /// Componentes de color (RGB o CMYK según el modo)
+    color: Vec<u8>,
+    /// Canal alfa, solo si la imagen tiene transparencia
+    alpha: Option<Vec<u8>>,
+}
+
+/// Exporta el SVG final como documento vectorial PDF o EPS
+pub fn export_vector(
+    svg: &str,
+    format: OutputFormat,
+    width_px: u32,
+    options: Option<&OutputOptions>,
+) -> QrResult<Vec<u8>> {
+    let dialect = match format {
+        OutputFormat::Pdf => Dialect::Pdf,
+        OutputFormat::Eps => Dialect::PostScript,
+        other => {
+            return Err(QrError::UnsupportedFormat(format!(
+                "{:?} no es un formato vectorial de impresión", other
+            )))
+        }
+    };
+
+    let width_pt = page_width_pt(width_px, options)?;
+    let color_space = options.and_then(|o| o.color_space).unwrap_or_default();
+
+    let tree = raster::parse_svg(svg)?;
+    let svg_size = tree.size();
+    let scale = width_pt / svg_size.width();
+    let height_pt = svg_size.height() * scale;
+
+    // Origen abajo-izquierda en PDF/PostScript: invertir el eje Y del SVG
+    let page_transform = tiny_skia::Transform::from_row(scale, 0.0, 0.0, -scale, 0.0, height_pt)
+        .pre_concat(tree.view_box().to_transform(svg_size));
+
+    let mut writer = ContentWriter::new(dialect, color_space);
+    writer.save();
+    writer.concat(page_transform);
+    writer.group(tree.root(), 1.0);
+    writer.restore();
+
+    match dialect {
+        Dialect::Pdf => writer.finish_pdf(width_pt, height_pt),
+        Dialect::PostScript => Ok(writer.finish_eps(width_pt, height_pt)),
+    }
+}
+
+/// Calcula el ancho de página en puntos a partir de `size_mm` o del tamaño en píxeles y DPI
+fn page_width_pt(width_px: u32, options: Option<&OutputOptions>) -> QrResult<f32> {
+    if let Some(size_mm) = options.and_then(|o| o.size_mm) {
+        if !(size_mm > 0.0 && size_mm <= MAX_SIZE_MM) {
+            return Err(QrError::ValidationError(format!(
+                "Tamaño físico fuera de rango: {} mm (máximo {} mm)", size_mm, MAX_SIZE_MM
+            )));
+        }
+        return Ok(size_mm * POINTS_PER_MM);
+    }
+
+    let dpi = options.and_then(|o| o.dpi).unwrap_or(DEFAULT_DPI);
+    if dpi == 0 {
+        return Err(QrError::ValidationError("DPI fuera de rango: 0".to_string()));
+    }
+    Ok(width_px as f32 * 72.0 / dpi as f32)
+}
+
+/// Convierte un color RGB a componentes del modo solicitado (0.0 - 1.0)
+fn color_components(color: usvg::Color, mode: ColorSpace) -> Vec<f32> {
+    let r = color.red as f32 / 255.0;
+    let g = color.green as f32 / 255.0;
+    let b = color.blue as f32 / 255.0;
+
+    match mode {
+        ColorSpace::Rgb => vec![r, g, b],
+        ColorSpace::Cmyk => {
+            let k = 1.0 - r.max(g).max(b);
+            if k >= 1.0 {
+                return vec![0.0, 0.0, 0.0, 1.0];
+            }
+            vec![
+                (1.0 - r - k) / (1.0 - k),
+                (1.0 - g - k) / (1.0 - k),
+                (1.0 - b - k) / (1.0 - k),
+                k,
+            ]
+        }
+    }
+}
+
+/// Formatea un número con precisión suficiente para impresión y sin ceros sobrantes
+fn num(value: f32) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let formatted = format!("{:.4}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "" | "-0" => "0".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn nums(values: &[f32]) -> String {
+    values.iter().map(|v| num(*v)).collect::<Vec<_>>().join(" ")
+}
+
+/// Reúne los paths visibles de un clipPath en un solo contorno, con sus transformaciones
+/// aplicadas: dentro de un path PDF no se puede cambiar la matriz. La regla par-impar
+/// solo se usa si todos los paths la piden.
+fn collect_clip_paths(
+    group: &usvg::Group,
+    transform: tiny_skia::Transform,
+    builder: &mut tiny_skia::PathBuilder,
+    even_odd: &mut bool,
+) {
+    for node in group.children() {
+        match node {
+            usvg::Node::Path(ref path) => {
+                if path.visibility() != usvg::Visibility::Visible {
+                    continue;
+                }
+                *even_odd &= path.fill().is_some_and(|fill| fill.rule() == usvg::FillRule::EvenOdd);
+                if let Some(outline) = path.data().clone().transform(transform) {
+                    builder.push_path(&outline);
+                }
+            }
+            usvg::Node::Group(ref child) => {
+                collect_clip_paths(child, transform.pre_concat(child.transform()), builder, even_odd);
+            }
+            usvg::Node::Text(ref text) => collect_clip_paths(text.flattened(), transform, builder, even_odd),
+            usvg::Node::Image(_) => {}
+        }
+    }
+}
+
+/// Comprime un stream con zlib (FlateDecode)
+fn deflate(data: &[u8]) -> QrResult<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Acumula operadores de dibujo y los recursos que referencian
+struct ContentWriter {
+    dialect: Dialect,
+    color_space: ColorSpace,
+    ops: String,
+    /// Diccionarios de sombreado (PDF: recursos /ShN)
+    shadings: Vec<String>,
+    /// Estados gráficos de opacidad (PDF: recursos /GSN)
+    opacities: Vec<(bool, f32)>,
+    /// Imágenes incrustadas (PDF: recursos /ImN)
+    images: Vec<EmbeddedImage>,
+}
+
+impl ContentWriter {
+    fn new(dialect: Dialect, color_space: ColorSpace) -> Self {
+        Self {
+            dialect,
+            color_space,
+            ops: String::new(),
+            shadings: Vec::new(),
+            opacities: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    fn op(&mut self, line: &str) {
+        self.ops.push_str(line);
+        self.ops.push('\n');
+    }
+
+    fn save(&mut self) {
+        self.op(match self.dialect {
+            Dialect::Pdf => "q",
+            Dialect::PostScript => "gsave",
+        });
+    }
+
+    fn restore(&mut self) {
+        self.op(match self.dialect {
+            Dialect::Pdf => "Q",
+            Dialect::PostScript => "grestore",
+        });
+    }
+
+    fn concat(&mut self, ts: tiny_skia::Transform) {
+        if ts.is_identity() {
+            return;
+        }
+        let matrix = nums(&[ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty]);
+        let line = match self.dialect {
+            Dialect::Pdf => format!("{} cm", matrix),
+            Dialect::PostScript => format!("[{}] concat", matrix),
+        };
+        self.op(&line);
+    }
+
+    /// Fija el color de relleno (`stroke` selecciona el color de trazo en PDF)
+    fn set_color(&mut self, color: usvg::Color, stroke: bool) {
+        let components = color_components(color, self.color_space);
+        let operator = match (self.dialect, self.color_space, stroke) {
+            (Dialect::Pdf, ColorSpace::Rgb, false) => "rg",
+            (Dialect::Pdf, ColorSpace::Rgb, true) => "RG",
+            (Dialect::Pdf, ColorSpace::Cmyk, false) => "k",
+            (Dialect::Pdf, ColorSpace::Cmyk, true) => "K",
+            (Dialect::PostScript, ColorSpace::Rgb, _) => "setrgbcolor",
+            (Dialect::PostScript, ColorSpace::Cmyk, _) => "setcmykcolor",
+        };
+        let line = format!("{} {}", nums(&components), operator);
+        self.op(&line);
+    }
+
+    /// Opacidad de relleno o trazo; PostScript no soporta transparencia y la ignora
+    fn set_opacity(&mut self, opacity: f32, stroke: bool) {
+        if self.dialect != Dialect::Pdf || opacity >= 1.0 {
+            return;
+        }
+        self.opacities.push((stroke, opacity.max(0.0)));
+        let line = format!("/GS{} gs", self.opacities.len() - 1);
+        self.op(&line);
+    }
+
+    fn path_data(&mut self, path: &tiny_skia::Path) {
+        let (move_to, line_to, curve_to, close) = match self.dialect {
+            Dialect::Pdf => ("m", "l", "c", "h"),
+            Dialect::PostScript => ("moveto", "lineto", "curveto", "closepath"),
+        };
+        if self.dialect == Dialect::PostScript {
+            self.op("newpath");
+        }
+
+        let mut current = tiny_skia::Point::zero();
+        let mut start = tiny_skia::Point::zero();
+        for segment in path.segments() {
+            let line = match segment {
+                tiny_skia::PathSegment::MoveTo(p) => {
+                    current = p;
+                    start = p;
+                    format!("{} {}", nums(&[p.x, p.y]), move_to)
+                }
+                tiny_skia::PathSegment::LineTo(p) => {
+                    current = p;
+                    format!("{} {}", nums(&[p.x, p.y]), line_to)
+                }
+                tiny_skia::PathSegment::QuadTo(p1, p) => {
+                    // Elevar la cuadrática a cúbica (PDF/PostScript solo tienen Bézier cúbicas)
+                    let c1x = current.x + 2.0 / 3.0 * (p1.x - current.x);
+                    let c1y = current.y + 2.0 / 3.0 * (p1.y - current.y);
+                    let c2x = p.x + 2.0 / 3.0 * (p1.x - p.x);
+                    let c2y = p.y + 2.0 / 3.0 * (p1.y - p.y);
+                    current = p;
+                    format!("{} {}", nums(&[c1x, c1y, c2x, c2y, p.x, p.y]), curve_to)
+                }
+                tiny_skia::PathSegment::CubicTo(p1, p2, p) => {
+                    current = p;
+                    format!("{} {}", nums(&[p1.x, p1.y, p2.x, p2.y, p.x, p.y]), curve_to)
+                }
+                tiny_skia::PathSegment::Close => {
+                    current = start;
+                    close.to_string()
+                }
+            };
+            self.op(&line);
+        }
+    }
+
+    fn group(&mut self, group: &usvg::Group, parent_opacity: f32) {
+        let opacity = parent_opacity * group.opacity().get();
+        self.save();
+        self.concat(group.transform());
+
+        // Como en resvg, clip-path y mask se aplican en el espacio del grupo
+        if let Some(clip_path) = group.clip_path() {
+            self.clip_to(clip_path);
+        }
+        if let Some(mask) = group.mask() {
+            self.clip_to_mask(mask);
+        }
+
+        for node in group.children() {
+            match node {
+                usvg::Node::Group(ref child) => self.group(child, opacity),
+                usvg::Node::Path(ref path) => self.draw_path(path, opacity),
+                usvg::Node::Image(ref image) => self.draw_image(image),
+                usvg::Node::Text(ref text) => self.group(text.flattened(), opacity),
+            }
+        }
+
+        self.restore();
+    }
+
+    /// Recorta a la unión de los paths de un clipPath; un clipPath anidado se
+    /// intersecta recortando otra vez
+    fn clip_to(&mut self, clip_path: &usvg::ClipPath) {
+        if let Some(inner) = clip_path.clip_path() {
+            self.clip_to(inner);
+        }
+
+        let mut builder = tiny_skia::PathBuilder::new();
+        let mut even_odd = true;
+        collect_clip_paths(clip_path.root(), clip_path.transform(), &mut builder, &mut even_odd);
+        match builder.finish() {
+            Some(outline) => {
+                self.path_data(&outline);
+                self.clip_current(even_odd);
+            }
+            // Un clipPath vacío oculta todo el grupo
+            None => self.clip_rect(None),
+        }
+    }
+
+    /// PDF y EPS no tienen máscaras de luminancia sin grupos de transparencia: se recorta
+    /// a la región de la máscara (y de las anidadas)
+    fn clip_to_mask(&mut self, mask: &usvg::Mask) {
+        if let Some(inner) = mask.mask() {
+            self.clip_to_mask(inner);
+        }
+        self.clip_rect(Some(mask.rect().to_rect()));
+    }
+
+    fn clip_rect(&mut self, rect: Option<tiny_skia::Rect>) {
+        match rect.map(tiny_skia::PathBuilder::from_rect) {
+            Some(outline) => {
+                self.path_data(&outline);
+                self.clip_current(false);
+            }
+            // Sin área: nada queda dentro del recorte
+            None => self.op(match self.dialect {
+                Dialect::Pdf => "0 0 0 0 re W n",
+                Dialect::PostScript => "newpath 0 0 moveto closepath clip newpath",
+            }),
+        }
+    }
+
+    /// Convierte el path actual en recorte (sin pintarlo)
+    fn clip_current(&mut self, even_odd: bool) {
+        self.op(match (self.dialect, even_odd) {
+            (Dialect::Pdf, false) => "W n",
+            (Dialect::Pdf, true) => "W* n",
+            (Dialect::PostScript, false) => "clip newpath",
+            (Dialect::PostScript, true) => "eoclip newpath",
+        });
+    }
+
+    fn draw_path(&mut self, path: &usvg::Path, opacity: f32) {
+        if path.visibility() != usvg::Visibility::Visible {
+            return;
+        }
+        if let Some(fill) = path.fill() {
+            self.fill_path(path.data(), fill, opacity);
+        }
+        if let Some(stroke) = path.stroke() {
+            self.stroke_path(path.data(), stroke, opacity);
+        }
+    }
+
+    fn fill_path(&mut self, data: &tiny_skia::Path, fill: &usvg::Fill, opacity: f32) {
+        let even_odd = fill.rule() == usvg::FillRule::EvenOdd;
+
+        self.save();
+        self.set_opacity(fill.opacity().get() * opacity, false);
+
+        match fill.paint() {
+            usvg::Paint::Color(color) => {
+                self.set_color(*color, false);
+                self.path_data(data);
+                self.op(match (self.dialect, even_odd) {
+                    (Dialect::Pdf, false) => "f",
+                    (Dialect::Pdf, true) => "f*",
+                    (Dialect::PostScript, false) => "fill",
+                    (Dialect::PostScript, true) => "eofill",
+                });
+            }
+            usvg::Paint::LinearGradient(gradient) => {
+                let coords = [gradient.x1(), gradient.y1(), gradient.x2(), gradient.y2()];
+                let dict = self.shading_dict(2, &coords, gradient.stops());
+                self.shade_clipped(data, even_odd, gradient.transform(), dict);
+            }
+            usvg::Paint::RadialGradient(gradient) => {
+                let coords = [
+                    gradient.fx(),
+                    gradient.fy(),
+                    0.0,
+                    gradient.cx(),
+                    gradient.cy(),
+                    gradient.r().get(),
+                ];
+                let dict = self.shading_dict(3, &coords, gradient.stops());
+                self.shade_clipped(data, even_odd, gradient.transform(), dict);
+            }
+            usvg::Paint::Pattern(_) => {
+                // Los patrones SVG no se usan en la salida del generador
+            }
+        }
+
+        self.restore();
+    }
+
+    /// Recorta al path y pinta el sombreado en el espacio del gradiente
+    fn shade_clipped(
+        &mut self,
+        data: &tiny_skia::Path,
+        even_odd: bool,
+        gradient_transform: tiny_skia::Transform,
+        dict: String,
+    ) {
+        self.path_data(data);
+        self.clip_current(even_odd);
+        self.concat(gradient_transform);
+
+        match self.dialect {
+            Dialect::Pdf => {
+                self.shadings.push(dict);
+                let line = format!("/Sh{} sh", self.shadings.len() - 1);
+                self.op(&line);
+            }
+            Dialect::PostScript => {
+                let line = format!("{} shfill", dict);
+                self.op(&line);
+            }
+        }
+    }
+
+    fn stroke_path(&mut self, data: &tiny_skia::Path, stroke: &usvg::Stroke, opacity: f32) {
+        let color = match stroke.paint() {
+            usvg::Paint::Color(color) => *color,
+            // Trazos con gradiente: se aproximan con el primer color
+            usvg::Paint::LinearGradient(g) => g.stops().first().map(|s| s.color()).unwrap_or(usvg::Color::black()),
+            usvg::Paint::RadialGradient(g) => g.stops().first().map(|s| s.color()).unwrap_or(usvg::Color::black()),
+            usvg::Paint::Pattern(_) => return,
+        };
+
+        let cap = match stroke.linecap() {
+            usvg::LineCap::Butt => 0,
+            usvg::LineCap::Round => 1,
+            usvg::LineCap::Square => 2,
+        };
+        let join = match stroke.linejoin() {
+            usvg::LineJoin::Miter | usvg::LineJoin::MiterClip => 0,
+            usvg::LineJoin::Round => 1,
+            usvg::LineJoin::Bevel => 2,
+        };
+        let dash = stroke
+            .dasharray()
+            .map(|d| format!("[{}] {}", nums(d), num(stroke.dashoffset())));
+
+        self.save();
+        self.set_opacity(stroke.opacity().get() * opacity, true);
+        self.set_color(color, true);
+
+        let width = num(stroke.width().get());
+        let miter = num(stroke.miterlimit().get());
+        let lines = match self.dialect {
+            Dialect::Pdf => vec![
+                format!("{} w", width),
+                format!("{} J", cap),
+                format!("{} j", join),
+                format!("{} M", miter),
+            ],
+            Dialect::PostScript => vec![
+                format!("{} setlinewidth", width),
+                format!("{} setlinecap", cap),
+                format!("{} setlinejoin", join),
+                format!("{} setmiterlimit", miter),
+            ],
+        };
+        for line in lines {
+            self.op(&line);
+        }
+        if let Some(dash) = dash {
+            let line = match self.dialect {
+                Dialect::Pdf => format!("{} d", dash),
+                Dialect::PostScript => format!("{} setdash", dash),
+            };
+            self.op(&line);
+        }
+
+        self.path_data(data);
+        self.op(match self.dialect {
+            Dialect::Pdf => "S",
+            Dialect::PostScript => "stroke",
+        });
+        self.restore();
+    }
+
+    /// Diccionario de sombreado axial (2) o radial (3); válido en PDF y PostScript 3
+    fn shading_dict(&self, shading_type: u8, coords: &[f32], stops: &[usvg::Stop]) -> String {
+        let color_space = match self.color_space {
+            ColorSpace::Rgb => "/DeviceRGB",
+            ColorSpace::Cmyk => "/DeviceCMYK",
+        };
+
+        // Normalizar paradas para cubrir el dominio [0, 1]
+        let mut points: Vec<(f32, Vec<f32>)> = stops
+            .iter()
+            .map(|s| (s.offset().get(), color_components(s.color(), self.color_space)))
+            .collect();
+        if points.is_empty() {
+            points.push((0.0, color_components(usvg::Color::black(), self.color_space)));
+        }
+        if points[0].0 > 0.0 {
+            let first = points[0].1.clone();
+            points.insert(0, (0.0, first));
+        }
+        if points[points.len() - 1].0 < 1.0 {
+            let last = points[points.len() - 1].1.clone();
+            points.push((1.0, last));
+        }
+        if points.len() == 1 {
+            let only = points[0].1.clone();
+            points.push((1.0, only));
+        }
+
+        let interpolation = |c0: &[f32], c1: &[f32]| {
+            format!(
+                "<< /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >>",
+                nums(c0),
+                nums(c1)
+            )
+        };
+
+        let function = if points.len() == 2 {
+            interpolation(&points[0].1, &points[1].1)
+        } else {
+            let functions: Vec<String> = points
+                .windows(2)
+                .map(|pair| interpolation(&pair[0].1, &pair[1].1))
+                .collect();
+            let bounds: Vec<f32> = points[1..points.len() - 1].iter().map(|p| p.0).collect();
+            let encode = vec!["0 1"; functions.len()].join(" ");
+            format!(
+                "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+                functions.join(" "),
+                nums(&bounds),
+                encode
+            )
+        };
+
+        format!(
+            "<< /ShadingType {} /ColorSpace {} /Coords [{}] /Function {} /Extend [true true] >>",
+            shading_type,
+            color_space,
+            nums(coords),
+            function
+        )
+    }
+
+    fn draw_image(&mut self, image: &usvg::Image) {
+        if image.visibility() != usvg::Visibility::Visible {
+            return;
+        }
+
+        let data = match image.kind() {
+            usvg::ImageKind::PNG(data) | usvg::ImageKind::JPEG(data) | usvg::ImageKind::GIF(data) => data,
+            usvg::ImageKind::SVG(tree) => {
+                // SVG anidado: se mantiene vectorial
+                let size = tree.size();
+                let placement = image_placement(&image.view_box(), size.width(), size.height());
+                self.save();
+                self.concat(placement);
+                self.concat(tree.view_box().to_transform(size));
+                self.group(tree.root(), 1.0);
+                self.restore();
+                return;
+            }
+        };
+
+        let decoded = match image::load_from_memory(data) {
+            Ok(decoded) => decoded.to_rgba8(),
+            Err(e) => {
+                tracing::warn!("No se pudo decodificar imagen para exportación vectorial: {}", e);
+                return;
+            }
+        };
+        let (width, height) = decoded.dimensions();
+        let placement = image_placement(&image.view_box(), width as f32, height as f32);
+        let embedded = self.embed_image(decoded.as_raw(), width, height);
+
+        self.save();
+        self.concat(placement);
+        match self.dialect {
+            Dialect::Pdf => {
+                self.concat(tiny_skia::Transform::from_row(
+                    width as f32, 0.0, 0.0, -(height as f32), 0.0, height as f32,
+                ));
+                self.images.push(embedded);
+                let line = format!("/Im{} Do", self.images.len() - 1);
+                self.op(&line);
+            }
+            Dialect::PostScript => {
+                self.concat(tiny_skia::Transform::from_scale(width as f32, height as f32));
+                self.postscript_image(&embedded);
+            }
+        }
+        self.restore();
+    }
+
+    /// Convierte RGBA al modo de color; PostScript no tiene máscara suave y compone sobre blanco
+    fn embed_image(&self, rgba: &[u8], width: u32, height: u32) -> EmbeddedImage {
+        let has_alpha = rgba.chunks_exact(4).any(|px| px[3] < 255);
+        let flatten = has_alpha && self.dialect == Dialect::PostScript;
+
+        let channels = match self.color_space {
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        };
+        let mut color = Vec::with_capacity((width * height) as usize * channels);
+        let mut alpha = Vec::with_capacity(if has_alpha { (width * height) as usize } else { 0 });
+
+        for px in rgba.chunks_exact(4) {
+            let (r, g, b) = if flatten {
+                let a = px[3] as u32;
+                let blend = |c: u8| ((c as u32 * a + 255 * (255 - a)) / 255) as u8;
+                (blend(px[0]), blend(px[1]), blend(px[2]))
+            } else {
+                (px[0], px[1], px[2])
+            };
+            match self.color_space {
+                ColorSpace::Rgb => color.extend_from_slice(&[r, g, b]),
+                ColorSpace::Cmyk => {
+                    let cmyk = color_components(usvg::Color::new_rgb(r, g, b), ColorSpace::Cmyk);
+                    color.extend(cmyk.iter().map(|c| (c * 255.0).round() as u8));
+                }
+            }
+            if has_alpha {
+                alpha.push(px[3]);
+            }
+        }
+
+        EmbeddedImage {
+            width,
+            height,
+            color,
+            alpha: if has_alpha && !flatten { Some(alpha) } else { None },
+        }
+    }
+
+    fn postscript_image(&mut self, image: &EmbeddedImage) {
+        let (color_space, decode) = match self.color_space {
+            ColorSpace::Rgb => ("/DeviceRGB", "0 1 0 1 0 1"),
+            ColorSpace::Cmyk => ("/DeviceCMYK", "0 1 0 1 0 1 0 1"),
+        };
+        let header = format!(
+            "{} setcolorspace\n<< /ImageType 1 /Width {} /Height {} /BitsPerComponent 8 /Decode [{}] /ImageMatrix [{} 0 0 {} 0 0] /DataSource currentfile /ASCIIHexDecode filter >> image",
+            color_space, image.width, image.height, decode, image.width, image.height
+        );
+        self.op(&header);
+
+        let mut hex = String::with_capacity(image.color.len() * 2 + image.color.len() / 32 + 2);
+        for line in image.color.chunks(32) {
+            for byte in line {
+                let _ = write!(hex, "{:02X}", byte);
+            }
+            hex.push('\n');
+        }
+        hex.push('>');
+        self.op(&hex);
+    }
+
+    /// Ensambla el documento PDF de una página
+    fn finish_pdf(self, width_pt: f32, height_pt: f32) -> QrResult<Vec<u8>> {
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+
+        // 1: Catalog, 2: Pages, 3: Page, 4: Contents, 5..: imágenes y máscaras
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec());
+        objects.push(Vec::new());
+
+        let content = deflate(self.ops.as_bytes())?;
+        objects.push(stream_object("/Filter /FlateDecode", &content));
+
+        let mut xobjects = String::new();
+        for (index, image) in self.images.iter().enumerate() {
+            let smask_ref = match &image.alpha {
+                Some(alpha) => {
+                    let mask = deflate(alpha)?;
+                    objects.push(stream_object(
+                        &format!(
+                            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode",
+                            image.width, image.height
+                        ),
+                        &mask,
+                    ));
+                    format!(" /SMask {} 0 R", objects.len())
+                }
+                None => String::new(),
+            };
+
+            let color_space = match self.color_space {
+                ColorSpace::Rgb => "/DeviceRGB",
+                ColorSpace::Cmyk => "/DeviceCMYK",
+            };
+            let data = deflate(&image.color)?;
+            objects.push(stream_object(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent 8 /Filter /FlateDecode{}",
+                    image.width, image.height, color_space, smask_ref
+                ),
+                &data,
+            ));
+            let _ = write!(xobjects, "/Im{} {} 0 R ", index, objects.len());
+        }
+
+        let mut resources = String::from("<<");
+        if !self.shadings.is_empty() {
+            resources.push_str(" /Shading <<");
+            for (index, shading) in self.shadings.iter().enumerate() {
+                let _ = write!(resources, " /Sh{} {}", index, shading);
+            }
+            resources.push_str(" >>");
+        }
+        if !self.opacities.is_empty() {
+            resources.push_str(" /ExtGState <<");
+            for (index, (stroke, opacity)) in self.opacities.iter().enumerate() {
+                let key = if *stroke { "CA" } else { "ca" };
+                let _ = write!(resources, " /GS{} << /{} {} >>", index, key, num(*opacity));
+            }
+            resources.push_str(" >>");
+        }
+        if !xobjects.is_empty() {
+            let _ = write!(resources, " /XObject << {}>>", xobjects);
+        }
+        resources.push_str(" >>");
+
+        objects[2] = format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R /Resources {} >>",
+            num(width_pt),
+            num(height_pt),
+            resources
+        )
+        .into_bytes();
+
+        let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj", index + 1)?;
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1)?;
+        for offset in offsets {
+            writeln!(pdf, "{:010} 00000 n ", offset)?;
+        }
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )?;
+
+        Ok(pdf)
+    }
+
+    /// Ensambla el documento EPS (PostScript nivel 3)
+    fn finish_eps(self, width_pt: f32, height_pt: f32) -> Vec<u8> {
+        let mut eps = String::with_capacity(self.ops.len() + 512);
+        eps.push_str("%!PS-Adobe-3.0 EPSF-3.0\n");
+        let _ = writeln!(
+            eps,
+            "%%BoundingBox: 0 0 {} {}",
+            width_pt.ceil() as u32,
+            height_pt.ceil() as u32
+        );
+        let _ = writeln!(eps, "%%HiResBoundingBox: 0 0 {} {}", num(width_pt), num(height_pt));
+        eps.push_str("%%Creator: QReable Engine\n");
+        eps.push_str("%%LanguageLevel: 3\n");
+        eps.push_str("%%Pages: 1\n");
+        eps.push_str("%%EndComments\n");
+        eps.push_str("%%Page: 1 1\n");
+        eps.push_str(&self.ops);
+        eps.push_str("showpage\n%%EOF\n");
+        eps.into_bytes()
+    }
+}
+
+/// Objeto stream PDF con su diccionario
+fn stream_object(dict_entries: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {} /Length {} >>\nstream\n", dict_entries, data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+/// Transformación que ubica una imagen de `width`x`height` dentro de su viewBox,
+/// respetando preserveAspectRatio
+fn image_placement(view_box: &usvg::ViewBox, width: f32, height: f32) -> tiny_skia::Transform {
+    use usvg::Align;
+
+    let rect = view_box.rect;
+    let (sx, sy) = if view_box.aspect.align == Align::None {
+        (rect.width() / width, rect.height() / height)
+    } else {
+        let fit_x = rect.width() / width;
+        let fit_y = rect.height() / height;
+        let s = if view_box.aspect.slice { fit_x.max(fit_y) } else { fit_x.min(fit_y) };
+        (s, s)
+    };
+
+    let (ax, ay) = match view_box.aspect.align {
+        Align::None | Align::XMinYMin => (0.0, 0.0),
+        Align::XMidYMin => (0.5, 0.0),
+        Align::XMaxYMin => (1.0, 0.0),
+        Align::XMinYMid => (0.0, 0.5),
+        Align::XMidYMid => (0.5, 0.5),
+        Align::XMaxYMid => (1.0, 0.5),
+        Align::XMinYMax => (0.0, 1.0),
+        Align::XMidYMax => (0.5, 1.0),
+        Align::XMaxYMax => (1.0, 1.0),
+    };
+
+    let x = rect.x() + (rect.width() - width * sx) * ax;
+    let y = rect.y() + (rect.height() - height * sy) * ay;
+    tiny_skia::Transform::from_row(sx, 0.0, 0.0, sy, x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRADIENT_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100" width="100" height="100">
+        <defs><linearGradient id="g" x1="0" y1="0" x2="100" y2="0" gradientUnits="userSpaceOnUse">
+            <stop offset="0" stop-color="#FF0000"/><stop offset="0.5" stop-color="#00FF00"/><stop offset="1" stop-color="#0000FF"/>
+        </linearGradient></defs>
+        <rect width="100" height="100" fill="#FFFFFF"/>
+        <path d="M10 10h20v20h-20z" fill="url(#g)"/>
+        <rect x="50" y="50" width="20" height="20" fill="#000000" stroke="#FF0000" stroke-width="2"/>
+    </svg>"##;
+
+    #[test]
+    fn test_pdf_structure_and_size_mm() {
+        let options = OutputOptions { size_mm: Some(50.0), ..Default::default() };
+        let pdf = export_vector(GRADIENT_SVG, OutputFormat::Pdf, 300, Some(&options)).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        // 50 mm = 141.7323 pt
+        assert!(text.contains("/MediaBox [0 0 141.7323 141.7323]"));
+        assert!(text.contains("/ShadingType 2"));
+        assert!(text.contains("/FunctionType 3"));
+    }
+
+    #[test]
+    fn test_eps_cmyk_output() {
+        let options = OutputOptions { color_space: Some(ColorSpace::Cmyk), ..Default::default() };
+        let eps = export_vector(GRADIENT_SVG, OutputFormat::Eps, 300, Some(&options)).unwrap();
+        let text = String::from_utf8(eps).unwrap();
+
+        assert!(text.starts_with("%!PS-Adobe-3.0 EPSF-3.0"));
+        // 300 px a 300 DPI = 1 pulgada = 72 pt
+        assert!(text.contains("%%BoundingBox: 0 0 72 72"));
+        assert!(text.contains("/DeviceCMYK"));
+        assert!(text.contains("0 0 0 1 setcmykcolor"));
+        assert!(text.contains("stroke"));
+        assert!(!text.contains("setrgbcolor"));
+    }
+
+    #[test]
+    fn test_clip_path_and_mask_are_applied() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100" width="100" height="100">
+            <defs>
+                <clipPath id="c"><circle cx="50" cy="50" r="40"/></clipPath>
+                <mask id="m" x="10" y="10" width="30" height="30" maskUnits="userSpaceOnUse">
+                    <rect width="100" height="100" fill="#FFFFFF"/>
+                </mask>
+            </defs>
+            <g clip-path="url(#c)"><rect width="100" height="100" fill="#FF0000"/></g>
+            <g mask="url(#m)"><rect width="100" height="100" fill="#0000FF"/></g>
+        </svg>"##;
+
+        let eps = export_vector(svg, OutputFormat::Eps, 300, None).unwrap();
+        let text = String::from_utf8(eps).unwrap();
+        assert_eq!(text.matches("clip newpath").count(), 2);
+
+        let tree = raster::parse_svg(svg).unwrap();
+        let mut writer = ContentWriter::new(Dialect::Pdf, ColorSpace::Rgb);
+        writer.group(tree.root(), 1.0);
+        assert_eq!(writer.ops.matches("W n").count(), 2);
+        // El círculo se recorta con curvas y la máscara con su rectángulo
+        assert!(writer.ops.contains(" c\n"));
+        assert!(writer.ops.contains("10 10 m"));
+    }
+
+    #[test]
+    fn test_color_components_cmyk() {
+        assert_eq!(color_components(usvg::Color::black(), ColorSpace::Cmyk), vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(color_components(usvg::Color::white(), ColorSpace::Cmyk), vec![0.0, 0.0, 0.0, 0.0]);
+        let red = color_components(usvg::Color::new_rgb(255, 0, 0), ColorSpace::Cmyk);
+        assert_eq!(red, vec![0.0, 1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_invalid_size_mm_rejected() {
+        let options = OutputOptions { size_mm: Some(0.0), ..Default::default() };
+        assert!(export_vector(GRADIENT_SVG, OutputFormat::Pdf, 300, Some(&options)).is_err());
+    }
+}
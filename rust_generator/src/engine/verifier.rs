@@ -0,0 +1,231 @@
+// engine/verifier.rs - Verificación de escaneo del resultado final
+// Decodifica el SVG renderizado con un lector real y, si no se lee,
+// propone correcciones progresivas sobre la solicitud
+
+use crate::standards::decoder::{DecodedData, QrDecoder};
+use crate::standards::validator::ValidationWarning;
+
+use super::error::{QrError, QrResult};
+use super::types::{LogoOptions, QrRequest};
+
+/// Regeneraciones por defecto al auto-corregir
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+/// Porcentaje mínimo al que se reduce un logo
+const MIN_LOGO_PERCENTAGE: f32 = 10.0;
+
+/// Factor de reducción del logo por intento
+const LOGO_SHRINK_FACTOR: f32 = 0.75;
+
+/// Verificador de escaneo
+pub struct ScanVerifier {
+    decoder: QrDecoder,
+}
+
+impl ScanVerifier {
+    pub fn new() -> Self {
+        Self {
+            decoder: QrDecoder::new(),
+        }
+    }
+
+    /// Decodifica el SVG y comprueba que el contenido coincida con el esperado
+    pub fn verify(&self, svg: &str, expected: &str) -> QrResult<DecodedData> {
+        let decoded = self.decoder.decode_svg(svg)?;
+        if !self.decoder.verify_content(&decoded, expected) {
+            return Err(QrError::DecodingError(format!(
+                "El contenido leído no coincide con los datos solicitados ({} de {} bytes)",
+                decoded.raw_data.len(),
+                expected.len()
+            )));
+        }
+        Ok(decoded)
+    }
+
+    /// Advertencias de una lectura correcta. El margen se mide sobre el lienzo del SVG:
+    /// un código sin zona de silencio propia se lee igual, pero depende del diseño que lo
+    /// contenga, y ninguna corrección lo cambia
+    pub fn warnings(&self, decoded: &DecodedData) -> Vec<ValidationWarning> {
+        let required = decoded.metadata.symbology.min_quiet_zone();
+        match decoded.metadata.quiet_zone_modules {
+            // Medio módulo de tolerancia por el redondeo de la medición
+            Some(margin) if margin + 0.5 < required as f32 => vec![ValidationWarning {
+                code: "QZ002".to_string(),
+                message: format!(
+                    "Zona de silencio de {:.1} módulos por debajo del mínimo de la simbología ({})",
+                    margin, required
+                ),
+                recommendation: Some("Reservar el margen claro en el diseño que contiene el código".to_string()),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Aplica la siguiente corrección disponible a la solicitud.
+    /// Retorna la descripción de la corrección, o `None` si no quedan correcciones.
+    ///
+    /// Orden: subir ECL al máximo de la simbología, reducir el logo y los overlays, simplificar
+    /// el patrón de datos, simplificar los ojos, quitar los efectos y por último los overlays.
+    pub fn next_correction(&self, request: &mut QrRequest) -> Option<String> {
+        let customization = request.customization.as_mut()?;
+
+        let max_ecl = customization.symbology.unwrap_or_default().max_error_correction();
+        if customization.error_correction != Some(max_ecl) {
+            customization.error_correction = Some(max_ecl);
+            return Some(format!("error_correction_{:?}", max_ecl).to_lowercase());
+        }
+
+        // Los overlays también tapan módulos: se reducen junto al logo principal
+        let mut overlays_shrunk = false;
+        for overlay in customization.overlays.iter_mut().flatten() {
+            overlays_shrunk |= shrink_logo(overlay);
+        }
+        if let Some(logo) = customization.logo.as_mut() {
+            if shrink_logo(logo) {
+                if let Some(ratio) = customization.logo_size_ratio.as_mut() {
+                    *ratio = logo.size_percentage / 100.0;
+                }
+                return Some(format!("logo_shrunk_{:.0}", logo.size_percentage));
+            }
+        }
+        if overlays_shrunk {
+            return Some("overlays_shrunk".to_string());
+        }
+
+        if customization.data_pattern.is_some() {
+            customization.data_pattern = None;
+            return Some("data_pattern_simplified".to_string());
+        }
+
+        if customization.eye_shape.is_some()
+            || customization.eye_border_style.is_some()
+            || customization.eye_center_style.is_some()
+        {
+            customization.eye_shape = None;
+            customization.eye_border_style = None;
+            customization.eye_center_style = None;
+            return Some("eye_style_simplified".to_string());
+        }
+
+        if customization.effects.is_some() || customization.selective_effects.is_some() {
+            customization.effects = None;
+            customization.selective_effects = None;
+            return Some("effects_removed".to_string());
+        }
+
+        // Quitar contenido del diseño es el último recurso
+        if customization.overlays.take().is_some_and(|overlays| !overlays.is_empty()) {
+            return Some("overlays_removed".to_string());
+        }
+
+        None
+    }
+}
+
+/// Reduce un logo un paso sin bajar del mínimo; indica si ha cambiado
+fn shrink_logo(logo: &mut LogoOptions) -> bool {
+    if logo.size_percentage <= MIN_LOGO_PERCENTAGE {
+        return false;
+    }
+    logo.size_percentage = (logo.size_percentage * LOGO_SHRINK_FACTOR).max(MIN_LOGO_PERCENTAGE);
+    true
+}
+
+impl Default for ScanVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::{DataPattern, ErrorCorrectionLevel, EyeBorderStyle, LogoOptions, LogoShape, OutputFormat, QrCustomization};
+    use crate::engine::QrGenerator;
+
+    fn styled_request() -> QrRequest {
+        QrRequest {
+            data: "https://example.com".to_string(),
+            size: 300,
+            format: OutputFormat::Svg,
+            customization: Some(QrCustomization {
+                eye_border_style: Some(EyeBorderStyle::Circle),
+                data_pattern: Some(DataPattern::Dots),
+                logo: Some(LogoOptions {
+                    data: String::new(),
+                    logo_id: None,
+                    size_percentage: 20.0,
+                    padding: 0,
+                    background: None,
+                    shape: LogoShape::Square,
+                    placement: None,
+                }),
+                error_correction: Some(ErrorCorrectionLevel::Medium),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verify_generated_code() {
+        let qr = QrGenerator::new().generate_basic("VERIFY-123", 300).unwrap();
+        let verifier = ScanVerifier::new();
+
+        assert!(verifier.verify(&qr.to_svg(), "VERIFY-123").is_ok());
+        assert!(matches!(
+            verifier.verify(&qr.to_svg(), "OTRO"),
+            Err(QrError::DecodingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_correction_sequence() {
+        let verifier = ScanVerifier::new();
+        let mut request = styled_request();
+
+        let mut corrections = Vec::new();
+        while let Some(correction) = verifier.next_correction(&mut request) {
+            corrections.push(correction);
+        }
+
+        assert_eq!(corrections, vec![
+            "error_correction_high",
+            "logo_shrunk_15",
+            "logo_shrunk_11",
+            "logo_shrunk_10",
+            "data_pattern_simplified",
+            "eye_style_simplified",
+        ]);
+        let customization = request.customization.unwrap();
+        assert_eq!(customization.error_correction, Some(ErrorCorrectionLevel::High));
+        assert_eq!(customization.logo.unwrap().size_percentage, MIN_LOGO_PERCENTAGE);
+    }
+
+    #[test]
+    fn test_correction_shrinks_and_drops_overlays() {
+        let verifier = ScanVerifier::new();
+        let mut request = styled_request();
+        let customization = request.customization.as_mut().unwrap();
+        let badge = LogoOptions { size_percentage: 25.0, ..customization.logo.clone().unwrap() };
+        customization.overlays = Some(vec![badge]);
+
+        let mut corrections = Vec::new();
+        while let Some(correction) = verifier.next_correction(&mut request) {
+            corrections.push(correction);
+        }
+
+        // El overlay se reduce a la par que el logo y solo se quita cuando no queda otra corrección
+        assert_eq!(corrections, vec![
+            "error_correction_high",
+            "logo_shrunk_15",
+            "logo_shrunk_11",
+            "logo_shrunk_10",
+            "overlays_shrunk",
+            "data_pattern_simplified",
+            "eye_style_simplified",
+            "overlays_removed",
+        ]);
+        assert!(request.customization.unwrap().overlays.is_none());
+    }
+}
@@ -1,6 +1,10 @@
 // engine/zones.rs - Mapeo de zonas intocables del código QR
 
-use super::constants::get_alignment_pattern_positions;
+use super::constants::{
+    get_alignment_pattern_positions, get_aztec_layers, get_datamatrix_size, get_datamatrix_version, get_rmqr_alignment_columns,
+    get_rmqr_size,
+};
+use super::types::QrSymbology;
 
 /// Tipo de zona funcional en el código QR
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,6 +23,22 @@ pub enum ZoneType {
     VersionInfo,
     /// Zona silenciosa (quiet zone)
     QuietZone,
+    /// Subpatrón de búsqueda 5×5 de rMQR (esquina inferior derecha)
+    FinderSubPattern,
+    /// Patrones de esquina de rMQR
+    CornerPattern,
+    /// Anillo de mensaje de modo Aztec (capas y palabras de datos) con las marcas de orientación
+    ModeMessage,
+    /// Rejilla de referencia de Aztec completo (cada 16 módulos desde el centro)
+    ReferenceGrid,
+    /// Patrón de inicio de PDF417
+    StartPattern,
+    /// Patrón de parada de PDF417 (una barra en el compacto)
+    StopPattern,
+    /// Indicadores de fila de PDF417
+    RowIndicator,
+    /// Área ya ocupada por otro logo (no es un patrón: evita que los overlays se pisen)
+    LogoArea,
 }
 
 /// Zona intocable del código QR
@@ -196,6 +216,204 @@ pub fn calculate_untouchable_zones(version: u8) -> Vec<UntouchableZone> {
     zones
 }
 
+/// Calcula las zonas intocables de un símbolo Micro QR (M1-M4)
+///
+/// Micro QR tiene un único patrón de búsqueda; la temporización recorre la fila
+/// y la columna 0 y la información de formato rodea el separador
+pub fn calculate_micro_untouchable_zones(version: u8) -> Vec<UntouchableZone> {
+    let mut zones = Vec::new();
+    
+    if version == 0 || version > 4 {
+        return zones;
+    }
+    
+    let size = 9 + 2 * version as u16;
+    
+    // 1. Patrón de búsqueda superior izquierdo
+    zones.push(UntouchableZone::new(ZoneType::FinderPattern, 0, 0, 7, 7));
+    
+    // 2. Separador
+    zones.push(UntouchableZone::new(ZoneType::Separator, 7, 0, 1, 8));
+    zones.push(UntouchableZone::new(ZoneType::Separator, 0, 7, 7, 1));
+    
+    // 3. Temporización en los bordes superior e izquierdo
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 8, 0, size - 8, 1));
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 0, 8, 1, size - 8));
+    
+    // 4. Información de formato (15 bits)
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, 8, 1, 1, 7));
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, 1, 8, 8, 1));
+    
+    zones
+}
+
+/// Calcula las zonas intocables de un símbolo rMQR (versiones 1-32, R7x43 … R17x139)
+pub fn calculate_rmqr_untouchable_zones(version: u8) -> Vec<UntouchableZone> {
+    let mut zones = Vec::new();
+    
+    let Some((width, height)) = get_rmqr_size(version) else {
+        return zones;
+    };
+    let (width, height) = (width as u16, height as u16);
+    
+    // 1. Patrón de búsqueda, subpatrón y esquinas
+    zones.push(UntouchableZone::new(ZoneType::FinderPattern, 0, 0, 7, 7));
+    zones.push(UntouchableZone::new(ZoneType::FinderSubPattern, width - 5, height - 5, 5, 5));
+    zones.push(UntouchableZone::new(ZoneType::CornerPattern, width - 2, 0, 2, 2));
+    if height > 7 {
+        // En R7 la esquina inferior izquierda queda dentro del patrón de búsqueda
+        zones.push(UntouchableZone::new(ZoneType::CornerPattern, 0, height - 2, 2, 2));
+    }
+    
+    // 2. Separador (en R7 solo la columna)
+    zones.push(UntouchableZone::new(ZoneType::Separator, 7, 0, 1, height.min(8)));
+    if height > 7 {
+        zones.push(UntouchableZone::new(ZoneType::Separator, 0, 7, 7, 1));
+    }
+    
+    // 3. Patrones de alineación en los bordes superior e inferior
+    let columns = get_rmqr_alignment_columns(width as u8);
+    for &column in columns {
+        let x = column as u16 - 1;
+        zones.push(UntouchableZone::new(ZoneType::AlignmentPattern, x, 0, 3, 3));
+        zones.push(UntouchableZone::new(ZoneType::AlignmentPattern, x, height - 3, 3, 3));
+    }
+    
+    // 4. Temporización en los cuatro bordes y vertical en cada columna de alineación
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 0, 0, width, 1));
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 0, height - 1, width, 1));
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 0, 0, 1, height));
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, width - 1, 0, 1, height));
+    for &column in columns {
+        zones.push(UntouchableZone::new(ZoneType::TimingPattern, column as u16, 3, 1, height - 6));
+    }
+    
+    // 5. Información de formato junto al patrón de búsqueda y al subpatrón (18 bits cada una)
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, 8, 1, 3, 5));
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, 11, 1, 1, 3));
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, width - 8, height - 6, 3, 5));
+    zones.push(UntouchableZone::new(ZoneType::FormatInfo, width - 5, height - 6, 3, 1));
+    
+    zones
+}
+
+/// Calcula las zonas intocables según la simbología del símbolo. La versión de Data Matrix
+/// es la de `DATA_MATRIX_SIZES`; Aztec y PDF417 no se identifican solo por versión y sus
+/// zonas se calculan con las dimensiones del símbolo (ver `QrCode::untouchable_zones`)
+pub fn calculate_symbol_untouchable_zones(symbology: QrSymbology, version: u8) -> Vec<UntouchableZone> {
+    match symbology {
+        QrSymbology::Qr => calculate_untouchable_zones(version),
+        QrSymbology::MicroQr => calculate_micro_untouchable_zones(version),
+        QrSymbology::Rmqr => calculate_rmqr_untouchable_zones(version),
+        QrSymbology::DataMatrix => get_datamatrix_size(version)
+            .map(|(rows, cols, _, _)| calculate_datamatrix_untouchable_zones(cols as usize, rows as usize))
+            .unwrap_or_default(),
+        QrSymbology::Aztec | QrSymbology::Pdf417 => Vec::new(),
+    }
+}
+
+/// Calcula las zonas intocables de un símbolo Data Matrix ECC 200 (cuadrado, rectangular o DMRE)
+///
+/// El patrón de búsqueda en L ocupa la columna izquierda y la fila inferior, la
+/// temporización la fila superior y la columna derecha, y los símbolos con varias regiones
+/// de datos llevan patrones de alineación de dos módulos entre ellas
+pub fn calculate_datamatrix_untouchable_zones(width: usize, height: usize) -> Vec<UntouchableZone> {
+    let mut zones = Vec::new();
+    
+    let Some((rows, cols, region_rows, region_cols)) = get_datamatrix_version(width, height)
+        .and_then(get_datamatrix_size)
+    else {
+        return zones;
+    };
+    let (width, height) = (cols as u16, rows as u16);
+    let (region_width, region_height) = (region_cols as u16 + 2, region_rows as u16 + 2);
+    
+    // 1. Patrón de búsqueda en L
+    zones.push(UntouchableZone::new(ZoneType::FinderPattern, 0, 0, 1, height));
+    zones.push(UntouchableZone::new(ZoneType::FinderPattern, 0, height - 1, width, 1));
+    
+    // 2. Temporización (clock track) superior y derecha
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, 1, 0, width - 1, 1));
+    zones.push(UntouchableZone::new(ZoneType::TimingPattern, width - 1, 1, 1, height - 2));
+    
+    // 3. Alineación entre regiones: temporización de una región junto al borde sólido de la siguiente
+    for x in (region_width..width).step_by(region_width as usize) {
+        zones.push(UntouchableZone::new(ZoneType::AlignmentPattern, x - 1, 1, 2, height - 2));
+    }
+    for y in (region_height..height).step_by(region_height as usize) {
+        zones.push(UntouchableZone::new(ZoneType::AlignmentPattern, 1, y - 1, width - 2, 2));
+    }
+    
+    zones
+}
+
+/// Calcula las zonas intocables de un símbolo Aztec de `size` módulos de lado
+///
+/// El patrón central (9x9 en compacto, 13x13 en completo) está rodeado por el anillo del
+/// mensaje de modo; el completo añade la rejilla de referencia, que recorre el símbolo
+/// cada 16 módulos desde el centro
+pub fn calculate_aztec_untouchable_zones(size: usize, compact: bool) -> Vec<UntouchableZone> {
+    let mut zones = Vec::new();
+    
+    if get_aztec_layers(size, compact).is_none() {
+        return zones;
+    }
+    
+    let size = size as u16;
+    let center = size / 2;
+    let ring = if compact { 5 } else { 7 };
+    
+    // 1. Patrón central (bullseye)
+    zones.push(UntouchableZone::new(
+        ZoneType::FinderPattern,
+        center - ring + 1, center - ring + 1, 2 * ring - 1, 2 * ring - 1
+    ));
+    
+    // 2. Mensaje de modo y marcas de orientación
+    zones.push(UntouchableZone::new(ZoneType::ModeMessage, center - ring, center - ring, 2 * ring + 1, 1));
+    zones.push(UntouchableZone::new(ZoneType::ModeMessage, center - ring, center + ring, 2 * ring + 1, 1));
+    zones.push(UntouchableZone::new(ZoneType::ModeMessage, center - ring, center - ring + 1, 1, 2 * ring - 1));
+    zones.push(UntouchableZone::new(ZoneType::ModeMessage, center + ring, center - ring + 1, 1, 2 * ring - 1));
+    
+    // 3. Rejilla de referencia (solo Aztec completo)
+    if !compact {
+        for offset in (0..=center).step_by(16) {
+            for line in [center - offset, center + offset] {
+                zones.push(UntouchableZone::new(ZoneType::ReferenceGrid, 0, line, size, 1));
+                zones.push(UntouchableZone::new(ZoneType::ReferenceGrid, line, 0, 1, size));
+            }
+        }
+    }
+    
+    zones
+}
+
+/// Calcula las zonas intocables de un símbolo PDF417 de `width` módulos de ancho
+///
+/// Cada fila empieza con el patrón de inicio y el indicador izquierdo; el símbolo completo
+/// termina con el indicador derecho y el patrón de parada, el compacto con una sola barra
+pub fn calculate_pdf417_untouchable_zones(width: usize, height: usize, compact: bool) -> Vec<UntouchableZone> {
+    let mut zones = Vec::new();
+    
+    // Inicio e indicadores de 17 módulos, parada de 18 (1 en compacto) y 1-30 columnas de datos
+    let overhead = if compact { 35 } else { 69 };
+    if height == 0 || width < overhead + 17 || !(width - overhead).is_multiple_of(17) || (width - overhead) / 17 > 30 {
+        return zones;
+    }
+    let (width, height) = (width as u16, height as u16);
+    
+    zones.push(UntouchableZone::new(ZoneType::StartPattern, 0, 0, 17, height));
+    zones.push(UntouchableZone::new(ZoneType::RowIndicator, 17, 0, 17, height));
+    if compact {
+        zones.push(UntouchableZone::new(ZoneType::StopPattern, width - 1, 0, 1, height));
+    } else {
+        zones.push(UntouchableZone::new(ZoneType::RowIndicator, width - 35, 0, 17, height));
+        zones.push(UntouchableZone::new(ZoneType::StopPattern, width - 18, 0, 18, height));
+    }
+    
+    zones
+}
+
 /// Verifica si un módulo está en alguna zona intocable
 pub fn is_in_untouchable_zone(x: u16, y: u16, zones: &[UntouchableZone]) -> bool {
     zones.iter().any(|zone| zone.contains_point(x, y))
@@ -265,8 +483,14 @@ mod tests {
         // Parcialmente superpuesto
         assert!(zone.intersects_rect(8, 8, 5, 5));
         
-        // Tocando el borde
-        assert!(zone.intersects_rect(15, 10, 5, 5));
+        // Un módulo en común
+        assert!(zone.intersects_rect(14, 10, 5, 5));
+        
+        // Tocando el borde: los rectángulos son semiabiertos en módulos, así que uno que
+        // empieza en x + width no comparte ningún módulo (un logo puede quedar pegado al
+        // separador sin taparlo)
+        assert!(!zone.intersects_rect(15, 10, 5, 5));
+        assert!(!zone.intersects_rect(10, 5, 5, 5));
         
         // Completamente fuera
         assert!(!zone.intersects_rect(20, 20, 5, 5));
@@ -330,4 +554,40 @@ mod tests {
         let total_modules_v1 = 21 * 21;
         assert!(area_v1 < total_modules_v1 as u32);
     }
-}
\ No newline at end of file
+    
+    /// Las zonas deben coincidir exactamente con los módulos de función que usa el lector
+    fn assert_matches_reader(zones: &[UntouchableZone], version: &rxing::qrcode::decoder::Version) {
+        let pattern = version.buildFunctionPattern().unwrap();
+        for y in 0..pattern.getHeight() {
+            for x in 0..pattern.getWidth() {
+                assert_eq!(
+                    is_in_untouchable_zone(x as u16, y as u16, zones),
+                    pattern.get(x, y),
+                    "módulo ({}, {}) de {:?}",
+                    x, y, version
+                );
+            }
+        }
+    }
+    
+    #[test]
+    fn test_micro_zones_single_finder() {
+        for version in 1..=4 {
+            let zones = calculate_micro_untouchable_zones(version);
+            let finders = zones.iter().filter(|z| z.zone_type == ZoneType::FinderPattern).count();
+            assert_eq!(finders, 1);
+            assert_matches_reader(&zones, rxing::qrcode::decoder::Version::Micro(version as u32).unwrap());
+        }
+        assert!(calculate_micro_untouchable_zones(5).is_empty());
+    }
+    
+    #[test]
+    fn test_rmqr_zones_match_reader() {
+        for version in 1..=32 {
+            let zones = calculate_symbol_untouchable_zones(QrSymbology::Rmqr, version);
+            assert!(zones.iter().any(|z| z.zone_type == ZoneType::FinderSubPattern));
+            assert_matches_reader(&zones, rxing::qrcode::decoder::Version::rMQR(version as u32).unwrap());
+        }
+        assert!(calculate_rmqr_untouchable_zones(33).is_empty());
+    }
+}
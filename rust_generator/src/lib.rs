@@ -81,55 +81,103 @@ pub fn generate_code(
     code_type: &str,
     data: &str,
     scale: u32,
-    _ecl: Option<&str>,      // Parámetro recibido pero IGNORADO por ahora
-    height: Option<u32>,   // Parámetro recibido pero IGNORADO por ahora por rxing
-    _includetext: Option<bool>, // Parámetro recibido pero IGNORADO por ahora
+    ecl: Option<&str>,
+    height: Option<u32>,   // Alto de barras en píxeles (códigos lineales)
+    includetext: Option<bool>, // Texto legible bajo las barras (códigos lineales)
     fgcolor: Option<&str>,
     bgcolor: Option<&str>,
+) -> Result<String, Box<dyn Error>> {
+    generate_code_with_options(
+        code_type,
+        data,
+        scale,
+        ecl,
+        height,
+        includetext,
+        fgcolor,
+        bgcolor,
+        &engine::MatrixCodeOptions::default(),
+    )
+}
+
+/// Igual que `generate_code`, con las dimensiones de DataMatrix, Aztec y PDF417.
+/// En estas simbologías `ecl` acepta L/M/Q/H o su valor nativo: nivel PDF417 (0-8)
+/// o porcentaje de corrección Aztec; DataMatrix ECC 200 tiene corrección fija.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_code_with_options(
+    code_type: &str,
+    data: &str,
+    scale: u32,
+    ecl: Option<&str>,
+    height: Option<u32>,   // Alto de barras en píxeles (códigos lineales)
+    includetext: Option<bool>, // Texto legible bajo las barras (códigos lineales)
+    fgcolor: Option<&str>,
+    bgcolor: Option<&str>,
+    matrix_options: &engine::MatrixCodeOptions,
 ) -> Result<String, Box<dyn Error>> {
     let binding = code_type.to_lowercase();
     let code_type = binding.trim();
+    let colors = engine::ColorOptions {
+        foreground: fgcolor.unwrap_or("#000000").to_string(),
+        background: bgcolor.unwrap_or("#FFFFFF").to_string(),
+        eye_colors: None,
+    };
+
+    // Los códigos lineales pasan por el motor: alto real, texto legible y barras agrupadas
+    if let Some(symbology) = engine::LinearSymbology::from_name(code_type) {
+        let scale = scale.max(1);
+        let options = engine::LinearOptions {
+            symbology,
+            bar_height: height.map(|h| h as f32 / scale as f32),
+            include_text: includetext,
+            ..Default::default()
+        };
+        let barcode = engine::linear::encode(data, &options).map_err(|e| Box::new(e) as Box<dyn Error>)?;
+        return Ok(barcode.to_svg(scale as usize, &options, Some(&colors), None));
+    }
+
+    // DataMatrix, Aztec y PDF417 también pasan por el motor (tamaños DMRE, capas, columnas)
+    if let Some(symbology) = engine::QrSymbology::from_name(code_type).filter(|s| !s.is_qr_family()) {
+        let mut options = matrix_options.clone();
+        let level = match ecl.map(|level| level.trim().to_uppercase()) {
+            None => None,
+            Some(level) => match level.as_str() {
+                "L" => Some(engine::ErrorCorrectionLevel::Low),
+                "M" => Some(engine::ErrorCorrectionLevel::Medium),
+                "Q" => Some(engine::ErrorCorrectionLevel::Quartile),
+                "H" => Some(engine::ErrorCorrectionLevel::High),
+                native => {
+                    let value: u8 = native
+                        .trim_end_matches('%')
+                        .parse()
+                        .map_err(|_| format!("Nivel de corrección no válido: {}", native))?;
+                    match symbology {
+                        engine::QrSymbology::Pdf417 => options.pdf417_ecc_level = Some(value),
+                        engine::QrSymbology::Aztec => options.aztec_ec_percent = Some(value),
+                        _ => {}
+                    }
+                    None
+                }
+            },
+        };
+        let customization = engine::QrCustomization {
+            colors: Some(colors),
+            error_correction: level,
+            symbology: Some(symbology),
+            matrix_code: Some(options.clone()),
+            ..Default::default()
+        };
+        // El SVG se escala con `scale`: el tamaño en píxeles del motor no interviene
+        let symbol = engine::QrGenerator::new()
+            .generate_matrix_symbol(data, engine::DEFAULT_QR_SIZE, symbology, level, &options)
+            .map_err(|e| Box::new(e) as Box<dyn Error>)?;
+        return Ok(symbol.to_svg_with_options(scale.max(1) as usize, &[], None, None, Some(&customization)));
+    }
 
     let format = match code_type {
         // QR Code y variantes
         "qr" | "qrcode" | "qr-code" | "qr_code" => BarcodeFormat::QR_CODE,
 
-        // Code 128 y variantes
-        "code128" | "code-128" | "code_128" | "code 128" => BarcodeFormat::CODE_128,
-
-        // PDF417 y variantes
-        "pdf417" | "pdf-417" | "pdf_417" | "pdf 417" => BarcodeFormat::PDF_417,
-
-        // Aztec
-        "aztec" => BarcodeFormat::AZTEC,
-
-        // DataMatrix y variantes
-        "datamatrix" | "data-matrix" | "data_matrix" | "data matrix" => BarcodeFormat::DATA_MATRIX,
-
-        // EAN-8 y variantes
-        "ean8" | "ean-8" | "ean_8" | "ean 8" => BarcodeFormat::EAN_8,
-
-        // EAN-13 y variantes
-        "ean13" | "ean-13" | "ean_13" | "ean 13" => BarcodeFormat::EAN_13,
-
-        // ITF
-        "itf" => BarcodeFormat::ITF,
-
-        // UPC-A y variantes
-        "upca" | "upc-a" | "upc_a" | "upc a" => BarcodeFormat::UPC_A,
-
-        // UPC-E y variantes
-        "upce" | "upc-e" | "upc_e" | "upc e" => BarcodeFormat::UPC_E,
-
-        // Code 39 y variantes
-        "code39" | "code-39" | "code_39" | "code 39" => BarcodeFormat::CODE_39,
-
-        // Code 93 y variantes
-        "code93" | "code-93" | "code_93" | "code 93" => BarcodeFormat::CODE_93,
-
-        // Codabar
-        "codabar" => BarcodeFormat::CODABAR,
-
         // No reconocido
         _ => return Err(format!("Tipo de código no soportado: {}", code_type).into()),
     };
@@ -137,10 +185,8 @@ pub fn generate_code(
     // --- Crear y poblar EncodeHints ---
     let mut hints = EncodeHints::default();
 
-    // Nivel de corrección de errores (ECL) - Principalmente para QR
-    if let Some(ecl_val) = _ecl {
-        // TODO: Validar que ecl_val sea L, M, Q, H si el formato es QR?
-        // La validación debería ocurrir antes, pero añadimos el hint igualmente.
+    // Nivel de corrección de errores (ECL)
+    if let Some(ecl_val) = ecl {
         hints.ErrorCorrection = Some(ecl_val.to_uppercase()); // Convertir a String
     }
 
@@ -155,20 +201,6 @@ pub fn generate_code(
          // Por ahora, si scale es 0, no establecemos el hint de margen.
     }
 
-    // TODO: Mapear otras opciones de BarcodeRequestOptions si es necesario
-    // Por ejemplo, para PDF417: min_columns, max_columns, compact
-    // Para DataMatrix: shape, compact
-    // Para Code128: compact, force_codeset
-    // if let Some(options) = ??? { // Necesitaríamos pasar las 'options' a esta función
-    //     if format == BarcodeFormat::PDF_417 {
-    //         if let Some(min_cols) = options.min_columns {
-    //              // hints.Pdf417Dimensions... necesita struct Dimensions
-    //         }
-    //         // ... etc ...
-    //     }
-    // }
-
-
     // Usar altura solo si se necesita para alguna lógica futura, si no, eliminar el let
     let _effective_height = height.unwrap_or(0) as i32;
     let width_hint = 0; // Usar 0 para que rxing decida el tamaño base
@@ -227,6 +259,25 @@ mod tests {
         check_is_svg(&result);
     }
 
+    #[test]
+    fn generate_matrix_codes_through_engine() {
+        let dmre = engine::MatrixCodeOptions {
+            datamatrix_shape: Some(engine::DataMatrixShape::Dmre),
+            ..Default::default()
+        };
+        let result = generate_code_with_options("data-matrix", "GTIN 09506000134352", 4, None, None, None, Some("#112233"), None, &dmre);
+        check_is_svg(&result);
+        let svg = result.unwrap();
+        assert!(svg.contains("#112233"));
+        // 26x12 módulos más 1 de zona silenciosa por lado
+        assert!(svg.contains(r#"viewBox="0 0 112 56""#), "{}", &svg[..200.min(svg.len())]);
+
+        check_is_svg(&generate_code("aztec", "Hello Aztec", 3, Some("50"), None, None, None, None));
+        check_is_svg(&generate_code("pdf417", "Hello PDF417", 3, Some("5"), None, None, None, None));
+        assert!(generate_code("pdf417", "Hello PDF417", 3, Some("9"), None, None, None, None).is_err());
+        assert!(generate_code("aztec", "Hello Aztec", 3, Some("X"), None, None, None, None).is_err());
+    }
+
     #[test]
     fn unsupported_type() {
         let result = generate_code("unsupported", "test", 3, None, None, None, None, None);
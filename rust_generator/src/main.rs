@@ -186,7 +186,11 @@ impl GenerationCache {
     // Almacena un valor en la caché con el TTL predeterminado
     fn put(&self, key: &CacheKey, svg: String) {
         let ttl_secs = self.default_ttl_secs.load(Ordering::Relaxed);
-        let effective_ttl = key.options.ttl_seconds.map(Duration::from_secs).unwrap_or_else(|| Duration::from_secs(ttl_secs));
+        // Un TTL de 0 en la petición equivale a no indicarlo
+        let effective_ttl = key.options.ttl_seconds
+            .filter(|&ttl| ttl > 0)
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| Duration::from_secs(ttl_secs));
         self.put_with_ttl(key, svg, effective_ttl);
     }
 
@@ -611,6 +615,15 @@ async fn main() {
         info!("Test metric recorded, check Redis for persistence");
     }
 
+    // Biblioteca de logos: se abre una vez y se inyecta en los motores que aceptan `logo_id`
+    let mut v3_engine = engine::QrEngine::new();
+    if let Some(store) = open_logo_store() {
+        if let Err(e) = engine::QR_ENGINE.attach_logo_store(store.clone()) {
+            error!("No se pudo inyectar la biblioteca de logos: {}", e);
+        }
+        v3_engine = v3_engine.with_logo_store(store);
+    }
+
     // Crear la capa CORS
     let cors = CorsLayer::new()
         .allow_origin([
@@ -650,7 +663,7 @@ async fn main() {
         .route("/api/qr/cache/warm", post(qr_cache_warm_handler))
         // QR Engine v3 - Structured data
         .nest("/", routes::qr_v3::routes(routes::qr_v3::QrV3State {
-            engine: Arc::new(engine::QrEngine::new()),
+            engine: Arc::new(v3_engine),
             cache: Arc::new(qreable_generator::cache::redis::RedisCache::new("redis://localhost:6379", "qr_engine_v3", 3600).unwrap_or_else(|_| qreable_generator::cache::redis::RedisCache::disabled())),
         }))
         .layer(cors); // Añadir la capa CORS
@@ -664,6 +677,23 @@ async fn main() {
         .unwrap();
 }
 
+/// Abre la biblioteca de logos en `LOGO_STORE_DIR` (por defecto `data/logos`); si no
+/// se puede abrir el servicio sigue funcionando sin `logo_id`
+fn open_logo_store() -> Option<Arc<engine::LogoStore>> {
+    let root = std::env::var("LOGO_STORE_DIR")
+        .unwrap_or_else(|_| engine::logo_store::DEFAULT_LOGO_STORE_DIR.to_string());
+    match engine::LogoStore::open(&root) {
+        Ok(store) => {
+            info!("Biblioteca de logos abierta en {}", root);
+            Some(Arc::new(store))
+        }
+        Err(e) => {
+            error!("No se pudo abrir la biblioteca de logos en {}: {}. logo_id deshabilitado.", root, e);
+            None
+        }
+    }
+}
+
 // Configura el sistema de logging
 fn setup_logging() {
     // Crear un appender para archivos de logs que rota diariamente
@@ -761,9 +791,22 @@ async fn generate_handler(Json(payload): Json<BarcodeRequest>) -> impl IntoRespo
     // Cache miss: Proceder a generar
     info!(type=%cache_key.barcode_type, cache="miss", options=?actual_options, "Cache miss, proceeding to generate barcode");
 
+    let matrix_options = match matrix_code_options(&actual_options) {
+        Ok(matrix_options) => matrix_options,
+        Err(validation_error) => {
+            error!("Validation error: {}", validation_error);
+            return handle_error(
+                validation_error.message,
+                StatusCode::BAD_REQUEST,
+                validation_error.suggestion,
+                Some(validation_error.code),
+            );
+        }
+    };
+
     // --- Llamada a la función de generación desde lib.rs --- 
     // Pasar todas las opciones extraídas de 'actual_options'
-    let generation_result = qreable_generator::generate_code( 
+    let generation_result = qreable_generator::generate_code_with_options( 
         &mapped_barcode_type, 
         &payload.data, 
         actual_options.scale,                  // Usar scale directamente de actual_options
@@ -772,6 +815,7 @@ async fn generate_handler(Json(payload): Json<BarcodeRequest>) -> impl IntoRespo
         actual_options.includetext,            
         actual_options.fgcolor.as_deref(),     
         actual_options.bgcolor.as_deref(),     
+        &matrix_options,
     );
     // ---------------------------------------------------------
     
@@ -865,6 +909,31 @@ fn handle_error(
         .into_response()
 }
 
+// Opciones de la API v1 para PDF417 y Aztec (columnas y modo compacto)
+fn matrix_code_options(
+    options: &BarcodeRequestOptions,
+) -> Result<qreable_generator::engine::MatrixCodeOptions, validators::ValidationError> {
+    let columns = |value: Option<u32>, code: &str| {
+        value
+            .map(|columns| {
+                u8::try_from(columns).map_err(|_| validators::ValidationError {
+                    code: code.to_string(),
+                    message: format!("Número de columnas inválido: {}", columns),
+                    suggestion: Some("El valor debe estar entre 1 y 30".to_string()),
+                })
+            })
+            .transpose()
+    };
+
+    Ok(qreable_generator::engine::MatrixCodeOptions {
+        pdf417_min_columns: columns(options.min_columns, "PDF417_INVALID_MIN_COLUMNS")?,
+        pdf417_max_columns: columns(options.max_columns, "PDF417_INVALID_MAX_COLUMNS")?,
+        pdf417_compact: options.compact,
+        aztec_compact: options.compact,
+        ..Default::default()
+    })
+}
+
 // Handler para batch processing
 async fn batch_generate_handler(Json(payload): Json<BatchBarcodeRequest>) -> impl IntoResponse {
     let batch_start_time = Instant::now();
@@ -1061,8 +1130,10 @@ async fn process_single_barcode(
 
     cache_misses.fetch_add(1, Ordering::Relaxed);
 
+    let matrix_options = matrix_code_options(&actual_options).map_err(|e| e.message)?;
+
     // Generar código
-    let generation_result = qreable_generator::generate_code(
+    let generation_result = qreable_generator::generate_code_with_options(
         &mapped_barcode_type,
         &request.data,
         actual_options.scale,
@@ -1071,6 +1142,7 @@ async fn process_single_barcode(
         actual_options.includetext,
         actual_options.fgcolor.as_deref(),
         actual_options.bgcolor.as_deref(),
+        &matrix_options,
     );
 
     match generation_result {
@@ -1353,6 +1425,22 @@ mod tests {
         assert_eq!(result, Some(svg));
     }
 
+    #[test]
+    fn test_matrix_code_options_columns() {
+        let options = BarcodeRequestOptions {
+            min_columns: Some(3),
+            max_columns: Some(5),
+            ..Default::default()
+        };
+        let matrix = matrix_code_options(&options).unwrap();
+        assert_eq!((matrix.pdf417_min_columns, matrix.pdf417_max_columns), (Some(3), Some(5)));
+
+        // Un valor que no cabe en u8 no se trunca: se rechaza
+        let overflow = BarcodeRequestOptions { max_columns: Some(259), ..Default::default() };
+        let error = matrix_code_options(&overflow).unwrap_err();
+        assert_eq!(error.code, "PDF417_INVALID_MAX_COLUMNS");
+    }
+
     #[test]
     fn test_cache_eviction() {
         let cache = GenerationCache::new(1, 60);
@@ -1455,6 +1543,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_integration_generate_and_cache() {
+        let _ = CACHE.set(GenerationCache::new(100, 60));
         let app = Router::new()
             .route("/generate", post(generate_handler))
             .route("/cache/clear", post(clear_cache_handler));
@@ -1778,20 +1867,9 @@ async fn qr_v2_preview_handler(
         
         Some(QrCustomization {
             eye_shape,
-            eye_border_style: None,
-            eye_center_style: None,
             data_pattern,
             colors,
-            gradient: None,
-            eye_border_gradient: None,
-            eye_center_gradient: None,
-            logo: None,
-            frame: None,
-            effects: None,
-            error_correction: None,
-            logo_size_ratio: None,
-            selective_effects: None,
-            fixed_size: None,
+            ..Default::default()
         })
     } else {
         None
@@ -1802,6 +1880,7 @@ async fn qr_v2_preview_handler(
         size,
         format: OutputFormat::Svg,
         customization,
+        ..Default::default()
     };
     
     // Generar QR
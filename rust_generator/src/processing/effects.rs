@@ -102,26 +102,51 @@ impl EffectProcessor {
     /// Crea un filtro de sombra SVG
     pub fn create_shadow_filter(&self, filter_id: &str, config: Option<ShadowConfig>) -> QrResult<String> {
         let cfg = config.unwrap_or_default();
+
+        if cfg.blur_radius < 0.0 {
+            return Err(QrError::ValidationError("El radio de blur no puede ser negativo".to_string()));
+        }
+        if !(0.0..=1.0).contains(&cfg.opacity) {
+            return Err(QrError::ValidationError("La opacidad debe estar entre 0 y 1".to_string()));
+        }
+
+        let color = ColorProcessor::parse_color(&cfg.color)?;
         Ok(format!(
-            r#"<filter id="{}">
-                <feDropShadow dx="{}" dy="{}" stdDeviation="{}" flood-color="{}" flood-opacity="{}"/>
+            r#"<filter id="{}" x="-50%" y="-50%" width="200%" height="200%">
+                <feGaussianBlur in="SourceAlpha" stdDeviation="{:.2}"/>
+                <feOffset dx="{:.2}" dy="{:.2}" result="offsetblur"/>
+                <feFlood flood-color="rgb({},{},{})" flood-opacity="{:.2}"/>
+                <feComposite in2="offsetblur" operator="in"/>
+                <feMerge>
+                    <feMergeNode/>
+                    <feMergeNode in="SourceGraphic"/>
+                </feMerge>
                </filter>"#,
-            filter_id, cfg.offset_x, cfg.offset_y, cfg.blur_radius, cfg.color, cfg.opacity
+            filter_id, cfg.blur_radius, cfg.offset_x, cfg.offset_y, color.r, color.g, color.b, cfg.opacity
         ))
     }
 
     /// Crea un filtro de resplandor SVG
     pub fn create_glow_filter(&self, filter_id: &str, config: Option<GlowConfig>) -> QrResult<String> {
         let cfg = config.unwrap_or_default();
+
+        if cfg.intensity < 0.0 {
+            return Err(QrError::ValidationError("La intensidad no puede ser negativa".to_string()));
+        }
+
+        let color = ColorProcessor::parse_color(&cfg.color)?;
         Ok(format!(
-            r#"<filter id="{}">
-                <feGaussianBlur stdDeviation="{}" result="coloredBlur"/>
+            r#"<filter id="{}" x="-50%" y="-50%" width="200%" height="200%">
+                <feMorphology operator="dilate" radius="{:.2}" in="SourceAlpha" result="thicken"/>
+                <feGaussianBlur in="thicken" stdDeviation="{:.2}" result="blurred"/>
+                <feFlood flood-color="rgb({},{},{})" result="glowColor"/>
+                <feComposite in="glowColor" in2="blurred" operator="in" result="softGlow"/>
                 <feMerge>
-                    <feMergeNode in="coloredBlur"/>
+                    <feMergeNode in="softGlow"/>
                     <feMergeNode in="SourceGraphic"/>
                 </feMerge>
                </filter>"#,
-            filter_id, cfg.intensity
+            filter_id, cfg.intensity * 0.5, cfg.intensity * 2.0, color.r, color.g, color.b
         ))
     }
 
@@ -139,6 +164,9 @@ impl EffectProcessor {
     /// Crea un filtro de ruido SVG
     pub fn create_noise_filter(&self, filter_id: &str, config: Option<NoiseConfig>) -> QrResult<String> {
         let cfg = config.unwrap_or_default();
+        if cfg.intensity < 0.0 {
+            return Err(QrError::ValidationError("La intensidad del ruido no puede ser negativa".to_string()));
+        }
         Ok(format!(
             r#"<filter id="{}">
                 <feTurbulence baseFrequency="{}" numOctaves="3" result="noise"/>
@@ -169,7 +197,7 @@ impl EffectProcessor {
     /// Crear filtro de distorsión
     pub fn create_distort_filter(&self, filter_id: &str, _config: Option<&crate::engine::types::EffectConfiguration>) -> QrResult<String> {
         Ok(format!(
-            r#"<filter id="{}">
+            r#"<filter id="{}" x="-50%" y="-50%" width="200%" height="200%">
                 <feTurbulence baseFrequency="0.02" numOctaves="3" result="noise"/>
                 <feDisplacementMap in="SourceGraphic" in2="noise" scale="10"/>
                </filter>"#,
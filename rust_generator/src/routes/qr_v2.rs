@@ -7,13 +7,17 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, error};
 use std::time::Instant;
 
-use crate::engine::QR_ENGINE;
-use crate::engine::types::{QrRequest as EngineQrRequest, OutputFormat, QrCustomization};
+use crate::engine::{QrPayload, QR_ENGINE};
+use crate::engine::types::{QrRequest as EngineQrRequest, OutputFormat, QrCustomization, LogoPlacement, MaskSelection, DEFAULT_QR_SIZE};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QrGenerateRequest {
+    #[serde(default)]
     pub data: String,
     pub options: Option<QrOptions>,
+    /// Contenido estructurado (WiFi, vCard, evento...) en lugar de `data`
+    #[serde(default)]
+    pub payload: Option<QrPayload>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -22,6 +26,17 @@ pub struct QrOptions {
     pub size: Option<u32>,
     pub margin: Option<u32>,
     pub error_correction: Option<String>,
+    /// Codificar `data` como cadena de elementos GS1 (FNC1 en primera posición)
+    #[serde(default)]
+    pub gs1: Option<bool>,
+    /// Versión (1-40) y máscara (0-7) explícitas
+    #[serde(default)]
+    pub version: Option<u8>,
+    #[serde(default)]
+    pub mask: Option<u8>,
+    /// Criterio de máscara: "standard" (penalización ISO) o "design"
+    #[serde(default)]
+    pub mask_selection: Option<MaskSelection>,
     
     // Basic customization
     pub eye_shape: Option<String>,
@@ -35,6 +50,9 @@ pub struct QrOptions {
     // Advanced features
     pub gradient: Option<GradientOptions>,
     pub logo: Option<LogoOptions>,
+    /// Logos adicionales (sellos, insignias) junto al principal
+    #[serde(default)]
+    pub overlays: Option<Vec<LogoOptions>>,
     pub frame: Option<FrameOptions>,
     pub effects: Option<Vec<EffectOptions>>,
     
@@ -64,10 +82,34 @@ pub struct StrokeStyle {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LogoOptions {
+    #[serde(default)]
     pub data: String, // Base64
+    #[serde(default)]
+    pub logo_id: Option<String>, // Logo subido a /api/v3/logos
     pub size: Option<f32>,
     pub padding: Option<u32>,
     pub background_color: Option<String>,
+    /// Ancla (`"bottom_right"`...) o `{"x": .., "y": ..}` en módulos; centrado por defecto
+    #[serde(default)]
+    pub placement: Option<LogoPlacement>,
+}
+
+impl LogoOptions {
+    fn to_engine(&self) -> crate::engine::types::LogoOptions {
+        crate::engine::types::LogoOptions {
+            data: self.data.clone(),
+            logo_id: self.logo_id.clone(),
+            size_percentage: self.size.unwrap_or(20.0),
+            padding: self.padding.unwrap_or(2),
+            background: self.background_color.clone(),
+            shape: match self.background_color.as_deref() {
+                Some("circle") => crate::engine::types::LogoShape::Circle,
+                Some("rounded-square") => crate::engine::types::LogoShape::RoundedSquare,
+                _ => crate::engine::types::LogoShape::Square,
+            },
+            placement: self.placement,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -144,8 +186,6 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
                     _ => None
                 }
             }),
-            eye_border_style: None,
-            eye_center_style: None,
             data_pattern: options.data_pattern.as_ref().and_then(|s| {
                 // Convert string to DataPattern enum
                 match s.as_str() {
@@ -207,19 +247,8 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
                     }),
                 }
             }),
-            logo: options.logo.as_ref().map(|l| {
-                crate::engine::types::LogoOptions {
-                    data: l.data.clone(),
-                    size_percentage: l.size.unwrap_or(20.0),
-                    padding: l.padding.unwrap_or(2),
-                    background: l.background_color.clone(),
-                    shape: match l.background_color.as_ref().map(|s| s.as_str()) {
-                        Some("circle") => crate::engine::types::LogoShape::Circle,
-                        Some("rounded-square") => crate::engine::types::LogoShape::RoundedSquare,
-                        _ => crate::engine::types::LogoShape::Square,
-                    },
-                }
-            }),
+            logo: options.logo.as_ref().map(LogoOptions::to_engine),
+            overlays: options.overlays.as_ref().map(|overlays| overlays.iter().map(LogoOptions::to_engine).collect()),
             frame: options.frame.as_ref().map(|f| {
                 crate::engine::types::FrameOptions {
                     frame_type: match f.style.as_str() {
@@ -292,11 +321,12 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
                     _ => None
                 }
             }),
-            eye_border_gradient: None,
-            eye_center_gradient: None,
-            selective_effects: None,
-            logo_size_ratio: None,
-            fixed_size: None,
+            quiet_zone: options.margin.map(|margin| margin as usize),
+            gs1_mode: options.gs1,
+            version: options.version,
+            mask: options.mask,
+            mask_selection: options.mask_selection,
+            ..Default::default()
         })
     } else {
         None
@@ -304,9 +334,11 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
 
     let engine_request = EngineQrRequest {
         data: request.data.clone(),
-        size: request.options.as_ref().and_then(|o| o.size).unwrap_or(300),
+        size: request.options.as_ref().and_then(|o| o.size).unwrap_or(DEFAULT_QR_SIZE),
         format: OutputFormat::Svg, // Default to SVG
         customization: customization.clone(),
+        payload: request.payload.clone(),
+        ..Default::default()
     };
     
     // Use the global QR engine
@@ -437,6 +469,7 @@ pub async fn preview_handler(Query(params): Query<QrPreviewQuery>) -> impl IntoR
     let _request = QrGenerateRequest {
         data: params.data,
         options: Some(options),
+        payload: None,
     };
     
     // For now, just generate and return SVG directly
@@ -499,6 +532,10 @@ impl Default for QrOptions {
             size: Some(300),
             margin: Some(4),
             error_correction: Some("M".to_string()),
+            gs1: None,
+            version: None,
+            mask: None,
+            mask_selection: None,
             eye_shape: None,
             data_pattern: None,
             foreground_color: Some("#000000".to_string()),
@@ -506,6 +543,7 @@ impl Default for QrOptions {
             eye_color: None,
             gradient: None,
             logo: None,
+            overlays: None,
             frame: None,
             effects: None,
             optimize_for_size: Some(false),
@@ -7,20 +7,24 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, error};
 use std::time::Instant;
 
-use crate::engine::{QrError, QR_ENGINE};
+use crate::engine::{QrError, QrPayload, QR_ENGINE};
 use crate::engine::types::{
     QrRequest as EngineQrRequest, QrOutput, OutputFormat, QrCustomization,
     EyeShape, DataPattern, ColorOptions, GradientOptions, LogoOptions,
     FrameOptions, EffectOptions, ErrorCorrectionLevel, GradientType,
-    LogoShape, FrameType, TextPosition, Effect, EffectConfiguration
+    LogoShape, FrameType, TextPosition, Effect, EffectConfiguration, DEFAULT_QR_SIZE
 };
 
 // API Request/Response structures that match frontend expectations
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QrGenerateRequest {
+    #[serde(default)]
     pub data: String,
     pub options: Option<QrOptions>,
+    /// Contenido estructurado (WiFi, vCard, evento...) en lugar de `data`
+    #[serde(default)]
+    pub payload: Option<QrPayload>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,6 +33,9 @@ pub struct QrOptions {
     pub size: Option<u32>,
     pub margin: Option<u32>,
     pub error_correction: Option<String>,
+    /// Codificar `data` como cadena de elementos GS1 (FNC1 en primera posición)
+    #[serde(default)]
+    pub gs1: Option<bool>,
     
     // Basic customization
     pub eye_shape: Option<String>,
@@ -223,10 +230,12 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
             }),
             logo: opts.logo.as_ref().map(|l| LogoOptions {
                 data: l.data.clone(),
+                logo_id: None,
                 size_percentage: l.size.unwrap_or(20.0),
                 padding: l.padding.unwrap_or(5),
                 background: l.background_color.clone(),
                 shape: l.shape.as_ref().map(|s| parse_logo_shape(s)).unwrap_or(LogoShape::Square),
+                placement: None,
             }),
             frame: opts.frame.as_ref().map(|f| FrameOptions {
                 frame_type: parse_frame_type(&f.style),
@@ -269,17 +278,20 @@ pub async fn generate_handler(Json(request): Json<QrGenerateRequest>) -> impl In
                 }).collect()
             }),
             error_correction: opts.error_correction.as_ref().map(|ec| parse_error_correction(ec)),
-            logo_size_ratio: None,
-            selective_effects: None,
+            quiet_zone: opts.margin.map(|margin| margin as usize),
+            gs1_mode: opts.gs1,
+            ..Default::default()
         }
     });
     
     // Build engine request
     let engine_request = EngineQrRequest {
         data: request.data.clone(),
-        size: request.options.as_ref().and_then(|o| o.size).unwrap_or(300),
+        size: request.options.as_ref().and_then(|o| o.size).unwrap_or(DEFAULT_QR_SIZE),
         format: OutputFormat::Svg,
         customization,
+        payload: request.payload.clone(),
+        ..Default::default()
     };
     
     // Use the global QR engine
@@ -453,6 +465,7 @@ pub async fn preview_handler(Query(params): Query<QrPreviewQuery>) -> impl IntoR
     let request = QrGenerateRequest {
         data: params.data,
         options: Some(options),
+        payload: None,
     };
     
     match generate_handler(Json(request)).await.into_response() {
@@ -484,6 +497,7 @@ impl Default for QrOptions {
             size: Some(300),
             margin: Some(4),
             error_correction: Some("M".to_string()),
+            gs1: None,
             eye_shape: None,
             data_pattern: None,
             foreground_color: Some("#000000".to_string()),
@@ -1,10 +1,11 @@
 // routes/qr_v3.rs - API v3 con datos estructurados (QR v3)
 
 use axum::{
-    extract::State,
+    body::Bytes,
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
-    routing::post,
+    routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -13,15 +14,27 @@ use std::sync::Arc;
 use std::time::Instant;
 use tracing::{error, info, instrument};
 
-use qreable_generator::engine::{QrEngine, QrCustomization, ErrorCorrectionLevel, error::QrError};
+use qreable_generator::engine::{QrEngine, QrCustomization, QrPayload, ErrorCorrectionLevel, DEFAULT_QR_SIZE, error::QrError};
+use qreable_generator::engine::payload::resolve_data;
+use qreable_generator::engine::ecl_optimizer::EclOptimizer;
+use qreable_generator::engine::logo_store::{LogoRecord, LogoReference};
+use qreable_generator::engine::reporter::QualityReporter;
+use qreable_generator::engine::types::QrSymbology;
+use qreable_generator::standards::decoder::{ContentType, DecodeQuality, DecodedData, ImageUpload};
+use qreable_generator::standards::{PrintQualityGrader, PrintQualityReport, QrDecoder};
 use qreable_generator::cache::redis;
 
 /// Request para generación v3
 #[derive(Debug, Clone, Deserialize)]
 pub struct QrV3Request {
-    /// Datos a codificar
+    /// Datos a codificar (vacío si se envía `payload`)
+    #[serde(default)]
     pub data: String,
     
+    /// Contenido estructurado (WiFi, vCard, evento...) en lugar de `data`
+    #[serde(default)]
+    pub payload: Option<QrPayload>,
+    
     /// Opciones de personalización
     #[serde(default)]
     pub options: QrV3Options,
@@ -36,6 +49,11 @@ pub struct QrV3Options {
     
     /// Customización completa (para features avanzadas)
     pub customization: Option<QrCustomization>,
+    
+    /// Lado en píxeles al que se dibujará el código (por defecto `DEFAULT_QR_SIZE`);
+    /// fija la resolución con la que se analiza la silueta de los logos
+    #[serde(default)]
+    pub size: Option<u32>,
 }
 
 /// Response v3 con datos estructurados
@@ -76,6 +94,119 @@ pub struct QrV3Metadata {
     pub processing_time_ms: u64,
 }
 
+/// Parámetros de `/api/v3/qr/decode`
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct QrV3DecodeQuery {
+    /// Formato del reporte de calidad por símbolo (`text` o `html`); sin él no se genera
+    #[serde(default)]
+    pub report: Option<String>,
+}
+
+/// Parámetros de `DELETE /api/v3/logos/:logo_id`
+#[derive(Debug, Clone, Deserialize)]
+pub struct QrV3LogoReleaseQuery {
+    /// Referencia devuelta al subir el logo
+    pub reference_id: String,
+}
+
+/// Response de decodificación v3
+#[derive(Debug, Clone, Serialize)]
+pub struct QrV3DecodeResponse {
+    /// Indicador de éxito
+    pub success: bool,
+    
+    /// Símbolos leídos en la imagen
+    pub codes: Vec<QrV3DecodedCode>,
+    
+    /// Información de error si falla
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<QrV3Error>,
+    
+    /// Metadata adicional
+    pub metadata: QrV3Metadata,
+}
+
+/// Símbolo leído de la imagen
+#[derive(Debug, Clone, Serialize)]
+pub struct QrV3DecodedCode {
+    /// Contenido tal como se leyó
+    pub data: String,
+    
+    /// Tipo de contenido detectado
+    pub content_type: ContentType,
+    
+    /// Simbología del símbolo
+    pub symbology: QrSymbology,
+    
+    /// Contenido estructurado (WiFi, vCard, evento...) si el formato es conocido
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<QrPayload>,
+    
+    /// Elementos GS1 (cadena de elementos o Digital Link)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gs1_elements: Vec<QrV3Gs1Element>,
+    
+    /// Versión QR (null si el lector no la informa)
+    pub version: Option<u8>,
+    
+    /// Máscara aplicada (null si el lector no la informa)
+    pub mask_pattern: Option<u8>,
+    
+    /// Nivel de corrección (vacío fuera de la familia QR)
+    pub error_correction: String,
+    
+    /// Calidad estimada de la lectura
+    pub quality: DecodeQuality,
+    
+    /// Grados ISO/IEC 15415, solo si la imagen contiene un único código QR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub print_quality: Option<PrintQualityReport>,
+    
+    /// Reporte de calidad en el formato pedido
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<String>,
+}
+
+/// Elemento GS1 leído
+#[derive(Debug, Clone, Serialize)]
+pub struct QrV3Gs1Element {
+    pub ai: String,
+    pub value: String,
+}
+
+/// Response de la biblioteca de logos
+#[derive(Debug, Clone, Serialize)]
+pub struct QrV3LogoResponse {
+    /// Indicador de éxito
+    pub success: bool,
+    
+    /// Logo almacenado (tras subirlo o consultarlo)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<LogoRecord>,
+    
+    /// Referencia adquirida al subir; necesaria para liberarla
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_id: Option<String>,
+    
+    /// Referencias restantes tras liberar el logo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_refs: Option<u32>,
+    
+    /// Información de error si falla
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<QrV3Error>,
+    
+    /// Metadata adicional
+    pub metadata: QrV3Metadata,
+}
+
+/// Formato del reporte de calidad
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    Text,
+    Html,
+}
+
 /// Estado compartido para v3
 #[derive(Clone)]
 pub struct QrV3State {
@@ -83,11 +214,28 @@ pub struct QrV3State {
     pub cache: Arc<redis::RedisCache>,
 }
 
+/// Optimizador de ECL con la silueta del logo principal y los overlays de la personalización,
+/// cargados al tamaño de salida
+fn logo_ecl_optimizer(
+    engine: &QrEngine,
+    customization: Option<&QrCustomization>,
+    size: u32,
+) -> Result<EclOptimizer, QrError> {
+    let Some(customization) = customization else {
+        return Ok(EclOptimizer::new());
+    };
+    let logos = engine.prepare_logos(customization, size)?;
+    Ok(EclOptimizer::for_customization(customization, &logos))
+}
+
 /// Crea las rutas v3
 pub fn routes(state: QrV3State) -> Router {
     Router::new()
         .route("/api/v3/qr/generate", post(generate_qr_v3))
         .route("/api/v3/qr/enhanced", post(generate_qr_v3_enhanced))
+        .route("/api/v3/qr/decode", post(decode_qr_v3))
+        .route("/api/v3/logos", post(upload_logo_v3))
+        .route("/api/v3/logos/:logo_id", get(get_logo_v3).delete(release_logo_v3))
         .with_state(state)
 }
 
@@ -95,9 +243,30 @@ pub fn routes(state: QrV3State) -> Router {
 #[instrument(skip(state, payload))]
 async fn generate_qr_v3(
     State(state): State<QrV3State>,
-    Json(payload): Json<QrV3Request>,
+    Json(mut payload): Json<QrV3Request>,
 ) -> Result<Json<QrV3Response>, StatusCode> {
     let start = Instant::now();
+    
+    // El contenido estructurado se convierte en `data` antes de validar
+    let structured = payload.payload.take();
+    payload.data = match resolve_data(std::mem::take(&mut payload.data), structured.as_ref()) {
+        Ok(data) => data,
+        Err(e) => {
+            return Ok(Json(QrV3Response {
+                success: false,
+                data: None,
+                error: Some(QrV3Error {
+                    code: "INVALID_PAYLOAD".to_string(),
+                    message: e.to_string(),
+                }),
+                metadata: QrV3Metadata {
+                    engine_version: "3.0.0".to_string(),
+                    cached: false,
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                },
+            }));
+        }
+    };
     info!("QR v3 generation request for data length: {}", payload.data.len());
     
     // Validar entrada
@@ -119,12 +288,13 @@ async fn generate_qr_v3(
     
     // Generar cache key basado en contenido y opciones
     let cache_key = format!(
-        "qrv3:{}:{}:{}",
+        "qrv3:{}:{}:{}:{}",
         sha2::Sha256::digest(payload.data.as_bytes())
             .iter()
             .map(|b| format!("{:02x}", b))
             .collect::<String>(),
-        payload.options.error_correction.as_deref().unwrap_or("M"),
+        payload.options.error_correction.as_deref().unwrap_or("auto"),
+        payload.options.size.unwrap_or(DEFAULT_QR_SIZE),
         serde_json::to_string(&payload.options.customization).unwrap_or_default()
     );
     
@@ -149,20 +319,22 @@ async fn generate_qr_v3(
     let result = tokio::task::spawn_blocking({
         let data = payload.data.clone();
         let options = payload.options.clone();
+        let engine = state.engine.clone();
         
         move || -> Result<qreable_generator::engine::types::QrStructuredOutput, QrError> {
             use qreable_generator::engine::generator::QrGenerator;
             
-            // Convertir nivel de corrección de string a enum
-            let ecl = options.error_correction
+            // Nivel pedido; sin él, con logo lo elige el optimizador (Medium sin logo)
+            let ecl_override = options.error_correction
                 .and_then(|ecl_str| match ecl_str.as_str() {
                     "L" => Some(ErrorCorrectionLevel::Low),
                     "M" => Some(ErrorCorrectionLevel::Medium),
                     "Q" => Some(ErrorCorrectionLevel::Quartile),
                     "H" => Some(ErrorCorrectionLevel::High),
                     _ => None,
-                })
-                .unwrap_or(ErrorCorrectionLevel::Medium);
+                });
+            let ecl = ecl_override.unwrap_or(ErrorCorrectionLevel::Medium);
+            let size = options.size.unwrap_or(DEFAULT_QR_SIZE);
             
             // Crear generador y generar código QR directamente
             let generator = QrGenerator::new();
@@ -181,11 +353,15 @@ async fn generate_qr_v3(
                     .and_then(|c| c.logo_size_ratio)
                     .unwrap_or(0.2);
                 
-                let (qr, analysis) = generator.generate_with_dynamic_ecl(
+                // La zona de exclusión sigue la silueta del logo y suma los overlays
+                let optimizer = logo_ecl_optimizer(&engine, options.customization.as_ref(), size)?;
+                let (qr, analysis) = generator.generate_with_ecl_optimizer(
+                    &optimizer,
                     &data,
-                    400,
+                    size,
                     logo_ratio,
-                    Some(ecl),
+                    ecl_override,
+                    true,
                 )?;
                 
                 let exclusion = Some(qreable_generator::engine::types::ExclusionInfo {
@@ -193,12 +369,14 @@ async fn generate_qr_v3(
                     affected_codewords: analysis.affected_codewords,
                     occlusion_percentage: analysis.occlusion_percentage,
                     selected_ecl: format!("{:?}", analysis.recommended_ecl),
-                    ecl_override: false,
+                    ecl_override: ecl_override.is_some(),
+                    recoverable: analysis.recoverable,
+                    block_damage: analysis.block_damage,
                 });
                 
                 (qr, exclusion)
             } else {
-                let qr = generator.generate_with_ecl(&data, 400, ecl)?;
+                let qr = generator.generate_with_ecl(&data, size, ecl)?;
                 (qr, None)
             };
             
@@ -235,9 +413,9 @@ async fn generate_qr_v3(
                 let cached_qr = redis::CachedQR {
                     svg: json_data,
                     metadata: redis::QRMetadata {
-                        version: 1,
-                        modules: structured_output.total_modules as usize,
-                        error_correction: structured_output.error_correction.clone(),
+                        version: None,
+                        modules: Some(structured_output.total_modules as usize),
+                        error_correction: Some(structured_output.error_correction.clone()),
                         processing_time_ms: structured_output.metadata.generation_time_ms,
                     },
                     generated_at: chrono::Utc::now().timestamp(),
@@ -280,9 +458,29 @@ async fn generate_qr_v3(
 #[instrument(skip(state, payload))]
 async fn generate_qr_v3_enhanced(
     State(state): State<QrV3State>,
-    Json(payload): Json<QrV3Request>,
+    Json(mut payload): Json<QrV3Request>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let start = Instant::now();
+    
+    // El contenido estructurado se convierte en `data` antes de validar
+    let structured = payload.payload.take();
+    payload.data = match resolve_data(std::mem::take(&mut payload.data), structured.as_ref()) {
+        Ok(data) => data,
+        Err(e) => {
+            return Ok(Json(serde_json::json!({
+                "success": false,
+                "error": {
+                    "code": "INVALID_PAYLOAD",
+                    "message": e.to_string()
+                },
+                "metadata": {
+                    "engine_version": "3.0.0-enhanced",
+                    "cached": false,
+                    "processing_time_ms": start.elapsed().as_millis()
+                }
+            })));
+        }
+    };
     info!("QR v3 Enhanced generation request for data length: {}", payload.data.len());
     
     // Log gradient configuration if present
@@ -315,12 +513,13 @@ async fn generate_qr_v3_enhanced(
     
     // Generar cache key incluyendo customization
     let cache_key = format!(
-        "qrv3e:{}:{}:{}",
+        "qrv3e:{}:{}:{}:{}",
         sha2::Sha256::digest(payload.data.as_bytes())
             .iter()
             .map(|b| format!("{:02x}", b))
             .collect::<String>(),
-        payload.options.error_correction.as_deref().unwrap_or("M"),
+        payload.options.error_correction.as_deref().unwrap_or("auto"),
+        payload.options.size.unwrap_or(DEFAULT_QR_SIZE),
         serde_json::to_string(&payload.options.customization).unwrap_or_default()
     );
     
@@ -344,20 +543,22 @@ async fn generate_qr_v3_enhanced(
     let result = tokio::task::spawn_blocking({
         let data = payload.data.clone();
         let options = payload.options.clone();
+        let engine = state.engine.clone();
         
         move || -> Result<qreable_generator::engine::types::QrEnhancedOutput, QrError> {
             use qreable_generator::engine::generator::QrGenerator;
             
-            // Convertir nivel de corrección
-            let ecl = options.error_correction
+            // Nivel pedido; sin él, con logo lo elige el optimizador (Medium sin logo)
+            let ecl_override = options.error_correction
                 .and_then(|ecl_str| match ecl_str.as_str() {
                     "L" => Some(ErrorCorrectionLevel::Low),
                     "M" => Some(ErrorCorrectionLevel::Medium),
                     "Q" => Some(ErrorCorrectionLevel::Quartile),
                     "H" => Some(ErrorCorrectionLevel::High),
                     _ => None,
-                })
-                .unwrap_or(ErrorCorrectionLevel::Medium);
+                });
+            let ecl = ecl_override.unwrap_or(ErrorCorrectionLevel::Medium);
+            let size = options.size.unwrap_or(DEFAULT_QR_SIZE);
             
             // Crear generador
             let generator = QrGenerator::new();
@@ -380,11 +581,15 @@ async fn generate_qr_v3_enhanced(
                 
                 info!("Generating QR with dynamic ECL. Logo ratio: {}", logo_ratio);
                 
-                let (mut qr, analysis) = generator.generate_with_dynamic_ecl(
+                // La zona de exclusión sigue la silueta del logo y suma los overlays
+                let optimizer = logo_ecl_optimizer(&engine, options.customization.as_ref(), size)?;
+                let (qr, analysis) = generator.generate_with_ecl_optimizer(
+                    &optimizer,
                     &data,
-                    400,
+                    size,
                     logo_ratio,
-                    Some(ecl),
+                    ecl_override,
+                    true,
                 )?;
                 
                 // Convertir análisis a ExclusionInfo
@@ -393,7 +598,9 @@ async fn generate_qr_v3_enhanced(
                     affected_codewords: analysis.affected_codewords,
                     occlusion_percentage: analysis.occlusion_percentage,
                     selected_ecl: format!("{:?}", analysis.recommended_ecl),
-                    ecl_override: false,
+                    ecl_override: ecl_override.is_some(),
+                    recoverable: analysis.recoverable,
+                    block_damage: analysis.block_damage,
                 });
                 
                 info!("Dynamic ECL analysis complete. Occluded modules: {}, ECL: {:?}", 
@@ -402,7 +609,7 @@ async fn generate_qr_v3_enhanced(
                 (qr, exclusion)
             } else {
                 // Generación estándar sin ECL dinámico
-                let qr = generator.generate_with_ecl(&data, 400, ecl)?;
+                let qr = generator.generate_with_ecl(&data, size, ecl)?;
                 (qr, None)
             };
             
@@ -446,9 +653,9 @@ async fn generate_qr_v3_enhanced(
                 let cached_qr = redis::CachedQR {
                     svg: json_data,
                     metadata: redis::QRMetadata {
-                        version: 1,
-                        modules: enhanced_output.paths.data.len(),
-                        error_correction: enhanced_output.metadata.error_correction.clone(),
+                        version: None,
+                        modules: Some(enhanced_output.paths.data.len()),
+                        error_correction: Some(enhanced_output.metadata.error_correction.clone()),
                         processing_time_ms: enhanced_output.metadata.generation_time_ms,
                     },
                     generated_at: chrono::Utc::now().timestamp(),
@@ -485,6 +692,243 @@ async fn generate_qr_v3_enhanced(
     }
 }
 
+/// Handler de decodificación: el cuerpo es la imagen (PNG, JPEG o SVG) sin codificar
+#[instrument(skip(body))]
+async fn decode_qr_v3(
+    Query(query): Query<QrV3DecodeQuery>,
+    body: Bytes,
+) -> Result<Json<QrV3DecodeResponse>, StatusCode> {
+    let start = Instant::now();
+    info!("QR v3 decode request for {} bytes", body.len());
+    
+    let result = tokio::task::spawn_blocking(move || decode_upload(&body, query.report.as_deref()))
+        .await
+        .map_err(|e| {
+            error!("Task join error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    let metadata = QrV3Metadata {
+        engine_version: "3.0.0".to_string(),
+        cached: false,
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    };
+    Ok(Json(match result {
+        Ok(codes) => {
+            info!("QR v3 decoded {} symbols", codes.len());
+            QrV3DecodeResponse { success: true, codes, error: None, metadata }
+        }
+        Err(e) => {
+            error!("QR v3 decode error: {:?}", e);
+            QrV3DecodeResponse { success: false, codes: Vec::new(), error: Some(e), metadata }
+        }
+    }))
+}
+
+/// Sube un logo a la biblioteca; el cuerpo es la imagen sin codificar
+#[instrument(skip(state, body))]
+async fn upload_logo_v3(
+    State(state): State<QrV3State>,
+    body: Bytes,
+) -> Result<Json<QrV3LogoResponse>, StatusCode> {
+    let start = Instant::now();
+    info!("Logo upload of {} bytes", body.len());
+    
+    let result = match state.engine.logo_store().cloned() {
+        Some(store) => tokio::task::spawn_blocking(move || store.put(&body))
+            .await
+            .map_err(|e| {
+                error!("Task join error: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .map_err(logo_error),
+        None => Err(logo_store_unavailable()),
+    };
+    Ok(Json(logo_response(result.map(LogoOutcome::uploaded), start)))
+}
+
+/// Consulta un logo de la biblioteca
+#[instrument(skip(state))]
+async fn get_logo_v3(
+    State(state): State<QrV3State>,
+    Path(logo_id): Path<String>,
+) -> Result<Json<QrV3LogoResponse>, StatusCode> {
+    let start = Instant::now();
+    
+    let result = match state.engine.logo_store() {
+        Some(store) => match store.get(&logo_id) {
+            Ok(Some(record)) => Ok(LogoOutcome { logo: Some(record), ..Default::default() }),
+            Ok(None) => Err(QrV3Error {
+                code: "LOGO_NOT_FOUND".to_string(),
+                message: format!("Logo {} not found", logo_id),
+            }),
+            Err(e) => Err(logo_error(e)),
+        },
+        None => Err(logo_store_unavailable()),
+    };
+    Ok(Json(logo_response(result, start)))
+}
+
+/// Libera la referencia `reference_id` adquirida al subir el logo; la última lo borra
+/// de la biblioteca
+#[instrument(skip(state, query))]
+async fn release_logo_v3(
+    State(state): State<QrV3State>,
+    Path(logo_id): Path<String>,
+    Query(query): Query<QrV3LogoReleaseQuery>,
+) -> Result<Json<QrV3LogoResponse>, StatusCode> {
+    let start = Instant::now();
+    
+    let result = match state.engine.logo_store() {
+        Some(store) => store
+            .release(&logo_id, &query.reference_id)
+            .map(|refs| LogoOutcome { remaining_refs: Some(refs), ..Default::default() })
+            .map_err(logo_error),
+        None => Err(logo_store_unavailable()),
+    };
+    Ok(Json(logo_response(result, start)))
+}
+
+/// Resultado de una operación de la biblioteca de logos
+#[derive(Default)]
+struct LogoOutcome {
+    logo: Option<LogoRecord>,
+    reference_id: Option<String>,
+    remaining_refs: Option<u32>,
+}
+
+impl LogoOutcome {
+    fn uploaded(reference: LogoReference) -> Self {
+        Self {
+            logo: Some(reference.record),
+            reference_id: Some(reference.reference_id),
+            remaining_refs: None,
+        }
+    }
+}
+
+fn logo_response(result: Result<LogoOutcome, QrV3Error>, start: Instant) -> QrV3LogoResponse {
+    let metadata = QrV3Metadata {
+        engine_version: "3.0.0".to_string(),
+        cached: false,
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    };
+    match result {
+        Ok(LogoOutcome { logo, reference_id, remaining_refs }) => {
+            QrV3LogoResponse { success: true, logo, reference_id, remaining_refs, error: None, metadata }
+        }
+        Err(e) => {
+            error!("Logo store error: {:?}", e);
+            QrV3LogoResponse { success: false, logo: None, reference_id: None, remaining_refs: None, error: Some(e), metadata }
+        }
+    }
+}
+
+fn logo_error(e: QrError) -> QrV3Error {
+    QrV3Error {
+        code: "LOGO_ERROR".to_string(),
+        message: e.to_string(),
+    }
+}
+
+fn logo_store_unavailable() -> QrV3Error {
+    QrV3Error {
+        code: "LOGO_STORE_UNAVAILABLE".to_string(),
+        message: "Logo store is not available".to_string(),
+    }
+}
+
+/// Identifica la imagen, lee todos sus símbolos y arma la respuesta de cada uno
+fn decode_upload(body: &[u8], report: Option<&str>) -> Result<Vec<QrV3DecodedCode>, QrV3Error> {
+    let report_format = match report {
+        None => None,
+        Some(format) if format.eq_ignore_ascii_case("text") => Some(ReportFormat::Text),
+        Some(format) if format.eq_ignore_ascii_case("html") => Some(ReportFormat::Html),
+        Some(format) => {
+            return Err(QrV3Error {
+                code: "INVALID_INPUT".to_string(),
+                message: format!("Unknown report format '{}', expected 'text' or 'html'", format),
+            });
+        }
+    };
+    
+    let upload = ImageUpload::parse(body).map_err(decode_error)?;
+    let decoded = QrDecoder::new().decode_upload(&upload).map_err(decode_error)?;
+    
+    // El grado ISO/IEC 15415 se mide sobre la imagen completa: solo es del símbolo si es el único
+    let print_quality = match decoded.as_slice() {
+        [single] if single.metadata.symbology == QrSymbology::Qr => {
+            let grader = PrintQualityGrader::new();
+            match &upload {
+                ImageUpload::Raster(image) => grader.grade_image(image).ok(),
+                ImageUpload::Svg(svg) => grader.grade_svg(svg).ok(),
+            }
+        }
+        _ => None,
+    };
+    
+    let reporter = QualityReporter::new();
+    decoded
+        .into_iter()
+        .map(|symbol| {
+            let report = match report_format {
+                Some(format) => {
+                    let quality_report = reporter
+                        .generate_scan_report(&symbol, print_quality.clone())
+                        .map_err(|e| QrV3Error {
+                            code: "REPORT_ERROR".to_string(),
+                            message: e.to_string(),
+                        })?;
+                    Some(match format {
+                        ReportFormat::Text => reporter.format_text_report(&quality_report),
+                        ReportFormat::Html => reporter.format_html_report(&quality_report),
+                    })
+                }
+                None => None,
+            };
+            Ok(decoded_code(symbol, print_quality.clone(), report))
+        })
+        .collect()
+}
+
+/// Entrada inválida o fuera de límites, formato no admitido o fallo de lectura
+fn decode_error(e: QrError) -> QrV3Error {
+    let code = match e {
+        QrError::ValidationError(_) => "INVALID_INPUT",
+        QrError::UnsupportedFormat(_) => "UNSUPPORTED_FORMAT",
+        _ => "DECODE_ERROR",
+    };
+    QrV3Error {
+        code: code.to_string(),
+        message: e.to_string(),
+    }
+}
+
+/// Convierte una lectura en la respuesta de la API
+fn decoded_code(
+    symbol: DecodedData,
+    print_quality: Option<PrintQualityReport>,
+    report: Option<String>,
+) -> QrV3DecodedCode {
+    QrV3DecodedCode {
+        payload: QrPayload::parse(&symbol.raw_data),
+        gs1_elements: symbol.gs1_elements
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(ai, value)| QrV3Gs1Element { ai: ai.code(), value })
+            .collect(),
+        content_type: symbol.content_type,
+        symbology: symbol.metadata.symbology,
+        version: symbol.metadata.qr_version,
+        mask_pattern: symbol.metadata.mask_pattern,
+        error_correction: symbol.metadata.error_correction,
+        quality: symbol.quality,
+        print_quality,
+        report,
+        data: symbol.raw_data,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -502,4 +946,89 @@ mod tests {
         assert_eq!(request.data, "https://example.com");
         assert_eq!(request.options.error_correction, Some("H".to_string()));
     }
+    
+    #[tokio::test]
+    async fn test_logo_library_endpoints() {
+        let root = std::env::temp_dir().join(format!("qreable_logos_{}", uuid::Uuid::new_v4()));
+        let store = Arc::new(qreable_generator::engine::LogoStore::open(&root).unwrap());
+        let state = QrV3State {
+            engine: Arc::new(QrEngine::new().with_logo_store(store)),
+            cache: Arc::new(redis::RedisCache::disabled()),
+        };
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(64, 64, |x, y| {
+            image::Rgba([(x * 4) as u8, (y * 4) as u8, 128, 255])
+        }))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .unwrap();
+        
+        let Json(uploaded) = upload_logo_v3(State(state.clone()), Bytes::from(png.clone())).await.unwrap();
+        let logo_id = uploaded.logo.unwrap().logo_id;
+        let first_ref = uploaded.reference_id.unwrap();
+        let Json(again) = upload_logo_v3(State(state.clone()), Bytes::from(png)).await.unwrap();
+        assert_eq!(again.logo.unwrap().refs, 2);
+        let second_ref = again.reference_id.unwrap();
+        
+        let Json(found) = get_logo_v3(State(state.clone()), Path(logo_id.clone())).await.unwrap();
+        assert!(found.success);
+        assert!(found.reference_id.is_none());
+        
+        // Solo el titular de una referencia puede liberarla
+        let release = |reference_id: &str| {
+            release_logo_v3(
+                State(state.clone()),
+                Path(logo_id.clone()),
+                Query(QrV3LogoReleaseQuery { reference_id: reference_id.to_string() }),
+            )
+        };
+        let Json(foreign) = release("ajena").await.unwrap();
+        assert_eq!(foreign.error.unwrap().code, "LOGO_ERROR");
+        let Json(released) = release(&first_ref).await.unwrap();
+        assert_eq!(released.remaining_refs, Some(1));
+        let Json(released) = release(&second_ref).await.unwrap();
+        assert_eq!(released.remaining_refs, Some(0));
+        
+        let Json(missing) = get_logo_v3(State(state.clone()), Path(logo_id)).await.unwrap();
+        assert_eq!(missing.error.unwrap().code, "LOGO_NOT_FOUND");
+        let Json(invalid) = upload_logo_v3(State(state), Bytes::from_static(b"texto")).await.unwrap();
+        assert_eq!(invalid.error.unwrap().code, "LOGO_ERROR");
+        
+        std::fs::remove_dir_all(root).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_generate_excludes_logo_silhouette() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+        
+        let state = QrV3State {
+            engine: Arc::new(QrEngine::new()),
+            cache: Arc::new(redis::RedisCache::disabled()),
+        };
+        // Wordmark 5:1: la silueta ocupa una franja, no la caja cuadrada completa
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(500, 100, image::Rgba([20, 20, 20, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let request = |logo: Option<serde_json::Value>| -> QrV3Request {
+            serde_json::from_value(serde_json::json!({
+                "data": "https://qreable.com/wordmark",
+                "options": {"customization": {"logo_size_ratio": 0.3, "logo": logo}}
+            }))
+            .unwrap()
+        };
+        let excluded = |response: QrV3Response| {
+            response.data.unwrap().metadata.exclusion_info.unwrap().excluded_modules
+        };
+        
+        let Json(boxed) = generate_qr_v3(State(state.clone()), Json(request(None))).await.unwrap();
+        let logo = serde_json::json!({
+            "data": format!("data:image/png;base64,{}", BASE64.encode(&png)),
+            "size_percentage": 30.0,
+            "padding": 0,
+            "shape": "square"
+        });
+        let Json(shaped) = generate_qr_v3(State(state), Json(request(Some(logo)))).await.unwrap();
+        
+        assert!(excluded(shaped) * 2 < excluded(boxed));
+    }
 }
\ No newline at end of file
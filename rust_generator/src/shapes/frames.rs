@@ -2,6 +2,15 @@
 
 use crate::engine::types::{FrameType, TextPosition};
 
+/// Geometría del marco y del símbolo que enmarca
+struct FrameLayout {
+    frame_width: f32,
+    frame_height: f32,
+    padding: f32,
+    qr_width: f32,
+    qr_height: f32,
+}
+
 /// Renderizador de marcos
 pub struct FrameRenderer {
     module_size: u32,
@@ -21,13 +30,27 @@ impl FrameRenderer {
         text_position: TextPosition,
         color: &str,
     ) -> String {
-        let size = qr_size as f32;
+        self.render_frame_rect(qr_size, qr_size, frame_type, text, text_position, color)
+    }
+
+    /// Renderiza un marco alrededor de un símbolo rectangular (rMQR, Data Matrix
+    /// rectangular, PDF417)
+    pub fn render_frame_rect(
+        &self,
+        qr_width: usize,
+        qr_height: usize,
+        frame_type: FrameType,
+        text: Option<&str>,
+        text_position: TextPosition,
+        color: &str,
+    ) -> String {
+        let (width, height) = (qr_width as f32, qr_height as f32);
         let padding = 20.0; // Padding del marco
-        let frame_width = size + 2.0 * padding;
+        let frame_width = width + 2.0 * padding;
         let frame_height = if text.is_some() {
-            size + 2.0 * padding + 30.0 // Espacio extra para texto
+            height + 2.0 * padding + 30.0 // Espacio extra para texto
         } else {
-            size + 2.0 * padding
+            height + 2.0 * padding
         };
 
         let mut svg = String::new();
@@ -36,42 +59,41 @@ impl FrameRenderer {
         match frame_type {
             FrameType::Simple => {
                 svg.push_str(&self.render_simple_frame(
-                    padding, padding, size, size, 4.0, color
+                    padding, padding, width, height, 4.0, color
                 ));
             },
             FrameType::Rounded => {
                 svg.push_str(&self.render_rounded_frame(
-                    padding, padding, size, size, 12.0, color
+                    padding, padding, width, height, 12.0, color
                 ));
             },
             FrameType::Bubble => {
                 svg.push_str(&self.render_bubble_frame(
-                    padding, padding, size, size, color
+                    padding, padding, width, height, color
                 ));
             },
             FrameType::Speech => {
                 svg.push_str(&self.render_speech_frame(
-                    padding, padding, size, size, color
+                    padding, padding, width, height, color
                 ));
             },
             FrameType::Badge => {
                 svg.push_str(&self.render_badge_frame(
-                    padding, padding, size, size, color
+                    padding, padding, width, height, color
                 ));
             },
         }
 
         // Agregar texto si existe
         if let Some(text_content) = text {
-            svg.push_str(&self.render_frame_text(
-                text_content,
-                text_position,
+            let layout = FrameLayout {
                 frame_width,
                 frame_height,
                 padding,
-                size,
-                color
-            ));
+                qr_width: width,
+                qr_height: height,
+            };
+            svg.push_str(&self.render_frame_text(text_content, text_position, &layout, color));
         }
 
         // Envolver en grupo con viewBox expandido
@@ -199,16 +221,8 @@ impl FrameRenderer {
     }
 
     /// Renderiza texto del marco
-    fn render_frame_text(
-        &self,
-        text: &str,
-        position: TextPosition,
-        frame_width: f32,
-        frame_height: f32,
-        padding: f32,
-        qr_size: f32,
-        color: &str,
-    ) -> String {
+    fn render_frame_text(&self, text: &str, position: TextPosition, layout: &FrameLayout, color: &str) -> String {
+        let FrameLayout { frame_width, frame_height, padding, qr_width, qr_height } = *layout;
         let font_size = 16.0;
         let (x, y, anchor) = match position {
             TextPosition::Top => (
@@ -218,7 +232,7 @@ impl FrameRenderer {
             ),
             TextPosition::Bottom => (
                 frame_width / 2.0,
-                padding + qr_size + 25.0,
+                padding + qr_height + 25.0,
                 "middle"
             ),
             TextPosition::Left => (
@@ -227,7 +241,7 @@ impl FrameRenderer {
                 "end"
             ),
             TextPosition::Right => (
-                padding + qr_size + 5.0,
+                padding + qr_width + 5.0,
                 frame_height / 2.0,
                 "start"
             ),
@@ -1,10 +1,100 @@
 // standards/decoder.rs - Decodificador y verificador de códigos QR
 
 use crate::engine::error::{QrError, QrResult};
+use crate::engine::constants::get_alignment_pattern_positions;
+use crate::engine::types::QrSymbology;
 use crate::standards::gs1::{Gs1Parser, ApplicationIdentifier};
-use image::{DynamicImage, GrayImage};
+use crate::standards::digital_link::DigitalLinkParser;
+use image::{DynamicImage, GrayImage, RgbaImage};
+use rxing::common::{BitMatrix, DetectorRXingResult, GlobalHistogramBinarizer, HybridBinarizer};
+use rxing::qrcode::decoder::{qrcode_decoder, BitMatrixParser, ErrorCorrectionLevel, Version};
+use rxing::qrcode::detector::Detector;
+use rxing::{BarcodeFormat, Binarizer, BinaryBitmap, DecodeHints, Luma8LuminanceSource, Point, RXingResult};
+use serde::Serialize;
+use std::collections::HashSet;
 use std::time::Instant;
 
+/// Ancho de rasterización para decodificar SVG (≥ 4 px por módulo hasta versión 40)
+const SVG_DECODE_WIDTH: u32 = 1024;
+
+/// Margen claro añadido alrededor del SVG rasterizado: 4 módulos de un QR versión 1
+/// que ocupe todo el ancho, la mayor zona de silencio mínima de cualquier simbología
+const SVG_DECODE_PADDING: u32 = SVG_DECODE_WIDTH * 4 / 21;
+
+/// Lado máximo en píxeles de una imagen subida para decodificar
+pub const MAX_DECODE_SIDE: u32 = 8192;
+
+/// Superficie máxima de una imagen subida para decodificar (32 Mpx)
+pub const MAX_DECODE_PIXELS: u64 = 32 * 1024 * 1024;
+
+/// Ancho mínimo al reducir la imagen en el último intento de lectura
+const MIN_FALLBACK_WIDTH: u32 = 96;
+
+/// Máximo de símbolos en un conjunto Structured Append
+const MAX_STRUCTURED_APPEND_SYMBOLS: usize = 16;
+
+/// Margen del recorte de cada símbolo al decodificar varios, relativo a la extensión de
+/// sus puntos detectados (los centros de los patrones de búsqueda quedan a 3.5 módulos del borde)
+const MULTI_CROP_MARGIN: f32 = 0.6;
+
+/// Margen con el que se borran los demás símbolos del recorte
+const MULTI_ERASE_MARGIN: f32 = 0.3;
+
+/// Margen mínimo en píxeles de recortes y borrados
+const MULTI_MIN_MARGIN_PX: f32 = 16.0;
+
+/// Imagen subida para decodificar, según su contenido
+pub enum ImageUpload {
+    Raster(DynamicImage),
+    Svg(String),
+}
+
+impl ImageUpload {
+    /// Reconoce PNG y JPEG por su firma y SVG por su raíz. Las dimensiones se comprueban
+    /// antes de decodificar o rasterizar: lo que exceda `MAX_DECODE_SIDE` o
+    /// `MAX_DECODE_PIXELS` se rechaza
+    pub fn parse(body: &[u8]) -> QrResult<Self> {
+        if body.is_empty() {
+            return Err(QrError::ValidationError("La imagen está vacía".to_string()));
+        }
+
+        match image::guess_format(body) {
+            Ok(format @ (image::ImageFormat::Png | image::ImageFormat::Jpeg)) => {
+                let invalid = |e: image::ImageError| {
+                    QrError::ValidationError(format!("Imagen {:?} inválida: {}", format, e))
+                };
+                let (width, height) = image::io::Reader::with_format(std::io::Cursor::new(body), format)
+                    .into_dimensions()
+                    .map_err(invalid)?;
+                check_decode_size(width as u64, height as u64)?;
+                image::load_from_memory_with_format(body, format).map(Self::Raster).map_err(invalid)
+            }
+            _ => match std::str::from_utf8(body) {
+                Ok(text) if text.trim_start().starts_with('<') && text.contains("<svg") => {
+                    // Se rasteriza a SVG_DECODE_WIDTH conservando la proporción del documento
+                    let size = crate::engine::raster::parse_svg(text)?.size();
+                    let height = (SVG_DECODE_WIDTH as f64 * size.height() as f64 / size.width() as f64).round();
+                    check_decode_size(SVG_DECODE_WIDTH as u64, height.min(u64::MAX as f64) as u64)?;
+                    Ok(Self::Svg(text.to_string()))
+                }
+                _ => Err(QrError::UnsupportedFormat("Se esperaba una imagen PNG, JPEG o SVG".to_string())),
+            },
+        }
+    }
+}
+
+/// Rechaza imágenes a decodificar por encima de `MAX_DECODE_SIDE` o `MAX_DECODE_PIXELS`
+fn check_decode_size(width: u64, height: u64) -> QrResult<()> {
+    let max_side = MAX_DECODE_SIDE as u64;
+    if width > max_side || height > max_side || width * height > MAX_DECODE_PIXELS {
+        return Err(QrError::ValidationError(format!(
+            "Imagen de {}x{} fuera del límite ({} px por lado, {} px en total)",
+            width, height, MAX_DECODE_SIDE, MAX_DECODE_PIXELS
+        )));
+    }
+    Ok(())
+}
+
 /// Datos decodificados de un código QR
 #[derive(Debug, Clone)]
 pub struct DecodedData {
@@ -21,7 +111,8 @@ pub struct DecodedData {
 }
 
 /// Tipo de contenido del QR
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContentType {
     Text,
     Url,
@@ -30,7 +121,12 @@ pub enum ContentType {
     Sms,
     Wifi,
     Gs1,
+    #[serde(rename = "vcard")]
     VCard,
+    #[serde(rename = "mecard")]
+    MeCard,
+    Event,
+    Geo,
     Unknown,
 }
 
@@ -39,18 +135,59 @@ pub enum ContentType {
 pub struct DecodeMetadata {
     /// Tiempo de decodificación en ms
     pub decode_time_ms: u64,
-    /// Versión del QR (tamaño)
-    pub qr_version: u8,
+    /// Versión del QR (tamaño); None si el lector no la informa
+    pub qr_version: Option<u8>,
     /// Nivel de corrección de errores detectado
     pub error_correction: String,
     /// Número de módulos de datos
     pub data_modules: usize,
-    /// Máscara aplicada
-    pub mask_pattern: u8,
+    /// Máscara aplicada; None si el lector no la informa
+    pub mask_pattern: Option<u8>,
+    /// Errores corregidos por Reed-Solomon durante la lectura
+    pub errors_corrected: usize,
+    /// Tamaño de módulo medido en píxeles (None si no se pudo medir la cuadrícula)
+    pub module_size_px: Option<f32>,
+    /// Centros de los patrones de búsqueda en píxeles: superior izquierdo,
+    /// superior derecho e inferior izquierdo (None si no se pudo medir la cuadrícula)
+    pub finder_centers_px: Option<[(f32, f32); 3]>,
+    /// Margen claro entre el símbolo y el borde de la imagen, en módulos
+    /// (None si no se pudo medir la cuadrícula)
+    pub quiet_zone_modules: Option<f32>,
+    /// Posición en un conjunto Structured Append (None si el símbolo es independiente)
+    pub structured_append: Option<StructuredAppendInfo>,
+    /// Símbolo codificado con FNC1 en primera posición (identificador "]Q3" o "]Q4")
+    pub gs1_mode: bool,
+    /// Simbología leída
+    pub symbology: QrSymbology,
+}
+
+/// Cabecera Structured Append de un símbolo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructuredAppendInfo {
+    /// Posición en la secuencia (desde 0)
+    pub index: u8,
+    /// Número total de símbolos del conjunto
+    pub total: u8,
+    /// Paridad del mensaje completo
+    pub parity: u8,
+}
+
+impl StructuredAppendInfo {
+    /// Interpreta el indicador de secuencia (posición y total - 1 en 4 bits cada uno) y la paridad
+    fn from_header(sequence: i32, parity: i32) -> Option<Self> {
+        if sequence < 0 || parity < 0 {
+            return None;
+        }
+        Some(Self {
+            index: ((sequence >> 4) & 0x0F) as u8,
+            total: ((sequence & 0x0F) + 1) as u8,
+            parity: (parity & 0xFF) as u8,
+        })
+    }
 }
 
 /// Calidad de la decodificación
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DecodeQuality {
     /// Puntuación general (0.0 - 1.0)
     pub overall_score: f32,
@@ -65,7 +202,8 @@ pub struct DecodeQuality {
 }
 
 /// Nivel de daño detectado
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DamageLevel {
     None,
     Minor,
@@ -74,7 +212,7 @@ pub enum DamageLevel {
 }
 
 /// Métricas de calidad detalladas
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct QualityMetrics {
     /// Desviación del módulo
     pub module_deviation: f32,
@@ -91,6 +229,7 @@ pub struct QualityMetrics {
 /// Decodificador de códigos QR
 pub struct QrDecoder {
     gs1_parser: Gs1Parser,
+    digital_link_parser: DigitalLinkParser,
     enable_quality_analysis: bool,
     enable_error_recovery: bool,
 }
@@ -99,6 +238,7 @@ impl QrDecoder {
     pub fn new() -> Self {
         Self {
             gs1_parser: Gs1Parser::new(),
+            digital_link_parser: DigitalLinkParser::new(),
             enable_quality_analysis: true,
             enable_error_recovery: true,
         }
@@ -111,25 +251,86 @@ impl QrDecoder {
         // Convertir a escala de grises
         let gray_image = image.to_luma8();
         
-        // Intentar decodificar con rxing (por ahora, simulado)
+        // Decodificar con rxing
         let (raw_data, qr_info) = self.decode_with_rxing(&gray_image)?;
         
-        // Detectar tipo de contenido
-        let content_type = self.detect_content_type(&raw_data);
-        
-        // Parsear GS1 si aplica
-        let gs1_elements = if content_type == ContentType::Gs1 {
-            match self.gs1_parser.parse(&raw_data) {
-                Ok(elements) => Some(elements),
-                Err(_) => None,
-            }
+        self.build_decoded(raw_data, qr_info, &gray_image, start_time)
+    }
+
+    /// Decodifica todos los símbolos de la imagen (QR, Micro QR, rMQR, Data Matrix, Aztec y PDF417).
+    /// Cada uno se analiza sobre un recorte propio con los demás borrados, de modo que
+    /// versión, cuadrícula y calidad corresponden a ese símbolo
+    pub fn decode_all(&self, image: &DynamicImage) -> QrResult<Vec<DecodedData>> {
+        let gray = image.to_luma8();
+        let (width, height) = gray.dimensions();
+
+        let mut hints = DecodeHints {
+            TryHarder: Some(true),
+            PossibleFormats: Some(all_symbologies()),
+            ..Default::default()
+        };
+        let results = rxing::helpers::detect_multiple_in_luma_with_hints(
+            gray.as_raw().clone(), width, height, &mut hints,
+        )
+        .unwrap_or_default();
+
+        // El lector múltiple no reintenta a otras escalas: la ruta simple cubre esos casos
+        if results.is_empty() {
+            return self.decode_image(image).map(|decoded| vec![decoded]);
+        }
+
+        let erase: Vec<_> = results.iter().map(|r| symbol_bounds(r, &gray, MULTI_ERASE_MARGIN)).collect();
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let start_time = Instant::now();
+                let mut isolated = gray.clone();
+                for (_, &(x0, y0, x1, y1)) in erase.iter().enumerate().filter(|(j, _)| *j != i) {
+                    for y in y0..y1 {
+                        for x in x0..x1 {
+                            isolated.put_pixel(x, y, image::Luma([255]));
+                        }
+                    }
+                }
+                let (x0, y0, x1, y1) = symbol_bounds(result, &isolated, MULTI_CROP_MARGIN);
+                let crop = image::imageops::crop_imm(&isolated, x0, y0, x1 - x0, y1 - y0).to_image();
+
+                // Si el recorte no reproduce la lectura, se conserva la del lector múltiple
+                let (raw_data, qr_info) = match self.decode_with_rxing(&crop) {
+                    Ok((text, info)) if text == result.getText() => (text, info),
+                    _ => (result.getText().to_string(), QrInfo::from_reader_result(result)),
+                };
+                self.build_decoded(raw_data, qr_info, &crop, start_time)
+            })
+            .collect()
+    }
+
+    /// Completa tipo de contenido, elementos GS1, metadatos y calidad de una lectura
+    fn build_decoded(
+        &self,
+        raw_data: String,
+        qr_info: QrInfo,
+        gray_image: &GrayImage,
+        start_time: Instant,
+    ) -> QrResult<DecodedData> {
+        // Detectar tipo de contenido; el modo FNC1 identifica los datos GS1 sin heurísticas
+        let content_type = if qr_info.gs1_mode {
+            ContentType::Gs1
         } else {
-            None
+            self.detect_content_type(&raw_data)
+        };
+        
+        // Parsear GS1 si aplica (cadena de elementos o URI GS1 Digital Link)
+        let gs1_elements = match content_type {
+            ContentType::Gs1 => self.gs1_parser.parse(&raw_data).ok(),
+            ContentType::Url => self.digital_link_parser.parse(&raw_data).ok(),
+            _ => None,
         };
         
         // Analizar calidad si está habilitado
         let quality = if self.enable_quality_analysis {
-            self.analyze_quality(&gray_image, &qr_info)?
+            self.analyze_quality(gray_image, &qr_info)?
         } else {
             self.create_default_quality()
         };
@@ -146,15 +347,187 @@ impl QrDecoder {
                 error_correction: qr_info.error_correction.clone(),
                 data_modules: qr_info.data_modules,
                 mask_pattern: qr_info.mask_pattern,
+                errors_corrected: qr_info.errors_corrected,
+                module_size_px: qr_info.grid.as_ref().map(|grid| grid.module_size_px),
+                finder_centers_px: qr_info.grid.as_ref().map(|grid| grid.finder_centers),
+                quiet_zone_modules: qr_info.grid.as_ref().map(|grid| {
+                    measure_quiet_zone(grid.module_size_px, grid.finder_centers, gray_image.width(), gray_image.height())
+                }),
+                structured_append: qr_info.structured_append,
+                gs1_mode: qr_info.gs1_mode,
+                symbology: qr_info.symbology,
             },
             quality,
         })
     }
     
-    /// Decodifica un código QR desde SVG (convierte a imagen primero)
+    /// Decodifica un código QR desde SVG (rasteriza con resvg primero).
+    /// El raster se lee con la zona de silencio mínima añadida alrededor, como un
+    /// código incrustado sobre un fondo claro; posiciones y margen medido se refieren
+    /// al lienzo del SVG, así que un símbolo sin zona de silencio propia informa 0 módulos
     pub fn decode_svg(&self, svg_data: &str) -> QrResult<DecodedData> {
-        // Por ahora, retornamos un error ya que necesitaríamos resvg o similar
-        Err(QrError::DecodingError("Decodificación SVG no implementada aún".to_string()))
+        let image = Self::rasterize_svg(svg_data)?;
+        let mut decoded = self.decode_image(&image)?;
+
+        let metadata = &mut decoded.metadata;
+        let padding = SVG_DECODE_PADDING as f32;
+        metadata.finder_centers_px = metadata.finder_centers_px
+            .map(|centers| centers.map(|(x, y)| (x - padding, y - padding)));
+        if let (Some(module), Some(centers)) = (metadata.module_size_px, metadata.finder_centers_px) {
+            metadata.quiet_zone_modules = Some(measure_quiet_zone(
+                module,
+                centers,
+                image.width() - 2 * SVG_DECODE_PADDING,
+                image.height() - 2 * SVG_DECODE_PADDING,
+            ));
+        }
+        Ok(decoded)
+    }
+
+    /// Como `decode_all`, desde SVG
+    pub fn decode_svg_all(&self, svg_data: &str) -> QrResult<Vec<DecodedData>> {
+        self.decode_all(&Self::rasterize_svg(svg_data)?)
+    }
+
+    /// Decodifica todos los símbolos de una imagen subida
+    pub fn decode_upload(&self, upload: &ImageUpload) -> QrResult<Vec<DecodedData>> {
+        match upload {
+            ImageUpload::Raster(image) => self.decode_all(image),
+            ImageUpload::Svg(svg) => self.decode_svg_all(svg),
+        }
+    }
+
+    /// Rasteriza el SVG sobre blanco con un margen claro alrededor
+    fn rasterize_svg(svg_data: &str) -> QrResult<DynamicImage> {
+        let raster = crate::engine::raster::render_svg(svg_data, SVG_DECODE_WIDTH)?;
+
+        // Componer sobre blanco: las zonas transparentes no deben leerse como módulos oscuros
+        let rgb = crate::engine::raster::flatten_on_white(&raster.pixels);
+        let image = image::RgbImage::from_raw(raster.width, raster.height, rgb)
+            .ok_or_else(|| QrError::DecodingError("Rasterización SVG inválida".to_string()))?;
+
+        let mut padded = image::RgbImage::from_pixel(
+            raster.width + 2 * SVG_DECODE_PADDING,
+            raster.height + 2 * SVG_DECODE_PADDING,
+            image::Rgb([255, 255, 255]),
+        );
+        image::imageops::overlay(&mut padded, &image, SVG_DECODE_PADDING as i64, SVG_DECODE_PADDING as i64);
+
+        Ok(DynamicImage::ImageRgb8(padded))
+    }
+
+    /// Decodifica un código QR desde una imagen RGBA componiendo la transparencia sobre blanco
+    pub fn decode_rgba(&self, image: &RgbaImage) -> QrResult<DecodedData> {
+        let rgb = crate::engine::raster::flatten_on_white(image.as_raw());
+        let image = image::RgbImage::from_raw(image.width(), image.height(), rgb)
+            .ok_or_else(|| QrError::DecodingError("Imagen RGBA inválida".to_string()))?;
+        self.decode_image(&DynamicImage::ImageRgb8(image))
+    }
+    
+    /// Reúne el mensaje de un conjunto Structured Append a partir de sus símbolos
+    /// decodificados, en cualquier orden; exige el conjunto completo y la paridad correcta
+    pub fn reassemble(&self, symbols: &[DecodedData]) -> QrResult<String> {
+        let parts = symbols
+            .iter()
+            .map(|symbol| {
+                symbol.metadata.structured_append
+                    .map(|info| (info, symbol.raw_data.clone()))
+                    .ok_or_else(|| QrError::DecodingError(
+                        "Un símbolo no pertenece a un conjunto Structured Append".to_string(),
+                    ))
+            })
+            .collect::<QrResult<Vec<_>>>()?;
+        Self::assemble_parts(parts)
+    }
+
+    /// Decodifica todos los símbolos de una imagen (p. ej. una hoja en mosaico) y reúne el mensaje.
+    /// Cada símbolo leído se borra de la imagen antes de buscar el siguiente.
+    pub fn decode_structured_append_image(&self, image: &DynamicImage) -> QrResult<String> {
+        let mut gray = image.to_luma8();
+        let mut parts = Vec::new();
+
+        for _ in 0..MAX_STRUCTURED_APPEND_SYMBOLS {
+            let Ok((text, info)) = self.decode_with_rxing(&gray) else {
+                break;
+            };
+            let header = info.structured_append.ok_or_else(|| QrError::DecodingError(
+                "La imagen contiene un símbolo ajeno al conjunto Structured Append".to_string(),
+            ))?;
+            parts.push((header, text));
+
+            // Sin cuadrícula medida no se puede borrar el símbolo: se volvería a leer
+            match &info.grid {
+                Some(grid) => Self::erase_symbol(&mut gray, grid),
+                None => break,
+            }
+        }
+
+        Self::assemble_parts(parts)
+    }
+
+    /// Pinta de blanco el área de un símbolo (a partir de sus patrones de búsqueda)
+    fn erase_symbol(image: &mut GrayImage, grid: &GridMeasurements) {
+        let [top_left, top_right, bottom_left] = grid.finder_centers;
+        let bottom_right = (top_right.0 + bottom_left.0 - top_left.0, top_right.1 + bottom_left.1 - top_left.1);
+        let corners = [top_left, top_right, bottom_left, bottom_right];
+
+        // Del centro del patrón al borde hay 3.5 módulos; un módulo más de margen
+        let margin = grid.module_size_px * 4.5;
+        let clamp_x = |v: f32| v.clamp(0.0, image.width() as f32) as u32;
+        let clamp_y = |v: f32| v.clamp(0.0, image.height() as f32) as u32;
+        let x0 = clamp_x(corners.iter().map(|c| c.0).fold(f32::MAX, f32::min) - margin);
+        let x1 = clamp_x(corners.iter().map(|c| c.0).fold(f32::MIN, f32::max) + margin);
+        let y0 = clamp_y(corners.iter().map(|c| c.1).fold(f32::MAX, f32::min) - margin);
+        let y1 = clamp_y(corners.iter().map(|c| c.1).fold(f32::MIN, f32::max) + margin);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                image.put_pixel(x, y, image::Luma([255]));
+            }
+        }
+    }
+
+    /// Ordena y concatena los fragmentos validando total, posiciones y paridad
+    fn assemble_parts(mut parts: Vec<(StructuredAppendInfo, String)>) -> QrResult<String> {
+        let (first, _) = parts.first().ok_or_else(|| {
+            QrError::DecodingError("El conjunto Structured Append está vacío".to_string())
+        })?;
+        let (total, parity) = (first.total, first.parity);
+
+        if parts.iter().any(|(info, _)| info.total != total || info.parity != parity) {
+            return Err(QrError::DecodingError(
+                "Los símbolos pertenecen a conjuntos Structured Append distintos".to_string(),
+            ));
+        }
+
+        parts.sort_by_key(|(info, _)| info.index);
+        parts.dedup_by_key(|(info, _)| info.index);
+        let missing: Vec<String> = (0..total)
+            .filter(|index| !parts.iter().any(|(info, _)| info.index == *index))
+            .map(|index| (index + 1).to_string())
+            .collect();
+        if !missing.is_empty() || parts.len() != total as usize {
+            return Err(QrError::DecodingError(format!(
+                "Faltan símbolos del conjunto ({} de {}): {}",
+                parts.len(), total, missing.join(", ")
+            )));
+        }
+
+        let message: String = parts.into_iter().map(|(_, text)| text).collect();
+
+        // La paridad se calcula sobre los bytes originales: UTF-8 o, en lectores
+        // que entregan Latin-1, un byte por carácter
+        let utf8_parity = message.bytes().fold(0u8, |acc, byte| acc ^ byte);
+        let latin1_parity = message.chars().all(|c| (c as u32) <= 0xFF)
+            .then(|| message.chars().fold(0u8, |acc, c| acc ^ c as u8));
+        if utf8_parity != parity && latin1_parity != Some(parity) {
+            return Err(QrError::DecodingError(format!(
+                "Paridad Structured Append incorrecta: esperada {:#04x}, calculada {:#04x}",
+                parity, utf8_parity
+            )));
+        }
+
+        Ok(message)
     }
     
     /// Verifica que el contenido decodificado coincida con el esperado
@@ -162,27 +535,124 @@ impl QrDecoder {
         decoded.raw_data == expected
     }
     
-    /// Decodifica usando rxing (simulado por ahora)
+    /// Decodifica usando rxing: detección de patrones, muestreo de la cuadrícula y
+    /// corrección Reed-Solomon, conservando la matriz muestreada para medir calidad
     fn decode_with_rxing(&self, image: &GrayImage) -> QrResult<(String, QrInfo)> {
-        // TODO: Integrar rxing real cuando esté configurado
-        // Por ahora, simulamos una decodificación exitosa
-        
-        // En una implementación real:
-        // let mut hints = HashMap::new();
-        // hints.insert(DecodeHintType::TryHarder, DecodeHintValue::TryHarder(true));
-        // let result = detect_multiple_in_luma(image, &hints)?;
-        
-        // Simulación para desarrollo
+        let (width, height) = image.dimensions();
+        let luma = image.as_raw().clone();
+
+        let mut hints = DecodeHints {
+            TryHarder: Some(true),
+            PossibleFormats: Some(HashSet::from([BarcodeFormat::QR_CODE])),
+            ..Default::default()
+        };
+
+        // 1. Binarización híbrida (mejor con iluminación irregular)
+        let hybrid = BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(
+            luma.clone(), width, height,
+        )));
+        let mut last_error = match self.decode_bitmap(&hybrid, &hints, image) {
+            Ok(result) => return Ok(result),
+            Err(e) => e,
+        };
+
+        if self.enable_error_recovery {
+            // 2. Histograma global (mejor con módulos grandes y fondos planos)
+            let global = BinaryBitmap::new(GlobalHistogramBinarizer::new(Luma8LuminanceSource::new(
+                luma, width, height,
+            )));
+            match self.decode_bitmap(&global, &hints, image) {
+                Ok(result) => return Ok(result),
+                Err(e) => last_error = e,
+            }
+
+            // 3. Lector completo (rotaciones, imagen pura, Micro QR, rMQR, Data Matrix, Aztec
+            // y PDF417); sin acceso a la cuadrícula.
+            // Con módulos muy grandes la binarización pierde los símbolos pequeños, así que
+            // se reintenta a escalas reducidas
+            hints.PureBarcode = None;
+            hints.PossibleFormats = Some(all_symbologies());
+            let mut scaled = image.clone();
+            let full_reader = loop {
+                let attempt = rxing::helpers::detect_in_luma_with_hints(
+                    scaled.as_raw().clone(), scaled.width(), scaled.height(), None, &mut hints,
+                );
+                if attempt.is_ok() || scaled.width() / 2 < MIN_FALLBACK_WIDTH {
+                    break attempt;
+                }
+                scaled = image::imageops::resize(
+                    &scaled, scaled.width() / 2, (scaled.height() / 2).max(1), image::imageops::FilterType::Triangle,
+                );
+            };
+            if let Ok(result) = full_reader {
+                return Ok((result.getText().to_string(), QrInfo::from_reader_result(&result)));
+            }
+        }
+
+        Err(QrError::DecodingError(format!("No se pudo decodificar el código QR: {}", last_error)))
+    }
+
+    /// Detecta, muestrea y decodifica sobre un bitmap binarizado
+    fn decode_bitmap<B: Binarizer>(
+        &self,
+        bitmap: &BinaryBitmap<B>,
+        hints: &DecodeHints,
+        image: &GrayImage,
+    ) -> Result<(String, QrInfo), String> {
+        let detection = Detector::new(bitmap.get_black_matrix())
+            .detect_with_hints(hints)
+            .map_err(|e| e.to_string())?;
+        let bits = detection.getBits();
+        let decoded = qrcode_decoder::decode_bitmatrix_with_hints(bits, hints)
+            .map_err(|e| e.to_string())?;
+
+        // Versión y máscara leídas de la propia matriz (también en lectura espejada)
+        let (version, mask_pattern) = Self::read_format(bits, false)
+            .or_else(|| Self::read_format(bits, true))
+            .ok_or_else(|| "Información de formato ilegible".to_string())?;
+
+        // rxing reporta el nivel con sus bits de formato ("0".."3"); FromStr acepta ambas formas
+        let ecl = decoded.getECLevel().parse::<ErrorCorrectionLevel>()
+            .map_err(|e| e.to_string())?;
+        let ec_capacity = (version.getECBlocksForLevel(ecl).getTotalECCodewords() / 2) as usize;
+
+        let dimension = bits.getHeight();
+        let function_modules = version
+            .buildFunctionPattern()
+            .map(|pattern| count_set_bits(&pattern))
+            .unwrap_or(0);
+
+        let grid = GridMeasurements::measure(image, bits, detection.getPoints(), version.getVersionNumber() as u8);
+
         Ok((
-            "\\FNC1010123456789012817251231210ABC123".to_string(),
+            decoded.getText().to_string(),
             QrInfo {
-                version: 5,
-                error_correction: "H".to_string(),
-                data_modules: 134,
-                mask_pattern: 2,
-            }
+                version: Some(version.getVersionNumber() as u8),
+                error_correction: ecl.to_string(),
+                data_modules: (dimension * dimension) as usize - function_modules,
+                mask_pattern: Some(mask_pattern),
+                errors_corrected: decoded.getErrorsCorrected(),
+                ec_capacity,
+                grid,
+                structured_append: StructuredAppendInfo::from_header(
+                    decoded.getStructuredAppendSequenceNumber(),
+                    decoded.getStructuredAppendParity(),
+                ),
+                // Modificadores 3 y 4: FNC1 en primera posición (sin y con ECI)
+                gs1_mode: matches!(decoded.getSymbologyModifier(), 3 | 4),
+                symbology: QrSymbology::Qr,
+            },
         ))
     }
+
+    /// Lee versión y máscara de la información de formato de la matriz muestreada
+    fn read_format(bits: &BitMatrix, mirrored: bool) -> Option<(&'static Version, u8)> {
+        let mut parser = BitMatrixParser::new(bits.clone()).ok()?;
+        parser.setMirror(mirrored);
+        let mask = parser.readFormatInformation().ok()?.getDataMask();
+        let version = parser.readVersion().ok()?;
+        Some((version, mask))
+    }
     
     /// Detecta el tipo de contenido
     fn detect_content_type(&self, data: &str) -> ContentType {
@@ -190,16 +660,22 @@ impl QrDecoder {
             ContentType::Gs1
         } else if data.starts_with("http://") || data.starts_with("https://") {
             ContentType::Url
-        } else if data.starts_with("mailto:") {
+        } else if starts_with_ignore_case(data, "mailto:") {
             ContentType::Email
-        } else if data.starts_with("tel:") {
+        } else if starts_with_ignore_case(data, "tel:") {
             ContentType::Phone
-        } else if data.starts_with("smsto:") || data.starts_with("sms:") {
+        } else if starts_with_ignore_case(data, "smsto:") || starts_with_ignore_case(data, "sms:") {
             ContentType::Sms
+        } else if starts_with_ignore_case(data, "geo:") {
+            ContentType::Geo
         } else if data.starts_with("WIFI:") {
             ContentType::Wifi
         } else if data.starts_with("BEGIN:VCARD") {
             ContentType::VCard
+        } else if data.starts_with("MECARD:") {
+            ContentType::MeCard
+        } else if data.starts_with("BEGIN:VEVENT") || data.starts_with("BEGIN:VCALENDAR") {
+            ContentType::Event
         } else if data.chars().all(|c| c.is_ascii_graphic() || c.is_whitespace()) {
             ContentType::Text
         } else {
@@ -209,9 +685,12 @@ impl QrDecoder {
     
     /// Analiza la calidad del código QR
     fn analyze_quality(&self, image: &GrayImage, qr_info: &QrInfo) -> QrResult<DecodeQuality> {
-        let contrast = self.calculate_contrast(image);
-        let uniformity = self.calculate_grid_uniformity(image, qr_info.version);
-        let damage = self.assess_damage(image);
+        let contrast = match &qr_info.grid {
+            Some(grid) => grid.symbol_contrast,
+            None => self.calculate_contrast(image),
+        };
+        let uniformity = self.calculate_grid_uniformity(qr_info);
+        let damage = self.assess_damage(image, qr_info);
         let metrics = self.calculate_quality_metrics(image, qr_info)?;
         
         // Calcular puntuación general
@@ -246,33 +725,33 @@ impl QrDecoder {
         }
     }
     
-    /// Calcula la uniformidad de la cuadrícula
-    fn calculate_grid_uniformity(&self, _image: &GrayImage, _version: u8) -> f32 {
-        // Simplificado: en producción, analizaríamos la regularidad de los módulos
-        0.85
+    /// Calcula la uniformidad de la cuadrícula medida sobre los patrones de búsqueda
+    fn calculate_grid_uniformity(&self, qr_info: &QrInfo) -> f32 {
+        match &qr_info.grid {
+            Some(grid) => (1.0 - grid.axial_nonuniformity.max(grid.grid_nonuniformity)).clamp(0.0, 1.0),
+            // Sin cuadrícula medible no se puede afirmar uniformidad
+            None => 0.0,
+        }
     }
     
-    /// Evalúa el nivel de daño
-    fn assess_damage(&self, image: &GrayImage) -> DamageLevel {
-        // Simplificado: analizar áreas blancas/negras irregulares
-        let total_pixels = (image.width() * image.height()) as f32;
-        let mut irregular_pixels = 0;
-        
-        // Contar píxeles que no son claramente blancos o negros
-        for pixel in image.pixels() {
-            let brightness = pixel[0];
-            if brightness > 50 && brightness < 200 {
-                irregular_pixels += 1;
-            }
-        }
-        
-        let damage_ratio = irregular_pixels as f32 / total_pixels;
+    /// Evalúa el nivel de daño según los errores corregidos respecto a la capacidad de corrección
+    fn assess_damage(&self, image: &GrayImage, qr_info: &QrInfo) -> DamageLevel {
+        let damage_ratio = if qr_info.ec_capacity > 0 {
+            qr_info.errors_corrected as f32 / qr_info.ec_capacity as f32
+        } else {
+            // Sin información de corrección: proporción de píxeles ni claros ni oscuros
+            let total_pixels = (image.width() * image.height()).max(1) as f32;
+            let irregular_pixels = image.pixels()
+                .filter(|pixel| pixel[0] > 50 && pixel[0] < 200)
+                .count();
+            irregular_pixels as f32 / total_pixels * 2.0
+        };
         
-        if damage_ratio < 0.05 {
+        if damage_ratio == 0.0 {
             DamageLevel::None
-        } else if damage_ratio < 0.15 {
+        } else if damage_ratio < 0.25 {
             DamageLevel::Minor
-        } else if damage_ratio < 0.30 {
+        } else if damage_ratio < 0.6 {
             DamageLevel::Moderate
         } else {
             DamageLevel::Severe
@@ -280,14 +759,24 @@ impl QrDecoder {
     }
     
     /// Calcula métricas de calidad detalladas
-    fn calculate_quality_metrics(&self, image: &GrayImage, _qr_info: &QrInfo) -> QrResult<QualityMetrics> {
-        // Implementación simplificada
-        Ok(QualityMetrics {
-            module_deviation: 0.05,
-            perspective_distortion: 0.02,
-            noise_level: self.calculate_noise_level(image),
-            finder_pattern_quality: 0.95,
-            alignment_pattern_quality: 0.90,
+    fn calculate_quality_metrics(&self, image: &GrayImage, qr_info: &QrInfo) -> QrResult<QualityMetrics> {
+        let noise_level = self.calculate_noise_level(image);
+        
+        Ok(match &qr_info.grid {
+            Some(grid) => QualityMetrics {
+                module_deviation: grid.module_deviation,
+                perspective_distortion: grid.perspective_distortion,
+                noise_level,
+                finder_pattern_quality: grid.finder_pattern_quality,
+                alignment_pattern_quality: grid.alignment_pattern_quality,
+            },
+            None => QualityMetrics {
+                module_deviation: 1.0,
+                perspective_distortion: 0.0,
+                noise_level,
+                finder_pattern_quality: 0.0,
+                alignment_pattern_quality: 0.0,
+            },
         })
     }
     
@@ -354,10 +843,10 @@ impl QrDecoder {
         
         // Información técnica
         report.push_str("INFORMACIÓN TÉCNICA:\n");
-        report.push_str(&format!("  Versión QR: {}\n", decoded.metadata.qr_version));
+        report.push_str(&format!("  Versión QR: {}\n", describe_optional(decoded.metadata.qr_version)));
         report.push_str(&format!("  Corrección de errores: {}\n", decoded.metadata.error_correction));
         report.push_str(&format!("  Módulos de datos: {}\n", decoded.metadata.data_modules));
-        report.push_str(&format!("  Patrón de máscara: {}\n", decoded.metadata.mask_pattern));
+        report.push_str(&format!("  Patrón de máscara: {}\n", describe_optional(decoded.metadata.mask_pattern)));
         report.push_str("\n");
         
         // Calidad
@@ -406,10 +895,287 @@ impl QrDecoder {
 
 /// Información del QR decodificado
 struct QrInfo {
-    version: u8,
+    version: Option<u8>,
     error_correction: String,
     data_modules: usize,
-    mask_pattern: u8,
+    mask_pattern: Option<u8>,
+    errors_corrected: usize,
+    /// Codewords corregibles (mitad de los de corrección)
+    ec_capacity: usize,
+    grid: Option<GridMeasurements>,
+    structured_append: Option<StructuredAppendInfo>,
+    gs1_mode: bool,
+    symbology: QrSymbology,
+}
+
+impl QrInfo {
+    /// Información disponible desde el lector completo de rxing, sin acceso a la cuadrícula
+    fn from_reader_result(result: &RXingResult) -> Self {
+        let symbology = match result.getBarcodeFormat() {
+            BarcodeFormat::MICRO_QR_CODE => QrSymbology::MicroQr,
+            BarcodeFormat::RECTANGULAR_MICRO_QR_CODE => QrSymbology::Rmqr,
+            BarcodeFormat::DATA_MATRIX => QrSymbology::DataMatrix,
+            BarcodeFormat::AZTEC => QrSymbology::Aztec,
+            BarcodeFormat::PDF_417 => QrSymbology::Pdf417,
+            _ => QrSymbology::Qr,
+        };
+        // Aztec y PDF417 informan su corrección como porcentaje o nivel 0-8,
+        // que no se corresponde con L/M/Q/H
+        let qr_family = matches!(symbology, QrSymbology::Qr | QrSymbology::MicroQr | QrSymbology::Rmqr);
+        let error_correction = result
+            .getRXingResultMetadata()
+            .get(&rxing::RXingResultMetadataType::ERROR_CORRECTION_LEVEL)
+            .and_then(|value| match value {
+                rxing::RXingResultMetadataValue::ErrorCorrectionLevel(level) if qr_family => {
+                    level.parse::<ErrorCorrectionLevel>().ok().map(|ecl| ecl.to_string())
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let gs1_mode = matches!(
+            result.getRXingResultMetadata().get(&rxing::RXingResultMetadataType::SYMBOLOGY_IDENTIFIER),
+            Some(rxing::RXingResultMetadataValue::SymbologyIdentifier(id)) if id == "]Q3" || id == "]Q4"
+        );
+        // Sin acceso a la cuadrícula no se conocen versión ni máscara
+        QrInfo {
+            version: None,
+            error_correction,
+            data_modules: 0,
+            mask_pattern: None,
+            errors_corrected: 0,
+            ec_capacity: 0,
+            grid: None,
+            // El lector completo no expone la cabecera SA de forma uniforme
+            structured_append: None,
+            gs1_mode,
+            symbology,
+        }
+    }
+}
+
+/// Simbologías matriciales que reconoce el lector completo
+fn all_symbologies() -> HashSet<BarcodeFormat> {
+    HashSet::from([
+        BarcodeFormat::QR_CODE,
+        BarcodeFormat::MICRO_QR_CODE,
+        BarcodeFormat::RECTANGULAR_MICRO_QR_CODE,
+        BarcodeFormat::DATA_MATRIX,
+        BarcodeFormat::AZTEC,
+        BarcodeFormat::PDF_417,
+    ])
+}
+
+/// Rectángulo (x0, y0, x1, y1) que cubre los puntos detectados de un símbolo con un margen
+/// relativo a su extensión, recortado a la imagen
+fn symbol_bounds(result: &RXingResult, image: &GrayImage, margin: f32) -> (u32, u32, u32, u32) {
+    let points = result.getPoints();
+    if points.is_empty() {
+        return (0, 0, image.width(), image.height());
+    }
+    let min_x = points.iter().map(|p| p.x).fold(f32::MAX, f32::min);
+    let max_x = points.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+    let min_y = points.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+    let max_y = points.iter().map(|p| p.y).fold(f32::MIN, f32::max);
+
+    let span = (max_x - min_x).max(max_y - min_y);
+    let margin = (span * margin).max(MULTI_MIN_MARGIN_PX);
+    let clamp_x = |v: f32| v.clamp(0.0, image.width() as f32) as u32;
+    let clamp_y = |v: f32| v.clamp(0.0, image.height() as f32) as u32;
+    (
+        clamp_x(min_x - margin),
+        clamp_y(min_y - margin),
+        clamp_x(max_x + margin).max(clamp_x(min_x - margin) + 1),
+        clamp_y(max_y + margin).max(clamp_y(min_y - margin) + 1),
+    )
+}
+
+/// Margen claro en módulos entre el símbolo, situado por los centros de sus patrones
+/// de búsqueda, y el borde de una imagen de `width` x `height` píxeles
+fn measure_quiet_zone(module_px: f32, centers: [(f32, f32); 3], width: u32, height: u32) -> f32 {
+    let [top_left, top_right, bottom_left] = centers;
+    let bottom_right = (top_right.0 + bottom_left.0 - top_left.0, top_right.1 + bottom_left.1 - top_left.1);
+    let corners = [top_left, top_right, bottom_left, bottom_right];
+
+    // Del centro del patrón de búsqueda al borde del símbolo hay 3.5 módulos
+    let edge = module_px * 3.5;
+    let left = corners.iter().map(|c| c.0).fold(f32::MAX, f32::min) - edge;
+    let right = width as f32 - corners.iter().map(|c| c.0).fold(f32::MIN, f32::max) - edge;
+    let top = corners.iter().map(|c| c.1).fold(f32::MAX, f32::min) - edge;
+    let bottom = height as f32 - corners.iter().map(|c| c.1).fold(f32::MIN, f32::max) - edge;
+    (left.min(right).min(top).min(bottom) / module_px).max(0.0)
+}
+
+/// Mediciones sobre la cuadrícula muestreada en la imagen original
+struct GridMeasurements {
+    /// Tamaño medio de módulo en píxeles
+    module_size_px: f32,
+    /// Centros de los patrones de búsqueda (superior izquierdo, superior derecho, inferior izquierdo)
+    finder_centers: [(f32, f32); 3],
+    /// Contraste entre la reflectancia media de módulos claros y oscuros (0.0 - 1.0)
+    symbol_contrast: f32,
+    /// Diferencia relativa del tamaño de módulo entre ejes
+    axial_nonuniformity: f32,
+    /// Desplazamiento del patrón de alineación respecto a la cuadrícula ideal (en módulos)
+    grid_nonuniformity: f32,
+    /// Desviación media de cada módulo respecto a la reflectancia de su clase
+    module_deviation: f32,
+    /// Desviación de la ortogonalidad entre ejes (|cos θ|)
+    perspective_distortion: f32,
+    finder_pattern_quality: f32,
+    alignment_pattern_quality: f32,
+}
+
+impl GridMeasurements {
+    /// Mide la cuadrícula a partir de los centros de los patrones de búsqueda
+    /// (`points` = [inferior izquierdo, superior izquierdo, superior derecho, alineación?])
+    fn measure(image: &GrayImage, bits: &BitMatrix, points: &[Point], version: u8) -> Option<Self> {
+        if points.len() < 3 {
+            return None;
+        }
+        let dimension = bits.getHeight();
+        let span = dimension as f32 - 7.0;
+        let (bottom_left, top_left, top_right) = (points[0], points[1], points[2]);
+
+        // Vectores de un módulo en cada eje (afín desde los centros de los patrones)
+        let u = ((top_right.x - top_left.x) / span, (top_right.y - top_left.y) / span);
+        let v = ((bottom_left.x - top_left.x) / span, (bottom_left.y - top_left.y) / span);
+        let module_x = (u.0 * u.0 + u.1 * u.1).sqrt();
+        let module_y = (v.0 * v.0 + v.1 * v.1).sqrt();
+        if module_x < 1.0 || module_y < 1.0 {
+            return None;
+        }
+
+        let sample = |col: f32, row: f32| -> Option<f32> {
+            // El centro del patrón superior izquierdo está en el módulo (3.5, 3.5)
+            let x = top_left.x + (col + 0.5 - 3.5) * u.0 + (row + 0.5 - 3.5) * v.0;
+            let y = top_left.y + (col + 0.5 - 3.5) * u.1 + (row + 0.5 - 3.5) * v.1;
+            if x < 0.0 || y < 0.0 || x >= image.width() as f32 || y >= image.height() as f32 {
+                return None;
+            }
+            Some(image.get_pixel(x as u32, y as u32)[0] as f32)
+        };
+
+        // Reflectancias por clase según la matriz muestreada por el detector
+        let mut samples = Vec::with_capacity((dimension * dimension) as usize);
+        let (mut dark_sum, mut dark_count, mut light_sum, mut light_count) = (0.0, 0usize, 0.0, 0usize);
+        for row in 0..dimension {
+            for col in 0..dimension {
+                let value = sample(col as f32, row as f32)?;
+                let dark = bits.get(col, row);
+                if dark {
+                    dark_sum += value;
+                    dark_count += 1;
+                } else {
+                    light_sum += value;
+                    light_count += 1;
+                }
+                samples.push((col, row, value, dark));
+            }
+        }
+        if dark_count == 0 || light_count == 0 {
+            return None;
+        }
+        let dark_mean = dark_sum / dark_count as f32;
+        let light_mean = light_sum / light_count as f32;
+        let range = (light_mean - dark_mean).max(1.0);
+        let threshold = (light_mean + dark_mean) / 2.0;
+
+        let module_deviation = samples
+            .iter()
+            .map(|&(_, _, value, dark)| {
+                let expected = if dark { dark_mean } else { light_mean };
+                (value - expected).abs() / range
+            })
+            .sum::<f32>()
+            / samples.len() as f32;
+
+        // Patrones de función comparados con su forma ideal
+        let is_dark = |col: u32, row: u32| samples[(row * dimension + col) as usize].2 < threshold;
+        let finder_ideal = |dx: u32, dy: u32| {
+            let ring = dx.min(dy).min(6 - dx).min(6 - dy);
+            ring != 1
+        };
+        let mut finder_matches = 0;
+        for (origin_x, origin_y) in [(0, 0), (dimension - 7, 0), (0, dimension - 7)] {
+            for dy in 0..7 {
+                for dx in 0..7 {
+                    if is_dark(origin_x + dx, origin_y + dy) == finder_ideal(dx, dy) {
+                        finder_matches += 1;
+                    }
+                }
+            }
+        }
+        let finder_pattern_quality = finder_matches as f32 / (3.0 * 49.0);
+
+        let alignment_positions = get_alignment_pattern_positions(version);
+        let alignment_pattern_quality = if alignment_positions.is_empty() {
+            1.0
+        } else {
+            let mut matches = 0;
+            for &(row, col) in &alignment_positions {
+                for dy in 0..5u32 {
+                    for dx in 0..5u32 {
+                        let ring = dx.min(dy).min(4 - dx).min(4 - dy);
+                        let ideal = ring != 1;
+                        if is_dark(col as u32 - 2 + dx, row as u32 - 2 + dy) == ideal {
+                            matches += 1;
+                        }
+                    }
+                }
+            }
+            matches as f32 / (alignment_positions.len() * 25) as f32
+        };
+
+        // Patrón de alineación inferior derecho: posición medida frente a la predicha
+        let grid_nonuniformity = match (points.get(3), alignment_positions.iter().max()) {
+            (Some(measured), Some(&(row, col))) => {
+                let predicted_x = top_left.x + (col as f32 - 3.0) * u.0 + (row as f32 - 3.0) * v.0;
+                let predicted_y = top_left.y + (col as f32 - 3.0) * u.1 + (row as f32 - 3.0) * v.1;
+                let distance = ((measured.x - predicted_x).powi(2) + (measured.y - predicted_y).powi(2)).sqrt();
+                (distance / ((module_x + module_y) / 2.0)).min(1.0)
+            }
+            _ => 0.0,
+        };
+
+        Some(Self {
+            module_size_px: (module_x + module_y) / 2.0,
+            finder_centers: [
+                (top_left.x, top_left.y),
+                (top_right.x, top_right.y),
+                (bottom_left.x, bottom_left.y),
+            ],
+            symbol_contrast: range / 255.0,
+            axial_nonuniformity: (module_x - module_y).abs() / module_x.max(module_y),
+            grid_nonuniformity,
+            module_deviation,
+            perspective_distortion: ((u.0 * v.0 + u.1 * v.1) / (module_x * module_y)).abs(),
+            finder_pattern_quality,
+            alignment_pattern_quality,
+        })
+    }
+}
+
+/// Cuenta módulos activos de una matriz
+fn count_set_bits(matrix: &BitMatrix) -> usize {
+    let mut count = 0;
+    for y in 0..matrix.getHeight() {
+        for x in 0..matrix.getWidth() {
+            if matrix.get(x, y) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Valor de un metadato opcional para el reporte de texto
+fn describe_optional(value: Option<u8>) -> String {
+    value.map_or_else(|| "desconocida".to_string(), |value| value.to_string())
+}
+
+/// Prefijo de esquema URI, que no distingue mayúsculas (`SMSTO:`, `smsto:`)
+fn starts_with_ignore_case(data: &str, prefix: &str) -> bool {
+    data.get(..prefix.len()).is_some_and(|head| head.eq_ignore_ascii_case(prefix))
 }
 
 impl Default for QrDecoder {
@@ -432,6 +1198,40 @@ mod tests {
         assert_eq!(decoder.detect_content_type("Hello World"), ContentType::Text);
     }
     
+    #[test]
+    fn test_payload_content_types() {
+        use crate::engine::QrPayload;
+        let decoder = QrDecoder::new();
+        
+        for (json, expected) in [
+            (r#"{"type": "wifi", "ssid": "Oficina", "password": "clave-segura"}"#, ContentType::Wifi),
+            (r#"{"type": "vcard", "version": "4.0", "first_name": "Ana"}"#, ContentType::VCard),
+            (r#"{"type": "mecard", "first_name": "Ana", "phones": ["600123456"]}"#, ContentType::MeCard),
+            (r#"{"type": "event", "summary": "Feria", "start": "2025-10-17"}"#, ContentType::Event),
+            (r#"{"type": "geo", "latitude": 40.4168, "longitude": -3.7038}"#, ContentType::Geo),
+            (r#"{"type": "sms", "number": "+34600123456", "message": "Hola"}"#, ContentType::Sms),
+            (r#"{"type": "email", "to": "ana@qreable.com", "subject": "Hola"}"#, ContentType::Email),
+        ] {
+            let payload: QrPayload = serde_json::from_str(json).unwrap();
+            assert_eq!(decoder.detect_content_type(&payload.encode().unwrap()), expected, "{}", json);
+        }
+    }
+    
+    #[test]
+    fn test_structured_append_assembly_checks() {
+        let info = |index, parity| StructuredAppendInfo { index, total: 2, parity };
+        let parity = b"abcd".iter().fold(0, |acc, byte| acc ^ byte);
+
+        let parts = vec![(info(1, parity), "cd".to_string()), (info(0, parity), "ab".to_string())];
+        assert_eq!(QrDecoder::assemble_parts(parts).unwrap(), "abcd");
+
+        let missing = vec![(info(1, parity), "cd".to_string())];
+        assert!(QrDecoder::assemble_parts(missing).is_err());
+
+        let wrong_parity = vec![(info(0, parity ^ 1), "ab".to_string()), (info(1, parity ^ 1), "cd".to_string())];
+        assert!(QrDecoder::assemble_parts(wrong_parity).is_err());
+    }
+    
     #[test]
     fn test_quality_calculation() {
         let decoder = QrDecoder::new();
@@ -448,4 +1248,172 @@ mod tests {
         let contrast = decoder.calculate_contrast(&image);
         assert!(contrast > 0.9); // Debe tener alto contraste
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_decode_generated_svg() {
+        let data = "https://example.com/decoder?id=12345";
+        let qr = crate::engine::QrGenerator::new().generate_basic(data, 300).unwrap();
+        let decoder = QrDecoder::new();
+        
+        let decoded = decoder.decode_svg(&qr.to_svg()).unwrap();
+        
+        assert_eq!(decoded.raw_data, data);
+        assert_eq!(decoded.content_type, ContentType::Url);
+        assert_eq!(decoded.metadata.qr_version.map(usize::from), Some((qr.size - 17) / 4));
+        // Las URLs se generan con corrección alta
+        assert_eq!(decoded.metadata.error_correction, "H");
+        assert!(decoded.metadata.mask_pattern.is_some_and(|mask| mask < 8));
+        assert!(decoded.metadata.data_modules > 0);
+        assert_eq!(decoded.metadata.errors_corrected, 0);
+        // SVG a 10 px por módulo rasterizado a SVG_DECODE_WIDTH
+        let expected_module = SVG_DECODE_WIDTH as f32 / (qr.size + 2 * qr.quiet_zone) as f32;
+        assert!((decoded.metadata.module_size_px.unwrap() - expected_module).abs() < 0.5);
+        
+        let quality = decoded.quality;
+        assert!(quality.symbol_contrast > 0.9);
+        assert!(quality.grid_uniformity > 0.9);
+        assert!(quality.metrics.finder_pattern_quality > 0.95);
+        assert!(quality.metrics.alignment_pattern_quality > 0.95);
+        assert!(quality.metrics.perspective_distortion < 0.05);
+        assert_eq!(quality.damage_assessment, DamageLevel::None);
+    }
+    
+    #[test]
+    fn test_decode_svg_measures_quiet_zone() {
+        let data = "https://example.com/embedded";
+        let mut qr = crate::engine::QrGenerator::new().generate_basic(data, 300).unwrap();
+        let decoder = QrDecoder::new();
+
+        // Sin margen propio se lee sobre el fondo claro añadido, pero el margen medido es 0
+        for quiet_zone in [0, 4] {
+            qr.quiet_zone = quiet_zone;
+            let decoded = decoder.decode_svg(&qr.to_svg()).unwrap();
+            assert_eq!(decoded.raw_data, data);
+            let measured = decoded.metadata.quiet_zone_modules.unwrap();
+            assert!((measured - quiet_zone as f32).abs() < 0.5, "{} frente a {}", measured, quiet_zone);
+        }
+    }
+    
+    #[test]
+    fn test_decode_upload() {
+        let generator = crate::engine::QrGenerator::new();
+        let wifi = "WIFI:T:WPA;S:Oficina;P:secreto123;;";
+        let gs1 = "(01)09506000134352(17)251231(10)LOTE42";
+        let mut canvas = GrayImage::from_pixel(680, 360, image::Luma([255]));
+        paint(&mut canvas, &generator.generate_basic(wifi, 300).unwrap().matrix, 40, 40, 8);
+        paint(&mut canvas, &generator.generate_basic(gs1, 300).unwrap().matrix, 360, 40, 8);
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma8(canvas)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+
+        let decoder = QrDecoder::new();
+        let upload = ImageUpload::parse(&png).unwrap();
+        assert!(matches!(upload, ImageUpload::Raster(_)));
+        let codes = decoder.decode_upload(&upload).unwrap();
+        assert_eq!(codes.len(), 2);
+
+        let wifi_code = codes.iter().find(|c| c.raw_data == wifi).unwrap();
+        assert_eq!(wifi_code.content_type, ContentType::Wifi);
+        match crate::engine::QrPayload::parse(&wifi_code.raw_data) {
+            Some(crate::engine::QrPayload::Wifi(payload)) => {
+                assert_eq!(payload.ssid, "Oficina");
+                assert_eq!(payload.password.as_deref(), Some("secreto123"));
+            }
+            other => panic!("{:?}", other),
+        }
+        let gs1_code = codes.iter().find(|c| c.raw_data == gs1).unwrap();
+        assert_eq!(gs1_code.content_type, ContentType::Gs1);
+        let ais: Vec<String> = gs1_code.gs1_elements.iter().flatten().map(|(ai, _)| ai.code()).collect();
+        assert_eq!(ais, vec!["01", "17", "10"]);
+
+        let svg = generator.generate_basic("https://example.com", 300).unwrap().to_svg();
+        let upload = ImageUpload::parse(svg.as_bytes()).unwrap();
+        assert!(matches!(upload, ImageUpload::Svg(_)));
+        let codes = decoder.decode_upload(&upload).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].content_type, ContentType::Url);
+    }
+
+    #[test]
+    fn test_image_upload_rejects_invalid_input() {
+        let png = |width: u32, height: u32| {
+            let mut bytes = Vec::new();
+            DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, image::Luma([255])))
+                .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+                .unwrap();
+            bytes
+        };
+
+        assert!(matches!(ImageUpload::parse(b""), Err(QrError::ValidationError(_))));
+        assert!(matches!(ImageUpload::parse(b"GIF89a..."), Err(QrError::UnsupportedFormat(_))));
+
+        // Imagen válida sin símbolos
+        let blank = ImageUpload::parse(&png(64, 64)).unwrap();
+        assert!(matches!(QrDecoder::new().decode_upload(&blank), Err(QrError::DecodingError(_))));
+
+        // Dimensiones fuera del límite, antes de decodificar o rasterizar
+        assert!(matches!(ImageUpload::parse(&png(MAX_DECODE_SIDE + 1, 1)), Err(QrError::ValidationError(_))));
+        let tall = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 1 10000000" width="1" height="10000000"/>"#;
+        assert!(matches!(ImageUpload::parse(tall.as_bytes()), Err(QrError::ValidationError(_))));
+    }
+    
+    /// Pinta una matriz de módulos en el lienzo a `scale` píxeles por módulo
+    fn paint(canvas: &mut GrayImage, modules: &[Vec<bool>], x0: u32, y0: u32, scale: u32) {
+        for (y, row) in modules.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, dark)| **dark) {
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        canvas.put_pixel(x0 + x as u32 * scale + dx, y0 + y as u32 * scale + dy, image::Luma([0]));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_all_symbols_in_image() {
+        use crate::engine::matrix_codes;
+        use crate::engine::types::MatrixCodeOptions;
+        
+        let generator = crate::engine::QrGenerator::new();
+        let wifi = "WIFI:T:WPA;S:Oficina;P:secreto123;;";
+        let url = "https://example.com/lote/42";
+        let first = generator.generate_basic(wifi, 300).unwrap();
+        let second = generator.generate_basic(url, 300).unwrap();
+        let datamatrix = matrix_codes::encode("LOTE-42", QrSymbology::DataMatrix, None, &MatrixCodeOptions::default()).unwrap();
+        
+        let mut canvas = GrayImage::from_pixel(900, 420, image::Luma([255]));
+        paint(&mut canvas, &first.matrix, 40, 40, 8);
+        paint(&mut canvas, &second.matrix, 360, 40, 8);
+        paint(&mut canvas, &datamatrix.modules, 700, 120, 8);
+        
+        let decoder = QrDecoder::new();
+        let mut decoded = decoder.decode_all(&DynamicImage::ImageLuma8(canvas)).unwrap();
+        decoded.sort_by(|a, b| a.raw_data.cmp(&b.raw_data));
+        
+        let texts: Vec<&str> = decoded.iter().map(|d| d.raw_data.as_str()).collect();
+        assert_eq!(texts, vec!["LOTE-42", wifi, url]);
+        assert_eq!(decoded[0].metadata.symbology, QrSymbology::DataMatrix);
+        // El lector completo no mide versión ni máscara: se informan como desconocidas
+        assert_eq!((decoded[0].metadata.qr_version, decoded[0].metadata.mask_pattern), (None, None));
+        assert_eq!(decoded[1].content_type, ContentType::Wifi);
+        assert_eq!(decoded[2].content_type, ContentType::Url);
+        
+        // Cada código QR se mide sobre su propio recorte
+        for (symbol, qr) in [(&decoded[1], &first), (&decoded[2], &second)] {
+            assert_eq!(symbol.metadata.symbology, QrSymbology::Qr);
+            assert_eq!(symbol.metadata.qr_version.map(usize::from), Some((qr.size - 17) / 4));
+            assert!((symbol.metadata.module_size_px.unwrap() - 8.0).abs() < 0.5);
+            assert_eq!(symbol.quality.damage_assessment, DamageLevel::None);
+        }
+    }
+    
+    #[test]
+    fn test_decode_rejects_image_without_qr() {
+        let decoder = QrDecoder::new();
+        let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(200, 200, image::Luma([255u8])));
+        
+        assert!(matches!(decoder.decode_image(&blank), Err(QrError::DecodingError(_))));
+    }
+}
@@ -0,0 +1,736 @@
+// standards/digital_link.rs - GS1 Digital Link (URIs web con claves GS1)
+
+use crate::engine::error::{QrError, QrResult};
+use super::gs1::{lookup_ai, split_components, validate_ai_value, validate_associations, AiMetadata, ApplicationIdentifier, CharSet};
+
+/// Dominio resolver por defecto de GS1
+pub const DEFAULT_DOMAIN: &str = "https://id.gs1.org";
+
+/// Alfabeto base64 seguro para URI usado por la forma comprimida
+const BASE64_URI: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Claves primarias y sus calificadores, en el orden en que deben aparecer en la ruta.
+/// Cada posición admite alternativas (p.ej. serie `21` o referencia de componente `235`).
+const PRIMARY_KEYS: &[(&str, &[&[&str]])] = &[
+    ("01", &[&["22"], &["10"], &["21", "235"]]),
+    ("8006", &[&["22"], &["10"], &["21"]]),
+    ("8010", &[&["8011"]]),
+    ("414", &[&["254", "7040"]]),
+    ("415", &[&["8020"]]),
+    ("417", &[&["7040"]]),
+    ("8017", &[&["8019"]]),
+    ("8018", &[&["8019"]]),
+    ("00", &[]),
+    ("253", &[]),
+    ("255", &[]),
+    ("401", &[]),
+    ("402", &[]),
+    ("8003", &[]),
+    ("8004", &[]),
+    ("8013", &[]),
+];
+
+/// Pares (código AI, valor)
+type CodedElements = Vec<(String, String)>;
+
+/// Componente de la forma comprimida
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Component {
+    numeric: bool,
+    fixed: bool,
+    length: usize,
+}
+
+/// Componentes con los que se comprime un AI. Los AIs con componentes
+/// opcionales se tratan como un único valor alfanumérico variable.
+fn compression_components(metadata: &AiMetadata) -> Vec<Component> {
+    if metadata.components.iter().any(|c| c.optional) {
+        return vec![Component { numeric: false, fixed: false, length: metadata.max_length }];
+    }
+    metadata
+        .components
+        .iter()
+        .map(|c| Component {
+            numeric: c.charset == CharSet::Numeric,
+            fixed: c.fixed,
+            length: c.length,
+        })
+        .collect()
+}
+
+/// Divide un valor según sus componentes de compresión
+fn split_for_compression<'a>(metadata: &AiMetadata, value: &'a str) -> QrResult<Vec<&'a str>> {
+    if metadata.components.iter().any(|c| c.optional) {
+        return Ok(vec![value]);
+    }
+    split_components(metadata, value)
+}
+
+/// Valida el valor de un AI admitido en GS1 Digital Link
+fn validate_value(code: &str, value: &str) -> QrResult<&'static AiMetadata> {
+    let metadata = lookup_ai(code).ok_or_else(|| {
+        QrError::ValidationError(format!("AI ({}) no admitido en GS1 Digital Link", code))
+    })?;
+    validate_ai_value(code, value)?;
+    Ok(metadata)
+}
+
+fn qualifiers_of(primary: &str) -> Option<&'static [&'static [&'static str]]> {
+    PRIMARY_KEYS
+        .iter()
+        .find(|(key, _)| *key == primary)
+        .map(|(_, qualifiers)| *qualifiers)
+}
+
+fn is_primary_key(code: &str) -> bool {
+    qualifiers_of(code).is_some()
+}
+
+fn is_any_qualifier(code: &str) -> bool {
+    PRIMARY_KEYS
+        .iter()
+        .flat_map(|(_, qualifiers)| qualifiers.iter())
+        .any(|alternatives| alternatives.contains(&code))
+}
+
+/// Ordena los elementos en clave primaria, calificadores de ruta y atributos
+fn arrange(elements: &[(ApplicationIdentifier, String)]) -> QrResult<(CodedElements, CodedElements)> {
+    let coded: Vec<(String, String)> = elements
+        .iter()
+        .map(|(ai, value)| (ai.code(), value.clone()))
+        .collect();
+
+    let mut primaries = coded.iter().filter(|(code, _)| is_primary_key(code));
+    let primary = primaries.next().ok_or_else(|| {
+        QrError::ValidationError("GS1 Digital Link requiere una clave primaria".to_string())
+    })?;
+    if primaries.next().is_some() {
+        return Err(QrError::ValidationError(
+            "GS1 Digital Link admite una sola clave primaria".to_string(),
+        ));
+    }
+
+    let qualifiers = qualifiers_of(&primary.0).unwrap_or(&[]);
+    let mut path = vec![primary.clone()];
+    for alternatives in qualifiers {
+        let mut found = coded.iter().filter(|(code, _)| alternatives.contains(&code.as_str()));
+        if let Some(qualifier) = found.next() {
+            if found.next().is_some() {
+                return Err(QrError::ValidationError(format!(
+                    "Calificador ({}) repetido",
+                    qualifier.0
+                )));
+            }
+            path.push(qualifier.clone());
+        }
+    }
+
+    let mut attributes = Vec::new();
+    for (code, value) in &coded {
+        if path.iter().any(|(path_code, _)| path_code == code) {
+            continue;
+        }
+        if is_primary_key(code) || is_any_qualifier(code) {
+            return Err(QrError::ValidationError(format!(
+                "El AI ({}) no es un calificador de la clave ({})",
+                code, primary.0
+            )));
+        }
+        if attributes.iter().any(|(seen, _): &(String, String)| seen == code) {
+            return Err(QrError::ValidationError(format!("AI ({}) repetido", code)));
+        }
+        attributes.push((code.clone(), value.clone()));
+    }
+
+    for (code, value) in path.iter().chain(&attributes) {
+        validate_value(code, value)?;
+    }
+    validate_associations(elements)?;
+
+    Ok((path, attributes))
+}
+
+/// Codificador de URIs GS1 Digital Link
+pub struct DigitalLinkEncoder {
+    domain: String,
+}
+
+impl DigitalLinkEncoder {
+    pub fn new() -> Self {
+        Self::with_domain(DEFAULT_DOMAIN)
+    }
+
+    /// Usa un dominio propio (p.ej. `https://example.com` o con prefijo de ruta)
+    pub fn with_domain(domain: &str) -> Self {
+        Self {
+            domain: domain.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Genera la forma sin comprimir: `/01/{gtin}/10/{lote}?17={fecha}`
+    pub fn encode(&self, elements: &[(ApplicationIdentifier, String)]) -> QrResult<String> {
+        let (path, attributes) = arrange(elements)?;
+
+        let mut uri = self.domain.clone();
+        for (code, value) in &path {
+            uri.push('/');
+            uri.push_str(code);
+            uri.push('/');
+            uri.push_str(&percent_encode(value));
+        }
+
+        for (index, (code, value)) in attributes.iter().enumerate() {
+            uri.push(if index == 0 { '?' } else { '&' });
+            uri.push_str(code);
+            uri.push('=');
+            uri.push_str(&percent_encode(value));
+        }
+
+        Ok(uri)
+    }
+
+    /// Genera la forma comprimida: los AIs se empaquetan en binario y se
+    /// codifican en base64 seguro para URI en un único segmento de ruta
+    pub fn encode_compressed(&self, elements: &[(ApplicationIdentifier, String)]) -> QrResult<String> {
+        let (path, attributes) = arrange(elements)?;
+
+        let mut bits = BitWriter::default();
+        for (code, value) in path.iter().chain(&attributes) {
+            let metadata = validate_value(code, value)?;
+            for digit in code.chars() {
+                bits.push_value(digit.to_digit(10).unwrap_or(0), 4);
+            }
+            let parts = split_for_compression(metadata, value)?;
+            for (part, component) in parts.iter().zip(compression_components(metadata)) {
+                compress_component(&mut bits, part, &component);
+            }
+        }
+
+        Ok(format!("{}/{}", self.domain, bits.to_base64()))
+    }
+}
+
+impl Default for DigitalLinkEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parser de URIs GS1 Digital Link (formas comprimida y sin comprimir)
+pub struct DigitalLinkParser;
+
+impl DigitalLinkParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extrae los elementos AI de una URI, en orden: clave primaria,
+    /// calificadores y atributos. Los parámetros de consulta no GS1 se ignoran.
+    pub fn parse(&self, uri: &str) -> QrResult<Vec<(ApplicationIdentifier, String)>> {
+        let rest = uri
+            .strip_prefix("https://")
+            .or_else(|| uri.strip_prefix("http://"))
+            .ok_or_else(|| QrError::ValidationError("GS1 Digital Link requiere http(s)".to_string()))?;
+        let rest = rest.split('#').next().unwrap_or(rest);
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+        let segments: Vec<&str> = path.split('/').skip(1).filter(|s| !s.is_empty()).collect();
+
+        let mut elements = match self.parse_path(&segments)? {
+            Some(elements) => elements,
+            None => self.parse_compressed(&segments)?,
+        };
+
+        for pair in query.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            if !key.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if is_primary_key(key) || is_any_qualifier(key) {
+                return Err(QrError::ValidationError(format!(
+                    "El AI ({}) debe ir en la ruta, no en la consulta",
+                    key
+                )));
+            }
+            elements.push((key.to_string(), percent_decode(value)?));
+        }
+
+        for (index, (code, value)) in elements.iter().enumerate() {
+            if elements[..index].iter().any(|(seen, _)| seen == code) {
+                return Err(QrError::ValidationError(format!("AI ({}) repetido", code)));
+            }
+            validate_value(code, value)?;
+        }
+
+        let elements: Vec<(ApplicationIdentifier, String)> = elements
+            .into_iter()
+            .map(|(code, value)| (ApplicationIdentifier::from_code(&code), value))
+            .collect();
+        validate_associations(&elements)?;
+
+        Ok(elements)
+    }
+
+    /// Forma sin comprimir. Devuelve `None` si la ruta no contiene clave primaria.
+    fn parse_path(&self, segments: &[&str]) -> QrResult<Option<Vec<(String, String)>>> {
+        // El dominio puede llevar un prefijo de ruta: la clave primaria es el
+        // primer par AI/valor tras el cual solo quedan pares
+        let start = (0..segments.len()).find(|&index| {
+            is_primary_key(segments[index]) && (segments.len() - index).is_multiple_of(2)
+        });
+        let Some(start) = start else {
+            return Ok(None);
+        };
+
+        let primary = segments[start];
+        let qualifiers = qualifiers_of(primary).unwrap_or(&[]);
+        let mut elements = vec![(primary.to_string(), percent_decode(segments[start + 1])?)];
+        let mut next_position = 0;
+
+        for pair in segments[start + 2..].chunks(2) {
+            let code = pair[0];
+            let position = qualifiers[next_position..]
+                .iter()
+                .position(|alternatives| alternatives.contains(&code))
+                .map(|offset| next_position + offset)
+                .ok_or_else(|| {
+                    QrError::ValidationError(format!(
+                        "Calificador ({}) inválido o fuera de orden para la clave ({})",
+                        code, primary
+                    ))
+                })?;
+            next_position = position + 1;
+            elements.push((code.to_string(), percent_decode(pair[1])?));
+        }
+
+        Ok(Some(elements))
+    }
+
+    /// Forma comprimida: último segmento en base64 seguro para URI
+    fn parse_compressed(&self, segments: &[&str]) -> QrResult<Vec<(String, String)>> {
+        let encoded = segments.last().ok_or_else(|| {
+            QrError::ValidationError("URI sin clave primaria GS1".to_string())
+        })?;
+        let mut bits = BitReader::from_base64(encoded)?;
+        let mut elements = Vec::new();
+
+        // Cada elemento empieza con al menos dos dígitos de AI (8 bits)
+        while bits.remaining() >= 8 {
+            let mut code = String::new();
+            let metadata = loop {
+                if code.len() == 4 {
+                    return Err(QrError::ValidationError(format!(
+                        "AI desconocido en la forma comprimida: {}",
+                        code
+                    )));
+                }
+                let digit = bits.read_value(4)?;
+                if digit > 9 {
+                    return Err(QrError::ValidationError(
+                        "Forma comprimida GS1 inválida".to_string(),
+                    ));
+                }
+                code.push(char::from_digit(digit, 10).unwrap_or('0'));
+                if code.len() >= 2 {
+                    if let Some(metadata) = lookup_ai(&code) {
+                        break metadata;
+                    }
+                }
+            };
+
+            let mut value = String::new();
+            for component in compression_components(metadata) {
+                value.push_str(&decompress_component(&mut bits, &component)?);
+            }
+            elements.push((code, value));
+        }
+
+        let primary = elements.first().map(|(code, _)| code.as_str()).unwrap_or("");
+        if !is_primary_key(primary) {
+            return Err(QrError::ValidationError("URI sin clave primaria GS1".to_string()));
+        }
+        Ok(elements)
+    }
+}
+
+impl Default for DigitalLinkParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Indica si una URI es un GS1 Digital Link válido
+pub fn is_digital_link(uri: &str) -> bool {
+    DigitalLinkParser::new().parse(uri).is_ok()
+}
+
+/// Bits necesarios para representar `digits` dígitos decimales
+fn numeric_bits(digits: usize) -> usize {
+    (digits as f64 * std::f64::consts::LOG2_10).ceil() as usize
+}
+
+/// Bits del indicador de longitud para un máximo dado
+fn length_bits(max: usize) -> usize {
+    (usize::BITS - max.leading_zeros()) as usize
+}
+
+/// Esquemas de codificación de valores alfanuméricos
+const ENCODING_NUMERIC: u32 = 0;
+const ENCODING_HEX_LOWER: u32 = 1;
+const ENCODING_HEX_UPPER: u32 = 2;
+const ENCODING_BASE64: u32 = 3;
+const ENCODING_ASCII: u32 = 4;
+
+fn compress_component(bits: &mut BitWriter, part: &str, component: &Component) {
+    if component.numeric {
+        if !component.fixed {
+            bits.push_value(part.len() as u32, length_bits(component.length));
+        }
+        bits.push_decimal(part);
+        return;
+    }
+
+    let encoding = if part.chars().all(|c| c.is_ascii_digit()) {
+        ENCODING_NUMERIC
+    } else if part.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)) {
+        ENCODING_HEX_LOWER
+    } else if part.chars().all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c)) {
+        ENCODING_HEX_UPPER
+    } else if part.bytes().all(|b| BASE64_URI.contains(&b)) {
+        ENCODING_BASE64
+    } else {
+        ENCODING_ASCII
+    };
+
+    bits.push_value(encoding, 3);
+    if !component.fixed {
+        bits.push_value(part.len() as u32, length_bits(component.length));
+    }
+
+    match encoding {
+        ENCODING_NUMERIC => bits.push_decimal(part),
+        ENCODING_HEX_LOWER | ENCODING_HEX_UPPER => {
+            for c in part.chars() {
+                bits.push_value(c.to_digit(16).unwrap_or(0), 4);
+            }
+        }
+        ENCODING_BASE64 => {
+            for b in part.bytes() {
+                let index = BASE64_URI.iter().position(|&x| x == b).unwrap_or(0);
+                bits.push_value(index as u32, 6);
+            }
+        }
+        _ => {
+            for b in part.bytes() {
+                bits.push_value(u32::from(b), 7);
+            }
+        }
+    }
+}
+
+fn decompress_component(bits: &mut BitReader, component: &Component) -> QrResult<String> {
+    if component.numeric {
+        let length = if component.fixed {
+            component.length
+        } else {
+            bits.read_value(length_bits(component.length))? as usize
+        };
+        return bits.read_decimal(length);
+    }
+
+    let encoding = bits.read_value(3)?;
+    let length = if component.fixed {
+        component.length
+    } else {
+        bits.read_value(length_bits(component.length))? as usize
+    };
+
+    let mut value = String::with_capacity(length);
+    match encoding {
+        ENCODING_NUMERIC => value = bits.read_decimal(length)?,
+        ENCODING_HEX_LOWER | ENCODING_HEX_UPPER => {
+            for _ in 0..length {
+                let nibble = bits.read_value(4)?;
+                let c = char::from_digit(nibble, 16).unwrap_or('0');
+                value.push(if encoding == ENCODING_HEX_UPPER { c.to_ascii_uppercase() } else { c });
+            }
+        }
+        ENCODING_BASE64 => {
+            for _ in 0..length {
+                value.push(BASE64_URI[bits.read_value(6)? as usize] as char);
+            }
+        }
+        ENCODING_ASCII => {
+            for _ in 0..length {
+                value.push(bits.read_value(7)? as u8 as char);
+            }
+        }
+        _ => {
+            return Err(QrError::ValidationError(format!(
+                "Codificación {} desconocida en la forma comprimida",
+                encoding
+            )))
+        }
+    }
+    Ok(value)
+}
+
+/// Acumulador de bits (MSB primero)
+#[derive(Default)]
+struct BitWriter {
+    bits: Vec<bool>,
+}
+
+impl BitWriter {
+    fn push_value(&mut self, value: u32, count: usize) {
+        for shift in (0..count).rev() {
+            self.bits.push((value >> shift) & 1 == 1);
+        }
+    }
+
+    /// Escribe una cadena de dígitos como entero binario de `numeric_bits(len)` bits.
+    /// Se opera sobre los dígitos para no limitar la longitud a 128 bits.
+    fn push_decimal(&mut self, digits: &str) {
+        let width = numeric_bits(digits.len());
+        let mut number: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+        let mut binary = Vec::with_capacity(width);
+
+        for _ in 0..width {
+            let mut remainder = 0;
+            for digit in number.iter_mut() {
+                let current = remainder * 10 + *digit;
+                *digit = current / 2;
+                remainder = current % 2;
+            }
+            binary.push(remainder == 1);
+        }
+
+        self.bits.extend(binary.into_iter().rev());
+    }
+
+    fn to_base64(&self) -> String {
+        self.bits
+            .chunks(6)
+            .map(|chunk| {
+                let index = (0..6).fold(0usize, |acc, i| {
+                    (acc << 1) | usize::from(chunk.get(i).copied().unwrap_or(false))
+                });
+                BASE64_URI[index] as char
+            })
+            .collect()
+    }
+}
+
+/// Lector de bits (MSB primero)
+struct BitReader {
+    bits: Vec<bool>,
+    position: usize,
+}
+
+impl BitReader {
+    fn from_base64(encoded: &str) -> QrResult<Self> {
+        let mut bits = Vec::with_capacity(encoded.len() * 6);
+        for b in encoded.bytes() {
+            let index = BASE64_URI.iter().position(|&x| x == b).ok_or_else(|| {
+                QrError::ValidationError("Forma comprimida GS1 inválida".to_string())
+            })?;
+            for shift in (0..6).rev() {
+                bits.push((index >> shift) & 1 == 1);
+            }
+        }
+        Ok(Self { bits, position: 0 })
+    }
+
+    fn remaining(&self) -> usize {
+        self.bits.len() - self.position
+    }
+
+    fn read_value(&mut self, count: usize) -> QrResult<u32> {
+        if count > self.remaining() {
+            return Err(QrError::ValidationError(
+                "Forma comprimida GS1 truncada".to_string(),
+            ));
+        }
+        let value = self.bits[self.position..self.position + count]
+            .iter()
+            .fold(0, |acc, &bit| (acc << 1) | u32::from(bit));
+        self.position += count;
+        Ok(value)
+    }
+
+    /// Lee un entero binario de `numeric_bits(digits)` bits como cadena de dígitos
+    fn read_decimal(&mut self, digits: usize) -> QrResult<String> {
+        let width = numeric_bits(digits);
+        if width > self.remaining() {
+            return Err(QrError::ValidationError(
+                "Forma comprimida GS1 truncada".to_string(),
+            ));
+        }
+
+        // Acumula en base 10 (dígito menos significativo primero)
+        let mut number = vec![0u32; digits.max(1)];
+        for &bit in &self.bits[self.position..self.position + width] {
+            let mut carry = u32::from(bit);
+            for digit in number.iter_mut() {
+                let current = *digit * 2 + carry;
+                *digit = current % 10;
+                carry = current / 10;
+            }
+            if carry > 0 {
+                return Err(QrError::ValidationError(
+                    "Valor numérico fuera de rango en la forma comprimida".to_string(),
+                ));
+            }
+        }
+        self.position += width;
+
+        Ok(number[..digits]
+            .iter()
+            .rev()
+            .map(|&d| char::from_digit(d, 10).unwrap_or('0'))
+            .collect())
+    }
+}
+
+/// Codifica un valor para un segmento de URI (RFC 3986, caracteres no reservados)
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> QrResult<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| QrError::ValidationError("Escape % inválido en la URI".to_string()))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded)
+        .map_err(|_| QrError::ValidationError("URI con UTF-8 inválido".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(pairs: &[(&str, &str)]) -> Vec<(ApplicationIdentifier, String)> {
+        pairs
+            .iter()
+            .map(|(code, value)| (ApplicationIdentifier::from_code(code), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_uncompressed_round_trip() {
+        let input = elements(&[
+            ("17", "251231"),
+            ("21", "SN-42/7"),
+            ("01", "09780345418913"),
+            ("10", "ABC123"),
+        ]);
+
+        let uri = DigitalLinkEncoder::new().encode(&input).unwrap();
+        assert_eq!(
+            uri,
+            "https://id.gs1.org/01/09780345418913/10/ABC123/21/SN-42%2F7?17=251231"
+        );
+
+        let parsed = DigitalLinkParser::new().parse(&uri).unwrap();
+        assert_eq!(
+            parsed,
+            elements(&[
+                ("01", "09780345418913"),
+                ("10", "ABC123"),
+                ("21", "SN-42/7"),
+                ("17", "251231"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compressed_known_vector() {
+        let input = elements(&[("01", "09780345418913")]);
+        let uri = DigitalLinkEncoder::new().encode_compressed(&input).unwrap();
+        assert_eq!(uri, "https://id.gs1.org/ARHKVAdpQg");
+
+        let parsed = DigitalLinkParser::new().parse(&uri).unwrap();
+        assert_eq!(parsed, input);
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let input = elements(&[
+            ("01", "09780345418913"),
+            ("10", "abc123"),
+            ("21", "Serie-7_X"),
+            ("17", "251231"),
+            ("3103", "000500"),
+            ("8200", "HTTPS://EXAMPLE.COM/P?Q=1"),
+        ]);
+
+        let encoder = DigitalLinkEncoder::with_domain("https://example.com/dl/");
+        let uri = encoder.encode_compressed(&input).unwrap();
+        assert!(uri.starts_with("https://example.com/dl/"));
+
+        let parsed = DigitalLinkParser::new().parse(&uri).unwrap();
+        assert_eq!(parsed, input);
+    }
+
+    #[test]
+    fn test_qualifier_rules() {
+        let encoder = DigitalLinkEncoder::new();
+        let parser = DigitalLinkParser::new();
+
+        // Sin clave primaria o con dos claves
+        assert!(encoder.encode(&elements(&[("10", "ABC")])).is_err());
+        assert!(encoder
+            .encode(&elements(&[("01", "09780345418913"), ("00", "106141411234567897")]))
+            .is_err());
+        // Calificador de otra clave
+        assert!(encoder
+            .encode(&elements(&[("01", "09780345418913"), ("254", "X")]))
+            .is_err());
+        // Dígito de control incorrecto
+        assert!(encoder.encode(&elements(&[("01", "09780345418912")])).is_err());
+
+        // Calificadores fuera de orden o en la consulta
+        assert!(parser
+            .parse("https://id.gs1.org/01/09780345418913/21/S1/10/L1")
+            .is_err());
+        assert!(parser
+            .parse("https://id.gs1.org/01/09780345418913?10=L1")
+            .is_err());
+
+        // Prefijo de ruta y parámetros no GS1
+        let parsed = parser
+            .parse("https://example.com/shop/01/09780345418913/10/L1?utm_source=qr&17=260101")
+            .unwrap();
+        assert_eq!(
+            parsed,
+            elements(&[("01", "09780345418913"), ("10", "L1"), ("17", "260101")])
+        );
+
+        assert!(!is_digital_link("https://example.com/producto"));
+    }
+}
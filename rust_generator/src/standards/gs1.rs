@@ -35,106 +35,767 @@ pub enum ApplicationIdentifier {
     Custom(String), // Para AIs no definidos aquí
 }
 
+impl ApplicationIdentifier {
+    /// Código numérico del AI
+    pub fn code(&self) -> String {
+        match self {
+            ApplicationIdentifier::GTIN => "01".to_string(),
+            ApplicationIdentifier::BatchLot => "10".to_string(),
+            ApplicationIdentifier::ProductionDate => "11".to_string(),
+            ApplicationIdentifier::DueDate => "12".to_string(),
+            ApplicationIdentifier::ExpiryDate => "17".to_string(),
+            ApplicationIdentifier::SerialNumber => "21".to_string(),
+            ApplicationIdentifier::NetWeight => "3103".to_string(),
+            ApplicationIdentifier::Length => "3113".to_string(),
+            ApplicationIdentifier::Width => "3123".to_string(),
+            ApplicationIdentifier::Height => "3133".to_string(),
+            ApplicationIdentifier::Area => "3143".to_string(),
+            ApplicationIdentifier::Volume => "3153".to_string(),
+            ApplicationIdentifier::CountryOfOrigin => "422".to_string(),
+            ApplicationIdentifier::GLN => "410".to_string(),
+            ApplicationIdentifier::SSCC => "00".to_string(),
+            ApplicationIdentifier::ProductURL => "8200".to_string(),
+            ApplicationIdentifier::Custom(code) => code.clone(),
+        }
+    }
+    
+    /// AI correspondiente a un código numérico
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "01" => ApplicationIdentifier::GTIN,
+            "10" => ApplicationIdentifier::BatchLot,
+            "11" => ApplicationIdentifier::ProductionDate,
+            "12" => ApplicationIdentifier::DueDate,
+            "17" => ApplicationIdentifier::ExpiryDate,
+            "21" => ApplicationIdentifier::SerialNumber,
+            "3103" => ApplicationIdentifier::NetWeight,
+            "3113" => ApplicationIdentifier::Length,
+            "3123" => ApplicationIdentifier::Width,
+            "3133" => ApplicationIdentifier::Height,
+            "3143" => ApplicationIdentifier::Area,
+            "3153" => ApplicationIdentifier::Volume,
+            "422" => ApplicationIdentifier::CountryOfOrigin,
+            "410" => ApplicationIdentifier::GLN,
+            "00" => ApplicationIdentifier::SSCC,
+            "8200" => ApplicationIdentifier::ProductURL,
+            _ => ApplicationIdentifier::Custom(code.to_string()),
+        }
+    }
+}
+
+/// Separador de grupo (GS, 0x1D): representa FNC1 dentro de los datos de un símbolo GS1
+pub const GROUP_SEPARATOR: char = '\u{1D}';
+
+/// Dígito de control GS1 (módulo 10, pesos 3 y 1 desde la derecha) de una clave numérica
+/// cuyo último dígito es el de control (GTIN, SSCC, GLN...)
+pub fn is_valid_check_digit(key: &str) -> bool {
+    match key.char_indices().last() {
+        Some((last, check)) if last > 0 => {
+            check.is_ascii_digit() && check_digit(&key[..last]) == check.to_digit(10)
+        }
+        _ => false,
+    }
+}
+
+/// Calcula el dígito de control GS1 de una clave sin él (None si no es numérica)
+pub fn check_digit(body: &str) -> Option<u32> {
+    let digits: Vec<u32> = body.chars().map(|c| c.to_digit(10)).collect::<Option<_>>()?;
+    if digits.is_empty() {
+        return None;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| if i % 2 == 0 { digit * 3 } else { digit })
+        .sum();
+    Some((10 - sum % 10) % 10)
+}
+
+/// Juego de caracteres de un componente
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CharSet {
+    Numeric, // N: dígitos
+    Cset82,  // X: conjunto de 82 caracteres GS1
+    Cset39,  // Y: mayúsculas, dígitos y # - /
+    Cset64,  // Z: base64 seguro para URI
+}
+
+/// Componente del valor de un AI (p.ej. `N13` o `X..17`)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AiComponent {
+    pub charset: CharSet,
+    pub length: usize,
+    pub fixed: bool,
+    pub optional: bool,
+}
+
 /// Metadatos de un AI
-struct AiMetadata {
-    code: &'static str,
-    name: &'static str,
+pub(crate) struct AiMetadata {
+    pub code: String,
+    pub title: &'static str,
+    pub components: Vec<AiComponent>,
     format: AiFormat,
-    min_length: usize,
-    max_length: usize,
-    fnc1_required: bool,
+    check: CheckRule,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub fnc1_required: bool,
 }
 
-/// Formato del valor del AI
+/// Semántica del valor del AI, además de juego de caracteres y longitud
 #[derive(Debug, Clone, Copy)]
 enum AiFormat {
-    Numeric,
-    Alphanumeric,
+    Generic,
     Date,       // YYMMDD
-    DateTime,   // YYMMDDHHMM
+    MonthDate,  // YYMMDD, DD = 00 indica el último día del mes
+    DateTime,   // YYMMDDHH[MM[SS]]
+}
+
+/// Carácter de control del valor
+#[derive(Debug, Clone, Copy)]
+enum CheckRule {
+    None,
+    Mod10(usize), // Dígito módulo 10 al final de los primeros n dígitos
+    CheckPair,    // Par de caracteres de control (GMN, MUDI)
+}
+
+/// Entrada del diccionario de AIs. Los códigos con `n` son familias cuyo
+/// último dígito es un indicador decimal o de secuencia (310n = 3100..3109).
+struct AiDefinition {
+    code: &'static str,
+    title: &'static str,
+    spec: &'static str,
+    format: AiFormat,
+    check: CheckRule,
 }
 
-/// Mapa global de AIs
-static AI_REGISTRY: Lazy<HashMap<&'static str, AiMetadata>> = Lazy::new(|| {
+const fn plain(code: &'static str, title: &'static str, spec: &'static str) -> AiDefinition {
+    AiDefinition { code, title, spec, format: AiFormat::Generic, check: CheckRule::None }
+}
+
+const fn key(code: &'static str, title: &'static str, spec: &'static str, digits: usize) -> AiDefinition {
+    AiDefinition { code, title, spec, format: AiFormat::Generic, check: CheckRule::Mod10(digits) }
+}
+
+const fn date(code: &'static str, title: &'static str, spec: &'static str) -> AiDefinition {
+    AiDefinition { code, title, spec, format: AiFormat::Date, check: CheckRule::None }
+}
+
+const fn month_date(code: &'static str, title: &'static str) -> AiDefinition {
+    AiDefinition { code, title, spec: "N6", format: AiFormat::MonthDate, check: CheckRule::None }
+}
+
+const fn date_time(code: &'static str, title: &'static str, spec: &'static str) -> AiDefinition {
+    AiDefinition { code, title, spec, format: AiFormat::DateTime, check: CheckRule::None }
+}
+
+const fn check_pair(code: &'static str, title: &'static str, spec: &'static str) -> AiDefinition {
+    AiDefinition { code, title, spec, format: AiFormat::Generic, check: CheckRule::CheckPair }
+}
+
+/// Diccionario de AIs de las GS1 General Specifications (formato: componentes
+/// separados por coma, `[..]` para componentes opcionales)
+const AI_TABLE: &[AiDefinition] = &[
+    // Claves de identificación
+    key("00", "SSCC", "N18", 18),
+    key("01", "GTIN", "N14", 14),
+    key("02", "CONTENT", "N14", 14),
+    key("03", "MTO GTIN", "N14", 14),
+    plain("10", "BATCH/LOT", "X..20"),
+    month_date("11", "PROD DATE"),
+    month_date("12", "DUE DATE"),
+    month_date("13", "PACK DATE"),
+    month_date("15", "BEST BEFORE"),
+    month_date("16", "SELL BY"),
+    month_date("17", "USE BY"),
+    plain("20", "VARIANT", "N2"),
+    plain("21", "SERIAL", "X..20"),
+    plain("22", "CPV", "X..20"),
+    plain("235", "TPX", "X..28"),
+    plain("240", "ADDITIONAL ID", "X..30"),
+    plain("241", "CUST. PART No.", "X..30"),
+    plain("242", "MTO VARIANT", "N..6"),
+    plain("243", "PCN", "X..20"),
+    plain("250", "SECONDARY SERIAL", "X..30"),
+    plain("251", "REF. TO SOURCE", "X..30"),
+    key("253", "GDTI", "N13,X..17", 13),
+    plain("254", "GLN EXTENSION COMPONENT", "X..20"),
+    key("255", "GCN", "N13,N..12", 13),
+    plain("30", "VAR. COUNT", "N..8"),
+    // Medidas comerciales y logísticas (n = posición decimal)
+    plain("310n", "NET WEIGHT (kg)", "N6"),
+    plain("311n", "LENGTH (m)", "N6"),
+    plain("312n", "WIDTH (m)", "N6"),
+    plain("313n", "HEIGHT (m)", "N6"),
+    plain("314n", "AREA (m2)", "N6"),
+    plain("315n", "NET VOLUME (l)", "N6"),
+    plain("316n", "NET VOLUME (m3)", "N6"),
+    plain("320n", "NET WEIGHT (lb)", "N6"),
+    plain("321n", "LENGTH (in)", "N6"),
+    plain("322n", "LENGTH (ft)", "N6"),
+    plain("323n", "LENGTH (yd)", "N6"),
+    plain("324n", "WIDTH (in)", "N6"),
+    plain("325n", "WIDTH (ft)", "N6"),
+    plain("326n", "WIDTH (yd)", "N6"),
+    plain("327n", "HEIGHT (in)", "N6"),
+    plain("328n", "HEIGHT (ft)", "N6"),
+    plain("329n", "HEIGHT (yd)", "N6"),
+    plain("330n", "GROSS WEIGHT (kg)", "N6"),
+    plain("331n", "LENGTH (m), log", "N6"),
+    plain("332n", "WIDTH (m), log", "N6"),
+    plain("333n", "HEIGHT (m), log", "N6"),
+    plain("334n", "AREA (m2), log", "N6"),
+    plain("335n", "VOLUME (l), log", "N6"),
+    plain("336n", "VOLUME (m3), log", "N6"),
+    plain("337n", "KG PER m2", "N6"),
+    plain("340n", "GROSS WEIGHT (lb)", "N6"),
+    plain("341n", "LENGTH (in), log", "N6"),
+    plain("342n", "LENGTH (ft), log", "N6"),
+    plain("343n", "LENGTH (yd), log", "N6"),
+    plain("344n", "WIDTH (in), log", "N6"),
+    plain("345n", "WIDTH (ft), log", "N6"),
+    plain("346n", "WIDTH (yd), log", "N6"),
+    plain("347n", "HEIGHT (in), log", "N6"),
+    plain("348n", "HEIGHT (ft), log", "N6"),
+    plain("349n", "HEIGHT (yd), log", "N6"),
+    plain("350n", "AREA (in2)", "N6"),
+    plain("351n", "AREA (ft2)", "N6"),
+    plain("352n", "AREA (yd2)", "N6"),
+    plain("353n", "AREA (in2), log", "N6"),
+    plain("354n", "AREA (ft2), log", "N6"),
+    plain("355n", "AREA (yd2), log", "N6"),
+    plain("356n", "NET WEIGHT (t oz)", "N6"),
+    plain("357n", "NET VOLUME (oz)", "N6"),
+    plain("360n", "NET VOLUME (qt)", "N6"),
+    plain("361n", "NET VOLUME (gal.)", "N6"),
+    plain("362n", "VOLUME (qt), log", "N6"),
+    plain("363n", "VOLUME (gal.), log", "N6"),
+    plain("364n", "VOLUME (in3)", "N6"),
+    plain("365n", "VOLUME (ft3)", "N6"),
+    plain("366n", "VOLUME (yd3)", "N6"),
+    plain("367n", "VOLUME (in3), log", "N6"),
+    plain("368n", "VOLUME (ft3), log", "N6"),
+    plain("369n", "VOLUME (yd3), log", "N6"),
+    plain("37", "COUNT", "N..8"),
+    // Importes y precios (n = posición decimal)
+    plain("390n", "AMOUNT", "N..15"),
+    plain("391n", "AMOUNT", "N3,N..15"),
+    plain("392n", "PRICE", "N..15"),
+    plain("393n", "PRICE", "N3,N..15"),
+    plain("394n", "PRCNT OFF", "N4"),
+    plain("395n", "PRICE/UoM", "N6"),
+    // Referencias y localizaciones
+    plain("400", "ORDER NUMBER", "X..30"),
+    plain("401", "GINC", "X..30"),
+    key("402", "GSIN", "N17", 17),
+    plain("403", "ROUTE", "X..30"),
+    key("410", "SHIP TO LOC", "N13", 13),
+    key("411", "BILL TO", "N13", 13),
+    key("412", "PURCHASE FROM", "N13", 13),
+    key("413", "SHIP FOR LOC", "N13", 13),
+    key("414", "LOC No.", "N13", 13),
+    key("415", "PAY TO", "N13", 13),
+    key("416", "PROD/SERV LOC", "N13", 13),
+    key("417", "PARTY", "N13", 13),
+    plain("420", "SHIP TO POST", "X..20"),
+    plain("421", "SHIP TO POST", "N3,X..9"),
+    plain("422", "ORIGIN", "N3"),
+    plain("423", "COUNTRY - INITIAL PROCESS", "N3,N..12"),
+    plain("424", "COUNTRY - PROCESS", "N3"),
+    plain("425", "COUNTRY - DISASSEMBLY", "N3,N..12"),
+    plain("426", "COUNTRY - FULL PROCESS", "N3"),
+    plain("427", "ORIGIN SUBDIVISION", "X..3"),
+    plain("4300", "SHIP TO COMP", "X..35"),
+    plain("4301", "SHIP TO NAME", "X..35"),
+    plain("4302", "SHIP TO ADD1", "X..70"),
+    plain("4303", "SHIP TO ADD2", "X..70"),
+    plain("4304", "SHIP TO SUB", "X..70"),
+    plain("4305", "SHIP TO LOC", "X..70"),
+    plain("4306", "SHIP TO REG", "X..70"),
+    plain("4307", "SHIP TO COUNTRY", "X2"),
+    plain("4308", "SHIP TO PHONE", "X..30"),
+    plain("4309", "SHIP TO GEO", "N20"),
+    plain("4310", "RTN TO COMP", "X..35"),
+    plain("4311", "RTN TO NAME", "X..35"),
+    plain("4312", "RTN TO ADD1", "X..70"),
+    plain("4313", "RTN TO ADD2", "X..70"),
+    plain("4314", "RTN TO SUB", "X..70"),
+    plain("4315", "RTN TO LOC", "X..70"),
+    plain("4316", "RTN TO REG", "X..70"),
+    plain("4317", "RTN TO COUNTRY", "X2"),
+    plain("4318", "RTN TO POST", "X..20"),
+    plain("4319", "RTN TO PHONE", "X..30"),
+    plain("4320", "SRV DESCRIPTION", "X..35"),
+    plain("4321", "DANGEROUS GOODS", "N1"),
+    plain("4322", "AUTH LEAVE", "N1"),
+    plain("4323", "SIG REQUIRED", "N1"),
+    date_time("4324", "NBEF DEL DT", "N10"),
+    date_time("4325", "NAFT DEL DT", "N10"),
+    date("4326", "REL DATE", "N6"),
+    plain("4330", "MAX TEMP F", "N6,[X1]"),
+    plain("4331", "MAX TEMP C", "N6,[X1]"),
+    plain("4332", "MIN TEMP F", "N6,[X1]"),
+    plain("4333", "MIN TEMP C", "N6,[X1]"),
+    // Información de producto, salud y trazabilidad
+    plain("7001", "NSN", "N13"),
+    plain("7002", "MEAT CUT", "X..30"),
+    date_time("7003", "EXPIRY TIME", "N10"),
+    plain("7004", "ACTIVE POTENCY", "N..4"),
+    plain("7005", "CATCH AREA", "X..12"),
+    date("7006", "FIRST FREEZE DATE", "N6"),
+    date("7007", "HARVEST DATE", "N6,[N6]"),
+    plain("7008", "AQUATIC SPECIES", "X..3"),
+    plain("7009", "FISHING GEAR TYPE", "X..10"),
+    plain("7010", "PROD METHOD", "X..2"),
+    date_time("7011", "TEST BY DATE", "N6,[N4]"),
+    plain("7020", "REFURB LOT", "X..20"),
+    plain("7021", "FUNC STAT", "X..20"),
+    plain("7022", "REV STAT", "X..20"),
+    plain("7023", "GIAI - ASSEMBLY", "X..30"),
+    plain("703n", "PROCESSOR #", "N3,X..27"),
+    plain("7040", "UIC+EXT", "N1,X3"),
+    plain("7041", "UFRGT UNIT TYPE", "X..4"),
+    plain("710", "NHRN PZN", "X..20"),
+    plain("711", "NHRN CIP", "X..20"),
+    plain("712", "NHRN CN", "X..20"),
+    plain("713", "NHRN DRN", "X..20"),
+    plain("714", "NHRN AIM", "X..20"),
+    plain("715", "NHRN NDC", "X..20"),
+    plain("716", "NHRN AIC", "X..20"),
+    plain("723n", "CERT #", "X2,X..28"),
+    plain("7240", "PROTOCOL", "X..20"),
+    plain("7241", "AIDC MEDIA TYPE", "N2"),
+    plain("7242", "VCN", "X..25"),
+    plain("7250", "DOB", "N8"),
+    plain("7251", "DOB TIME", "N12"),
+    plain("7252", "BIO SEX", "N1"),
+    plain("7253", "FAMILY NAME", "X..40"),
+    plain("7254", "GIVEN NAME", "X..40"),
+    plain("7255", "SUFFIX", "X..10"),
+    plain("7256", "FULL NAME", "X..90"),
+    plain("7257", "PERSON ADDR", "X..70"),
+    plain("7258", "BIRTH SEQUENCE", "N1,X1,N1"),
+    plain("7259", "BABY", "X..40"),
+    plain("8001", "DIMENSIONS", "N14"),
+    plain("8002", "CMT No.", "X..20"),
+    key("8003", "GRAI", "N14,X..16", 14),
+    plain("8004", "GIAI", "X..30"),
+    plain("8005", "PRICE PER UNIT", "N6"),
+    key("8006", "ITIP", "N14,N2,N2", 14),
+    plain("8007", "IBAN", "X..34"),
+    date_time("8008", "PROD TIME", "N8,[N..4]"),
+    plain("8009", "OPTSEN", "X..50"),
+    plain("8010", "CPID", "Y..30"),
+    plain("8011", "CPID SERIAL", "N..12"),
+    plain("8012", "VERSION", "X..20"),
+    check_pair("8013", "GMN", "X..25"),
+    check_pair("8014", "MUDI", "X..25"),
+    key("8017", "GSRN - PROVIDER", "N18", 18),
+    key("8018", "GSRN - RECIPIENT", "N18", 18),
+    plain("8019", "SRIN", "N..10"),
+    plain("8020", "REF No.", "X..25"),
+    key("8026", "ITIP CONTENT", "N14,N2,N2", 14),
+    plain("8030", "DIGSIG", "Z..90"),
+    plain("8110", "COUPON", "X..70"),
+    plain("8111", "POINTS", "N4"),
+    plain("8112", "COUPON", "X..70"),
+    plain("8200", "PRODUCT URL", "X..70"),
+    // Uso interno de la empresa
+    plain("90", "INTERNAL", "X..30"),
+    plain("91", "INTERNAL", "X..90"),
+    plain("92", "INTERNAL", "X..90"),
+    plain("93", "INTERNAL", "X..90"),
+    plain("94", "INTERNAL", "X..90"),
+    plain("95", "INTERNAL", "X..90"),
+    plain("96", "INTERNAL", "X..90"),
+    plain("97", "INTERNAL", "X..90"),
+    plain("98", "INTERNAL", "X..90"),
+    plain("99", "INTERNAL", "X..90"),
+];
+
+/// Prefijos de AI con longitud predefinida: no necesitan separador FNC1
+const PREDEFINED_LENGTH_PREFIXES: &[&str] = &[
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20",
+    "31", "32", "33", "34", "35", "36", "41",
+];
+
+/// Asociaciones obligatorias: el AI exige al menos un AI de cada grupo.
+/// `n` en un patrón admite cualquier dígito.
+const REQUIRED_ASSOCIATIONS: &[(&str, &[&[&str]])] = &[
+    ("02", &[&["37"]]),
+    ("37", &[&["02", "8026"]]),
+    ("10", &[&["01", "02", "03", "8006", "8026"]]),
+    ("11", &[&["01", "02", "03", "8006", "8026"]]),
+    ("12", &[&["8020"]]),
+    ("13", &[&["01", "02", "03", "8006", "8026"]]),
+    ("15", &[&["01", "02", "03", "8006", "8026"]]),
+    ("16", &[&["01", "02", "03", "8006", "8026"]]),
+    ("17", &[&["01", "02", "03", "8006", "8026"]]),
+    ("20", &[&["01", "02", "03", "8006", "8026"]]),
+    ("21", &[&["01", "03", "8006"]]),
+    ("22", &[&["01"]]),
+    ("235", &[&["01"]]),
+    ("240", &[&["01", "02", "03", "8006", "8026"]]),
+    ("241", &[&["01", "02", "03", "8006", "8026"]]),
+    ("242", &[&["01", "03"]]),
+    ("243", &[&["01"]]),
+    ("250", &[&["01", "8006"], &["21"]]),
+    ("251", &[&["01", "8006"]]),
+    ("254", &[&["414"]]),
+    ("30", &[&["01", "02"]]),
+    ("31nn", &[&["01", "02"]]),
+    ("32nn", &[&["01", "02"]]),
+    ("33nn", &[&["00", "01", "02"]]),
+    ("34nn", &[&["00", "01", "02"]]),
+    ("35nn", &[&["01", "02"]]),
+    ("36nn", &[&["01", "02"]]),
+    ("390n", &[&["255", "8020"]]),
+    ("391n", &[&["8020"]]),
+    ("392n", &[&["01"]]),
+    ("393n", &[&["01"]]),
+    ("394n", &[&["255"]]),
+    ("395n", &[&["01"]]),
+    ("403", &[&["00"]]),
+    ("430n", &[&["00"]]),
+    ("431n", &[&["00"]]),
+    ("432n", &[&["00"]]),
+    ("433n", &[&["00", "01", "02"]]),
+    ("700n", &[&["01", "02"]]),
+    ("701n", &[&["01", "02"]]),
+    ("702n", &[&["01", "8006"]]),
+    ("7023", &[&["8004"]]),
+    ("703n", &[&["01", "02"]]),
+    ("7040", &[&["414", "417"]]),
+    ("71n", &[&["01"]]),
+    ("723n", &[&["01", "8004"]]),
+    ("7240", &[&["01"]]),
+    ("725n", &[&["8018"]]),
+    ("8001", &[&["01"]]),
+    ("8005", &[&["01", "02"]]),
+    ("8008", &[&["01", "02", "8006"]]),
+    ("8011", &[&["8010"]]),
+    ("8012", &[&["01", "8006"]]),
+    ("8019", &[&["8017", "8018"]]),
+    ("8020", &[&["415"]]),
+    ("8026", &[&["37"]]),
+    ("8200", &[&["01"]]),
+];
+
+/// Pares de AIs que no pueden aparecer juntos
+const INVALID_PAIRS: &[(&str, &str)] = &[
+    ("01", "02"),
+    ("01", "03"),
+    ("01", "37"),
+    ("01", "255"),
+    ("01", "8006"),
+    ("01", "8026"),
+    ("02", "03"),
+    ("02", "8006"),
+    ("21", "235"),
+    ("390n", "391n"),
+    ("392n", "393n"),
+    ("394n", "8111"),
+    ("422", "426"),
+    ("423", "426"),
+    ("424", "426"),
+    ("425", "426"),
+    ("8006", "8026"),
+    ("8017", "8018"),
+];
+
+/// Mapa global de AIs, con las familias expandidas
+static AI_REGISTRY: Lazy<HashMap<String, AiMetadata>> = Lazy::new(|| {
     let mut registry = HashMap::new();
     
-    // Identificación de productos
-    registry.insert("01", AiMetadata {
-        code: "01",
-        name: "GTIN",
-        format: AiFormat::Numeric,
-        min_length: 14,
-        max_length: 14,
-        fnc1_required: false,
-    });
-    
-    registry.insert("10", AiMetadata {
-        code: "10",
-        name: "Batch/Lot Number",
-        format: AiFormat::Alphanumeric,
-        min_length: 1,
-        max_length: 20,
-        fnc1_required: true,
-    });
-    
-    registry.insert("11", AiMetadata {
-        code: "11",
-        name: "Production Date",
-        format: AiFormat::Date,
-        min_length: 6,
-        max_length: 6,
-        fnc1_required: false,
-    });
-    
-    registry.insert("17", AiMetadata {
-        code: "17",
-        name: "Expiry Date",
-        format: AiFormat::Date,
-        min_length: 6,
-        max_length: 6,
-        fnc1_required: false,
-    });
-    
-    registry.insert("21", AiMetadata {
-        code: "21",
-        name: "Serial Number",
-        format: AiFormat::Alphanumeric,
-        min_length: 1,
-        max_length: 20,
-        fnc1_required: true,
-    });
-    
-    // Medidas (ejemplo: 3103 = peso neto en kg con 3 decimales)
-    registry.insert("3103", AiMetadata {
-        code: "3103",
-        name: "Net Weight (kg)",
-        format: AiFormat::Numeric,
-        min_length: 6,
-        max_length: 6,
-        fnc1_required: false,
-    });
-    
-    registry.insert("00", AiMetadata {
-        code: "00",
-        name: "SSCC",
-        format: AiFormat::Numeric,
-        min_length: 18,
-        max_length: 18,
-        fnc1_required: false,
-    });
-    
-    registry.insert("8200", AiMetadata {
-        code: "8200",
-        name: "Product URL",
-        format: AiFormat::Alphanumeric,
-        min_length: 1,
-        max_length: 70,
-        fnc1_required: true,
-    });
+    for definition in AI_TABLE {
+        let codes: Vec<String> = match definition.code.strip_suffix('n') {
+            Some(prefix) => (0..10).map(|digit| format!("{}{}", prefix, digit)).collect(),
+            None => vec![definition.code.to_string()],
+        };
+        
+        let components = parse_components(definition.spec);
+        let min_length = components
+            .iter()
+            .filter(|c| !c.optional)
+            .map(|c| if c.fixed { c.length } else { 1 })
+            .sum();
+        let max_length = components.iter().map(|c| c.length).sum();
+        
+        for code in codes {
+            let fnc1_required = !PREDEFINED_LENGTH_PREFIXES.contains(&&code[..2]);
+            registry.insert(code.clone(), AiMetadata {
+                code,
+                title: definition.title,
+                components: components.clone(),
+                format: definition.format,
+                check: definition.check,
+                min_length,
+                max_length,
+                fnc1_required,
+            });
+        }
+    }
     
     registry
 });
 
+/// Interpreta la notación de formato GS1 (`N13,X..17`, `N6,[X1]`)
+fn parse_components(spec: &str) -> Vec<AiComponent> {
+    spec.split(',')
+        .map(|part| {
+            let optional = part.starts_with('[');
+            let part = part.trim_start_matches('[').trim_end_matches(']');
+            let charset = match part.as_bytes()[0] {
+                b'N' => CharSet::Numeric,
+                b'Y' => CharSet::Cset39,
+                b'Z' => CharSet::Cset64,
+                _ => CharSet::Cset82,
+            };
+            let fixed = !part[1..].starts_with("..");
+            let length = part[1..].trim_start_matches("..").parse().unwrap_or(0);
+            AiComponent { charset, length, fixed, optional }
+        })
+        .collect()
+}
+
+/// Busca los metadatos de un AI
+pub(crate) fn lookup_ai(code: &str) -> Option<&'static AiMetadata> {
+    AI_REGISTRY.get(code)
+}
+
+/// Indica si un carácter pertenece al juego de caracteres
+fn in_charset(c: char, charset: CharSet) -> bool {
+    match charset {
+        CharSet::Numeric => c.is_ascii_digit(),
+        CharSet::Cset82 => CSET82.contains(c),
+        CharSet::Cset39 => c.is_ascii_uppercase() || c.is_ascii_digit() || "#-/".contains(c),
+        CharSet::Cset64 => c.is_ascii_alphanumeric() || "-_=".contains(c),
+    }
+}
+
+/// Conjunto de 82 caracteres GS1, en el orden usado por el par de control
+const CSET82: &str = "!\"%&'()*+,-./0123456789:;<=>?ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+
+/// Alfabeto del par de caracteres de control
+const CSET32: &[u8; 32] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// Verifica el par de caracteres de control final (GMN y MUDI): suma de los
+/// valores CSET 82 ponderados por primos desde la derecha, módulo 1021
+fn is_valid_check_pair(value: &str) -> bool {
+    const PRIMES: [usize; 23] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83];
+    
+    if value.len() < 3 || value.len() > PRIMES.len() + 2 || !value.is_ascii() {
+        return false;
+    }
+    let (body, pair) = value.split_at(value.len() - 2);
+    
+    let mut sum = 0;
+    for (c, weight) in body.chars().rev().zip(PRIMES) {
+        match CSET82.find(c) {
+            Some(index) => sum += index * weight,
+            None => return false,
+        }
+    }
+    sum %= 1021;
+    
+    pair.as_bytes() == [CSET32[sum / 32], CSET32[sum % 32]]
+}
+
+/// Valida una fecha YYMMDD; `month_only` admite DD = 00
+fn is_valid_date(value: &str, month_only: bool) -> bool {
+    let field = |range: std::ops::Range<usize>| value.get(range).and_then(|s| s.parse::<u32>().ok());
+    let (Some(year), Some(month), Some(day)) = (field(0..2), field(2..4), field(4..6)) else {
+        return false;
+    };
+    
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (day == 0 && month_only) || (1..=days_in_month).contains(&day)
+}
+
+/// Divide un valor en los componentes de su AI
+pub(crate) fn split_components<'a>(metadata: &AiMetadata, value: &'a str) -> QrResult<Vec<&'a str>> {
+    let mut parts = Vec::with_capacity(metadata.components.len());
+    let mut rest = value;
+    
+    for (index, component) in metadata.components.iter().enumerate() {
+        if rest.is_empty() && component.optional {
+            break;
+        }
+        let take = if component.fixed {
+            component.length
+        } else if index + 1 == metadata.components.len() {
+            rest.len()
+        } else {
+            component.length.min(rest.len())
+        };
+        
+        if rest.len() < take || !rest.is_char_boundary(take) {
+            return Err(QrError::ValidationError(format!(
+                "AI {} ({}) incompleto: '{}'",
+                metadata.code, metadata.title, value
+            )));
+        }
+        let (part, tail) = rest.split_at(take);
+        parts.push(part);
+        rest = tail;
+    }
+    
+    if !rest.is_empty() {
+        return Err(QrError::ValidationError(format!(
+            "AI {} ({}) demasiado largo: '{}'",
+            metadata.code, metadata.title, value
+        )));
+    }
+    Ok(parts)
+}
+
+/// Valida longitud, juego de caracteres, fechas y caracteres de control
+fn check_ai_value(metadata: &AiMetadata, value: &str, verify_check_characters: bool) -> QrResult<()> {
+    if value.len() < metadata.min_length || value.len() > metadata.max_length {
+        return Err(QrError::ValidationError(format!(
+            "AI {} requiere longitud entre {} y {}, se proporcionó {}",
+            metadata.code, metadata.min_length, metadata.max_length, value.len()
+        )));
+    }
+    
+    let parts = split_components(metadata, value)?;
+    for (part, component) in parts.iter().zip(&metadata.components) {
+        if part.is_empty() || part.len() > component.length {
+            return Err(QrError::ValidationError(format!(
+                "AI {} ({}) tiene un componente de longitud inválida",
+                metadata.code, metadata.title
+            )));
+        }
+        if let Some(c) = part.chars().find(|&c| !in_charset(c, component.charset)) {
+            return Err(QrError::ValidationError(match component.charset {
+                CharSet::Numeric => format!("AI {} requiere solo dígitos numéricos", metadata.code),
+                _ => format!("AI {} contiene el carácter no válido '{}'", metadata.code, c),
+            }));
+        }
+    }
+    
+    match metadata.format {
+        AiFormat::Generic => {},
+        AiFormat::Date | AiFormat::MonthDate | AiFormat::DateTime => {
+            let month_only = matches!(metadata.format, AiFormat::MonthDate);
+            if !is_valid_date(value, month_only) {
+                return Err(QrError::ValidationError(format!(
+                    "AI {} contiene fecha inválida: {}",
+                    metadata.code, value
+                )));
+            }
+            if matches!(metadata.format, AiFormat::DateTime) {
+                let hour = value.get(6..8).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0);
+                let minutes = value.get(8..10).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0);
+                let seconds = value.get(10..12).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0);
+                if hour > 23 || minutes > 59 || seconds > 59 {
+                    return Err(QrError::ValidationError(format!(
+                        "AI {} contiene hora inválida: {}",
+                        metadata.code, value
+                    )));
+                }
+            }
+        },
+    }
+    
+    if verify_check_characters {
+        match metadata.check {
+            CheckRule::None => {},
+            CheckRule::Mod10(digits) => {
+                let key = &value[..digits];
+                if !is_valid_check_digit(key) {
+                    let expected = (0..10)
+                        .find(|digit| is_valid_check_digit(&format!("{}{}", &key[..digits - 1], digit)))
+                        .unwrap_or(0);
+                    return Err(QrError::ValidationError(format!(
+                        "Dígito de verificación {} inválido. Esperado: {}, Recibido: {}",
+                        metadata.title, expected, &key[digits - 1..]
+                    )));
+                }
+            },
+            CheckRule::CheckPair => {
+                if !is_valid_check_pair(value) {
+                    return Err(QrError::ValidationError(format!(
+                        "Par de caracteres de control inválido en AI {} ({})",
+                        metadata.code, metadata.title
+                    )));
+                }
+            },
+        }
+    }
+    
+    Ok(())
+}
+
+/// Valida el valor de un AI contra el diccionario GS1
+pub fn validate_ai_value(code: &str, value: &str) -> QrResult<()> {
+    let metadata = lookup_ai(code)
+        .ok_or_else(|| QrError::ValidationError(format!("AI desconocido: {}", code)))?;
+    check_ai_value(metadata, value, true)
+}
+
+/// Compara un código de AI con un patrón (`n` admite cualquier dígito)
+fn matches_pattern(pattern: &str, code: &str) -> bool {
+    pattern.len() == code.len()
+        && pattern.chars().zip(code.chars()).all(|(p, c)| p == 'n' || p == c)
+}
+
+/// Valida las asociaciones obligatorias, los pares inválidos y las repeticiones
+/// de un conjunto de elementos GS1
+pub fn validate_associations(elements: &[(ApplicationIdentifier, String)]) -> QrResult<()> {
+    let codes: Vec<String> = elements.iter().map(|(ai, _)| ai.code()).collect();
+    let present = |pattern: &str| codes.iter().any(|code| matches_pattern(pattern, code));
+    
+    for (index, (code, (_, value))) in codes.iter().zip(elements).enumerate() {
+        for (other, (_, other_value)) in codes.iter().zip(elements).skip(index + 1) {
+            if code == other && value != other_value {
+                return Err(QrError::ValidationError(format!(
+                    "AI {} repetido con valores distintos",
+                    code
+                )));
+            }
+            // Variantes de la misma medida con distinta posición decimal
+            let is_measure = code.len() == 4 && code.starts_with('3') && ('1'..='6').contains(&code.chars().nth(1).unwrap_or('0'));
+            if is_measure && code != other && code[..3] == other[..3] {
+                return Err(QrError::ValidationError(format!(
+                    "Los AIs {} y {} expresan la misma medida",
+                    code, other
+                )));
+            }
+        }
+        
+        for (pattern, groups) in REQUIRED_ASSOCIATIONS {
+            if !matches_pattern(pattern, code) {
+                continue;
+            }
+            if let Some(group) = groups.iter().find(|group| !group.iter().any(|required| present(required))) {
+                return Err(QrError::ValidationError(format!(
+                    "AI {} requiere alguno de: {}",
+                    code,
+                    group.join(", ")
+                )));
+            }
+        }
+    }
+    
+    for (first, second) in INVALID_PAIRS {
+        if present(first) && present(second) {
+            return Err(QrError::ValidationError(format!(
+                "Los AIs {} y {} no pueden aparecer juntos",
+                first.trim_end_matches('n'), second.trim_end_matches('n')
+            )));
+        }
+    }
+    
+    Ok(())
+}
+
 /// Codificador GS1 para códigos QR y DataMatrix
 pub struct Gs1Encoder {
     use_fnc1: bool,
@@ -160,7 +821,7 @@ impl Gs1Encoder {
         
         for (ai, value) in elements {
             let ai_code = self.get_ai_code(ai)?;
-            let metadata = AI_REGISTRY.get(ai_code.as_str())
+            let metadata = lookup_ai(&ai_code)
                 .ok_or_else(|| QrError::ValidationError(format!("AI desconocido: {}", ai_code)))?;
             
             // Validar formato y longitud
@@ -176,6 +837,32 @@ impl Gs1Encoder {
             }
         }
         
+        validate_associations(elements)?;
+        
+        Ok(encoded)
+    }
+    
+    /// Codifica la cadena de elementos tal como va en el símbolo en modo FNC1:
+    /// sin FNC1 inicial (lo aporta el indicador de modo) y con GS tras cada valor
+    /// de longitud no predefinida que no sea el último
+    pub fn encode_element_string(&self, elements: &[(ApplicationIdentifier, String)]) -> QrResult<String> {
+        let mut encoded = String::new();
+        
+        for (index, (ai, value)) in elements.iter().enumerate() {
+            let ai_code = self.get_ai_code(ai)?;
+            let metadata = lookup_ai(&ai_code)
+                .ok_or_else(|| QrError::ValidationError(format!("AI desconocido: {}", ai_code)))?;
+            self.validate_ai_value(metadata, value)?;
+            
+            encoded.push_str(&ai_code);
+            encoded.push_str(value);
+            if metadata.fnc1_required && index + 1 < elements.len() {
+                encoded.push(GROUP_SEPARATOR);
+            }
+        }
+        
+        validate_associations(elements)?;
+        
         Ok(encoded)
     }
     
@@ -198,127 +885,22 @@ impl Gs1Encoder {
     
     /// Obtiene el código de AI
     fn get_ai_code(&self, ai: &ApplicationIdentifier) -> QrResult<String> {
-        Ok(match ai {
-            ApplicationIdentifier::GTIN => "01".to_string(),
-            ApplicationIdentifier::BatchLot => "10".to_string(),
-            ApplicationIdentifier::ProductionDate => "11".to_string(),
-            ApplicationIdentifier::DueDate => "12".to_string(),
-            ApplicationIdentifier::ExpiryDate => "17".to_string(),
-            ApplicationIdentifier::SerialNumber => "21".to_string(),
-            ApplicationIdentifier::NetWeight => "3103".to_string(),
-            ApplicationIdentifier::Length => "3113".to_string(),
-            ApplicationIdentifier::Width => "3123".to_string(),
-            ApplicationIdentifier::Height => "3133".to_string(),
-            ApplicationIdentifier::Area => "3143".to_string(),
-            ApplicationIdentifier::Volume => "3153".to_string(),
-            ApplicationIdentifier::CountryOfOrigin => "422".to_string(),
-            ApplicationIdentifier::GLN => "410".to_string(),
-            ApplicationIdentifier::SSCC => "00".to_string(),
-            ApplicationIdentifier::ProductURL => "8200".to_string(),
-            ApplicationIdentifier::Custom(code) => code.clone(),
-        })
+        Ok(ai.code())
     }
     
     /// Valida el valor de un AI
     fn validate_ai_value(&self, metadata: &AiMetadata, value: &str) -> QrResult<()> {
-        // Validar longitud
-        if value.len() < metadata.min_length || value.len() > metadata.max_length {
-            return Err(QrError::ValidationError(format!(
-                "AI {} requiere longitud entre {} y {}, se proporcionó {}",
-                metadata.code, metadata.min_length, metadata.max_length, value.len()
-            )));
-        }
-        
-        // Validar formato
-        match metadata.format {
-            AiFormat::Numeric => {
-                if !value.chars().all(|c| c.is_ascii_digit()) {
-                    return Err(QrError::ValidationError(format!(
-                        "AI {} requiere solo dígitos numéricos",
-                        metadata.code
-                    )));
-                }
-            },
-            AiFormat::Date => {
-                if value.len() != 6 || !value.chars().all(|c| c.is_ascii_digit()) {
-                    return Err(QrError::ValidationError(format!(
-                        "AI {} requiere formato de fecha YYMMDD",
-                        metadata.code
-                    )));
-                }
-                // Validación básica de fecha
-                let year: u32 = value[0..2].parse().unwrap_or(0);
-                let month: u32 = value[2..4].parse().unwrap_or(0);
-                let day: u32 = value[4..6].parse().unwrap_or(0);
-                
-                if month < 1 || month > 12 || day < 1 || day > 31 {
-                    return Err(QrError::ValidationError(format!(
-                        "AI {} contiene fecha inválida: {}",
-                        metadata.code, value
-                    )));
-                }
-            },
-            AiFormat::DateTime => {
-                if value.len() != 10 || !value.chars().all(|c| c.is_ascii_digit()) {
-                    return Err(QrError::ValidationError(format!(
-                        "AI {} requiere formato YYMMDDHHMM",
-                        metadata.code
-                    )));
-                }
-            },
-            AiFormat::Alphanumeric => {
-                // Permitir caracteres alfanuméricos y algunos especiales
-                if !value.chars().all(|c| c.is_ascii_alphanumeric() || "-._/".contains(c)) {
-                    return Err(QrError::ValidationError(format!(
-                        "AI {} contiene caracteres no válidos",
-                        metadata.code
-                    )));
-                }
-            },
-        }
-        
-        // Validar dígito de verificación para GTIN si está habilitado
-        if self.validate_check_digits && metadata.code == "01" {
-            self.validate_gtin_check_digit(value)?;
-        }
-        
-        Ok(())
+        check_ai_value(metadata, value, self.validate_check_digits)
     }
-    
-    /// Valida el dígito de verificación de un GTIN
-    fn validate_gtin_check_digit(&self, gtin: &str) -> QrResult<()> {
-        if gtin.len() != 14 {
-            return Err(QrError::ValidationError("GTIN debe tener 14 dígitos".to_string()));
-        }
-        
-        let digits: Vec<u32> = gtin.chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
-        
-        if digits.len() != 14 {
-            return Err(QrError::ValidationError("GTIN contiene caracteres no numéricos".to_string()));
-        }
-        
-        // Algoritmo de verificación GTIN
-        let mut sum = 0;
-        for (i, &digit) in digits[..13].iter().enumerate() {
-            if i % 2 == 0 {
-                sum += digit;
-            } else {
-                sum += digit * 3;
-            }
-        }
-        
-        let check_digit = (10 - (sum % 10)) % 10;
-        
-        if check_digit != digits[13] {
-            return Err(QrError::ValidationError(format!(
-                "Dígito de verificación GTIN inválido. Esperado: {}, Recibido: {}",
-                check_digit, digits[13]
-            )));
-        }
-        
-        Ok(())
+}
+
+/// Posición y longitud del siguiente separador: FNC1 textual o carácter GS
+fn find_separator(data: &str) -> Option<(usize, usize)> {
+    let textual = data.find("\\FNC1").map(|index| (index, 5));
+    let group = data.find(GROUP_SEPARATOR).map(|index| (index, 1));
+    match (textual, group) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
     }
 }
 
@@ -339,14 +921,18 @@ impl Gs1Parser {
         let mut elements = Vec::new();
         let mut remaining = data;
         
-        // Remover FNC1 inicial si existe
-        if remaining.starts_with("\\FNC1") {
-            remaining = &remaining[5..];
+        // Remover el identificador de simbología (p.ej. "]Q3" de un lector) y el FNC1 inicial
+        if remaining.starts_with(']') && remaining.is_char_boundary(3.min(remaining.len())) {
+            remaining = &remaining[3.min(remaining.len())..];
+        }
+        if let Some((0, len)) = find_separator(remaining) {
+            remaining = &remaining[len..];
         }
         
         while !remaining.is_empty() {
             // Buscar el AI (entre paréntesis o directamente)
-            let (ai_code, value_start) = if remaining.starts_with('(') {
+            let parenthesized = remaining.starts_with('(');
+            let (ai_code, value_start) = if parenthesized {
                 // Formato con paréntesis: (01)12345678901234
                 let end = remaining.find(')').ok_or_else(|| 
                     QrError::ValidationError("Paréntesis sin cerrar en datos GS1".to_string()))?;
@@ -359,34 +945,49 @@ impl Gs1Parser {
             };
             
             // Obtener metadata del AI
-            let metadata = AI_REGISTRY.get(ai_code.as_str())
+            let metadata = lookup_ai(&ai_code)
                 .ok_or_else(|| QrError::ValidationError(format!("AI desconocido: {}", ai_code)))?;
             
             // Extraer el valor
             remaining = &remaining[value_start..];
-            let value_end = if metadata.fnc1_required {
-                // Buscar FNC1 o siguiente AI
-                remaining.find("\\FNC1")
-                    .or_else(|| self.find_next_ai(remaining))
-                    .unwrap_or(remaining.len())
+            let value_end = if parenthesized {
+                // Con paréntesis el valor termina donde empieza el siguiente AI
+                remaining.find('(').unwrap_or(remaining.len())
+            } else if metadata.fnc1_required && metadata.min_length != metadata.max_length {
+                // Longitud variable: el valor termina en el separador (FNC1 o GS) o al final
+                find_separator(remaining).map(|(index, _)| index).unwrap_or(remaining.len())
             } else {
                 // Longitud fija
                 metadata.max_length.min(remaining.len())
             };
             
+            if !remaining.is_char_boundary(value_end) {
+                return Err(QrError::ValidationError(format!(
+                    "AI {} contiene caracteres no válidos",
+                    ai_code
+                )));
+            }
             let value = remaining[..value_end].to_string();
             remaining = &remaining[value_end..];
             
-            // Remover FNC1 si existe
-            if remaining.starts_with("\\FNC1") {
-                remaining = &remaining[5..];
+            // Remover el separador si existe
+            if let Some((0, len)) = find_separator(remaining) {
+                remaining = &remaining[len..];
             }
             
             // Convertir a ApplicationIdentifier
+            if self.strict_mode {
+                check_ai_value(metadata, &value, true)?;
+            }
+            
             let ai = self.code_to_ai(&ai_code)?;
             elements.push((ai, value));
         }
         
+        if self.strict_mode {
+            validate_associations(&elements)?;
+        }
+        
         Ok(elements)
     }
     
@@ -396,7 +997,7 @@ impl Gs1Parser {
         for len in &[4, 3, 2] {
             if data.len() >= *len {
                 let potential_ai = &data[..*len];
-                if AI_REGISTRY.contains_key(potential_ai) {
+                if lookup_ai(potential_ai).is_some() {
                     return Ok(potential_ai.to_string());
                 }
             }
@@ -405,37 +1006,9 @@ impl Gs1Parser {
         Err(QrError::ValidationError("No se pudo identificar AI válido".to_string()))
     }
     
-    /// Busca el siguiente AI en la cadena
-    fn find_next_ai(&self, data: &str) -> Option<usize> {
-        for i in 1..data.len() {
-            if let Ok(_) = self.identify_ai(&data[i..]) {
-                return Some(i);
-            }
-        }
-        None
-    }
-    
     /// Convierte código a ApplicationIdentifier
     fn code_to_ai(&self, code: &str) -> QrResult<ApplicationIdentifier> {
-        Ok(match code {
-            "01" => ApplicationIdentifier::GTIN,
-            "10" => ApplicationIdentifier::BatchLot,
-            "11" => ApplicationIdentifier::ProductionDate,
-            "12" => ApplicationIdentifier::DueDate,
-            "17" => ApplicationIdentifier::ExpiryDate,
-            "21" => ApplicationIdentifier::SerialNumber,
-            "3103" => ApplicationIdentifier::NetWeight,
-            "3113" => ApplicationIdentifier::Length,
-            "3123" => ApplicationIdentifier::Width,
-            "3133" => ApplicationIdentifier::Height,
-            "3143" => ApplicationIdentifier::Area,
-            "3153" => ApplicationIdentifier::Volume,
-            "422" => ApplicationIdentifier::CountryOfOrigin,
-            "410" => ApplicationIdentifier::GLN,
-            "00" => ApplicationIdentifier::SSCC,
-            "8200" => ApplicationIdentifier::ProductURL,
-            _ => ApplicationIdentifier::Custom(code.to_string()),
-        })
+        Ok(ApplicationIdentifier::from_code(code))
     }
 }
 
@@ -510,6 +1083,94 @@ mod tests {
         assert_eq!(elements.len(), 3);
     }
     
+    #[test]
+    fn test_registry_formats() {
+        // AIs de salud y de medida variable
+        assert!(validate_ai_value("7003", "2512312359").is_ok());
+        assert!(validate_ai_value("7003", "2512312460").is_err());
+        assert!(validate_ai_value("8008", "25123112").is_ok());
+        assert!(validate_ai_value("8008", "251231121530").is_ok());
+        assert!(validate_ai_value("8020", "REF-2024/77").is_ok());
+        assert!(validate_ai_value("3922", "1250").is_ok());
+        assert!(validate_ai_value("3932", "9781250").is_ok());
+        assert!(validate_ai_value("3932", "97").is_err());
+        assert!(validate_ai_value("713", "12345678").is_ok());
+        assert!(validate_ai_value("3105", "12345A").is_err());
+        
+        // DD = 00 solo se admite en las fechas 11-17
+        assert!(validate_ai_value("17", "251200").is_ok());
+        assert!(validate_ai_value("17", "250230").is_err());
+        assert!(validate_ai_value("7006", "251200").is_err());
+        
+        // Caracteres de control: SSCC, GLN y par alfanumérico del GMN
+        assert!(validate_ai_value("00", "106141411234567897").is_ok());
+        assert!(validate_ai_value("414", "9521321000001").is_ok());
+        assert!(validate_ai_value("414", "9521321000005").is_err());
+        assert!(validate_ai_value("8013", "1987654Ad4X4bL5ttr2310c2K").is_ok());
+        assert!(validate_ai_value("8013", "1987654Ad4X4bL5ttr2310c2L").is_err());
+        
+        assert!(validate_ai_value("8010", "cpid").is_err());
+        assert!(validate_ai_value("9999", "X").is_err());
+    }
+    
+    #[test]
+    fn test_associations() {
+        let elements = |pairs: &[(&str, &str)]| -> Vec<(ApplicationIdentifier, String)> {
+            pairs.iter().map(|(code, value)| (ApplicationIdentifier::from_code(code), value.to_string())).collect()
+        };
+        
+        assert!(validate_associations(&elements(&[("01", "01234567890128"), ("17", "251231"), ("10", "L1")])).is_ok());
+        assert!(validate_associations(&elements(&[("02", "01234567890128"), ("37", "12")])).is_ok());
+        
+        // Asociaciones obligatorias
+        assert!(validate_associations(&elements(&[("17", "251231")])).is_err());
+        assert!(validate_associations(&elements(&[("02", "01234567890128")])).is_err());
+        assert!(validate_associations(&elements(&[("8011", "1")])).is_err());
+        
+        // Pares inválidos, medidas duplicadas y repeticiones
+        assert!(validate_associations(&elements(&[("01", "01234567890128"), ("02", "01234567890128"), ("37", "1")])).is_err());
+        assert!(validate_associations(&elements(&[("01", "01234567890128"), ("3102", "000100"), ("3103", "000100")])).is_err());
+        assert!(validate_associations(&elements(&[("01", "01234567890128"), ("10", "A"), ("10", "B")])).is_err());
+        
+        // El codificador aplica las mismas reglas
+        let encoder = Gs1Encoder::new();
+        assert!(encoder.encode(&elements(&[("10", "ABC")])).is_err());
+    }
+    
+    #[test]
+    fn test_parse_fnc1_data() {
+        let parser = Gs1Parser::new();
+        
+        let data = "\\FNC101012345678901281725123110ABC/12\\FNC170032512312359\\FNC121SN-9";
+        let elements = parser.parse(data).unwrap();
+        let codes: Vec<String> = elements.iter().map(|(ai, _)| ai.code()).collect();
+        assert_eq!(codes, vec!["01", "17", "10", "7003", "21"]);
+        assert_eq!(elements[2].1, "ABC/12");
+        assert_eq!(elements[4].1, "SN-9");
+        
+        // En modo estricto se verifican valores y asociaciones
+        assert!(parser.parse("(17)251231(10)ABC").is_err());
+        assert!(parser.parse("(01)01234567890123").is_err());
+    }
+    
+    #[test]
+    fn test_element_string_with_group_separators() {
+        let encoder = Gs1Encoder::new();
+        let elements = vec![
+            (ApplicationIdentifier::GTIN, "01234567890128".to_string()),
+            (ApplicationIdentifier::BatchLot, "ABC123".to_string()),
+            (ApplicationIdentifier::ExpiryDate, "251231".to_string()),
+            (ApplicationIdentifier::SerialNumber, "SN-9".to_string()),
+        ];
+        
+        let encoded = encoder.encode_element_string(&elements).unwrap();
+        assert_eq!(encoded, "010123456789012810ABC123\u{1D}1725123121SN-9");
+        
+        // El parser acepta GS y el identificador de simbología de un lector
+        let parsed = Gs1Parser::new().parse(&format!("]Q3{}", encoded)).unwrap();
+        assert_eq!(parsed, elements);
+    }
+    
     #[test]
     fn test_human_readable_format() {
         let encoder = Gs1Encoder::new();
@@ -1,9 +1,17 @@
 // standards/mod.rs - Módulo de estándares industriales
 
 pub mod gs1;
+pub mod digital_link;
+pub mod udi;
 pub mod validator;
 pub mod decoder;
+pub mod robustness;
+pub mod print_quality;
 
 pub use gs1::{Gs1Encoder, Gs1Parser, ApplicationIdentifier};
+pub use digital_link::{DigitalLinkEncoder, DigitalLinkParser};
+pub use udi::{IssuingAgency, Udi, UdiParser};
 pub use validator::{StandardValidator, ValidationProfile};
-pub use decoder::{QrDecoder, DecodedData};
\ No newline at end of file
+pub use decoder::{QrDecoder, DecodedData, ImageUpload};
+pub use robustness::{RobustnessAnalyzer, RobustnessReport, Perturbation};
+pub use print_quality::{Grade, PrintQualityGrader, PrintQualityReport};
\ No newline at end of file
@@ -0,0 +1,597 @@
+// standards/print_quality.rs - Verificación de calidad de impresión ISO/IEC 15415
+// Gradúa un símbolo QR escaneado o renderizado con los parámetros de ISO/IEC 15415
+// y los específicos de ISO/IEC 18004 (daño de patrones fijos, no uniformidad de cuadrícula)
+
+use crate::engine::bitstream::data_capacity;
+use crate::engine::codeword_map::CodewordMap;
+use crate::engine::constants::get_alignment_pattern_positions;
+use crate::engine::error::{QrError, QrResult};
+use image::{DynamicImage, GrayImage};
+use qrcodegen::{Mask, QrCode as QrCodeGen, QrCodeEcc, Version as QrVersion};
+use rxing::common::{DetectorRXingResult, GlobalHistogramBinarizer, HybridBinarizer};
+use rxing::qrcode::decoder::{qrcode_decoder, BitMatrixParser, ErrorCorrectionLevel};
+use rxing::qrcode::detector::Detector;
+use rxing::{BarcodeFormat, Binarizer, BinaryBitmap, DecodeHints, Luma8LuminanceSource, Point};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Ancho de rasterización para graduar SVG
+const GRADING_WIDTH: u32 = 1024;
+
+/// Margen blanco alrededor del SVG rasterizado (zona de silencio medible)
+const GRADING_PADDING: u32 = 64;
+
+/// Apertura de medición por defecto, como fracción del tamaño de módulo
+const DEFAULT_APERTURE: f32 = 0.8;
+
+/// Umbrales mínimos de A, B, C y D
+const SYMBOL_CONTRAST_THRESHOLDS: [f32; 4] = [0.70, 0.55, 0.40, 0.20];
+const MODULATION_THRESHOLDS: [f32; 4] = [0.50, 0.40, 0.30, 0.20];
+const UEC_THRESHOLDS: [f32; 4] = [0.62, 0.50, 0.37, 0.25];
+
+/// Umbrales máximos de A, B, C y D
+const AXIAL_THRESHOLDS: [f32; 4] = [0.06, 0.08, 0.10, 0.12];
+const GRID_THRESHOLDS: [f32; 4] = [0.38, 0.50, 0.63, 0.75];
+const TIMING_THRESHOLDS: [f32; 4] = [0.0, 0.07, 0.11, 0.14];
+
+/// Grado de calidad ISO/IEC 15415 (4 = A ... 0 = F)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Grade {
+    F,
+    D,
+    C,
+    B,
+    A,
+}
+
+impl Grade {
+    /// Grados aprobatorios, de mejor a peor
+    pub const PASSING: [Grade; 4] = [Grade::A, Grade::B, Grade::C, Grade::D];
+
+    /// Valor numérico del grado (A = 4 ... F = 0)
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Grade::A => 'A',
+            Grade::B => 'B',
+            Grade::C => 'C',
+            Grade::D => 'D',
+            Grade::F => 'F',
+        }
+    }
+
+    /// Grado de un parámetro que mejora al crecer
+    fn at_least(value: f32, thresholds: [f32; 4]) -> Self {
+        Self::PASSING
+            .into_iter()
+            .zip(thresholds)
+            .find(|&(_, threshold)| value >= threshold)
+            .map_or(Grade::F, |(grade, _)| grade)
+    }
+
+    /// Grado de un parámetro que empeora al crecer
+    fn at_most(value: f32, thresholds: [f32; 4]) -> Self {
+        Self::PASSING
+            .into_iter()
+            .zip(thresholds)
+            .find(|&(_, threshold)| value <= threshold)
+            .map_or(Grade::F, |(grade, _)| grade)
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter())
+    }
+}
+
+/// Valor medido de un parámetro y su grado
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParameterGrade {
+    pub value: f32,
+    pub grade: Grade,
+}
+
+impl ParameterGrade {
+    fn failed() -> Self {
+        Self { value: 0.0, grade: Grade::F }
+    }
+}
+
+/// Resultado de la verificación de calidad de impresión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintQualityReport {
+    /// Decodificación de referencia (A o F)
+    pub decode: Grade,
+    /// Diferencia entre la reflectancia máxima y mínima (SC)
+    pub symbol_contrast: ParameterGrade,
+    /// Uniformidad de la reflectancia de los módulos (MOD); valor del peor codeword
+    pub modulation: ParameterGrade,
+    /// Modulación con signo respecto al color correcto de cada módulo (RM); valor del peor codeword
+    pub reflectance_margin: ParameterGrade,
+    /// Daño de búsqueda, separadores, temporización y alineación (FPD); valor = módulos erróneos
+    pub fixed_pattern_damage: ParameterGrade,
+    /// Diferencia relativa del paso de la cuadrícula entre ejes (AN)
+    pub axial_nonuniformity: ParameterGrade,
+    /// Mayor desplazamiento de un patrón de alineación respecto a la cuadrícula ideal, en módulos (GN)
+    pub grid_nonuniformity: ParameterGrade,
+    /// Fracción de la corrección de errores no consumida por el peor bloque (UEC)
+    pub unused_error_correction: ParameterGrade,
+    /// Grado del símbolo: el peor de todos los parámetros
+    pub overall: Grade,
+    /// Tamaño de módulo medido en píxeles
+    pub module_size_px: f32,
+    /// Diámetro de la apertura de medición en píxeles
+    pub aperture_px: f32,
+}
+
+impl PrintQualityReport {
+    /// Reporte de un símbolo que no se pudo decodificar: todos los parámetros en F
+    pub fn undecodable() -> Self {
+        Self {
+            decode: Grade::F,
+            symbol_contrast: ParameterGrade::failed(),
+            modulation: ParameterGrade::failed(),
+            reflectance_margin: ParameterGrade::failed(),
+            fixed_pattern_damage: ParameterGrade::failed(),
+            axial_nonuniformity: ParameterGrade::failed(),
+            grid_nonuniformity: ParameterGrade::failed(),
+            unused_error_correction: ParameterGrade::failed(),
+            overall: Grade::F,
+            module_size_px: 0.0,
+            aperture_px: 0.0,
+        }
+    }
+
+    /// Parámetros con su abreviatura ISO, en el orden del reporte
+    pub fn parameters(&self) -> [(&'static str, ParameterGrade); 7] {
+        [
+            ("SC", self.symbol_contrast),
+            ("MOD", self.modulation),
+            ("RM", self.reflectance_margin),
+            ("FPD", self.fixed_pattern_damage),
+            ("AN", self.axial_nonuniformity),
+            ("GN", self.grid_nonuniformity),
+            ("UEC", self.unused_error_correction),
+        ]
+    }
+}
+
+/// Verificador de calidad de impresión
+pub struct PrintQualityGrader {
+    aperture: f32,
+}
+
+impl PrintQualityGrader {
+    pub fn new() -> Self {
+        Self { aperture: DEFAULT_APERTURE }
+    }
+
+    /// Apertura de medición como fracción del tamaño de módulo
+    pub fn with_aperture(mut self, aperture: f32) -> Self {
+        self.aperture = aperture.clamp(0.1, 1.0);
+        self
+    }
+
+    /// Gradúa un SVG generado (se rasteriza sobre blanco con margen)
+    pub fn grade_svg(&self, svg: &str) -> QrResult<PrintQualityReport> {
+        let raster = crate::engine::raster::render_svg(svg, GRADING_WIDTH)?;
+        let rgb = crate::engine::raster::flatten_on_white(&raster.pixels);
+        let image = image::RgbImage::from_raw(raster.width, raster.height, rgb)
+            .ok_or_else(|| QrError::RenderError("Rasterización SVG inválida".to_string()))?;
+
+        let mut padded = image::RgbImage::from_pixel(
+            raster.width + 2 * GRADING_PADDING,
+            raster.height + 2 * GRADING_PADDING,
+            image::Rgb([255, 255, 255]),
+        );
+        image::imageops::overlay(&mut padded, &image, GRADING_PADDING as i64, GRADING_PADDING as i64);
+        self.grade_image(&DynamicImage::ImageRgb8(padded))
+    }
+
+    /// Gradúa el símbolo QR de una imagen escaneada o renderizada
+    pub fn grade_image(&self, image: &DynamicImage) -> QrResult<PrintQualityReport> {
+        let gray = image.to_luma8();
+        let Some(symbol) = read_symbol(&gray) else {
+            return Ok(PrintQualityReport::undecodable());
+        };
+        let (Some(grid), Some(map)) = (
+            Grid::from_points(&symbol.points, symbol.size),
+            CodewordMap::new(symbol.version, symbol.ecl),
+        ) else {
+            return Ok(PrintQualityReport::undecodable());
+        };
+        let size = symbol.size;
+        let module_size = grid.module_size();
+        let radius = self.aperture * module_size / 2.0;
+
+        // Reflectancia de cada módulo con la apertura circular centrada en él
+        let mut reflectance = Vec::with_capacity(size * size);
+        for row in 0..size {
+            for col in 0..size {
+                match aperture_reflectance(&gray, grid.center(col as f32, row as f32), radius) {
+                    Some(value) => reflectance.push(value),
+                    None => return Ok(PrintQualityReport::undecodable()),
+                }
+            }
+        }
+
+        // La reflectancia máxima incluye los dos módulos de zona de silencio que rodean el símbolo
+        let ring = -2..size as i32 + 2;
+        let quiet_zone: Vec<f32> = ring
+            .clone()
+            .flat_map(|row| ring.clone().map(move |col| (col, row)))
+            .filter(|&(col, row)| col < 0 || row < 0 || col >= size as i32 || row >= size as i32)
+            .filter_map(|(col, row)| aperture_reflectance(&gray, grid.center(col as f32, row as f32), radius))
+            .collect();
+        let r_max = reflectance.iter().chain(&quiet_zone).copied().fold(0.0, f32::max);
+        let r_min = reflectance.iter().copied().fold(1.0, f32::min);
+        let contrast = (r_max - r_min).max(0.0);
+        let threshold = (r_max + r_min) / 2.0;
+
+        let ideal_dark = |col: usize, row: usize| symbol.ideal.get_module(col as i32, row as i32);
+        let measured_dark = |col: usize, row: usize| reflectance[row * size + col] < threshold;
+        let scale = if contrast > 0.0 { 2.0 / contrast } else { 0.0 };
+
+        // MOD y RM por codeword (su peor módulo) y codewords erróneos por bloque
+        let mut modulation = Vec::with_capacity(map.codewords.len());
+        let mut margin = Vec::with_capacity(map.codewords.len());
+        let mut errors = vec![0; map.blocks.len()];
+        for codeword in &map.codewords {
+            let (mut worst_mod, mut worst_margin, mut wrong) = (f32::MAX, f32::MAX, false);
+            for &(col, row) in &codeword.modules {
+                let value = reflectance[row * size + col];
+                let signed = if ideal_dark(col, row) { threshold - value } else { value - threshold };
+                worst_mod = worst_mod.min((value - threshold).abs() * scale);
+                worst_margin = worst_margin.min((signed * scale).max(0.0));
+                wrong |= measured_dark(col, row) != ideal_dark(col, row);
+            }
+            modulation.push(worst_mod);
+            margin.push(worst_margin);
+            if wrong {
+                errors[codeword.block] += 1;
+            }
+        }
+        let uec = map.unused_error_correction(&errors);
+
+        // Daño de patrones fijos: búsqueda con separador, alineación y temporización
+        let pattern_errors = |cols: std::ops::Range<usize>, rows: std::ops::Range<usize>| {
+            rows.flat_map(|row| cols.clone().map(move |col| (col, row)))
+                .filter(|&(col, row)| measured_dark(col, row) != ideal_dark(col, row))
+                .count()
+        };
+        let pattern_grade = |errors: usize| Grade::PASSING.get(errors).copied().unwrap_or(Grade::F);
+        let mut segments: Vec<usize> = [(0, 0), (size - 8, 0), (0, size - 8)]
+            .into_iter()
+            .map(|(col, row)| pattern_errors(col..col + 8, row..row + 8))
+            .collect();
+        let alignment_positions = get_alignment_pattern_positions(symbol.version);
+        segments.extend(alignment_positions.iter().map(|&(row, col)| {
+            let (row, col) = (row as usize, col as usize);
+            pattern_errors(col - 2..col + 3, row - 2..row + 3)
+        }));
+        let timing_errors = pattern_errors(8..size - 8, 6..7) + pattern_errors(6..7, 8..size - 8);
+        let timing_fraction = timing_errors as f32 / (2 * (size - 16)) as f32;
+        let fixed_pattern_grade = segments
+            .iter()
+            .map(|&errors| pattern_grade(errors))
+            .chain([Grade::at_most(timing_fraction, TIMING_THRESHOLDS)])
+            .fold(Grade::A, Grade::min);
+
+        // No uniformidad: paso de la cuadrícula por eje y centros medidos de los patrones de alineación
+        let (module_x, module_y) = grid.module_steps();
+        let axial = (module_x - module_y).abs() / module_size;
+        let grid_deviation = alignment_positions
+            .iter()
+            .filter_map(|&(row, col)| {
+                let predicted = grid.center(col as f32, row as f32);
+                let measured = pattern_centroid(&gray, predicted, module_size, threshold)?;
+                Some(((measured.0 - predicted.0).powi(2) + (measured.1 - predicted.1).powi(2)).sqrt() / module_size)
+            })
+            .fold(0.0, f32::max);
+
+        let symbol_contrast = ParameterGrade {
+            value: contrast,
+            grade: Grade::at_least(contrast, SYMBOL_CONTRAST_THRESHOLDS),
+        };
+        let modulation = codeword_parameter(&map, &modulation);
+        let reflectance_margin = codeword_parameter(&map, &margin);
+        let fixed_pattern_damage = ParameterGrade {
+            value: (segments.iter().sum::<usize>() + timing_errors) as f32,
+            grade: fixed_pattern_grade,
+        };
+        let axial_nonuniformity = ParameterGrade { value: axial, grade: Grade::at_most(axial, AXIAL_THRESHOLDS) };
+        let grid_nonuniformity = ParameterGrade {
+            value: grid_deviation,
+            grade: Grade::at_most(grid_deviation, GRID_THRESHOLDS),
+        };
+        let unused_error_correction = ParameterGrade { value: uec, grade: Grade::at_least(uec, UEC_THRESHOLDS) };
+
+        let mut report = PrintQualityReport {
+            decode: Grade::A,
+            symbol_contrast,
+            modulation,
+            reflectance_margin,
+            fixed_pattern_damage,
+            axial_nonuniformity,
+            grid_nonuniformity,
+            unused_error_correction,
+            overall: Grade::A,
+            module_size_px: module_size,
+            aperture_px: 2.0 * radius,
+        };
+        report.overall = report.parameters().iter().map(|(_, p)| p.grade).fold(report.decode, Grade::min);
+        Ok(report)
+    }
+}
+
+impl Default for PrintQualityGrader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Símbolo leído: geometría detectada y matriz ideal reconstruida desde los datos corregidos
+struct ReadSymbol {
+    points: Vec<Point>,
+    size: usize,
+    version: u8,
+    ecl: QrCodeEcc,
+    ideal: QrCodeGen,
+}
+
+fn read_symbol(image: &GrayImage) -> Option<ReadSymbol> {
+    let (width, height) = image.dimensions();
+    let hints = DecodeHints {
+        TryHarder: Some(true),
+        PossibleFormats: Some(HashSet::from([BarcodeFormat::QR_CODE])),
+        ..Default::default()
+    };
+    let source = || Luma8LuminanceSource::new(image.as_raw().clone(), width, height);
+    read_bitmap(&BinaryBitmap::new(HybridBinarizer::new(source())), &hints)
+        .or_else(|| read_bitmap(&BinaryBitmap::new(GlobalHistogramBinarizer::new(source())), &hints))
+}
+
+fn read_bitmap<B: Binarizer>(bitmap: &BinaryBitmap<B>, hints: &DecodeHints) -> Option<ReadSymbol> {
+    let detection = Detector::new(bitmap.get_black_matrix()).detect_with_hints(hints).ok()?;
+    let bits = detection.getBits();
+    let decoded = qrcode_decoder::decode_bitmatrix_with_hints(bits, hints).ok()?;
+
+    let mut parser = BitMatrixParser::new(bits.clone()).ok()?;
+    let mask = parser.readFormatInformation().ok()?.getDataMask();
+    let version = parser.readVersion().ok()?.getVersionNumber() as u8;
+    let ecl = match decoded.getECLevel().parse::<ErrorCorrectionLevel>().ok()? {
+        ErrorCorrectionLevel::L => QrCodeEcc::Low,
+        ErrorCorrectionLevel::M => QrCodeEcc::Medium,
+        ErrorCorrectionLevel::Q => QrCodeEcc::Quartile,
+        ErrorCorrectionLevel::H => QrCodeEcc::High,
+        ErrorCorrectionLevel::Invalid => return None,
+    };
+
+    // Los codewords de datos corregidos reproducen el símbolo tal como se codificó
+    let data = decoded.getRawBytes();
+    if data.len() != data_capacity(version, ecl) {
+        return None;
+    }
+    let ideal = QrCodeGen::encode_codewords(QrVersion::new(version), ecl, data, Some(Mask::new(mask)));
+
+    Some(ReadSymbol {
+        points: detection.getPoints().to_vec(),
+        size: bits.getHeight() as usize,
+        version,
+        ecl,
+        ideal,
+    })
+}
+
+/// Cuadrícula afín a partir de los centros de los patrones de búsqueda
+/// (`points` = [inferior izquierdo, superior izquierdo, superior derecho, ...])
+struct Grid {
+    top_left: (f32, f32),
+    u: (f32, f32),
+    v: (f32, f32),
+}
+
+impl Grid {
+    fn from_points(points: &[Point], size: usize) -> Option<Self> {
+        if points.len() < 3 || size < 21 {
+            return None;
+        }
+        let span = size as f32 - 7.0;
+        let (bottom_left, top_left, top_right) = (points[0], points[1], points[2]);
+        let grid = Self {
+            top_left: (top_left.x, top_left.y),
+            u: ((top_right.x - top_left.x) / span, (top_right.y - top_left.y) / span),
+            v: ((bottom_left.x - top_left.x) / span, (bottom_left.y - top_left.y) / span),
+        };
+        let (module_x, module_y) = grid.module_steps();
+        (module_x >= 1.0 && module_y >= 1.0).then_some(grid)
+    }
+
+    /// Paso de la cuadrícula en píxeles en cada eje
+    fn module_steps(&self) -> (f32, f32) {
+        (self.u.0.hypot(self.u.1), self.v.0.hypot(self.v.1))
+    }
+
+    fn module_size(&self) -> f32 {
+        let (module_x, module_y) = self.module_steps();
+        (module_x + module_y) / 2.0
+    }
+
+    /// Centro en píxeles del módulo (col, row); el patrón superior izquierdo está centrado en (3, 3)
+    fn center(&self, col: f32, row: f32) -> (f32, f32) {
+        let (dx, dy) = (col - 3.0, row - 3.0);
+        (
+            self.top_left.0 + dx * self.u.0 + dy * self.v.0,
+            self.top_left.1 + dx * self.u.1 + dy * self.v.1,
+        )
+    }
+}
+
+/// Reflectancia media (0.0 - 1.0) dentro de una apertura circular
+fn aperture_reflectance(image: &GrayImage, (cx, cy): (f32, f32), radius: f32) -> Option<f32> {
+    let (width, height) = image.dimensions();
+    if cx < 0.0 || cy < 0.0 || cx >= width as f32 || cy >= height as f32 {
+        return None;
+    }
+    let radius = radius.max(0.5);
+    let (x0, x1) = ((cx - radius).floor().max(0.0) as u32, ((cx + radius).ceil() as u32).min(width));
+    let (y0, y1) = ((cy - radius).floor().max(0.0) as u32, ((cy + radius).ceil() as u32).min(height));
+    let (mut sum, mut count) = (0u64, 0u64);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                sum += image.get_pixel(x, y)[0] as u64;
+                count += 1;
+            }
+        }
+    }
+    (count > 0).then(|| sum as f32 / count as f32 / 255.0)
+}
+
+/// Centro medido de un patrón de alineación: centroide de los píxeles oscuros en una
+/// ventana de 5x5 módulos, recentrada sobre el resultado
+fn pattern_centroid(image: &GrayImage, start: (f32, f32), module_size: f32, threshold: f32) -> Option<(f32, f32)> {
+    let (width, height) = image.dimensions();
+    let half = 2.5 * module_size;
+    let cutoff = threshold * 255.0;
+    let mut center = start;
+    for _ in 0..4 {
+        let (x0, x1) = ((center.0 - half).max(0.0) as u32, ((center.0 + half).max(0.0) as u32).min(width));
+        let (y0, y1) = ((center.1 - half).max(0.0) as u32, ((center.1 + half).max(0.0) as u32).min(height));
+        let (mut sx, mut sy, mut count) = (0.0, 0.0, 0usize);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if (image.get_pixel(x, y)[0] as f32) < cutoff {
+                    sx += x as f32 + 0.5;
+                    sy += y as f32 + 0.5;
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+        center = (sx / count as f32, sy / count as f32);
+    }
+    Some(center)
+}
+
+/// Grado de un parámetro medido por codeword (ISO/IEC 18004): en cada nivel, los codewords
+/// por debajo cuentan como errores y el grado queda limitado por la corrección que resta
+fn codeword_parameter(map: &CodewordMap, values: &[f32]) -> ParameterGrade {
+    let grade = Grade::PASSING
+        .into_iter()
+        .map(|level| {
+            let mut errors = vec![0; map.blocks.len()];
+            for (codeword, &value) in map.codewords.iter().zip(values) {
+                if Grade::at_least(value, MODULATION_THRESHOLDS) < level {
+                    errors[codeword.block] += 1;
+                }
+            }
+            level.min(Grade::at_least(map.unused_error_correction(&errors), UEC_THRESHOLDS))
+        })
+        .max()
+        .unwrap_or(Grade::F);
+    ParameterGrade {
+        value: values.iter().copied().fold(1.0, f32::min),
+        grade,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use qrcodegen::QrSegment;
+
+    /// Rasteriza un símbolo con zona de silencio de 4 módulos
+    fn render(qr: &QrCodeGen, dark: u8, light: u8, scale: u32) -> GrayImage {
+        let side = (qr.size() as u32 + 8) * scale;
+        GrayImage::from_fn(side, side, |x, y| {
+            let (col, row) = ((x / scale) as i32 - 4, (y / scale) as i32 - 4);
+            Luma([if qr.get_module(col, row) { dark } else { light }])
+        })
+    }
+
+    fn version_1_m() -> QrCodeGen {
+        let segments = QrSegment::make_segments("PRINT QUALITY");
+        QrCodeGen::encode_segments_advanced(
+            &segments, QrCodeEcc::Medium, QrVersion::new(1), QrVersion::new(1), None, false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_clean_svg_grades_a() {
+        let qr = crate::engine::QrGenerator::new().generate_basic("https://example.com/lote/42", 300).unwrap();
+        let report = PrintQualityGrader::new().grade_svg(&qr.to_svg()).unwrap();
+
+        assert_eq!(report.decode, Grade::A);
+        assert_eq!(report.overall, Grade::A, "{:?}", report);
+        assert!(report.symbol_contrast.value > 0.9);
+        assert_eq!(report.unused_error_correction.value, 1.0);
+        assert!(report.aperture_px > 0.0 && report.aperture_px < report.module_size_px);
+    }
+
+    #[test]
+    fn test_low_contrast_lowers_symbol_contrast() {
+        // Módulos grises (reflectancia 0.39) sobre blanco: SC ≈ 0.61
+        let image = render(&version_1_m(), 100, 255, 10);
+        let report = PrintQualityGrader::new().grade_image(&DynamicImage::ImageLuma8(image)).unwrap();
+
+        assert_eq!(report.decode, Grade::A);
+        assert_eq!(report.symbol_contrast.grade, Grade::B);
+        assert_eq!(report.overall, Grade::B);
+        assert_eq!(report.fixed_pattern_damage.grade, Grade::A);
+    }
+
+    #[test]
+    fn test_damaged_codewords_consume_error_correction() {
+        let qr = version_1_m();
+        let map = CodewordMap::new(1, QrCodeEcc::Medium).unwrap();
+        let mut image = render(&qr, 0, 255, 10);
+
+        // Dos codewords invertidos en 1-M (10 de corrección, 2 de protección): UEC = 1 - 4/8
+        for codeword in &map.codewords[3..5] {
+            for &(col, row) in &codeword.modules {
+                let value = if qr.get_module(col as i32, row as i32) { 255 } else { 0 };
+                for y in 0..10 {
+                    for x in 0..10 {
+                        image.put_pixel((col as u32 + 4) * 10 + x, (row as u32 + 4) * 10 + y, Luma([value]));
+                    }
+                }
+            }
+        }
+        let report = PrintQualityGrader::new().grade_image(&DynamicImage::ImageLuma8(image)).unwrap();
+
+        assert_eq!(report.unused_error_correction.value, 0.5);
+        assert_eq!(report.unused_error_correction.grade, Grade::B);
+        assert_eq!(report.reflectance_margin.grade, Grade::B);
+        assert_eq!(report.overall, Grade::B);
+    }
+
+    #[test]
+    fn test_blank_image_is_undecodable() {
+        let image = GrayImage::from_pixel(200, 200, Luma([255]));
+        let report = PrintQualityGrader::new().grade_image(&DynamicImage::ImageLuma8(image)).unwrap();
+
+        assert_eq!(report.decode, Grade::F);
+        assert_eq!(report.overall, Grade::F);
+    }
+
+    #[test]
+    fn test_grade_thresholds() {
+        assert_eq!(Grade::at_least(0.71, SYMBOL_CONTRAST_THRESHOLDS), Grade::A);
+        assert_eq!(Grade::at_least(0.19, SYMBOL_CONTRAST_THRESHOLDS), Grade::F);
+        assert_eq!(Grade::at_most(0.07, AXIAL_THRESHOLDS), Grade::B);
+        assert_eq!(Grade::at_most(0.0, TIMING_THRESHOLDS), Grade::A);
+        assert!(Grade::C > Grade::D);
+        assert_eq!(Grade::B.to_string(), "B");
+    }
+}
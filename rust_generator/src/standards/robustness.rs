@@ -0,0 +1,427 @@
+// standards/robustness.rs - Simulación de degradación de impresión/cámara
+// Decodifica el código bajo perturbaciones sintéticas de severidad creciente
+// y reporta una curva de supervivencia por perturbación
+
+use crate::engine::error::{QrError, QrResult};
+use crate::standards::decoder::QrDecoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, GrayImage, Luma};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Ancho de rasterización para analizar SVG
+const ANALYSIS_WIDTH: u32 = 400;
+
+/// Zona de silencio asumida si el decodificador no pudo medir la cuadrícula
+const ASSUMED_QUIET_ZONE: u32 = 4;
+
+/// Perturbaciones sintéticas disponibles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Perturbation {
+    /// Desenfoque gaussiano (σ en módulos)
+    Blur,
+    /// Compresión JPEG (calidad 1-100)
+    JpegCompression,
+    /// Inclinación de cámara (relación entre el borde lejano y el cercano)
+    PerspectiveSkew,
+    /// Rotación en el plano (grados)
+    Rotation,
+    /// Poca luz (factor de brillo)
+    LowLight,
+    /// Ruido gaussiano del sensor (σ en niveles de gris)
+    Noise,
+    /// Oclusión parcial central (fracción del área del símbolo)
+    Occlusion,
+}
+
+impl Perturbation {
+    /// Todas las perturbaciones, en el orden del reporte
+    pub const ALL: [Perturbation; 7] = [
+        Perturbation::Blur,
+        Perturbation::JpegCompression,
+        Perturbation::PerspectiveSkew,
+        Perturbation::Rotation,
+        Perturbation::LowLight,
+        Perturbation::Noise,
+        Perturbation::Occlusion,
+    ];
+
+    /// Niveles evaluados, de menor a mayor severidad
+    pub fn levels(&self) -> &'static [f32] {
+        match self {
+            Perturbation::Blur => &[0.25, 0.5, 0.75, 1.0, 1.25, 1.5],
+            Perturbation::JpegCompression => &[60.0, 40.0, 25.0, 15.0, 8.0, 3.0],
+            Perturbation::PerspectiveSkew => &[0.95, 0.85, 0.75, 0.65, 0.55, 0.45],
+            Perturbation::Rotation => &[5.0, 15.0, 30.0, 45.0, 60.0, 90.0],
+            Perturbation::LowLight => &[0.6, 0.4, 0.25, 0.15, 0.08, 0.04],
+            Perturbation::Noise => &[10.0, 25.0, 40.0, 60.0, 80.0, 100.0],
+            Perturbation::Occlusion => &[0.02, 0.05, 0.1, 0.15, 0.2, 0.3],
+        }
+    }
+
+    /// Nombre legible para reportes
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perturbation::Blur => "Desenfoque",
+            Perturbation::JpegCompression => "Compresión JPEG",
+            Perturbation::PerspectiveSkew => "Perspectiva",
+            Perturbation::Rotation => "Rotación",
+            Perturbation::LowLight => "Poca luz",
+            Perturbation::Noise => "Ruido",
+            Perturbation::Occlusion => "Oclusión",
+        }
+    }
+
+    /// Unidad del nivel
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Perturbation::Blur => "σ módulos",
+            Perturbation::JpegCompression => "calidad",
+            Perturbation::PerspectiveSkew => "relación de bordes",
+            Perturbation::Rotation => "grados",
+            Perturbation::LowLight => "brillo",
+            Perturbation::Noise => "σ gris",
+            Perturbation::Occlusion => "fracción de área",
+        }
+    }
+}
+
+/// Resultado de un nivel de perturbación
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurvivalPoint {
+    /// Severidad normalizada (0.0 - 1.0)
+    pub severity: f32,
+    /// Nivel en las unidades de la perturbación
+    pub level: f32,
+    /// Se decodificó el contenido esperado
+    pub decoded: bool,
+}
+
+/// Curva de supervivencia de una perturbación
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurvivalCurve {
+    pub perturbation: Perturbation,
+    pub points: Vec<SurvivalPoint>,
+    /// Fracción de niveles que se decodificaron
+    pub survival_rate: f32,
+    /// Primer nivel en que falla la lectura (None si sobrevive a todos)
+    pub breaking_level: Option<f32>,
+}
+
+/// Reporte de robustez completo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustnessReport {
+    pub curves: Vec<SurvivalCurve>,
+    /// Supervivencia media de todas las perturbaciones (0.0 - 1.0)
+    pub overall_survival: f32,
+    /// Tamaño de módulo estimado en píxeles de la imagen analizada
+    pub module_size_px: f32,
+    pub analysis_time_ms: u64,
+}
+
+impl RobustnessReport {
+    /// Curva de una perturbación concreta
+    pub fn curve(&self, perturbation: Perturbation) -> Option<&SurvivalCurve> {
+        self.curves.iter().find(|c| c.perturbation == perturbation)
+    }
+}
+
+/// Analizador de robustez de escaneo
+pub struct RobustnessAnalyzer {
+    decoder: QrDecoder,
+    perturbations: Vec<Perturbation>,
+}
+
+impl RobustnessAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            decoder: QrDecoder::new(),
+            perturbations: Perturbation::ALL.to_vec(),
+        }
+    }
+
+    /// Limita el análisis a un subconjunto de perturbaciones
+    pub fn with_perturbations(mut self, perturbations: Vec<Perturbation>) -> Self {
+        self.perturbations = perturbations;
+        self
+    }
+
+    /// Analiza un SVG generado (se rasteriza sobre blanco)
+    pub fn analyze_svg(&self, svg: &str, expected: &str) -> QrResult<RobustnessReport> {
+        let raster = crate::engine::raster::render_svg(svg, ANALYSIS_WIDTH)?;
+        let rgb = crate::engine::raster::flatten_on_white(&raster.pixels);
+        let image = image::RgbImage::from_raw(raster.width, raster.height, rgb)
+            .ok_or_else(|| QrError::RenderError("Rasterización SVG inválida".to_string()))?;
+        self.analyze_image(&DynamicImage::ImageRgb8(image), expected)
+    }
+
+    /// Analiza una imagen del código
+    pub fn analyze_image(&self, image: &DynamicImage, expected: &str) -> QrResult<RobustnessReport> {
+        let start = Instant::now();
+        let gray = image.to_luma8();
+
+        // La imagen sin perturbar debe leerse; si no, las curvas no significan nada
+        let baseline = self.decoder.decode_image(&DynamicImage::ImageLuma8(gray.clone()))?;
+        if !self.decoder.verify_content(&baseline, expected) {
+            return Err(QrError::DecodingError(
+                "La imagen original no contiene los datos esperados".to_string(),
+            ));
+        }
+
+        let version = baseline.metadata.qr_version.unwrap_or(1).max(1) as u32;
+        let dimension = 17 + 4 * version;
+        let module_size_px = baseline.metadata.module_size_px.unwrap_or_else(|| {
+            gray.width().min(gray.height()) as f32 / (dimension + 2 * ASSUMED_QUIET_ZONE) as f32
+        });
+        let symbol_side_px = dimension as f32 * module_size_px;
+
+        // Cada nivel es independiente: se evalúan en paralelo
+        let trials: Vec<(Perturbation, usize, f32)> = self
+            .perturbations
+            .iter()
+            .flat_map(|&p| p.levels().iter().enumerate().map(move |(i, &level)| (p, i, level)))
+            .collect();
+        let results: Vec<bool> = trials
+            .par_iter()
+            .map(|&(perturbation, _, level)| {
+                let degraded = apply_perturbation(&gray, perturbation, level, module_size_px, symbol_side_px);
+                self.decoder
+                    .decode_image(&DynamicImage::ImageLuma8(degraded))
+                    .map(|decoded| self.decoder.verify_content(&decoded, expected))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        let curves: Vec<SurvivalCurve> = self
+            .perturbations
+            .iter()
+            .map(|&perturbation| {
+                let count = perturbation.levels().len();
+                let points: Vec<SurvivalPoint> = trials
+                    .iter()
+                    .zip(&results)
+                    .filter(|((p, _, _), _)| *p == perturbation)
+                    .map(|(&(_, index, level), &decoded)| SurvivalPoint {
+                        severity: (index + 1) as f32 / count as f32,
+                        level,
+                        decoded,
+                    })
+                    .collect();
+                let survived = points.iter().filter(|p| p.decoded).count();
+                SurvivalCurve {
+                    perturbation,
+                    survival_rate: survived as f32 / points.len().max(1) as f32,
+                    breaking_level: points.iter().find(|p| !p.decoded).map(|p| p.level),
+                    points,
+                }
+            })
+            .collect();
+
+        let overall_survival = if curves.is_empty() {
+            1.0
+        } else {
+            curves.iter().map(|c| c.survival_rate).sum::<f32>() / curves.len() as f32
+        };
+
+        Ok(RobustnessReport {
+            curves,
+            overall_survival,
+            module_size_px,
+            analysis_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+impl Default for RobustnessAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Aplica una perturbación con el nivel indicado; el símbolo se asume centrado en la imagen
+pub fn apply_perturbation(
+    image: &GrayImage,
+    perturbation: Perturbation,
+    level: f32,
+    module_size_px: f32,
+    symbol_side_px: f32,
+) -> GrayImage {
+    match perturbation {
+        Perturbation::Blur => image::imageops::blur(image, (level * module_size_px).max(0.1)),
+        Perturbation::JpegCompression => jpeg_roundtrip(image, level.clamp(1.0, 100.0) as u8),
+        Perturbation::PerspectiveSkew => perspective_skew(image, level),
+        Perturbation::Rotation => rotate(image, level),
+        Perturbation::LowLight => {
+            let mut dark = image.clone();
+            for pixel in dark.pixels_mut() {
+                pixel[0] = (pixel[0] as f32 * level).round() as u8;
+            }
+            dark
+        }
+        Perturbation::Noise => add_noise(image, level),
+        Perturbation::Occlusion => occlude(image, level, symbol_side_px),
+    }
+}
+
+/// Codifica y decodifica en JPEG con la calidad indicada
+fn jpeg_roundtrip(image: &GrayImage, quality: u8) -> GrayImage {
+    let mut buffer = Vec::new();
+    let encoded = JpegEncoder::new_with_quality(&mut buffer, quality)
+        .encode(image.as_raw(), image.width(), image.height(), image::ColorType::L8);
+    match encoded.ok().and_then(|_| image::load_from_memory(&buffer).ok()) {
+        Some(decoded) => decoded.to_luma8(),
+        None => image.clone(),
+    }
+}
+
+/// Inclinación de cámara sobre el eje vertical (homografía).
+/// `edge_ratio` es la altura del borde derecho relativa al izquierdo.
+fn perspective_skew(image: &GrayImage, edge_ratio: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let half = cx.max(1.0);
+    let p = (1.0 - edge_ratio) / (1.0 + edge_ratio);
+
+    // Directa: x' = (1-p)·x/(1+p·x), y' = (1-p)·y/(1+p·x) en coordenadas normalizadas;
+    // el factor (1-p) mantiene el borde cercano dentro de la imagen
+    GrayImage::from_fn(width, height, |x, y| {
+        let a = (x as f32 - cx) / half / (1.0 - p);
+        let denominator = 1.0 - p * a;
+        if denominator <= 0.0 {
+            return Luma([255]);
+        }
+        let source_x = a / denominator;
+        let source_y = (y as f32 - cy) / half / ((1.0 - p) * denominator);
+        Luma([sample_bilinear(image, cx + source_x * half, cy + source_y * half)])
+    })
+}
+
+/// Rotación alrededor del centro; el lienzo crece para no recortar las esquinas
+fn rotate(image: &GrayImage, degrees: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let rotated_width = (width as f32 * cos.abs() + height as f32 * sin.abs()).round().max(1.0) as u32;
+    let rotated_height = (width as f32 * sin.abs() + height as f32 * cos.abs()).round().max(1.0) as u32;
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let (rcx, rcy) = (rotated_width as f32 / 2.0, rotated_height as f32 / 2.0);
+
+    GrayImage::from_fn(rotated_width, rotated_height, |x, y| {
+        let dx = x as f32 + 0.5 - rcx;
+        let dy = y as f32 + 0.5 - rcy;
+        let source_x = cx + dx * cos + dy * sin;
+        let source_y = cy - dx * sin + dy * cos;
+        Luma([sample_bilinear(image, source_x - 0.5, source_y - 0.5)])
+    })
+}
+
+/// Ruido gaussiano determinista (xorshift + Box-Muller)
+fn add_noise(image: &GrayImage, sigma: f32) -> GrayImage {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next_uniform = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        ((state >> 11) as f64 / (1u64 << 53) as f64).max(f64::MIN_POSITIVE)
+    };
+
+    let mut noisy = image.clone();
+    for pixel in noisy.pixels_mut() {
+        let (u1, u2) = (next_uniform(), next_uniform());
+        let gaussian = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        pixel[0] = (pixel[0] as f64 + gaussian * sigma as f64).round().clamp(0.0, 255.0) as u8;
+    }
+    noisy
+}
+
+/// Cubre con una mancha oscura centrada la fracción indicada del área del símbolo
+fn occlude(image: &GrayImage, fraction: f32, symbol_side_px: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let side = symbol_side_px * fraction.clamp(0.0, 1.0).sqrt();
+
+    let x0 = ((width as f32 - side) / 2.0).max(0.0) as u32;
+    let y0 = ((height as f32 - side) / 2.0).max(0.0) as u32;
+    let x1 = (x0 + side.round() as u32).min(width);
+    let y1 = (y0 + side.round() as u32).min(height);
+
+    let mut occluded = image.clone();
+    for y in y0..y1 {
+        for x in x0..x1 {
+            occluded.put_pixel(x, y, Luma([0]));
+        }
+    }
+    occluded
+}
+
+/// Muestreo bilineal; fuera de la imagen se considera fondo blanco
+fn sample_bilinear(image: &GrayImage, x: f32, y: f32) -> u8 {
+    let (width, height) = image.dimensions();
+    let pixel = |px: i64, py: i64| -> f32 {
+        if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+            255.0
+        } else {
+            image.get_pixel(px as u32, py as u32)[0] as f32
+        }
+    };
+
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let top = pixel(ix, iy) * (1.0 - fx) + pixel(ix + 1, iy) * fx;
+    let bottom = pixel(ix, iy + 1) * (1.0 - fx) + pixel(ix + 1, iy + 1) * fx;
+    (top * (1.0 - fy) + bottom * fy).round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::QrGenerator;
+
+    #[test]
+    fn test_survival_curves() {
+        let data = "ROBUST-42";
+        let svg = QrGenerator::new().generate_basic(data, 300).unwrap().to_svg();
+        let analyzer = RobustnessAnalyzer::new()
+            .with_perturbations(vec![Perturbation::Rotation, Perturbation::Occlusion]);
+
+        let report = analyzer.analyze_svg(&svg, data).unwrap();
+        assert_eq!(report.curves.len(), 2);
+        assert!(report.module_size_px > 1.0);
+
+        // La rotación no destruye información: debe sobrevivir a todos los niveles
+        let rotation = report.curve(Perturbation::Rotation).unwrap();
+        assert_eq!(rotation.points.len(), Perturbation::Rotation.levels().len());
+        assert_eq!(rotation.survival_rate, 1.0);
+        assert!(rotation.breaking_level.is_none());
+
+        // Tapar el 30% del símbolo supera cualquier nivel de corrección
+        let occlusion = report.curve(Perturbation::Occlusion).unwrap();
+        assert!(!occlusion.points.last().unwrap().decoded);
+        assert!(occlusion.breaking_level.is_some());
+        assert!(report.overall_survival > 0.0 && report.overall_survival < 1.0);
+    }
+
+    #[test]
+    fn test_analysis_requires_readable_baseline() {
+        let svg = QrGenerator::new().generate_basic("A", 300).unwrap().to_svg();
+        assert!(RobustnessAnalyzer::new().analyze_svg(&svg, "B").is_err());
+    }
+
+    #[test]
+    fn test_perturbations_preserve_dimensions() {
+        let image = GrayImage::from_fn(64, 48, |x, _| Luma([if x < 32 { 0 } else { 255 }]));
+        for perturbation in Perturbation::ALL {
+            if perturbation == Perturbation::Rotation {
+                continue;
+            }
+            let level = perturbation.levels()[0];
+            let degraded = apply_perturbation(&image, perturbation, level, 2.0, 40.0);
+            assert_eq!(degraded.dimensions(), (64, 48), "{:?}", perturbation);
+        }
+
+        // La rotación no recorta: el lienzo crece; 0 y 90 grados conservan el contenido
+        assert_eq!(rotate(&image, 0.0), image);
+        assert_eq!(rotate(&image, 90.0).dimensions(), (48, 64));
+        assert_eq!(rotate(&image, 45.0).dimensions(), (79, 79));
+    }
+}
@@ -0,0 +1,730 @@
+// standards/udi.rs - Identificación única de dispositivos (UDI) de la FDA
+// Las tres agencias emisoras acreditadas definen su propio formato para el identificador
+// de dispositivo (DI) y los de producción (PI): GS1, HIBCC (HIBC LIC) e ICCBBA (ISBT 128)
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::engine::error::{QrError, QrResult};
+use super::digital_link::{is_digital_link, DigitalLinkParser};
+use super::gs1::{ApplicationIdentifier, Gs1Encoder, Gs1Parser};
+
+/// Juego de caracteres HIBC: el valor de cada carácter es su posición (módulo 43)
+const HIBC_CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// Juego de caracteres del control ISO 7064 MOD 37-2 de ISBT 128
+const ISO7064_CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ*";
+
+/// Longitud máxima del código de producto, lote y serie en HIBC LIC
+const HIBC_MAX_FIELD: usize = 18;
+
+/// Longitud fija del lote MPHO y del número de identificación suplementario (ISBT 128)
+const ISBT_MPHO_FIELD: usize = 18;
+
+/// Identificadores de datos ISBT 128 admitidos y longitud de su contenido
+const ISBT_PPIC: (&str, usize) = ("=/", 11);
+const ISBT_EXPIRATION: (&str, usize) = ("=>", 6);
+const ISBT_PRODUCTION: (&str, usize) = ("=}", 6);
+const ISBT_MPHO_LOT: (&str, usize) = ("&,1", ISBT_MPHO_FIELD);
+const ISBT_SUPPLEMENTAL: (&str, usize) = ("&,2", ISBT_MPHO_FIELD);
+const ISBT_COMPOUND: (&str, usize) = ("=+", 5);
+/// Número de identificación de la donación: 13 caracteres y 2 de indicadores
+const ISBT_DIN_LENGTH: usize = 15;
+
+/// Agencia emisora acreditada por la FDA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssuingAgency {
+    Gs1,
+    Hibcc,
+    Iccbba,
+}
+
+/// Identificadores de producción (PI)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProductionIdentifiers {
+    pub lot: Option<String>,
+    pub serial: Option<String>,
+    pub expiration_date: Option<NaiveDate>,
+    pub manufacturing_date: Option<NaiveDate>,
+    /// Número de identificación de la donación (ISBT 128, productos de origen humano)
+    pub donation_id: Option<String>,
+}
+
+impl ProductionIdentifiers {
+    /// Sin ningún identificador de producción
+    pub fn is_empty(&self) -> bool {
+        self.lot.is_none()
+            && self.serial.is_none()
+            && self.expiration_date.is_none()
+            && self.manufacturing_date.is_none()
+            && self.donation_id.is_none()
+    }
+}
+
+/// UDI completo: identificador de dispositivo y de producción
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Udi {
+    pub agency: IssuingAgency,
+    /// DI: GTIN-14 (GS1), LIC + código de producto + unidad de medida (HIBCC)
+    /// o código de instalación + código de producto del procesador (ISBT 128)
+    pub device_identifier: String,
+    pub production: ProductionIdentifiers,
+}
+
+impl Udi {
+    /// UDI GS1 a partir del GTIN-14
+    pub fn gs1(gtin: &str) -> Self {
+        Self::new(IssuingAgency::Gs1, gtin.to_string())
+    }
+
+    /// UDI HIBC LIC: código de etiquetador (LIC), código de producto y unidad de medida (0-9)
+    pub fn hibc(labeler: &str, product: &str, unit_of_measure: u8) -> Self {
+        Self::new(IssuingAgency::Hibcc, format!("{}{}{}", labeler, product, unit_of_measure))
+    }
+
+    /// UDI ISBT 128: código de instalación (FIN, 5 caracteres) y código de producto
+    /// del procesador (6 caracteres), que forman el PPIC
+    pub fn isbt128(facility: &str, product: &str) -> Self {
+        Self::new(IssuingAgency::Iccbba, format!("{}{}", facility, product))
+    }
+
+    fn new(agency: IssuingAgency, device_identifier: String) -> Self {
+        Self {
+            agency,
+            device_identifier,
+            production: ProductionIdentifiers::default(),
+        }
+    }
+
+    pub fn with_lot(mut self, lot: &str) -> Self {
+        self.production.lot = Some(lot.to_string());
+        self
+    }
+
+    pub fn with_serial(mut self, serial: &str) -> Self {
+        self.production.serial = Some(serial.to_string());
+        self
+    }
+
+    pub fn with_expiration_date(mut self, date: NaiveDate) -> Self {
+        self.production.expiration_date = Some(date);
+        self
+    }
+
+    pub fn with_manufacturing_date(mut self, date: NaiveDate) -> Self {
+        self.production.manufacturing_date = Some(date);
+        self
+    }
+
+    pub fn with_donation_id(mut self, donation_id: &str) -> Self {
+        self.production.donation_id = Some(donation_id.to_string());
+        self
+    }
+
+    /// Verifica el formato del DI y los PI según la agencia y las reglas UDI comunes
+    pub fn validate(&self) -> QrResult<()> {
+        let production = &self.production;
+        if let (Some(manufactured), Some(expires)) = (production.manufacturing_date, production.expiration_date) {
+            if expires < manufactured {
+                return Err(udi_error("la fecha de caducidad es anterior a la de fabricación"));
+            }
+        }
+        if production.donation_id.is_some() && self.agency != IssuingAgency::Iccbba {
+            return Err(udi_error("el número de donación solo existe en ISBT 128"));
+        }
+
+        match self.agency {
+            // GTIN y valores se validan con el registro de AIs
+            IssuingAgency::Gs1 => Gs1Encoder::new().encode_element_string(&self.gs1_elements()?).map(|_| ()),
+            IssuingAgency::Hibcc => {
+                split_hibc_di(&self.device_identifier)?;
+                for (name, value) in [("lote", &production.lot), ("serie", &production.serial)] {
+                    if let Some(value) = value {
+                        check_hibc_field(name, value)?;
+                    }
+                }
+                Ok(())
+            },
+            IssuingAgency::Iccbba => {
+                check_isbt_field("PPIC", &self.device_identifier, ISBT_PPIC.1)?;
+                if let Some(lot) = &production.lot {
+                    check_isbt_field("lote MPHO", lot, ISBT_MPHO_FIELD)?;
+                }
+                if let Some(serial) = &production.serial {
+                    check_isbt_field("identificación suplementaria", serial, ISBT_MPHO_FIELD)?;
+                }
+                if let Some(din) = &production.donation_id {
+                    check_din(din)?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Cadena a codificar en el símbolo. Para GS1 es la cadena de elementos con
+    /// separadores GS, que debe generarse en modo GS1 (FNC1 en primera posición).
+    pub fn encode(&self) -> QrResult<String> {
+        self.validate()?;
+        match self.agency {
+            IssuingAgency::Gs1 => Gs1Encoder::new().encode_element_string(&self.gs1_elements()?),
+            IssuingAgency::Hibcc => Ok(self.encode_hibc()),
+            IssuingAgency::Iccbba => Ok(self.encode_isbt()),
+        }
+    }
+
+    /// Interpretación legible (HRI) que acompaña al símbolo en la etiqueta
+    pub fn human_readable(&self) -> QrResult<String> {
+        self.validate()?;
+        match self.agency {
+            IssuingAgency::Gs1 => Ok(Gs1Encoder::new().format_human_readable(&self.gs1_elements()?)),
+            // HIBC se imprime con los delimitadores de Code 39
+            IssuingAgency::Hibcc => Ok(format!("*{}*", self.encode_hibc())),
+            IssuingAgency::Iccbba => Ok(self.isbt_eye_readable()),
+        }
+    }
+
+    fn gs1_elements(&self) -> QrResult<Vec<(ApplicationIdentifier, String)>> {
+        if self.device_identifier.len() != 14 {
+            return Err(udi_error("el DI GS1 es un GTIN-14"));
+        }
+        let production = &self.production;
+        let mut elements = vec![(ApplicationIdentifier::GTIN, self.device_identifier.clone())];
+        if let Some(date) = production.manufacturing_date {
+            elements.push((ApplicationIdentifier::ProductionDate, date.format("%y%m%d").to_string()));
+        }
+        if let Some(date) = production.expiration_date {
+            elements.push((ApplicationIdentifier::ExpiryDate, date.format("%y%m%d").to_string()));
+        }
+        if let Some(lot) = &production.lot {
+            elements.push((ApplicationIdentifier::BatchLot, lot.clone()));
+        }
+        if let Some(serial) = &production.serial {
+            elements.push((ApplicationIdentifier::SerialNumber, serial.clone()));
+        }
+        Ok(elements)
+    }
+
+    /// Estructura primaria y secundaria concatenadas: `+DI/secundaria` y un único
+    /// carácter de control al final
+    fn encode_hibc(&self) -> String {
+        let production = &self.production;
+        let mut data = format!("+{}", self.device_identifier);
+
+        if !production.is_empty() {
+            data.push('/');
+            let lot = production.lot.as_deref();
+            match (production.expiration_date, lot, production.serial.as_deref()) {
+                // Caducidad YYMMDD (formato 3) seguida del lote o, sin lote, de la serie
+                (Some(date), Some(lot), _) => data.push_str(&format!("$$3{}{}", date.format("%y%m%d"), lot)),
+                (Some(date), None, Some(serial)) => data.push_str(&format!("$$+3{}{}", date.format("%y%m%d"), serial)),
+                (Some(date), None, None) => data.push_str(&format!("$$3{}", date.format("%y%m%d"))),
+                (None, Some(lot), _) => data.push_str(&format!("${}", lot)),
+                (None, None, Some(serial)) => data.push_str(&format!("$+{}", serial)),
+                (None, None, None) => data.push('$'),
+            }
+            // Con lote, la serie va como dato suplementario
+            if let (Some(_), Some(serial)) = (lot, production.serial.as_deref()) {
+                data.push_str(&format!("/S{}", serial));
+            }
+            if let Some(date) = production.manufacturing_date {
+                data.push_str(&format!("/16D{}", date.format("%Y%m%d")));
+            }
+        }
+
+        let check = hibc_check_character(&data).unwrap_or('0');
+        data.push(check);
+        data
+    }
+
+    /// Mensaje compuesto (`=+nn000`) con las estructuras de datos de longitud fija
+    fn encode_isbt(&self) -> String {
+        let production = &self.production;
+        let mut structures = vec![format!("{}{}", ISBT_PPIC.0, self.device_identifier)];
+        if let Some(din) = &production.donation_id {
+            structures.push(format!("={}", din));
+        }
+        if let Some(date) = production.expiration_date {
+            structures.push(format!("{}{}", ISBT_EXPIRATION.0, isbt_date(date)));
+        }
+        if let Some(date) = production.manufacturing_date {
+            structures.push(format!("{}{}", ISBT_PRODUCTION.0, isbt_date(date)));
+        }
+        if let Some(lot) = &production.lot {
+            structures.push(format!("{}{}", ISBT_MPHO_LOT.0, lot));
+        }
+        if let Some(serial) = &production.serial {
+            structures.push(format!("{}{}", ISBT_SUPPLEMENTAL.0, serial));
+        }
+
+        format!("{}{:02}000{}", ISBT_COMPOUND.0, structures.len(), structures.concat())
+    }
+
+    /// Texto legible ISBT 128: sin identificadores de datos y con el carácter de
+    /// control ISO 7064 del número de donación
+    fn isbt_eye_readable(&self) -> String {
+        let production = &self.production;
+        let (facility, product) = self.device_identifier.split_at(5);
+        let mut parts = vec![format!("PPIC {} {}", facility, product)];
+        if let Some(din) = &production.donation_id {
+            let check = iso7064_check_character(&din[..13]).unwrap_or('*');
+            parts.push(format!("DIN {} {} {} {} {}", &din[..5], &din[5..7], &din[7..13], &din[13..], check));
+        }
+        if let Some(date) = production.expiration_date {
+            parts.push(format!("EXP {}", date.format("%d %b %Y").to_string().to_uppercase()));
+        }
+        if let Some(date) = production.manufacturing_date {
+            parts.push(format!("PROD {}", date.format("%d %b %Y").to_string().to_uppercase()));
+        }
+        if let Some(lot) = &production.lot {
+            parts.push(format!("LOT {}", lot));
+        }
+        if let Some(serial) = &production.serial {
+            parts.push(format!("SEC {}", serial));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Parser de UDI: detecta la agencia emisora por el formato de los datos
+pub struct UdiParser {
+    gs1_parser: Gs1Parser,
+    digital_link_parser: DigitalLinkParser,
+}
+
+impl UdiParser {
+    pub fn new() -> Self {
+        Self {
+            gs1_parser: Gs1Parser::new(),
+            digital_link_parser: DigitalLinkParser::new(),
+        }
+    }
+
+    /// Interpreta y valida un UDI de cualquiera de las tres agencias
+    pub fn parse(&self, data: &str) -> QrResult<Udi> {
+        let udi = match issuing_agency(data) {
+            IssuingAgency::Hibcc => parse_hibc(data.trim_matches('*'))?,
+            IssuingAgency::Iccbba => parse_isbt(data)?,
+            IssuingAgency::Gs1 => self.parse_gs1(data)?,
+        };
+        udi.validate()?;
+        Ok(udi)
+    }
+
+    fn parse_gs1(&self, data: &str) -> QrResult<Udi> {
+        let elements = if is_digital_link(data) {
+            self.digital_link_parser.parse(data)?
+        } else {
+            self.gs1_parser.parse(data)?
+        };
+
+        let mut gtin = None;
+        let mut production = ProductionIdentifiers::default();
+        for (ai, value) in elements {
+            match ai {
+                ApplicationIdentifier::GTIN => gtin = Some(value),
+                ApplicationIdentifier::ExpiryDate => production.expiration_date = gs1_date(&value),
+                ApplicationIdentifier::ProductionDate => production.manufacturing_date = gs1_date(&value),
+                ApplicationIdentifier::BatchLot => production.lot = Some(value),
+                ApplicationIdentifier::SerialNumber => production.serial = Some(value),
+                _ => {}
+            }
+        }
+
+        let gtin = gtin.ok_or_else(|| udi_error("falta el GTIN (01) que identifica el dispositivo"))?;
+        Ok(Udi { production, ..Udi::gs1(&gtin) })
+    }
+}
+
+impl Default for UdiParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Agencia emisora según el formato de los datos: HIBC empieza por `+`,
+/// ISBT 128 por un identificador de datos (`=` o `&`) y el resto se trata como GS1
+pub fn issuing_agency(data: &str) -> IssuingAgency {
+    if data.starts_with('+') || data.starts_with("*+") {
+        IssuingAgency::Hibcc
+    } else if data.starts_with('=') || data.starts_with('&') {
+        IssuingAgency::Iccbba
+    } else {
+        IssuingAgency::Gs1
+    }
+}
+
+/// Carácter de control HIBC (módulo 43) de un mensaje LIC o PAS sin su control
+pub fn hibc_check_character(data: &str) -> Option<char> {
+    let sum = data.chars().try_fold(0usize, |sum, c| HIBC_CHARSET.find(c).map(|value| sum + value))?;
+    HIBC_CHARSET.chars().nth(sum % 43)
+}
+
+/// Verifica el carácter de control de un mensaje HIBC LIC o PAS (`+...`)
+pub fn is_valid_hibc(data: &str) -> bool {
+    let data = data.trim_matches('*');
+    match data.char_indices().last() {
+        Some((index, check)) if data.starts_with('+') && index > 1 => {
+            hibc_check_character(&data[..index]) == Some(check)
+        },
+        _ => false,
+    }
+}
+
+/// Carácter de control ISO 7064 MOD 37-2 (número de donación ISBT 128)
+pub fn iso7064_check_character(data: &str) -> Option<char> {
+    let sum = data
+        .chars()
+        .try_fold(0usize, |sum, c| ISO7064_CHARSET.find(c).map(|value| ((sum + value) * 2) % 37))?;
+    ISO7064_CHARSET.chars().nth((38 - sum) % 37)
+}
+
+fn udi_error(message: &str) -> QrError {
+    QrError::ValidationError(format!("UDI no válido: {}", message))
+}
+
+/// Separa el DI HIBC en LIC (4), código de producto (1-18) y unidad de medida (0-9)
+fn split_hibc_di(di: &str) -> QrResult<(&str, &str, u8)> {
+    if !di.is_ascii() || di.len() < 6 || di.len() > 4 + HIBC_MAX_FIELD + 1 {
+        return Err(udi_error("el DI HIBC debe tener LIC, código de producto y unidad de medida"));
+    }
+    let (labeler, rest) = di.split_at(4);
+    let (product, unit) = rest.split_at(rest.len() - 1);
+
+    if !labeler.starts_with(|c: char| c.is_ascii_uppercase())
+        || !labeler.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err(udi_error("el LIC HIBC son 4 caracteres alfanuméricos que empiezan por letra"));
+    }
+    check_hibc_field("código de producto", product)?;
+    let unit = unit.parse::<u8>().map_err(|_| udi_error("la unidad de medida HIBC es un dígito"))?;
+    Ok((labeler, product, unit))
+}
+
+fn check_hibc_field(name: &str, value: &str) -> QrResult<()> {
+    if value.is_empty()
+        || value.len() > HIBC_MAX_FIELD
+        || !value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err(udi_error(&format!(
+            "el {} HIBC debe tener de 1 a {} caracteres alfanuméricos en mayúsculas",
+            name, HIBC_MAX_FIELD
+        )));
+    }
+    Ok(())
+}
+
+fn check_isbt_field(name: &str, value: &str, length: usize) -> QrResult<()> {
+    if value.len() != length || !value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Err(udi_error(&format!(
+            "el {} ISBT 128 tiene {} caracteres alfanuméricos en mayúsculas",
+            name, length
+        )));
+    }
+    Ok(())
+}
+
+/// Número de donación: identificador de instalación (α + 4), año (2), secuencia (6) e indicadores (2)
+fn check_din(din: &str) -> QrResult<()> {
+    check_isbt_field("número de donación", din, ISBT_DIN_LENGTH)?;
+    let valid_first = din.starts_with(|c: char| matches!(c, 'A'..='N' | 'P'..='Z' | '1'..='9'));
+    if !valid_first || !din[5..].chars().all(|c| c.is_ascii_digit()) {
+        return Err(udi_error("número de donación ISBT 128 mal formado"));
+    }
+    Ok(())
+}
+
+/// Fecha GS1 YYMMDD; el día 00 indica el último día del mes
+fn gs1_date(value: &str) -> Option<NaiveDate> {
+    let year = 2000 + value.get(0..2)?.parse::<i32>().ok()?;
+    let month = value.get(2..4)?.parse::<u32>().ok()?;
+    match value.get(4..6)?.parse::<u32>().ok()? {
+        0 => last_day_of_month(year, month),
+        day => NaiveDate::from_ymd_opt(year, month, day),
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+/// Fecha ISBT 128 `cyyjjj`: siglo desde 2000, año y día juliano
+fn isbt_date(date: NaiveDate) -> String {
+    format!("{:03}{:03}", date.year() - 2000, date.ordinal())
+}
+
+fn parse_isbt_date(value: &str) -> Option<NaiveDate> {
+    let year = 2000 + value.get(0..3)?.parse::<i32>().ok()?;
+    NaiveDate::from_yo_opt(year, value.get(3..6)?.parse().ok()?)
+}
+
+/// Estructura primaria LIC seguida, opcionalmente, de la secundaria concatenada
+fn parse_hibc(data: &str) -> QrResult<Udi> {
+    if !is_valid_hibc(data) {
+        return Err(udi_error("carácter de control HIBC incorrecto"));
+    }
+    if data.starts_with("+/") {
+        return Err(udi_error("HIBC PAS identifica datos del proveedor, no dispositivos"));
+    }
+    if data.starts_with("+$") {
+        return Err(udi_error("estructura secundaria HIBC sin la primaria"));
+    }
+
+    let body = &data[1..data.len() - 1];
+    let (primary, secondary) = body.split_once('/').unwrap_or((body, ""));
+    let (labeler, product, unit) = split_hibc_di(primary)?;
+    let mut udi = Udi::hibc(labeler, product, unit);
+    if secondary.is_empty() {
+        return Ok(udi);
+    }
+
+    let mut fields = secondary.split('/');
+    parse_hibc_lot_field(fields.next().unwrap_or_default(), &mut udi.production)?;
+    for field in fields {
+        let production = &mut udi.production;
+        if let Some(serial) = field.strip_prefix('S') {
+            production.serial = Some(serial.to_string());
+        } else if let Some(date) = field.strip_prefix("16D") {
+            production.manufacturing_date = NaiveDate::parse_from_str(date, "%Y%m%d").ok();
+            if production.manufacturing_date.is_none() {
+                return Err(udi_error("fecha de fabricación HIBC no válida"));
+            }
+        } else if let Some(date) = field.strip_prefix("14D") {
+            production.expiration_date = NaiveDate::parse_from_str(date, "%Y%m%d").ok();
+            if production.expiration_date.is_none() {
+                return Err(udi_error("fecha de caducidad HIBC no válida"));
+            }
+        } else if !field.starts_with('Q') {
+            return Err(udi_error(&format!("campo suplementario HIBC desconocido: /{}", field)));
+        }
+    }
+    Ok(udi)
+}
+
+/// Campo de lote o serie de la estructura secundaria: `$lote`, `$+serie`,
+/// `$$[formato]fecha lote` o `$$+[formato]fecha serie`
+fn parse_hibc_lot_field(field: &str, production: &mut ProductionIdentifiers) -> QrResult<()> {
+    let (dated, rest) = match field.strip_prefix("$$") {
+        Some(rest) => (true, rest),
+        None => (false, field.strip_prefix('$').ok_or_else(|| udi_error("estructura secundaria HIBC sin '$'"))?),
+    };
+    let (is_serial, rest) = match rest.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+
+    let rest = if dated {
+        // El primer carácter indica el formato; un 0 o 1 es ya el mes de MMYY
+        let (format, rest) = match rest.chars().next() {
+            Some(c @ '2'..='7') => (c, &rest[1..]),
+            _ => ('1', rest),
+        };
+        let length = match format {
+            '1' => 4,
+            '2' | '3' => 6,
+            '4' => 8,
+            '5' => 5,
+            '6' => 7,
+            _ => 0,
+        };
+        let date = rest.get(..length).ok_or_else(|| udi_error("fecha HIBC incompleta"))?;
+        if length > 0 {
+            let expiration = match format {
+                '1' => date[..2].parse().ok().zip(date[2..].parse::<i32>().ok())
+                    .and_then(|(month, year)| last_day_of_month(2000 + year, month)),
+                '2' => NaiveDate::parse_from_str(date, "%m%d%y").ok(),
+                '3' => NaiveDate::parse_from_str(date, "%y%m%d").ok(),
+                '4' => NaiveDate::parse_from_str(&date[..6], "%y%m%d").ok(),
+                _ => date.get(..2).zip(date.get(2..5)).and_then(|(year, day)| {
+                    NaiveDate::from_yo_opt(2000 + year.parse::<i32>().ok()?, day.parse().ok()?)
+                }),
+            };
+            production.expiration_date = Some(expiration.ok_or_else(|| udi_error("fecha de caducidad HIBC no válida"))?);
+        }
+        &rest[length..]
+    } else {
+        rest
+    };
+
+    if !rest.is_empty() {
+        if is_serial {
+            production.serial = Some(rest.to_string());
+        } else {
+            production.lot = Some(rest.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Mensaje compuesto o estructuras ISBT 128 concatenadas
+fn parse_isbt(data: &str) -> QrResult<Udi> {
+    let mut remaining = data;
+    let mut ppic = None;
+    let mut production = ProductionIdentifiers::default();
+
+    if let Some(rest) = remaining.strip_prefix(ISBT_COMPOUND.0) {
+        if rest.len() < ISBT_COMPOUND.1 || !rest[..ISBT_COMPOUND.1].chars().all(|c| c.is_ascii_digit()) {
+            return Err(udi_error("cabecera de mensaje compuesto ISBT 128 mal formada"));
+        }
+        remaining = &rest[ISBT_COMPOUND.1..];
+    }
+
+    while !remaining.is_empty() {
+        let (identifier, length) = [ISBT_MPHO_LOT, ISBT_SUPPLEMENTAL, ISBT_PPIC, ISBT_EXPIRATION, ISBT_PRODUCTION]
+            .into_iter()
+            .find(|(identifier, _)| remaining.starts_with(identifier))
+            .or_else(|| remaining.starts_with('=').then_some(("=", ISBT_DIN_LENGTH)))
+            .ok_or_else(|| udi_error("identificador de datos ISBT 128 no admitido"))?;
+        let end = identifier.len() + length;
+        let value = remaining
+            .get(identifier.len()..end)
+            .ok_or_else(|| udi_error("estructura de datos ISBT 128 incompleta"))?
+            .to_string();
+        remaining = &remaining[end..];
+
+        match identifier {
+            "=/" => ppic = Some(value),
+            "=>" | "=}" => {
+                let date = parse_isbt_date(&value).ok_or_else(|| udi_error("fecha ISBT 128 no válida"))?;
+                if identifier == ISBT_EXPIRATION.0 {
+                    production.expiration_date = Some(date);
+                } else {
+                    production.manufacturing_date = Some(date);
+                }
+            },
+            "&,1" => production.lot = Some(value),
+            "&,2" => production.serial = Some(value),
+            _ => production.donation_id = Some(value),
+        }
+    }
+
+    let ppic = ppic.ok_or_else(|| udi_error("falta el PPIC (=/) que identifica el producto"))?;
+    Ok(Udi {
+        agency: IssuingAgency::Iccbba,
+        device_identifier: ppic,
+        production,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_gs1_udi() {
+        let udi = Udi::gs1("00614141999996")
+            .with_expiration_date(date(2025, 12, 31))
+            .with_lot("A123")
+            .with_serial("SN9");
+
+        let encoded = udi.encode().unwrap();
+        assert_eq!(encoded, "01006141419999961725123110A123\u{1D}21SN9");
+        assert_eq!(udi.human_readable().unwrap(), "(01)00614141999996 (17)251231 (10)A123 (21)SN9");
+
+        let parser = UdiParser::new();
+        assert_eq!(parser.parse(&encoded).unwrap(), udi);
+        assert_eq!(parser.parse("(01)00614141999996(17)251231(10)A123(21)SN9").unwrap(), udi);
+        assert_eq!(parser.parse("https://id.gs1.org/01/00614141999996/10/A123").unwrap().production.lot.as_deref(), Some("A123"));
+
+        // Día 00: último día del mes
+        let udi = parser.parse("(01)00614141999996(17)250200").unwrap();
+        assert_eq!(udi.production.expiration_date, Some(date(2025, 2, 28)));
+
+        assert!(Udi::gs1("00614141999997").encode().is_err());
+        assert!(parser.parse("(10)A123").is_err());
+    }
+
+    #[test]
+    fn test_hibc_check_character() {
+        assert_eq!(hibc_check_character("+H123ABC01234567890"), Some('D'));
+        assert!(is_valid_hibc("+H123ABC01234567890D"));
+        assert!(is_valid_hibc("*+A123BJC5D6E71G*"));
+        assert!(!is_valid_hibc("+H123ABC01234567890E"));
+
+        // El control de PAS se calcula igual, pero no identifica un dispositivo
+        let pas = "+/EU123";
+        let pas = format!("{}{}", pas, hibc_check_character(pas).unwrap());
+        assert!(is_valid_hibc(&pas));
+        assert!(UdiParser::new().parse(&pas).is_err());
+    }
+
+    #[test]
+    fn test_hibc_udi() {
+        let udi = Udi::hibc("H123", "ABC0123456789", 0);
+        assert_eq!(udi.encode().unwrap(), "+H123ABC01234567890D");
+
+        let udi = udi
+            .with_expiration_date(date(2025, 12, 31))
+            .with_lot("LOT42")
+            .with_serial("S001")
+            .with_manufacturing_date(date(2024, 6, 1));
+        let encoded = udi.encode().unwrap();
+        assert!(encoded.starts_with("+H123ABC01234567890/$$3251231LOT42/SS001/16D20240601"));
+        assert!(is_valid_hibc(&encoded));
+        assert_eq!(udi.human_readable().unwrap(), format!("*{}*", encoded));
+
+        let parser = UdiParser::new();
+        assert_eq!(parser.parse(&encoded).unwrap(), udi);
+
+        // Variantes de la secundaria: MMYY, YYJJJ y serie con fecha
+        let with_check = |data: &str| format!("{}{}", data, hibc_check_character(data).unwrap());
+        let parsed = parser.parse(&with_check("+A999123451/$$0725LOT1")).unwrap();
+        assert_eq!(parsed.production.expiration_date, Some(date(2025, 7, 31)));
+        assert_eq!(parsed.production.lot.as_deref(), Some("LOT1"));
+        let parsed = parser.parse(&with_check("+A999123451/$$525032X")).unwrap();
+        assert_eq!(parsed.production.expiration_date, Some(date(2025, 2, 1)));
+        let parsed = parser.parse(&with_check("+A999123451/$$+3251231SER7")).unwrap();
+        assert_eq!(parsed.production.serial.as_deref(), Some("SER7"));
+
+        assert!(Udi::hibc("1234", "ABC", 1).encode().is_err());
+        assert!(Udi::hibc("H123", "ABC", 0).with_lot("lote/1").encode().is_err());
+    }
+
+    #[test]
+    fn test_isbt128_udi() {
+        let udi = Udi::isbt128("A9999", "XYZ100")
+            .with_donation_id("A999925123456")
+            .with_expiration_date(date(2025, 12, 31))
+            .with_lot("LOT000000000000042");
+        assert!(udi.encode().is_err(), "el número de donación lleva dos indicadores");
+
+        let udi = udi.with_donation_id("A99992512345600");
+        let encoded = udi.encode().unwrap();
+        assert_eq!(encoded, "=+04000=/A9999XYZ100=A99992512345600=>025365&,1LOT000000000000042");
+        assert_eq!(UdiParser::new().parse(&encoded).unwrap(), udi);
+
+        let readable = udi.human_readable().unwrap();
+        let check = iso7064_check_character("A999925123456").unwrap();
+        assert!(readable.contains(&format!("DIN A9999 25 123456 00 {}", check)));
+        assert!(readable.contains("EXP 31 DEC 2025"));
+
+        assert!(UdiParser::new().parse("=/A9999XYZ10").is_err());
+        assert!(Udi::isbt128("A9999", "XYZ100").with_lot("CORTO").encode().is_err());
+    }
+
+    #[test]
+    fn test_udi_rules() {
+        // La caducidad no puede preceder a la fabricación
+        let udi = Udi::gs1("00614141999996")
+            .with_manufacturing_date(date(2025, 6, 1))
+            .with_expiration_date(date(2025, 1, 1));
+        assert!(udi.validate().is_err());
+
+        // El número de donación es propio de ISBT 128
+        assert!(Udi::gs1("00614141999996").with_donation_id("A99992512345600").validate().is_err());
+
+        assert!(Udi::gs1("00614141999996").production.is_empty());
+    }
+
+    #[test]
+    fn test_iso7064_check_character() {
+        assert_eq!(iso7064_check_character("G123489654321"), Some('Y'));
+        assert_eq!(iso7064_check_character("G12348965432!"), None);
+    }
+}
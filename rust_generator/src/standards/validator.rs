@@ -2,6 +2,10 @@
 
 use crate::engine::error::{QrError, QrResult};
 use crate::engine::types::QrCode;
+use super::digital_link::{is_digital_link, DigitalLinkParser};
+use super::gs1::{ApplicationIdentifier, Gs1Parser};
+use super::udi::{issuing_agency, IssuingAgency, UdiParser};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Perfil de validación para diferentes industrias
@@ -42,7 +46,7 @@ pub struct ValidationIssue {
 }
 
 /// Advertencia de validación
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationWarning {
     pub code: String,
     pub message: String,
@@ -188,6 +192,17 @@ impl StandardValidator {
                 standard: Some("ISO/IEC 18004".to_string()),
             });
             score -= 20.0;
+        } else if qr.quiet_zone < qr.symbology.min_quiet_zone() {
+            // El perfil lo admite, pero la simbología exige más margen para lectores genéricos
+            warnings.push(ValidationWarning {
+                code: "QZ002".to_string(),
+                message: format!(
+                    "Zona de silencio de {} módulos por debajo del mínimo de la simbología ({})",
+                    qr.quiet_zone, qr.symbology.min_quiet_zone()
+                ),
+                recommendation: Some("Reservar el margen claro en el diseño que contiene el código".to_string()),
+            });
+            score -= 5.0;
         }
         compliance.insert("quiet_zone".to_string(), qr.quiet_zone >= config.min_quiet_zone);
         
@@ -266,18 +281,64 @@ impl StandardValidator {
             }
         }
         
+        // Una cadena de elementos GS1 solo se interpreta como tal si el símbolo lleva
+        // FNC1 en primera posición; sin él los lectores la entregan como texto plano
+        let requires_gs1 = config.required_standards.iter()
+            .any(|standard| matches!(standard, Standard::GS1General | Standard::GS1Healthcare));
+        let gs1_data = issuing_agency(data) == IssuingAgency::Gs1
+            && !data.starts_with("https://")
+            && !data.starts_with("http://");
+        if requires_gs1 && gs1_data {
+            if !qr.gs1_mode {
+                issues.push(ValidationIssue {
+                    code: "GS1002".to_string(),
+                    severity: ValidationSeverity::Error,
+                    message: "Símbolo sin FNC1 en primera posición: los datos no se leerán como GS1".to_string(),
+                    standard: Some("GS1 General Specifications".to_string()),
+                });
+                score -= 15.0;
+            }
+            compliance.insert("gs1_mode".to_string(), qr.gs1_mode);
+        }
+        
         // Validaciones adicionales para perfiles específicos
         match profile {
-            ValidationProfile::Healthcare => {
-                // Validar requisitos FDA UDI
-                if !data.contains("(01)") || !data.contains("(17)") || !data.contains("(10)") {
+            ValidationProfile::Retail if data.starts_with("https://") || data.starts_with("http://") => {
+                // Una URL en retail debe ser un GS1 Digital Link válido para el punto de venta
+                if let Err(e) = DigitalLinkParser::new().parse(data) {
                     issues.push(ValidationIssue {
-                        code: "UDI001".to_string(),
+                        code: "GS1DL001".to_string(),
                         severity: ValidationSeverity::Error,
-                        message: "Faltan elementos requeridos para FDA UDI (GTIN, fecha de caducidad, lote)".to_string(),
-                        standard: Some("FDA UDI".to_string()),
+                        message: format!("URI no válida como GS1 Digital Link: {}", e),
+                        standard: Some("GS1 Digital Link".to_string()),
                     });
-                    score -= 30.0;
+                    score -= 20.0;
+                }
+            },
+            ValidationProfile::Healthcare => {
+                // Validar requisitos FDA UDI: DI de una agencia emisora y PI del dispositivo
+                match UdiParser::new().parse(data) {
+                    Ok(udi) => {
+                        if udi.production.is_empty() {
+                            warnings.push(ValidationWarning {
+                                code: "UDI002".to_string(),
+                                message: "UDI sin identificadores de producción (lote, serie o fechas)".to_string(),
+                                recommendation: Some("Incluir los PI que figuran en la etiqueta del dispositivo".to_string()),
+                            });
+                            score -= 10.0;
+                        }
+                        compliance.insert("FDA_UDI".to_string(), true);
+                    },
+                    Err(e) => {
+                        issues.push(ValidationIssue {
+                            code: "UDI001".to_string(),
+                            severity: ValidationSeverity::Error,
+                            message: e.to_string(),
+                            standard: Some("FDA UDI".to_string()),
+                        });
+                        compliance.insert("FDA_UDI".to_string(), false);
+                        score -= 30.0;
+                    },
                 }
             },
             ValidationProfile::FoodBeverage => {
@@ -314,19 +375,23 @@ impl StandardValidator {
             Standard::ISO15415 => {
                 // Validación simplificada ISO 15415
                 // En producción, esto incluiría medición de contraste, decodificación, etc.
-                Ok(qr.quiet_zone >= 4 && qr.size >= 21)
+                Ok(qr.quiet_zone >= qr.symbology.min_quiet_zone() && qr.size >= qr.symbology.min_size())
             },
             Standard::GS1General => {
-                // Verificar que contenga al menos un AI GS1
-                Ok(data.contains("(") && data.contains(")"))
+                // Verificar que sea una cadena de elementos GS1 válida o un Digital Link
+                Ok(Gs1Parser::new().parse(data).is_ok() || is_digital_link(data))
             },
             Standard::GS1Healthcare => {
-                // Verificar AIs específicos de healthcare
-                Ok(data.contains("(01)") && (data.contains("(17)") || data.contains("(7003)")))
+                // Verificar AIs específicos de healthcare y que todos los valores sean válidos
+                let Ok(elements) = Gs1Parser::new().parse(data) else {
+                    return Ok(false);
+                };
+                let has = |code: &str| elements.iter().any(|(ai, _)| ai.code() == code);
+                Ok(elements.iter().any(|(ai, _)| *ai == ApplicationIdentifier::GTIN) && (has("17") || has("7003")))
             },
             Standard::FdaUdi => {
-                // Verificar requisitos UDI
-                Ok(data.contains("(01)") && data.contains("(17)") && data.contains("(10)"))
+                // UDI de cualquiera de las agencias emisoras (GS1, HIBCC, ICCBBA)
+                Ok(UdiParser::new().parse(data).is_ok())
             },
             Standard::AnsiMh10 => {
                 // Validación básica ANSI MH10
@@ -408,26 +473,137 @@ mod tests {
         assert!(validation.compliance.get("FDA_UDI").copied().unwrap_or(false));
     }
     
+    #[test]
+    fn test_quiet_zone_validation() {
+        let validator = StandardValidator::new();
+        let data = "(01)01234567890128(10)ABC123";
+        let mut qr = create_test_qr();
+        
+        // Logística exige 6 módulos: la zona estándar de 4 no basta
+        let result = validator.validate(&qr, ValidationProfile::Logistics, data).unwrap();
+        assert!(result.issues.iter().any(|i| i.code == "QZ001"));
+        assert_eq!(result.compliance.get("quiet_zone"), Some(&false));
+        
+        qr.quiet_zone = 8;
+        let result = validator.validate(&qr, ValidationProfile::Logistics, data).unwrap();
+        assert!(!result.issues.iter().any(|i| i.code == "QZ001"));
+        assert_eq!(result.compliance.get("quiet_zone"), Some(&true));
+        
+        // Uso general admite 2 módulos, con advertencia para QR modelo 2
+        qr.quiet_zone = 2;
+        let result = validator.validate(&qr, ValidationProfile::General, data).unwrap();
+        assert!(result.issues.iter().all(|i| !i.code.starts_with("QZ")));
+        assert!(result.warnings.iter().any(|w| w.code == "QZ002"));
+        
+        qr.quiet_zone = 0;
+        let result = validator.validate(&qr, ValidationProfile::General, data).unwrap();
+        assert!(result.issues.iter().any(|i| i.code == "QZ001"));
+    }
+    
+    #[test]
+    fn test_gs1_element_string_validation() {
+        let validator = StandardValidator::new();
+        let qr = create_test_qr();
+        
+        let data = "(01)01234567890128(17)251231(10)ABC123(7003)2512311200";
+        let result = validator.validate(&qr, ValidationProfile::Healthcare, data).unwrap();
+        assert_eq!(result.compliance.get("GS1Healthcare"), Some(&true));
+        
+        // Fecha imposible en AI (17)
+        let data = "(01)01234567890128(17)251332(10)ABC123";
+        let result = validator.validate(&qr, ValidationProfile::Healthcare, data).unwrap();
+        assert_eq!(result.compliance.get("GS1Healthcare"), Some(&false));
+        
+        // AI (10) sin clave a la que calificar
+        let result = validator.validate(&qr, ValidationProfile::Retail, "(10)ABC123").unwrap();
+        assert_eq!(result.compliance.get("GS1General"), Some(&false));
+    }
+    
+    #[test]
+    fn test_udi_agencies() {
+        let validator = StandardValidator::new();
+        let qr = create_test_qr();
+        
+        // Las tres agencias emisoras son válidas para FDA UDI
+        for data in [
+            "(01)00614141999996(17)251231(10)A123",
+            "+H123ABC01234567890/$$3251231LOT42D",
+            "=+02000=/A9999XYZ100=>025365",
+        ] {
+            let result = validator.validate(&qr, ValidationProfile::Healthcare, data).unwrap();
+            assert_eq!(result.compliance.get("FDA_UDI"), Some(&true), "{}", data);
+            assert!(!result.issues.iter().any(|i| i.code == "UDI001"), "{}", data);
+        }
+        
+        // Sin identificadores de producción o con el control HIBC incorrecto
+        let result = validator.validate(&qr, ValidationProfile::Healthcare, "+H123ABC01234567890D").unwrap();
+        assert!(result.warnings.iter().any(|w| w.code == "UDI002"));
+        let result = validator.validate(&qr, ValidationProfile::Healthcare, "+H123ABC01234567890E").unwrap();
+        assert!(result.issues.iter().any(|i| i.code == "UDI001"));
+        assert_eq!(result.compliance.get("FDA_UDI"), Some(&false));
+        
+        // HIBC e ISBT 128 no usan FNC1: el indicador GS1 no aplica
+        let result = validator.validate(&qr, ValidationProfile::Healthcare, "+H123ABC01234567890D").unwrap();
+        assert!(!result.issues.iter().any(|i| i.code == "GS1002"));
+    }
+    
+    #[test]
+    fn test_gs1_symbol_flag() {
+        let validator = StandardValidator::new();
+        let mut qr = create_test_qr();
+        let data = "(01)01234567890128(10)ABC123";
+        
+        // Sin FNC1 en primera posición los datos GS1 se leerían como texto
+        let result = validator.validate(&qr, ValidationProfile::Retail, data).unwrap();
+        assert!(result.issues.iter().any(|i| i.code == "GS1002"));
+        assert_eq!(result.compliance.get("gs1_mode"), Some(&false));
+        assert!(!result.is_valid);
+        
+        qr.gs1_mode = true;
+        let result = validator.validate(&qr, ValidationProfile::Retail, data).unwrap();
+        assert!(!result.issues.iter().any(|i| i.code == "GS1002"));
+        assert_eq!(result.compliance.get("gs1_mode"), Some(&true));
+        
+        // Un Digital Link es una URI: no lleva FNC1
+        qr.gs1_mode = false;
+        let data = "https://id.gs1.org/01/09780345418913/10/ABC123";
+        let result = validator.validate(&qr, ValidationProfile::Retail, data).unwrap();
+        assert!(!result.compliance.contains_key("gs1_mode"));
+        
+        // Los perfiles sin estándares GS1 no exigen el indicador
+        let result = validator.validate(&qr, ValidationProfile::General, "Hola").unwrap();
+        assert!(!result.issues.iter().any(|i| i.code == "GS1002"));
+    }
+    
+    #[test]
+    fn test_retail_digital_link() {
+        let validator = StandardValidator::new();
+        let qr = create_test_qr();
+        
+        let data = "https://id.gs1.org/01/09780345418913/10/ABC123";
+        let result = validator.validate(&qr, ValidationProfile::Retail, data).unwrap();
+        assert!(!result.issues.iter().any(|i| i.code == "GS1DL001"));
+        assert_eq!(result.compliance.get("GS1General"), Some(&true));
+        
+        // Calificadores en orden incorrecto
+        let data = "https://id.gs1.org/01/09780345418913/21/S1/10/ABC123";
+        let result = validator.validate(&qr, ValidationProfile::Retail, data).unwrap();
+        assert!(result.issues.iter().any(|i| i.code == "GS1DL001"));
+        assert!(!result.is_valid);
+    }
+    
     fn create_test_qr() -> QrCode {
         QrCode {
             matrix: vec![vec![false; 25]; 25],
             size: 25,
             quiet_zone: 4,
             customization: Some(crate::engine::types::QrCustomization {
-                eye_shape: None,
-                eye_border_style: None,
-                eye_center_style: None,
-                data_pattern: None,
-                colors: None,
-                gradient: None,
-                logo: None,
-                frame: None,
-                effects: None,
                 error_correction: Some(crate::engine::types::ErrorCorrectionLevel::High),
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
             logo_zone: None,
+            symbology: crate::engine::types::QrSymbology::Qr,
+            gs1_mode: false,
         }
     }
 }
\ No newline at end of file
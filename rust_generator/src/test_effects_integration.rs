@@ -13,14 +13,6 @@ mod tests {
             size: 400,
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
-                eye_shape: None,
-                eye_border_style: None,
-                eye_center_style: None,
-                data_pattern: None,
-                colors: None,
-                gradient: None,
-                logo: None,
-                frame: None,
                 effects: Some(vec![
                     EffectOptions {
                         effect_type: Effect::Shadow,
@@ -33,10 +25,9 @@ mod tests {
                         },
                     },
                 ]),
-                error_correction: None,
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         let result = engine.generate(request).await;
@@ -66,17 +57,12 @@ mod tests {
             format: OutputFormat::Svg,
             customization: Some(QrCustomization {
                 eye_shape: Some(EyeShape::Circle),
-                eye_border_style: None,
-                eye_center_style: None,
                 data_pattern: Some(DataPattern::Dots),
                 colors: Some(ColorOptions {
                     foreground: "#2563eb".to_string(),
                     background: "#ffffff".to_string(),
                     eye_colors: None,
                 }),
-                gradient: None,
-                logo: None,
-                frame: None,
                 effects: Some(vec![
                     EffectOptions {
                         effect_type: Effect::Glow,
@@ -97,9 +83,9 @@ mod tests {
                     },
                 ]),
                 error_correction: Some(ErrorCorrectionLevel::High),
-                logo_size_ratio: None,
-                selective_effects: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         
         let result = engine.generate(request).await;
@@ -63,7 +63,6 @@ pub struct BarcodeRequestOptions {
     #[serde(rename = "maxColumns")]
     pub max_columns: Option<u32>,
 
-    #[allow(dead_code)] // Se mantiene para futuras implementaciones
     pub compact: Option<bool>,
 
     // Nuevo campo para TTL personalizado